@@ -24,38 +24,55 @@ bind! {
     _zval_struct__bindgen_ty_1,
     _zval_struct__bindgen_ty_2,
     ext_php_rs_executor_globals,
+    ext_php_rs_zts_module_globals,
     ext_php_rs_php_build_id,
     ext_php_rs_zend_object_alloc,
     ext_php_rs_zend_object_release,
+    ext_php_rs_zend_register_array_constant,
+    ext_php_rs_zend_string_alloc,
     ext_php_rs_zend_string_init,
+    ext_php_rs_zend_string_realloc,
     ext_php_rs_zend_string_release,
+    module_registry,
     object_properties_init,
     php_info_print_table_end,
     php_info_print_table_header,
     php_info_print_table_row,
     php_info_print_table_start,
+    php_json_serializable_ce,
     std_object_handlers,
     zend_array_destroy,
     zend_array_dup,
     zend_call_known_function,
     zend_ce_argument_count_error,
     zend_ce_arithmetic_error,
+    zend_ce_arrayaccess,
     zend_ce_compile_error,
+    zend_ce_countable,
     zend_ce_division_by_zero_error,
+    zend_ce_error,
     zend_ce_error_exception,
     zend_ce_exception,
+    zend_ce_iterator,
     zend_ce_parse_error,
+    zend_ce_stringable,
     zend_ce_throwable,
     zend_ce_type_error,
     zend_ce_unhandled_match_error,
     zend_ce_value_error,
     zend_class_entry,
+    zend_create_fake_closure,
     zend_declare_class_constant,
     zend_declare_property,
+    zend_declare_typed_class_constant,
+    zend_declare_typed_property,
     zend_do_implement_interface,
+    zend_enum_add_case_cstr,
+    zend_enum_get_case_cstr,
     zend_execute_data,
     zend_function_entry,
     zend_hash_clean,
+    zend_hash_extend,
     zend_hash_index_del,
     zend_hash_index_find,
     zend_hash_index_update,
@@ -63,25 +80,42 @@ bind! {
     zend_hash_str_del,
     zend_hash_str_find,
     zend_hash_str_update,
+    zend_ini_entry_def,
+    zend_ini_long,
+    zend_ini_string_ex,
     zend_internal_arg_info,
+    zend_is_auto_global_str,
     zend_is_callable,
+    zend_list_close,
+    zend_list_delete,
     zend_long,
     zend_lookup_class_ex,
+    zend_module_dep,
     zend_module_entry,
     zend_object,
     zend_object_handlers,
     zend_object_std_init,
     zend_objects_clone_members,
+    zend_read_static_property_ex,
     zend_register_bool_constant,
     zend_register_double_constant,
+    zend_register_functions,
+    zend_register_ini_entries,
     zend_register_internal_class_ex,
+    zend_register_internal_enum,
+    zend_register_list_destructors_ex,
     zend_register_long_constant,
+    zend_register_resource,
     zend_register_string_constant,
     zend_resource,
+    zend_rsrc_list_get_rsrc_type,
     zend_string,
     zend_string_init_interned,
     zend_throw_exception_ex,
     zend_type,
+    zend_unregister_functions,
+    zend_unregister_ini_entries,
+    zend_update_static_property_ex,
     zend_value,
     zend_wrong_parameters_count_error,
     zval,
@@ -89,6 +123,7 @@ bind! {
     CONST_DEPRECATED,
     CONST_NO_FILE_CACHE,
     CONST_PERSISTENT,
+    HASH_FLAG_PACKED,
     HT_MIN_SIZE,
     IS_ARRAY,
     IS_ARRAY_EX,
@@ -100,6 +135,7 @@ bind! {
     IS_INTERNED_STRING_EX,
     IS_LONG,
     IS_MIXED,
+    IS_NEVER,
     IS_NULL,
     IS_OBJECT,
     IS_OBJECT_EX,
@@ -117,6 +153,11 @@ bind! {
     IS_PTR,
     MAY_BE_ANY,
     MAY_BE_BOOL,
+    MODULE_DEP_CONFLICTS,
+    MODULE_DEP_OPTIONAL,
+    MODULE_DEP_REQUIRED,
+    PHP_MAJOR_VERSION,
+    PHP_MINOR_VERSION,
     USING_ZTS,
     ZEND_ACC_ABSTRACT,
     ZEND_ACC_ANON_CLASS,
@@ -164,12 +205,17 @@ bind! {
     ZEND_ACC_VARIADIC,
     ZEND_DEBUG,
     ZEND_HAS_STATIC_IN_METHODS,
+    ZEND_INI_ALL,
+    ZEND_INI_PERDIR,
+    ZEND_INI_SYSTEM,
+    ZEND_INI_USER,
     ZEND_ISEMPTY,
     ZEND_MM_ALIGNMENT,
     ZEND_MM_ALIGNMENT_MASK,
     ZEND_MODULE_API_NO,
     ZEND_PROPERTY_EXISTS,
     ZEND_PROPERTY_ISSET,
+    ZEND_UNCOMPARABLE,
     Z_TYPE_FLAGS_SHIFT,
     _IS_BOOL,
     _ZEND_IS_VARIADIC_BIT,
@@ -185,6 +231,7 @@ bind! {
     zend_std_write_property,
     zend_std_get_properties,
     zend_std_has_property,
+    zend_std_cast_object_tostring,
     zend_objects_new,
     zend_standard_class_def,
     zend_class_serialize_deny,
@@ -192,5 +239,6 @@ bind! {
     zend_objects_store_del,
     gc_possible_root,
     ZEND_ACC_NOT_SERIALIZABLE,
+    ZEND_ACC_READONLY,
     executor_globals
 }
@@ -1,5 +1,6 @@
 use std::{
     env,
+    ffi::OsString,
     path::{Path, PathBuf},
     process::Command,
     str,
@@ -15,6 +16,7 @@ fn main() {
     println!("cargo:rerun-if-changed=src/wrapper.h");
     println!("cargo:rerun-if-changed=src/wrapper.c");
     println!("cargo:rerun-if-changed=allowed_bindings.rs");
+    println!("cargo:rerun-if-env-changed=PHP_CONFIG");
 
     let out_dir = env::var_os("OUT_DIR").expect("Failed to get OUT_DIR");
     let out_path = PathBuf::from(out_dir).join("bindings.rs");
@@ -30,7 +32,7 @@ fn main() {
     }
 
     // use php-config to fetch includes
-    let includes_cmd = Command::new("php-config")
+    let includes_cmd = Command::new(php_config())
         .arg("--includes")
         .output()
         .expect("Unable to run `php-config`. Please ensure it is visible in your PATH.");
@@ -79,10 +81,23 @@ fn main() {
     // should get both the `php81` and `php82` flags.
     const PHP_81_API_VER: u32 = 20210902;
 
+    const PHP_83_API_VER: u32 = 20230831;
+
     if api_ver >= PHP_81_API_VER {
         println!("cargo:rustc-cfg=php81");
     }
 
+    if api_ver >= PHP_83_API_VER {
+        println!("cargo:rustc-cfg=php83");
+    }
+
+    if env::var_os("CARGO_FEATURE_ENUM").is_some() && api_ver < PHP_81_API_VER {
+        panic!(
+            "The `enum` feature requires PHP 8.1 or later. Current PHP API version: {}",
+            api_ver
+        );
+    }
+
     let includes =
         String::from_utf8(includes_cmd.stdout).expect("unable to parse `php-config` stdout");
 
@@ -130,11 +145,18 @@ fn main() {
     }
 }
 
+/// Returns the `php-config` executable to use, read from the `PHP_CONFIG`
+/// variable if it is set, so the extension can be built against a PHP
+/// installation which is not in the `PATH`.
+fn php_config() -> OsString {
+    env::var_os("PHP_CONFIG").unwrap_or_else(|| OsString::from("php-config"))
+}
+
 struct Configure(String);
 
 impl Configure {
     pub fn get() -> Self {
-        let cmd = Command::new("php-config")
+        let cmd = Command::new(php_config())
         .arg("--configure-options")
         .output()
         .expect("Unable to run `php-config --configure-options`. Please ensure it is visible in your PATH.");
@@ -151,8 +173,14 @@ impl Configure {
         Self(stdout)
     }
 
+    /// Checks whether PHP was configured with `--enable-zts`
+    /// (`--enable-maintainer-zts` before PHP 8). Matches `is_zts` in
+    /// `cargo-php`, so both agree on the thread safety of PHP.
     pub fn has_zts(&self) -> bool {
-        self.0.contains("--enable-zts")
+        self.0
+            .split_whitespace()
+            .map(|option| option.trim_matches('\''))
+            .any(|option| option == "--enable-zts" || option == "--enable-maintainer-zts")
     }
 
     pub fn debug(&self) -> bool {
@@ -1,9 +1,17 @@
+//! Loading of extensions built with `ext-php-rs`, to retrieve their
+//! description.
+
 use std::path::PathBuf;
 
-use anyhow::{Context, Result};
-use ext_php_rs::describe::Description;
+use anyhow::{bail, Context, Result};
+use ext_php_rs::describe::{Description, ABI_VERSION};
 use libloading::os::unix::{Library, Symbol};
 
+/// An extension library loaded into the current process.
+///
+/// The library is kept loaded for the lifetime of the value, as the
+/// description returned by [`Ext::describe`] refers to memory inside the
+/// library.
 pub struct Ext {
     // These need to be here to keep the libraries alive. The extension library needs to be alive
     // to access the describe function. Missing here is the lifetime on `Symbol<'a, fn() ->
@@ -11,10 +19,17 @@ pub struct Ext {
     #[allow(dead_code)]
     ext_lib: Library,
     describe_fn: Symbol<extern "C" fn() -> Description>,
+    // Missing from extensions built with a version of `ext-php-rs` from before
+    // the layout of the description was versioned.
+    abi_version_fn: Option<Symbol<extern "C" fn() -> u32>>,
 }
 
 impl Ext {
     /// Loads an extension.
+    ///
+    /// # Parameters
+    ///
+    /// * `ext_path` - Path to the extension dynamic library.
     pub fn load(ext_path: PathBuf) -> Result<Self> {
         let ext_lib = unsafe { Library::new(ext_path) }
             .with_context(|| "Failed to load extension library")?;
@@ -25,14 +40,27 @@ impl Ext {
                 .with_context(|| "Failed to load describe function symbol from extension library")?
         };
 
+        let abi_version_fn = unsafe { ext_lib.get(b"ext_php_rs_describe_abi_version") }.ok();
+
         Ok(Self {
             ext_lib,
             describe_fn,
+            abi_version_fn,
         })
     }
 
-    /// Describes the extension.
-    pub fn describe(&self) -> Description {
-        (self.describe_fn)()
+    /// Describes the extension, returning its functions, classes and constants
+    /// along with their types. The description must not outlive the
+    /// extension.
+    ///
+    /// Returns an error if the description is laid out differently than the
+    /// CLI expects, in which case the describe function is not called.
+    pub fn describe(&self) -> Result<Description> {
+        let abi_version = self.abi_version_fn.as_ref().map(|f| f());
+        if abi_version != Some(ABI_VERSION) {
+            bail!("Extension was compiled with an incompatible version of `ext-php-rs`, so it cannot be described. Rebuild the extension with the same version of `ext-php-rs` as `cargo php`.");
+        }
+
+        Ok((self.describe_fn)())
     }
 }
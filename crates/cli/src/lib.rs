@@ -1,24 +1,32 @@
 #![doc = include_str!("../README.md")]
 
-mod ext;
+pub mod ext;
+mod test_runner;
 
 use anyhow::{bail, Context, Result as AResult};
-use cargo_metadata::{camino::Utf8PathBuf, Target};
-use clap::Parser;
+use cargo_metadata::{
+    camino::{Utf8Path, Utf8PathBuf},
+    Target,
+};
+use clap::{IntoApp, Parser};
 use dialoguer::{Confirm, Select};
+use env_logger::WriteStyle;
+use log::{debug, info, trace, warn, LevelFilter};
 
 use std::{
     borrow::Cow,
     ffi::OsString,
-    fs::{File, OpenOptions},
-    io::{BufRead, BufReader, Write},
-    path::PathBuf,
+    fs::OpenOptions,
+    io::{BufReader, Write},
+    path::{Path, PathBuf},
     process::{Command, Stdio},
     str::FromStr,
+    time::Duration,
 };
 
 use self::ext::Ext;
-use ext_php_rs::describe::ToStub;
+use self::test_runner::{find_tests, run_test};
+use ext_php_rs::describe::{php_string, Description, PhpVersion, StubOptions, ToStub};
 
 /// Generates mock symbols required to generate stub files from a downstream
 /// crates CLI application.
@@ -50,7 +58,37 @@ pub fn run() -> Result {
         args.remove(1);
     }
 
-    Args::parse_from(args).handle()
+    let args = Args::parse_from(args);
+    init_logger(args.log_level().unwrap_or_else(|e| e.exit()));
+    args.command.handle()
+}
+
+/// Configures the logger used to print messages to stderr, printing messages
+/// up to the given level.
+///
+/// Messages are colored when stderr is a terminal, unless the `NO_COLOR`
+/// variable is set to a non-empty value.
+fn init_logger(level: LevelFilter) {
+    let style = match std::env::var_os("NO_COLOR") {
+        Some(no_color) if !no_color.is_empty() => WriteStyle::Never,
+        _ => WriteStyle::Auto,
+    };
+
+    env_logger::Builder::new()
+        .filter_level(level)
+        .write_style(style)
+        .format(|buf, record| {
+            let label = match record.level() {
+                log::Level::Error => "error",
+                log::Level::Warn => "warning",
+                log::Level::Info => "info",
+                log::Level::Debug => "debug",
+                log::Level::Trace => "trace",
+            };
+            let style = buf.default_level_style(record.level());
+            writeln!(buf, "{}: {}", style.value(label), record.args())
+        })
+        .init();
 }
 
 #[derive(Parser)]
@@ -59,7 +97,29 @@ pub fn run() -> Result {
     author = "David Cole <david.cole1340@gmail.com>",
     version = env!("CARGO_PKG_VERSION")
 )]
-enum Args {
+struct Args {
+    /// Prints debug messages, such as the commands run and the files written.
+    /// Given twice, also prints trace messages.
+    #[clap(short, long, global = true, parse(from_occurrences))]
+    verbose: u64,
+    /// Only prints errors. The output of the command, such as stubs printed
+    /// with `--stdout`, is still printed.
+    #[clap(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+#[derive(Parser)]
+enum Commands {
+    /// Sets up the current crate to be built as a PHP extension.
+    ///
+    /// This sets the crate type of the library to `cdylib`, adds `ext-php-rs`
+    /// as a dependency, writes a hello world extension to the library of the
+    /// crate, `src/lib.rs` unless another `path` is set in `[lib]`, and writes
+    /// the linker configuration required on macOS to `.cargo/config.toml`.
+    /// Prompts before overwriting an existing library.
+    Init(Init),
     /// Installs the extension in the current PHP installation.
     ///
     /// This copies the extension to the PHP installation and adds the
@@ -71,6 +131,10 @@ enum Args {
     /// variable (if it is set), and will use this as the path to the executable
     /// instead.
     Install(Install),
+    /// Copies a built extension and updates `php.ini`, run under `sudo` by
+    /// `install --sudo`.
+    #[clap(setting = clap::AppSettings::Hidden)]
+    InstallFiles(InstallFiles),
     /// Removes the extension in the current PHP installation.
     ///
     /// This deletes the extension from the PHP installation and also removes it
@@ -82,11 +146,75 @@ enum Args {
     /// variable (if it is set), and will use this as the path to the executable
     /// instead.
     Remove(Remove),
+    /// Lists the ext-php-rs extensions loaded by the current PHP installation.
+    ///
+    /// This reads the `extension=` and `zend_extension=` directives of the
+    /// main PHP configuration file and the files in its scan directory, and
+    /// loads each extension which exists to check whether it was built with
+    /// `ext-php-rs`. The name of each extension, the version of `ext-php-rs` it
+    /// was built with and its path are printed. Other extensions are skipped.
+    ///
+    /// Note that this uses the `php-config` executable installed alongside PHP
+    /// to locate your `php.ini` file and extension directory. If you want to
+    /// use a different `php-config`, the application will read the `PHP_CONFIG`
+    /// variable (if it is set), and will use this as the path to the executable
+    /// instead.
+    List(List),
     /// Generates stub PHP files for the extension.
     ///
     /// These stub files can be used in IDEs to provide typehinting for
     /// extension classes, functions and constants.
     Stubs(Stubs),
+    /// Describes the functions, classes and constants of the extension.
+    ///
+    /// The description contains the same information used to generate stub
+    /// files. With `--json`, the description is printed as JSON so it can be
+    /// consumed by other tools.
+    Describe(Describe),
+    /// Prints the path of the extension dynamic library.
+    ///
+    /// The path is where `cargo build` places the extension for the selected
+    /// profile, printed as a single absolute path so scripts can load the
+    /// extension with `-d extension=...` without searching the target
+    /// directory themselves. The extension is not built unless `--build` is
+    /// passed and the library does not exist.
+    Which(Which),
+    /// Runs PHP test scripts against the extension.
+    ///
+    /// The extension is built and loaded into PHP without the `php.ini` file of
+    /// the current PHP installation. Each `.php` file in the test directory
+    /// passes if it exits successfully. Each `.phpt` file passes if the output
+    /// of its `--FILE--` section matches its `--EXPECT--` section.
+    ///
+    /// Note that this uses the `php-config` executable installed alongside PHP
+    /// to locate the PHP binary. If you want to use a
+    /// different `php-config`, the application will read the `PHP_CONFIG`
+    /// variable (if it is set), and will use this as the path to the executable
+    /// instead.
+    Test(Test),
+    /// Benchmarks the extension by running a PHP script against it.
+    ///
+    /// The extension is built and loaded into PHP in the same way as the
+    /// `test` subcommand. The script is run the given number of times, each
+    /// time in a new PHP process, and the time taken to run the script is
+    /// measured inside PHP, excluding the startup of PHP. With `--baseline`,
+    /// the runs alternate between the extension and a previously built
+    /// extension, and the difference between the two is printed.
+    ///
+    /// Note that this uses the `php-config` executable installed alongside PHP
+    /// to locate the PHP binary. If you want to use a
+    /// different `php-config`, the application will read the `PHP_CONFIG`
+    /// variable (if it is set), and will use this as the path to the executable
+    /// instead.
+    Bench(Bench),
+}
+
+#[derive(Parser)]
+struct Init {
+    /// Path to the Cargo manifest of the crate. Defaults to the nearest
+    /// manifest in the directory the command is called or its parents.
+    #[clap(long)]
+    manifest: Option<PathBuf>,
 }
 
 #[derive(Parser)]
@@ -102,13 +230,69 @@ struct Install {
     /// file.
     #[clap(long)]
     disable: bool,
+    /// Adds the extension to the block of the `php.ini` file between the
+    /// `; BEGIN ext-php-rs` and `; END ext-php-rs` comments, creating the block
+    /// at the end of the file if it does not exist, instead of appending it to
+    /// the end of the file.
+    #[clap(long)]
+    block: bool,
     /// Whether to install the release version of the extension.
     #[clap(long)]
     release: bool,
-    /// Path to the Cargo manifest of the extension. Defaults to the manifest in
-    /// the directory the command is called.
+    /// Installs the extension even if it was built for a thread-safe (ZTS)
+    /// build of PHP and the PHP installation is not thread-safe, or vice
+    /// versa.
+    #[clap(long)]
+    force: bool,
+    /// Installs a symbolic link to the extension library in the target
+    /// directory instead of copying it, so rebuilding the extension updates the
+    /// installed extension. Falls back to copying the extension if the link
+    /// cannot be created.
+    #[clap(long)]
+    symlink: bool,
+    /// Builds the extension as the current user, and then copies the
+    /// extension and updates `php.ini` with `sudo` if the current user cannot
+    /// write to them.
+    #[clap(long)]
+    sudo: bool,
+    /// Path to the Cargo manifest of the extension. Defaults to the nearest
+    /// manifest in the directory the command is called or its parents.
     #[clap(long)]
     manifest: Option<PathBuf>,
+    /// Name of the workspace member containing the extension. Defaults to the
+    /// package of the manifest if it has a `cdylib` or `dylib` target,
+    /// otherwise prompts for one of the workspace members with such a target.
+    #[clap(short, long)]
+    package: Option<String>,
+}
+
+/// Copies a built extension into the extension directory and adds it to
+/// `php.ini`. Run under `sudo` by `install --sudo`.
+#[derive(Parser, Debug, PartialEq)]
+struct InstallFiles {
+    /// Path to the built extension.
+    #[clap(long)]
+    ext: PathBuf,
+    /// Path the extension is installed to.
+    #[clap(long)]
+    dest: PathBuf,
+    /// Path to the `php.ini` file to update with the new extension.
+    #[clap(long)]
+    ini_path: Option<PathBuf>,
+    /// Adds the extension to the `php.ini` file commented out.
+    #[clap(long)]
+    disable: bool,
+    /// Adds the extension to the block of ext-php-rs directives in the
+    /// `php.ini` file.
+    #[clap(long)]
+    block: bool,
+    /// Links the extension instead of copying it.
+    #[clap(long)]
+    symlink: bool,
+    /// Records the file name of the installed extension under the given name,
+    /// so it can be found by `remove`.
+    #[clap(long)]
+    record: Option<String>,
 }
 
 #[derive(Parser)]
@@ -121,10 +305,26 @@ struct Remove {
     /// Path to the `php.ini` file to remove the extension from.
     #[clap(long)]
     ini_path: Option<PathBuf>,
-    /// Path to the Cargo manifest of the extension. Defaults to the manifest in
-    /// the directory the command is called.
+    /// Path to the Cargo manifest of the extension. Defaults to the nearest
+    /// manifest in the directory the command is called or its parents.
     #[clap(long)]
     manifest: Option<PathBuf>,
+    /// Name of the workspace member containing the extension. Defaults to the
+    /// package of the manifest if it has a `cdylib` or `dylib` target,
+    /// otherwise prompts for one of the workspace members with such a target.
+    #[clap(short, long)]
+    package: Option<String>,
+}
+
+#[derive(Parser)]
+struct List {
+    /// Changes the directory that extensions given by name are loaded from.
+    #[clap(long)]
+    install_dir: Option<PathBuf>,
+    /// Path to the `php.ini` file to read the extensions from. The files in
+    /// the scan directory of the PHP installation are not read when given.
+    #[clap(long)]
+    ini_path: Option<PathBuf>,
 }
 
 #[derive(Parser)]
@@ -140,102 +340,461 @@ struct Stubs {
     /// `out`.
     #[clap(long, conflicts_with = "out")]
     stdout: bool,
-    /// Path to the Cargo manifest of the extension. Defaults to the manifest in
-    /// the directory the command is called.
+    /// Path to the Cargo manifest of the extension. Defaults to the nearest
+    /// manifest in the directory the command is called or its parents.
+    ///
+    /// This cannot be provided alongside the `ext` option, as that option
+    /// provides a direct path to the extension shared library.
+    #[clap(long, conflicts_with = "ext")]
+    manifest: Option<PathBuf>,
+    /// Name of the workspace member containing the extension. Defaults to the
+    /// package of the manifest if it has a `cdylib` or `dylib` target,
+    /// otherwise prompts for one of the workspace members with such a target.
+    ///
+    /// This cannot be provided alongside the `ext` option.
+    #[clap(short, long, conflicts_with = "ext")]
+    package: Option<String>,
+    /// Version of PHP to generate stubs for, e.g. `8.1`. Types and syntax not
+    /// available in the given version are documented with PHPDoc annotations
+    /// instead. Defaults to the version of PHP the extension was built against.
+    #[clap(long, parse(try_from_str = parse_php_version))]
+    php_version: Option<PhpVersion>,
+    /// Add a preamble asserting that the extension is loaded, so static
+    /// analysis tools treat `extension_loaded()` checks for the extension as
+    /// always true.
+    #[clap(long)]
+    declare_loaded: bool,
+    /// Omit the functions and classes which are only registered when a
+    /// condition holds when the extension starts up. By default, they are
+    /// stubbed with a note describing the condition.
+    #[clap(long)]
+    skip_conditional: bool,
+    /// Write class names fully-qualified, e.g. `\MyExt\Foo`, in parameter,
+    /// property and return types and in `extends`, `implements` and `use`
+    /// clauses. By default, class names are written as they were registered,
+    /// which PHP resolves relative to the namespace they are declared in.
+    #[clap(long)]
+    fqn: bool,
+    /// Also writes a `.phpstorm.meta.php` file alongside the stub file,
+    /// containing the return type overrides of the functions and methods of
+    /// the extension. Cannot be used with `stdout`.
+    #[clap(long, conflicts_with = "stdout")]
+    meta: bool,
+}
+
+#[derive(Parser)]
+struct Describe {
+    /// Path to extension to describe. Defaults for searching the directory the
+    /// executable is located in.
+    ext: Option<PathBuf>,
+    /// Print the description as JSON.
+    #[clap(long)]
+    json: bool,
+    /// Path to the Cargo manifest of the extension. Defaults to the nearest
+    /// manifest in the directory the command is called or its parents.
     ///
     /// This cannot be provided alongside the `ext` option, as that option
     /// provides a direct path to the extension shared library.
     #[clap(long, conflicts_with = "ext")]
     manifest: Option<PathBuf>,
+    /// Name of the workspace member containing the extension. Defaults to the
+    /// package of the manifest if it has a `cdylib` or `dylib` target,
+    /// otherwise prompts for one of the workspace members with such a target.
+    ///
+    /// This cannot be provided alongside the `ext` option.
+    #[clap(short, long, conflicts_with = "ext")]
+    package: Option<String>,
+}
+
+#[derive(Parser)]
+struct Which {
+    /// Whether to print the path of the release version of the extension.
+    #[clap(long)]
+    release: bool,
+    /// Builds the extension if the library does not exist yet.
+    #[clap(long)]
+    build: bool,
+    /// Target triple the extension is built for, as passed to `cargo build
+    /// --target`. Defaults to the `CARGO_BUILD_TARGET` variable if it is set,
+    /// otherwise the host.
+    #[clap(long, value_name = "TRIPLE")]
+    target: Option<String>,
+    /// Path to the Cargo manifest of the extension. Defaults to the nearest
+    /// manifest in the directory the command is called or its parents.
+    #[clap(long)]
+    manifest: Option<PathBuf>,
+    /// Name of the workspace member containing the extension. Defaults to the
+    /// package of the manifest if it has a `cdylib` or `dylib` target,
+    /// otherwise prompts for one of the workspace members with such a target.
+    #[clap(short, long)]
+    package: Option<String>,
+}
+
+#[derive(Parser)]
+struct Test {
+    /// Directory containing the `.php` and `.phpt` test files, searched
+    /// recursively.
+    #[clap(default_value = "tests")]
+    dir: PathBuf,
+    /// Only runs the tests with a path containing the given string.
+    #[clap(long)]
+    filter: Option<String>,
+    /// Sets a `php.ini` option while running the tests, given as
+    /// `<name>=<value>`. Can be given multiple times. The `php.ini` file of the
+    /// PHP installation is not loaded, so other extensions the tests need must
+    /// be loaded with `-d extension=<name>`.
+    #[clap(
+        short = 'd',
+        long = "define",
+        multiple_occurrences = true,
+        number_of_values = 1
+    )]
+    defines: Vec<String>,
+    /// Whether to test the release version of the extension.
+    #[clap(long)]
+    release: bool,
+    /// Path to the Cargo manifest of the extension. Defaults to the nearest
+    /// manifest in the directory the command is called or its parents.
+    #[clap(long)]
+    manifest: Option<PathBuf>,
+    /// Name of the workspace member containing the extension. Defaults to the
+    /// package of the manifest if it has a `cdylib` or `dylib` target,
+    /// otherwise prompts for one of the workspace members with such a target.
+    #[clap(short, long)]
+    package: Option<String>,
+}
+
+#[derive(Parser)]
+struct Bench {
+    /// Path to the benchmark `.php` script.
+    script: PathBuf,
+    /// Number of times to run the script.
+    #[clap(short = 'n', long, default_value = "10")]
+    iterations: u32,
+    /// Also reports the peak memory usage of the script, as returned by
+    /// `memory_get_peak_usage()`.
+    #[clap(long)]
+    memory: bool,
+    /// Path to a previously built extension library to compare the extension
+    /// against, e.g. a copy of the library built before an optimization.
+    #[clap(long)]
+    baseline: Option<PathBuf>,
+    /// Sets a `php.ini` option while running the script, given as
+    /// `<name>=<value>`. Can be given multiple times. The `php.ini` file of the
+    /// PHP installation is not loaded, so other extensions the script needs
+    /// must be loaded with `-d extension=<name>`.
+    #[clap(
+        short = 'd',
+        long = "define",
+        multiple_occurrences = true,
+        number_of_values = 1
+    )]
+    defines: Vec<String>,
+    /// Whether to benchmark the release version of the extension. Benchmarks of
+    /// the debug version are rarely meaningful.
+    #[clap(long)]
+    release: bool,
+    /// Path to the Cargo manifest of the extension. Defaults to the nearest
+    /// manifest in the directory the command is called or its parents.
+    #[clap(long)]
+    manifest: Option<PathBuf>,
+    /// Name of the workspace member containing the extension. Defaults to the
+    /// package of the manifest if it has a `cdylib` or `dylib` target,
+    /// otherwise prompts for one of the workspace members with such a target.
+    #[clap(short, long)]
+    package: Option<String>,
 }
 
 impl Args {
+    /// Returns the level of the messages printed. Warnings and informational
+    /// messages are printed by default, debug messages are printed with `-v`
+    /// and trace messages with `-vv`. Only errors are printed with `--quiet`.
+    ///
+    /// Global flags given on either side of the subcommand are merged by
+    /// `clap` without checking for conflicts, so `--quiet` and `--verbose` are
+    /// checked here.
+    fn log_level(&self) -> clap::Result<LevelFilter> {
+        Ok(match (self.quiet, self.verbose) {
+            (true, 0) => LevelFilter::Error,
+            (true, _) => {
+                return Err(Args::into_app().error(
+                    clap::ErrorKind::ArgumentConflict,
+                    "`--quiet` cannot be used with `--verbose`",
+                ))
+            }
+            (false, 0) => LevelFilter::Info,
+            (false, 1) => LevelFilter::Debug,
+            (false, _) => LevelFilter::Trace,
+        })
+    }
+}
+
+impl Commands {
     pub fn handle(self) -> Result {
         match self {
-            Args::Install(install) => install.handle(),
-            Args::Remove(remove) => remove.handle(),
-            Args::Stubs(stubs) => stubs.handle(),
+            Commands::Init(init) => init.handle(),
+            Commands::Install(install) => install.handle(),
+            Commands::InstallFiles(files) => files.handle(),
+            Commands::Remove(remove) => remove.handle(),
+            Commands::List(list) => list.handle(),
+            Commands::Stubs(stubs) => stubs.handle(),
+            Commands::Describe(describe) => describe.handle(),
+            Commands::Which(which) => which.handle(),
+            Commands::Test(test) => test.handle(),
+            Commands::Bench(bench) => bench.handle(),
+        }
+    }
+}
+
+impl Init {
+    pub fn handle(self) -> Result {
+        let manifest = match self.manifest {
+            Some(manifest) => manifest,
+            None => find_manifest(&std::env::current_dir()?)?,
+        };
+        let dir = match manifest.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        let contents = std::fs::read_to_string(&manifest)
+            .with_context(|| format!("Failed to read `{}`", manifest.display()))?;
+        let updated = init_manifest(&contents)?;
+        if updated == contents {
+            info!("`{}` is already set up.", manifest.display());
+        } else {
+            debug!("Updating `{}`", manifest.display());
+            std::fs::write(&manifest, &updated)
+                .with_context(|| format!("Failed to update `{}`", manifest.display()))?;
+            info!("Updated `{}`.", manifest.display());
+        }
+
+        let lib = dir.join(lib_path(&updated).unwrap_or_else(|| "src/lib.rs".into()));
+        if !lib.exists()
+            || Confirm::new()
+                .with_prompt(format!(
+                    "`{}` already exists. Do you want to overwrite it with a hello world extension?",
+                    lib.display()
+                ))
+                .default(false)
+                .interact()?
+        {
+            debug!("Writing `{}`", lib.display());
+            if let Some(lib_dir) = lib.parent() {
+                std::fs::create_dir_all(lib_dir).with_context(|| {
+                    format!("Failed to create `{}` directory", lib_dir.display())
+                })?;
+            }
+            std::fs::write(&lib, LIB_TEMPLATE)
+                .with_context(|| format!("Failed to write `{}`", lib.display()))?;
+            info!("Wrote `{}`.", lib.display());
+        }
+
+        // Cargo also reads the configuration from `.cargo/config`, which is
+        // left as is.
+        let config_dir = dir.join(".cargo");
+        if !config_dir.join("config.toml").exists() && !config_dir.join("config").exists() {
+            let config = config_dir.join("config.toml");
+            debug!("Writing `{}`", config.display());
+            std::fs::create_dir_all(&config_dir)
+                .with_context(|| "Failed to create `.cargo` directory")?;
+            std::fs::write(&config, CONFIG_TEMPLATE)
+                .with_context(|| format!("Failed to write `{}`", config.display()))?;
+            info!("Wrote `{}`.", config.display());
         }
+
+        info!("Run `cargo php install` to build and install the extension.");
+        Ok(())
     }
 }
 
 impl Install {
     pub fn handle(self) -> Result {
-        let artifact = find_ext(&self.manifest)?;
-        let ext_path = build_ext(&artifact, self.release)?;
+        if std::env::var_os("SUDO_USER").is_some() {
+            warn!("Running under `sudo`, so the extension is built as root. Pass `--sudo` instead to build the extension as the current user and only install it as root.");
+        }
 
-        let (mut ext_dir, mut php_ini) = if let Some(install_dir) = self.install_dir {
-            (install_dir, None)
+        let artifact = find_ext(&self.manifest, &self.package)?;
+
+        let php_config = PhpConfig::new();
+        let (mut ext_dir, mut php_ini) = if let Some(install_dir) = &self.install_dir {
+            (install_dir.clone(), None)
         } else {
-            let php_config = PhpConfig::new();
             (php_config.get_ext_dir()?, Some(php_config.get_php_ini()?))
         };
 
-        if let Some(ini_path) = self.ini_path {
-            php_ini = Some(ini_path);
+        if let Some(ini_path) = &self.ini_path {
+            php_ini = Some(ini_path.clone());
+        }
+
+        // Checked before building the extension, which can take a while, so
+        // the installation fails early if it cannot be completed.
+        if ext_dir.is_dir() {
+            ext_dir.push(ext_file_name(&artifact.target));
+        }
+        let writable = check_writable(&ext_dir).and_then(|_| match &php_ini {
+            Some(php_ini) => check_writable(php_ini),
+            None => Ok(()),
+        });
+        if let Err(e) = &writable {
+            if !self.sudo {
+                let command = command_line();
+                bail!(
+                    "{:#}\n\nPass `--sudo` to build the extension as the current user and install it with `sudo`:\n\n    {} --sudo\n\nor run the whole command with `sudo`, which also builds the extension as root:\n\n    sudo {}",
+                    e,
+                    command,
+                    command
+                );
+            }
+            debug!("{:#}, installing with `sudo`", e);
+        }
+
+        let build = build_ext(&artifact, self.release, None)?;
+        let ext_path = build.path;
+
+        if self.install_dir.is_none() {
+            if !self.force {
+                check_php_zts(&php_config, build.php_zts)?;
+            }
+            check_php_debug(&php_config, build.php_debug);
         }
 
         if !Confirm::new()
             .with_prompt(format!(
                 "Are you sure you want to install the extension `{}`?",
-                artifact.name
+                artifact.target.name
             ))
             .interact()?
         {
             bail!("Installation cancelled.");
         }
 
-        debug_assert!(ext_path.is_file());
-        let ext_name = ext_path.file_name().expect("ext path wasn't a filepath");
-
-        if ext_dir.is_dir() {
-            ext_dir.push(ext_name);
+        let files = InstallFiles {
+            ext: ext_path.into(),
+            dest: ext_dir,
+            ini_path: php_ini,
+            disable: self.disable,
+            block: self.block,
+            symlink: self.symlink,
+            record: Some(record_key(&artifact)),
+        };
+        if writable.is_err() {
+            files.handle_with_sudo()
+        } else {
+            files.handle()
         }
+    }
+}
 
-        std::fs::copy(&ext_path, &ext_dir).with_context(|| {
-            "Failed to copy extension from target directory to extension directory"
-        })?;
+impl InstallFiles {
+    pub fn handle(self) -> Result {
+        debug_assert!(self.ext.is_file());
+        // The name of the installed file can differ from the name of the built
+        // library when the extension is installed to a file path.
+        let ext_name = self
+            .dest
+            .file_name()
+            .expect("ext path wasn't a filepath")
+            .to_string_lossy();
+
+        // A previously installed link is removed, otherwise copying the
+        // extension would write through the link into the target directory.
+        if is_symlink(&self.dest) {
+            std::fs::remove_file(&self.dest)
+                .with_context(|| "Failed to remove previously installed extension")?;
+        }
 
-        if let Some(php_ini) = php_ini {
-            let mut file = OpenOptions::new()
-                .read(true)
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(php_ini)
-                .with_context(|| "Failed to open `php.ini`")?;
+        debug!("Installing extension to `{}`", self.dest.display());
+        let linked = self.symlink
+            && match link_ext(&self.ext, &self.dest) {
+                Ok(_) => true,
+                Err(e) => {
+                    warn!("Failed to link extension, copying it instead: {}", e);
+                    false
+                }
+            };
+        if !linked {
+            std::fs::copy(&self.ext, &self.dest).with_context(|| {
+                "Failed to copy extension from target directory to extension directory"
+            })?;
+        }
 
-            let mut ext_line = format!("extension={}", ext_name);
+        if let Some(php_ini) = self.ini_path {
+            debug!("Adding extension to `{}`", php_ini.display());
+            let contents = read_ini(&php_ini)?;
+            let contents = add_ini_extension(&contents, &ext_name, self.disable, self.block)?;
+            std::fs::write(&php_ini, contents).with_context(|| "Failed to update `php.ini`")?;
+        }
 
-            let mut new_lines = vec![];
-            for line in BufReader::new(&file).lines() {
-                let line = line.with_context(|| "Failed to read line from `php.ini`")?;
-                if !line.contains(&ext_line) {
-                    new_lines.push(line);
-                }
-            }
+        if let Some(key) = &self.record {
+            let record = record_path(&self.dest);
+            debug!("Recording installed extension in `{}`", record.display());
+            let contents = read_record(&record)?;
+            let contents = add_record(&contents, key, &ext_name);
+            std::fs::write(&record, contents)
+                .with_context(|| "Failed to record installed extension")?;
+        }
 
-            // Comment out extension if user specifies disable flag
-            if self.disable {
-                ext_line.insert(0, ';');
-            }
+        Ok(())
+    }
 
-            new_lines.push(ext_line);
-            file.write(new_lines.join("\n").as_bytes())
-                .with_context(|| "Failed to update `php.ini`")?;
+    /// Runs the `install-files` subcommand of this executable under `sudo`,
+    /// so only copying the extension and updating `php.ini` is done as root.
+    fn handle_with_sudo(self) -> Result {
+        let exe = std::env::current_exe()
+            .with_context(|| "Failed to locate the `cargo-php` executable")?;
+        let mut cmd = self.sudo_command(&exe, log::max_level());
+
+        debug!("Running {:?}", cmd);
+        let status = cmd.status().with_context(|| "Failed to run `sudo`")?;
+        if !status.success() {
+            bail!("Failed to install the extension with `sudo`.");
         }
 
         Ok(())
     }
+
+    /// Returns the command running the `install-files` subcommand of the given
+    /// executable under `sudo` with the same options, printing messages up to
+    /// the given level.
+    fn sudo_command(&self, exe: &Path, level: LevelFilter) -> Command {
+        let mut cmd = Command::new("sudo");
+        cmd.arg(exe)
+            .arg("install-files")
+            .arg("--ext")
+            .arg(&self.ext)
+            .arg("--dest")
+            .arg(&self.dest);
+        if let Some(php_ini) = &self.ini_path {
+            cmd.arg("--ini-path").arg(php_ini);
+        }
+        if self.disable {
+            cmd.arg("--disable");
+        }
+        if self.block {
+            cmd.arg("--block");
+        }
+        if self.symlink {
+            cmd.arg("--symlink");
+        }
+        if let Some(key) = &self.record {
+            cmd.arg("--record").arg(key);
+        }
+        match level {
+            LevelFilter::Off | LevelFilter::Error => cmd.arg("--quiet"),
+            LevelFilter::Debug => cmd.arg("-v"),
+            LevelFilter::Trace => cmd.arg("-vv"),
+            _ => &mut cmd,
+        };
+        cmd
+    }
 }
 
 impl Remove {
     pub fn handle(self) -> Result {
-        use std::env::consts;
+        let artifact = find_ext(&self.manifest, &self.package)?;
 
-        let artifact = find_ext(&self.manifest)?;
-
-        let (mut ext_path, mut php_ini) = if let Some(install_dir) = self.install_dir {
+        let (ext_dir, mut php_ini) = if let Some(install_dir) = self.install_dir {
             (install_dir, None)
         } else {
             let php_config = PhpConfig::new();
@@ -246,49 +805,106 @@ impl Remove {
             php_ini = Some(ini_path);
         }
 
-        let ext_file = format!(
-            "{}{}{}",
-            consts::DLL_PREFIX,
-            artifact.name.replace("-", "_"),
-            consts::DLL_SUFFIX
-        );
-        ext_path.push(&ext_file);
+        // The file name is guessed from the library target for extensions
+        // installed before installations were recorded.
+        let key = record_key(&artifact);
+        let ext_file = match installed_file(&ext_dir, &key)? {
+            Some(ext_file) => ext_file,
+            None => ext_file_name(&artifact.target),
+        };
+        let ext_path = ext_dir.join(&ext_file);
 
-        if !ext_path.is_file() {
+        // An extension installed with `--symlink` is removed even if the
+        // library it links to no longer exists.
+        if !ext_path.is_file() && !is_symlink(&ext_path) {
             bail!("Unable to find extension installed.");
         }
 
         if !Confirm::new()
             .with_prompt(format!(
                 "Are you sure you want to remove the extension `{}`?",
-                artifact.name
+                artifact.target.name
             ))
             .interact()?
         {
             bail!("Installation cancelled.");
         }
 
-        std::fs::remove_file(ext_path).with_context(|| "Failed to remove extension")?;
+        remove_installed(&ext_path, php_ini.as_deref(), &key)
+    }
+}
 
-        if let Some(php_ini) = php_ini.filter(|path| path.is_file()) {
-            let mut file = OpenOptions::new()
-                .read(true)
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(php_ini)
-                .with_context(|| "Failed to open `php.ini`")?;
+impl List {
+    pub fn handle(self) -> Result {
+        let php_config = PhpConfig::new();
+        let ext_dir = match self.install_dir {
+            Some(install_dir) => install_dir,
+            None => php_config.get_ext_dir()?,
+        };
+        let ini_files = match self.ini_path {
+            Some(ini_path) => vec![ini_path],
+            None => {
+                let mut ini_files = vec![php_config.get_php_ini()?];
+                for dir in php_config.get_ini_scan_dirs()? {
+                    ini_files.extend(find_ini_files(&dir)?);
+                }
+                ini_files
+            }
+        };
 
-            let mut new_lines = vec![];
-            for line in BufReader::new(&file).lines() {
-                let line = line.with_context(|| "Failed to read line from `php.ini`")?;
-                if !line.contains(&ext_file) {
-                    new_lines.push(line);
+        let mut paths: Vec<PathBuf> = vec![];
+        for ini in ini_files {
+            debug!("Reading extensions from `{}`", ini.display());
+            let contents = read_ini(&ini)?;
+            for ext in ini_extensions(&contents) {
+                match resolve_ext(&ext_dir, ext) {
+                    Some(path) if !paths.contains(&path) => paths.push(path),
+                    Some(_) => continue,
+                    None => debug!("Skipping `{}`, which does not exist", ext),
                 }
             }
+        }
+
+        let mut rows = vec![];
+        for path in paths {
+            let ext = match Ext::load(path.clone()) {
+                Ok(ext) => ext,
+                Err(e) => {
+                    debug!("Skipping `{}`: {:#}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let (name, version) = match ext.describe() {
+                Ok(result) => (result.module.name.to_string(), result.version.to_string()),
+                Err(_) => {
+                    warn!(
+                        "`{}` was built with an incompatible version of `ext-php-rs`, so it cannot be described.",
+                        path.display()
+                    );
+                    ("?".into(), "?".into())
+                }
+            };
+            rows.push([name, version, path.display().to_string()]);
+        }
+
+        if rows.is_empty() {
+            info!("No ext-php-rs extensions are loaded by PHP.");
+            return Ok(());
+        }
 
-            file.write(new_lines.join("\n").as_bytes())
-                .with_context(|| "Failed to update `php.ini`")?;
+        let header = ["NAME", "EXT-PHP-RS", "PATH"].map(String::from);
+        let name_width = rows.iter().chain([&header]).map(|row| row[0].len()).max();
+        let version_width = rows.iter().chain([&header]).map(|row| row[1].len()).max();
+        for [name, version, path] in std::iter::once(&header).chain(&rows) {
+            println!(
+                "{:name_width$}  {:version_width$}  {}",
+                name,
+                version,
+                path,
+                name_width = name_width.unwrap_or_default(),
+                version_width = version_width.unwrap_or_default()
+            );
         }
 
         Ok(())
@@ -297,35 +913,22 @@ impl Remove {
 
 impl Stubs {
     pub fn handle(self) -> Result {
-        let ext_path = if let Some(ext_path) = self.ext {
-            ext_path
-        } else {
-            let target = find_ext(&self.manifest)?;
-            build_ext(&target, false)?.into()
-        };
-
-        if !ext_path.is_file() {
-            bail!("Invalid extension path given, not a file.");
-        }
+        let ext = load_ext(self.ext, &self.manifest, &self.package)?;
+        let mut result = ext.describe()?;
+        check_version(&result)?;
 
-        let ext = Ext::load(ext_path)?;
-        let result = ext.describe();
-
-        // Ensure extension and CLI `ext-php-rs` versions are compatible.
-        let cli_version = semver::VersionReq::from_str(ext_php_rs::VERSION).with_context(|| {
-            "Failed to parse `ext-php-rs` version that `cargo php` was compiled with"
-        })?;
-        let ext_version = semver::Version::from_str(result.version).with_context(|| {
-            "Failed to parse `ext-php-rs` version that your extension was compiled with"
-        })?;
-
-        if !cli_version.matches(&ext_version) {
-            bail!("Extension was compiled with an incompatible version of `ext-php-rs` - Extension: {}, CLI: {}", ext_version, cli_version);
+        if self.skip_conditional {
+            result.module.remove_conditional();
         }
 
+        let opts = StubOptions {
+            declare_loaded: self.declare_loaded,
+            fully_qualified: self.fqn,
+            ..StubOptions::new(self.php_version.unwrap_or(result.php_version))
+        };
         let stubs = result
             .module
-            .to_stub()
+            .to_stub_with(opts)
             .with_context(|| "Failed to generate stubs.")?;
 
         if self.stdout {
@@ -342,101 +945,762 @@ impl Stubs {
 
             std::fs::write(out_path.as_ref(), &stubs)
                 .with_context(|| "Failed to write stubs to file")?;
+
+            if self.meta {
+                let meta = result
+                    .module
+                    .to_phpstorm_meta()
+                    .with_context(|| "Failed to generate PhpStorm meta.")?;
+                match meta {
+                    Some(meta) => {
+                        let meta_path = out_path
+                            .parent()
+                            .unwrap_or_else(|| Path::new(""))
+                            .join(".phpstorm.meta.php");
+                        std::fs::write(meta_path, meta)
+                            .with_context(|| "Failed to write PhpStorm meta to file")?;
+                    }
+                    None => info!("No functions or methods have a return type override, so no `.phpstorm.meta.php` file was written."),
+                }
+            }
         }
 
         Ok(())
     }
 }
 
-struct PhpConfig {
-    path: OsString,
-}
-
-impl PhpConfig {
-    /// Creates a new `php-config` instance.
-    pub fn new() -> Self {
-        Self {
-            path: if let Some(php_config) = std::env::var_os("PHP_CONFIG") {
-                php_config
-            } else {
-                OsString::from("php-config")
-            },
+impl Describe {
+    pub fn handle(self) -> Result {
+        let ext = load_ext(self.ext, &self.manifest, &self.package)?;
+        let result = ext.describe()?;
+        check_version(&result)?;
+
+        if self.json {
+            let json = serde_json::to_string_pretty(&result)
+                .with_context(|| "Failed to serialize description.")?;
+            println!("{}", json);
+            return Ok(());
         }
-    }
 
-    /// Calls `php-config` and retrieves the extension directory.
-    pub fn get_ext_dir(&self) -> AResult<PathBuf> {
-        Ok(PathBuf::from(
-            self.exec(
-                |cmd| cmd.arg("--extension-dir"),
-                "retrieve extension directory",
-            )?
-            .trim(),
-        ))
+        let module = &result.module;
+        println!(
+            "Extension `{}`, built with ext-php-rs {} against PHP {}",
+            module.name, result.version, result.php_version
+        );
+
+        let section = |title: &str, names: Vec<&str>| {
+            if !names.is_empty() {
+                println!();
+                println!("{}:", title);
+                for name in names {
+                    println!("    {}", name);
+                }
+            }
+        };
+        section(
+            "Functions",
+            module.functions.iter().map(|f| f.name.str()).collect(),
+        );
+        section(
+            "Classes",
+            module.classes.iter().map(|c| c.name.str()).collect(),
+        );
+        section(
+            "Constants",
+            module.constants.iter().map(|c| c.name.str()).collect(),
+        );
+
+        Ok(())
     }
+}
 
-    /// Calls `php-config` and retrieves the `php.ini` file path.
-    pub fn get_php_ini(&self) -> AResult<PathBuf> {
-        let mut path = PathBuf::from(
-            self.exec(|cmd| cmd.arg("--ini-path"), "retrieve `php.ini` path")?
-                .trim(),
+impl Which {
+    pub fn handle(self) -> Result {
+        let artifact = find_ext(&self.manifest, &self.package)?;
+        let target = self
+            .target
+            .or_else(|| std::env::var("CARGO_BUILD_TARGET").ok())
+            .filter(|target| !target.is_empty());
+        let mut path = ext_path(
+            &artifact.target_dir,
+            &artifact.target.name,
+            self.release,
+            target.as_deref(),
         );
-        path.push("php.ini");
 
-        if !path.exists() {
-            File::create(&path).with_context(|| "Failed to create `php.ini`")?;
+        if !path.is_file() {
+            if !self.build {
+                bail!(
+                    "Extension has not been built at `{}`. Pass `--build` to build it.",
+                    path
+                );
+            }
+            path = build_ext(&artifact, self.release, target.as_deref())?.path;
         }
 
-        Ok(path)
+        println!("{}", path);
+        Ok(())
     }
+}
 
-    /// Executes the `php-config` binary. The given function `f` is used to
-    /// modify the given mutable [`Command`]. If successful, a [`String`]
-    /// representing stdout is returned.
-    fn exec<F>(&self, f: F, ctx: &str) -> AResult<String>
-    where
-        F: FnOnce(&mut Command) -> &mut Command,
-    {
-        let mut cmd = Command::new(&self.path);
-        f(&mut cmd);
-        let out = cmd
-            .output()
-            .with_context(|| format!("Failed to {} from `php-config`", ctx))?;
-        String::from_utf8(out.stdout)
-            .with_context(|| "Failed to convert `php-config` output to string")
+impl Test {
+    pub fn handle(self) -> Result {
+        check_defines(&self.defines)?;
+
+        let mut tests = vec![];
+        find_tests(&self.dir, &mut tests)
+            .with_context(|| format!("Failed to read test directory `{}`", self.dir.display()))?;
+        if let Some(filter) = &self.filter {
+            tests.retain(|path| path.to_string_lossy().contains(filter.as_str()));
+        }
+        tests.sort();
+
+        if tests.is_empty() {
+            bail!("No tests were found in `{}`.", self.dir.display());
+        }
+
+        let artifact = find_ext(&self.manifest, &self.package)?;
+        let build = build_ext(&artifact, self.release, None)?;
+        let ext_path = build.path;
+
+        let php_config = PhpConfig::new();
+        check_php_debug(&php_config, build.php_debug);
+        let php = php_config.get_php_binary()?;
+        let php_args = php_args(ext_path.as_std_path(), &self.defines);
+
+        let mut failed = vec![];
+        for test in &tests {
+            match run_test(&php, &php_args, test) {
+                Ok(()) => println!("PASS {}", test.display()),
+                Err(e) => {
+                    println!("FAIL {}", test.display());
+                    println!("{:#}", e);
+                    failed.push(test);
+                }
+            }
+        }
+
+        println!();
+        println!(
+            "{} passed, {} failed",
+            tests.len() - failed.len(),
+            failed.len()
+        );
+
+        if !failed.is_empty() {
+            bail!("{} test(s) failed.", failed.len());
+        }
+
+        Ok(())
     }
 }
 
-/// Attempts to find an extension in the target directory.
-fn find_ext(manifest: &Option<PathBuf>) -> AResult<cargo_metadata::Target> {
-    // TODO(david): Look for cargo manifest option or env
-    let mut cmd = cargo_metadata::MetadataCommand::new();
-    if let Some(manifest) = manifest {
-        cmd.manifest_path(manifest);
+impl Bench {
+    pub fn handle(self) -> Result {
+        check_defines(&self.defines)?;
+        if self.iterations == 0 {
+            bail!("The number of iterations must be at least 1.");
+        }
+        if !self.script.is_file() {
+            bail!(
+                "Benchmark script `{}` does not exist.",
+                self.script.display()
+            );
+        }
+        if let Some(baseline) = &self.baseline {
+            if !baseline.is_file() {
+                bail!(
+                    "Baseline extension `{}` does not exist.",
+                    baseline.display()
+                );
+            }
+        }
+        let script = self
+            .script
+            .canonicalize()
+            .with_context(|| "Failed to resolve the path of the benchmark script")?;
+
+        let artifact = find_ext(&self.manifest, &self.package)?;
+        let build = build_ext(&artifact, self.release, None)?;
+
+        let php_config = PhpConfig::new();
+        check_php_debug(&php_config, build.php_debug);
+        let php = php_config.get_php_binary()?;
+
+        let work_dir = std::env::temp_dir().join(format!("cargo-php-bench-{}", std::process::id()));
+        std::fs::create_dir_all(&work_dir)
+            .with_context(|| "Failed to create temporary benchmark directory")?;
+
+        let result = self.run(&php, &build, &script, &work_dir);
+        let _ = std::fs::remove_dir_all(&work_dir);
+        result
+    }
+
+    /// Runs the benchmark script against the extension and the baseline
+    /// extension, if any, and prints the results.
+    fn run(&self, php: &Path, build: &Build, script: &Path, work_dir: &Path) -> Result {
+        let args = php_args(build.path.as_std_path(), &self.defines);
+        let baseline_args = self
+            .baseline
+            .as_ref()
+            .map(|baseline| php_args(baseline, &self.defines));
+
+        let runner = work_dir.join("bench.php");
+        let result = work_dir.join("result");
+        std::fs::write(&runner, bench_runner(script, &result))
+            .with_context(|| "Failed to write benchmark runner script")?;
+
+        println!(
+            "Running `{}` {} time(s)...",
+            self.script.display(),
+            self.iterations
+        );
+
+        // Runs of the two extensions alternate, so changes in the load of the
+        // machine affect both in the same way.
+        let mut samples = vec![];
+        let mut baseline_samples = vec![];
+        for _ in 0..self.iterations {
+            if let Some(baseline_args) = &baseline_args {
+                baseline_samples.push(run_bench(php, baseline_args, &runner, &result)?);
+            }
+            samples.push(run_bench(php, &args, &runner, &result)?);
+        }
+
+        let summary = BenchSummary::new(&samples);
+        println!();
+        match &self.baseline {
+            Some(_) => {
+                let baseline = BenchSummary::new(&baseline_samples);
+                baseline.print("baseline", self.memory);
+                summary.print("current", self.memory);
+                println!(
+                    "{:<8}  mean {}",
+                    "change",
+                    percent_change(baseline.mean, summary.mean)
+                );
+                if self.memory {
+                    println!(
+                        "{:<8}  peak memory {}",
+                        "",
+                        percent_change(baseline.memory as f64, summary.memory as f64)
+                    );
+                }
+            }
+            None => summary.print("current", self.memory),
+        }
+
+        Ok(())
+    }
+}
+
+/// Ensures the given `php.ini` options are formatted as `<name>=<value>`, where
+/// the name is made of letters, digits, `_`, `.` and `-`, and the value is on
+/// a single line. A value which has to be quoted by [`quote_define`] cannot
+/// also contain a double quote.
+fn check_defines(defines: &[String]) -> AResult<()> {
+    for define in defines {
+        let valid = match define.split_once('=') {
+            Some((name, value)) => {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+                    && !value.contains(&['\n', '\r'][..])
+            }
+            None => false,
+        };
+        if !valid {
+            bail!(
+                "Invalid `php.ini` option `{}`, expected `<name>=<value>`.",
+                define
+            );
+        }
+        if define
+            .split_once('=')
+            .is_some_and(|(_, value)| needs_quotes(value) && value.contains('"'))
+        {
+            bail!(
+                "Invalid `php.ini` option `{}`, a value containing `;` must be quoted as a whole when it contains `\"`.",
+                define
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the arguments to pass to PHP to run a script with the given
+/// extension loaded.
+///
+/// PHP is run with `-n`, so no `php.ini` file is loaded and an extension
+/// installed into the configuration of the PHP installation is not loaded a
+/// second time. The extension and the given options are set with `-d`.
+///
+/// # Parameters
+///
+/// * `ext_path` - Path to the extension dynamic library.
+/// * `defines` - Additional `php.ini` options, given as `<name>=<value>`.
+fn php_args(ext_path: &Path, defines: &[String]) -> Vec<OsString> {
+    let mut extension = OsString::from("extension=");
+    extension.push(ext_path);
+
+    let mut args = vec!["-n".into(), "-d".into(), extension];
+    for define in defines {
+        args.push("-d".into());
+        args.push(quote_define(define).as_ref().into());
+    }
+    args
+}
+
+/// Quotes the value of a `php.ini` option given as `<name>=<value>` if it
+/// contains a `;`, which PHP would otherwise treat as the start of a comment,
+/// truncating the value.
+fn quote_define(define: &str) -> Cow<'_, str> {
+    match define.split_once('=') {
+        Some((name, value)) if needs_quotes(value) => format!("{}=\"{}\"", name, value).into(),
+        _ => define.into(),
+    }
+}
+
+/// Returns whether the value of a `php.ini` option given with `-d` has to be
+/// quoted. PHP already quotes values which start with a character other than a
+/// letter or digit, unless they start with a quote, so only values starting
+/// with a letter or digit and containing a `;` have to be quoted.
+fn needs_quotes(value: &str) -> bool {
+    value.starts_with(|c: char| c.is_ascii_alphanumeric()) && value.contains(';')
+}
+
+/// Loads the extension at the given path, or builds the extension of the given
+/// manifest if no path is given.
+fn load_ext(
+    ext_path: Option<PathBuf>,
+    manifest: &Option<PathBuf>,
+    package: &Option<String>,
+) -> AResult<Ext> {
+    let ext_path = if let Some(ext_path) = ext_path {
+        ext_path
+    } else {
+        let target = find_ext(manifest, package)?;
+        build_ext(&target, false, None)?.path.into()
+    };
+
+    if !ext_path.is_file() {
+        bail!("Invalid extension path given, not a file.");
+    }
+
+    Ext::load(ext_path)
+}
+
+/// Ensures the extension was built for a thread-safe (ZTS) build of PHP if the
+/// PHP installation is thread-safe, or for a non-thread-safe (NTS) build
+/// otherwise, as PHP fails to start with the extension in that case.
+fn check_php_zts(php_config: &PhpConfig, ext_zts: bool) -> AResult<()> {
+    let php_zts = php_config.is_zts()?;
+    if php_zts != ext_zts {
+        let build = |zts| {
+            if zts {
+                "thread-safe (ZTS)"
+            } else {
+                "non-thread-safe (NTS)"
+            }
+        };
+        bail!(
+            "The extension was built for a {} build of PHP, but the PHP installation is a {} build, so PHP would fail to start with the extension. Set `PHP_CONFIG` to the `php-config` of the PHP installation to build the extension against it, or pass `--force` to install the extension anyway.",
+            build(ext_zts),
+            build(php_zts)
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints a warning if the extension was built for a debug build of PHP and the
+/// PHP installation is not a debug build, or vice versa, as PHP refuses to load
+/// the extension in that case.
+fn check_php_debug(php_config: &PhpConfig, ext_debug: bool) {
+    let php_debug = match php_config.is_debug() {
+        Ok(debug) => debug,
+        Err(_) => return,
+    };
+    if php_debug == ext_debug {
+        return;
+    }
+
+    let build = |debug| if debug { "a debug" } else { "a non-debug" };
+    warn!(
+        "The extension was built for {} build of PHP, but the PHP installation is {} build. PHP will refuse to load the extension.",
+        build(ext_debug),
+        build(php_debug)
+    );
+    if php_debug {
+        warn!("Set `PHP_CONFIG` to the `php-config` of the debug build to build the extension against it.");
+    } else {
+        warn!("Set `PHP_CONFIG` to the `php-config` of the PHP installation the extension is built against.");
+    }
+}
+
+/// Ensures the extension and CLI `ext-php-rs` versions are compatible.
+fn check_version(result: &Description) -> AResult<()> {
+    let cli_version = semver::VersionReq::from_str(ext_php_rs::VERSION).with_context(|| {
+        "Failed to parse `ext-php-rs` version that `cargo php` was compiled with"
+    })?;
+    let ext_version = semver::Version::from_str(result.version).with_context(|| {
+        "Failed to parse `ext-php-rs` version that your extension was compiled with"
+    })?;
+
+    if !cli_version.matches(&ext_version) {
+        bail!("Extension was compiled with an incompatible version of `ext-php-rs` - Extension: {}, CLI: {}", ext_version, cli_version);
+    }
+
+    Ok(())
+}
+
+/// Returns a PHP script which runs the benchmark script and writes the time
+/// taken in nanoseconds and the peak memory usage in bytes to the result file.
+/// The results are written from a shutdown function, so they are still written
+/// if the benchmark script calls `exit()`.
+fn bench_runner(script: &Path, result: &Path) -> String {
+    format!(
+        r#"<?php
+register_shutdown_function(static function ($start) {{
+    $time = hrtime(true) - $start;
+    file_put_contents({}, $time . ' ' . memory_get_peak_usage());
+}}, hrtime(true));
+require {};
+"#,
+        php_string(&result.to_string_lossy()),
+        php_string(&script.to_string_lossy())
+    )
+}
+
+/// A single run of a benchmark script.
+struct BenchSample {
+    /// Time taken to run the script.
+    time: Duration,
+    /// Peak memory usage of the script in bytes.
+    memory: u64,
+}
+
+/// Runs the benchmark runner script once, returning the time taken and the
+/// peak memory usage reported by the script.
+///
+/// # Parameters
+///
+/// * `php` - Path to the PHP binary.
+/// * `php_args` - Arguments loading the extension, returned by [`php_args`].
+/// * `runner` - Path to the runner script returned by [`bench_runner`].
+/// * `result` - Path to the file the runner script writes its results to.
+fn run_bench(
+    php: &Path,
+    php_args: &[OsString],
+    runner: &Path,
+    result: &Path,
+) -> AResult<BenchSample> {
+    let _ = std::fs::remove_file(result);
+    let output = Command::new(php)
+        .args(php_args)
+        .arg(runner)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .with_context(|| "Failed to run `php`")?;
+
+    if !output.status.success() {
+        bail!(
+            "PHP exited with {}:\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let contents = std::fs::read_to_string(result)
+        .with_context(|| "Benchmark script did not report its results")?;
+    let (time, memory) = contents
+        .trim()
+        .split_once(' ')
+        .and_then(|(time, memory)| Some((time.parse().ok()?, memory.parse().ok()?)))
+        .with_context(|| format!("Invalid benchmark results `{}`", contents))?;
+
+    Ok(BenchSample {
+        time: Duration::from_nanos(time),
+        memory,
+    })
+}
+
+/// Summary of the runs of a benchmark script.
+struct BenchSummary {
+    /// Mean time taken in seconds.
+    mean: f64,
+    /// Shortest time taken in seconds.
+    min: f64,
+    /// Longest time taken in seconds.
+    max: f64,
+    /// Highest peak memory usage in bytes.
+    memory: u64,
+}
+
+impl BenchSummary {
+    /// Summarizes the given runs, of which there must be at least one.
+    fn new(samples: &[BenchSample]) -> Self {
+        let times = samples.iter().map(|sample| sample.time.as_secs_f64());
+
+        Self {
+            mean: times.clone().sum::<f64>() / samples.len() as f64,
+            min: times.clone().fold(f64::INFINITY, f64::min),
+            max: times.fold(0.0, f64::max),
+            memory: samples
+                .iter()
+                .map(|sample| sample.memory)
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Prints the summary on a single line, prefixed with the given label.
+    fn print(&self, label: &str, memory: bool) {
+        print!(
+            "{:<8}  mean {:.3} ms, min {:.3} ms, max {:.3} ms",
+            label,
+            self.mean * 1e3,
+            self.min * 1e3,
+            self.max * 1e3
+        );
+        if memory {
+            print!(
+                ", peak memory {:.2} MiB",
+                self.memory as f64 / 1024.0 / 1024.0
+            );
+        }
+        println!();
+    }
+}
+
+/// Formats the change from `before` to `after` as a signed percentage.
+fn percent_change(before: f64, after: f64) -> String {
+    if before == 0.0 {
+        return "n/a".into();
+    }
+
+    format!("{:+.2}%", (after - before) / before * 100.0)
+}
+
+/// Parses a PHP version given as `<major>.<minor>`, e.g. `8.1`.
+fn parse_php_version(version: &str) -> std::result::Result<PhpVersion, String> {
+    let invalid = || format!("invalid PHP version `{}`, expected e.g. `8.1`", version);
+    let (major, minor) = version.split_once('.').ok_or_else(invalid)?;
+
+    Ok(PhpVersion::new(
+        major.parse().map_err(|_| invalid())?,
+        minor.parse().map_err(|_| invalid())?,
+    ))
+}
+
+struct PhpConfig {
+    path: OsString,
+}
+
+impl PhpConfig {
+    /// Creates a new `php-config` instance.
+    pub fn new() -> Self {
+        Self {
+            path: if let Some(php_config) = std::env::var_os("PHP_CONFIG") {
+                php_config
+            } else {
+                OsString::from("php-config")
+            },
+        }
+    }
+
+    /// Calls `php-config` and retrieves the extension directory.
+    pub fn get_ext_dir(&self) -> AResult<PathBuf> {
+        Ok(PathBuf::from(
+            self.exec(
+                |cmd| cmd.arg("--extension-dir"),
+                "retrieve extension directory",
+            )?
+            .trim(),
+        ))
+    }
+
+    /// Calls `php-config` and retrieves the path to the PHP binary.
+    pub fn get_php_binary(&self) -> AResult<PathBuf> {
+        Ok(PathBuf::from(
+            self.exec(|cmd| cmd.arg("--php-binary"), "retrieve PHP binary")?
+                .trim(),
+        ))
+    }
+
+    /// Calls `php-config` and returns whether PHP was configured with
+    /// `--enable-debug`.
+    pub fn is_debug(&self) -> AResult<bool> {
+        Ok(self
+            .get_configure_options()?
+            .iter()
+            .any(|option| option == "--enable-debug"))
+    }
+
+    /// Calls `php-config` and returns whether PHP is thread-safe, i.e. was
+    /// configured with `--enable-zts` (`--enable-maintainer-zts` before PHP 8).
+    /// Must agree with `has_zts` in the build script of `ext-php-rs`, which
+    /// sets the `php_zts` cfg of the extension.
+    pub fn is_zts(&self) -> AResult<bool> {
+        Ok(self
+            .get_configure_options()?
+            .iter()
+            .any(|option| option == "--enable-zts" || option == "--enable-maintainer-zts"))
+    }
+
+    /// Calls `php-config` and retrieves the options PHP was configured with.
+    fn get_configure_options(&self) -> AResult<Vec<String>> {
+        Ok(self
+            .exec(
+                |cmd| cmd.arg("--configure-options"),
+                "retrieve configure options",
+            )?
+            .split_whitespace()
+            .map(|option| option.trim_matches('\'').to_string())
+            .collect())
+    }
+
+    /// Calls `php-config` and retrieves the directories scanned for additional
+    /// `.ini` files, which are overridden by the `PHP_INI_SCAN_DIR` variable
+    /// if it is set. An empty directory in the variable stands for the
+    /// directory PHP was configured with.
+    pub fn get_ini_scan_dirs(&self) -> AResult<Vec<PathBuf>> {
+        let configured = self
+            .exec(|cmd| cmd.arg("--ini-dir"), "retrieve `.ini` scan directory")?
+            .trim()
+            .to_string();
+
+        let dirs = match std::env::var("PHP_INI_SCAN_DIR") {
+            Ok(dirs) => dirs
+                .split(':')
+                .map(|dir| if dir.is_empty() { &configured } else { dir }.to_string())
+                .collect(),
+            Err(_) => vec![configured],
+        };
+        Ok(dirs
+            .into_iter()
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .collect())
+    }
+
+    /// Calls `php-config` and retrieves the `php.ini` file path.
+    pub fn get_php_ini(&self) -> AResult<PathBuf> {
+        let mut path = PathBuf::from(
+            self.exec(|cmd| cmd.arg("--ini-path"), "retrieve `php.ini` path")?
+                .trim(),
+        );
+        path.push("php.ini");
+
+        Ok(path)
+    }
+
+    /// Executes the `php-config` binary. The given function `f` is used to
+    /// modify the given mutable [`Command`]. If successful, a [`String`]
+    /// representing stdout is returned.
+    fn exec<F>(&self, f: F, ctx: &str) -> AResult<String>
+    where
+        F: FnOnce(&mut Command) -> &mut Command,
+    {
+        let mut cmd = Command::new(&self.path);
+        f(&mut cmd);
+        debug!("Running {:?}", cmd);
+        let out = cmd
+            .output()
+            .with_context(|| format!("Failed to {} from `php-config`", ctx))?;
+        let out = String::from_utf8(out.stdout)
+            .with_context(|| "Failed to convert `php-config` output to string")?;
+        trace!("`php-config` returned `{}`", out.trim());
+        Ok(out)
     }
+}
 
-    let meta = cmd
+/// An extension library target of a package in the workspace.
+struct ExtTarget {
+    /// Name of the package containing the target.
+    package: String,
+    /// Path to the manifest of the package containing the target.
+    manifest: Utf8PathBuf,
+    /// The library target of the extension.
+    target: Target,
+    /// Path to the target directory of the workspace.
+    target_dir: Utf8PathBuf,
+}
+
+/// Attempts to find an extension in the target directory.
+///
+/// If no manifest is given, the nearest `Cargo.toml` is searched for in the
+/// current directory and its parents, in the same way as `cargo`. The extension
+/// is searched for in the given workspace member. If no package is given, the
+/// package of the manifest is used if it is an extension, otherwise the user
+/// chooses between the workspace members which are extensions.
+fn find_ext(manifest: &Option<PathBuf>, package: &Option<String>) -> AResult<ExtTarget> {
+    let manifest = match manifest {
+        Some(manifest) => manifest.clone(),
+        None => find_manifest(&std::env::current_dir()?)?,
+    };
+    debug!("Using manifest `{}`", manifest.display());
+
+    let meta = cargo_metadata::MetadataCommand::new()
+        .manifest_path(manifest)
         .features(cargo_metadata::CargoOpt::AllFeatures)
         .exec()
         .with_context(|| "Failed to call `cargo metadata`")?;
 
-    let package = meta
-        .root_package()
-        .with_context(|| "Failed to retrieve metadata about crate")?;
+    let members: Vec<_> = meta
+        .packages
+        .iter()
+        .filter(|package| meta.workspace_members.contains(&package.id))
+        .collect();
+
+    let package = match package {
+        Some(name) => {
+            let package = members
+                .iter()
+                .find(|package| &package.name == name)
+                .with_context(|| format!("Package `{}` is not a member of the workspace.", name))?;
+            if !package.targets.iter().any(is_ext_target) {
+                bail!(
+                    "Package `{}` has no `cdylib` or `dylib` target, so it cannot be built as an extension.",
+                    name
+                );
+            }
+            package
+        }
+        None => match meta.root_package() {
+            Some(root) if root.targets.iter().any(is_ext_target) => root,
+            _ => {
+                let packages: Vec<_> = members
+                    .iter()
+                    .filter(|package| package.targets.iter().any(is_ext_target))
+                    .collect();
+                match packages.len() {
+                    0 => bail!("No packages with a `cdylib` or `dylib` target were found in the workspace."),
+                    1 => packages[0],
+                    _ => {
+                        let package_names: Vec<_> =
+                            packages.iter().map(|package| &package.name).collect();
+                        let chosen = Select::new()
+                            .with_prompt("There were multiple extensions detected in the workspace. Which would you like to use?")
+                            .items(&package_names)
+                            .interact()?;
+                        packages[chosen]
+                    }
+                }
+            }
+        },
+    };
 
     let targets: Vec<_> = package
         .targets
         .iter()
-        .filter(|target| {
-            target
-                .crate_types
-                .iter()
-                .any(|ty| ty == "dylib" || ty == "cdylib")
-        })
+        .filter(|target| is_ext_target(target))
         .collect();
 
     let target = match targets.len() {
-        0 => bail!("No library targets were found."),
         1 => targets[0],
         _ => {
             let target_names: Vec<_> = targets.iter().map(|target| &target.name).collect();
@@ -448,28 +1712,653 @@ fn find_ext(manifest: &Option<PathBuf>) -> AResult<cargo_metadata::Target> {
         }
     };
 
-    Ok(target.clone())
+    debug!(
+        "Using target `{}` of package `{}`",
+        target.name, package.name
+    );
+    Ok(ExtTarget {
+        package: package.name.clone(),
+        manifest: package.manifest_path.clone(),
+        target: target.clone(),
+        target_dir: meta.target_directory.clone(),
+    })
+}
+
+/// Returns the path `cargo build` places the dynamic library of the extension
+/// at, without building it.
+///
+/// Libraries built for a target given through `--target` are placed in a
+/// directory named after the target triple, and use the file name of a library
+/// on that target rather than the host.
+///
+/// # Parameters
+///
+/// * `target_dir` - The target directory of the workspace.
+/// * `name` - The name of the library target of the extension.
+/// * `release` - Whether to return the path of the release build.
+/// * `target` - The target triple the extension is built for, if any.
+fn ext_path(target_dir: &Utf8Path, name: &str, release: bool, target: Option<&str>) -> Utf8PathBuf {
+    let profile = if release { "release" } else { "debug" };
+    let mut path = target_dir.to_path_buf();
+    if let Some(target) = target {
+        path.push(target);
+    }
+    let (prefix, suffix) = lib_affixes(target);
+    path.push(profile);
+    path.push(format!("{}{}{}", prefix, name.replace('-', "_"), suffix));
+    path
+}
+
+/// Returns the prefix and suffix of the file name of a dynamic library built
+/// for the given target triple, or the host if no target is given.
+fn lib_affixes(target: Option<&str>) -> (&'static str, &'static str) {
+    match target {
+        None => (std::env::consts::DLL_PREFIX, std::env::consts::DLL_SUFFIX),
+        Some(target) if target.contains("-windows") => ("", ".dll"),
+        Some(target) if target.contains("-apple-") => ("lib", ".dylib"),
+        Some(_) => ("lib", ".so"),
+    }
+}
+
+/// Returns the file name of the dynamic library built for the given target.
+fn ext_file_name(target: &Target) -> String {
+    format!(
+        "{}{}{}",
+        std::env::consts::DLL_PREFIX,
+        target.name.replace('-', "_"),
+        std::env::consts::DLL_SUFFIX
+    )
+}
+
+/// Returns the command line `cargo php` was called with, quoted so it can be
+/// pasted into a shell.
+fn command_line() -> String {
+    let mut args: Vec<_> = std::env::args().skip(1).collect();
+    if args.first().map(|arg| arg == "php").unwrap_or(false) {
+        args.remove(0);
+    }
+
+    let mut command = String::from("cargo php");
+    for arg in args {
+        command.push(' ');
+        if !arg.is_empty()
+            && arg
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c))
+        {
+            command.push_str(&arg);
+        } else {
+            command.push_str(&format!("'{}'", arg.replace('\'', "'\\''")));
+        }
+    }
+    command
+}
+
+/// Hello world extension written to the library of the crate by `cargo php
+/// init`.
+const LIB_TEMPLATE: &str = r#"use ext_php_rs::prelude::*;
+
+/// Returns a greeting for the given name.
+#[php_function]
+pub fn hello_world(name: String) -> String {
+    format!("Hello, {}!", name)
+}
+
+#[php_module]
+pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}
+"#;
+
+/// Cargo configuration written to `.cargo/config.toml` by `cargo php init`.
+/// The macOS linker otherwise fails on the PHP symbols, which are only
+/// resolved when PHP loads the extension.
+const CONFIG_TEMPLATE: &str = r#"[target.'cfg(target_os = "macos")']
+rustflags = ["-C", "link-arg=-Wl,-undefined,dynamic_lookup"]
+"#;
+
+/// Returns the contents of a Cargo manifest with the crate type of the library
+/// set to `cdylib` and `ext-php-rs` added as a dependency. Other crate types
+/// of the library and existing `ext-php-rs` dependencies are kept.
+///
+/// The manifest is edited line by line rather than parsed, as `cargo-php` does
+/// not depend on a TOML parser which preserves formatting. Comments, quoted
+/// keys and `crate-type` arrays spanning multiple lines are recognised, but the
+/// library and dependencies are only found in `[lib]`, `[dependencies]` and
+/// `[dependencies.ext-php-rs]` sections, not in dotted keys or inline tables
+/// such as `lib = { crate-type = [...] }`.
+fn init_manifest(contents: &str) -> AResult<String> {
+    let mut lines: Vec<String> = contents.lines().map(String::from).collect();
+
+    if section(&lines, "package").is_none() {
+        bail!("Expected the manifest to contain a `[package]` section.");
+    }
+
+    match section(&lines, "lib") {
+        Some((start, end)) => {
+            let crate_type =
+                (start + 1..end).find(|&idx| manifest_key(&lines[idx]) == Some("crate-type"));
+            match crate_type {
+                Some(idx) if array_contains(&lines[idx..end], "cdylib") => {}
+                Some(idx) => {
+                    let line = &lines[idx];
+                    let open = line
+                        .find('[')
+                        .with_context(|| "Expected `crate-type` of `[lib]` to be an array.")?;
+                    let rest = &line[open + 1..];
+                    let sep = if rest.trim_start().starts_with(']') {
+                        ""
+                    } else {
+                        ", "
+                    };
+                    lines[idx] =
+                        format!("{}\"cdylib\"{}{}", &line[..=open], sep, rest.trim_start());
+                }
+                None => lines.insert(start + 1, "crate-type = [\"cdylib\"]".into()),
+            }
+        }
+        None => push_section(&mut lines, "[lib]", "crate-type = [\"cdylib\"]"),
+    }
+
+    let dependency = format!("ext-php-rs = \"{}\"", ext_php_rs::VERSION);
+    if section(&lines, "dependencies.ext-php-rs").is_none() {
+        match section(&lines, "dependencies") {
+            Some((start, end)) => {
+                if !(start + 1..end).any(|idx| manifest_key(&lines[idx]) == Some("ext-php-rs")) {
+                    // Added after the last dependency, before any blank lines
+                    // separating the section from the next one.
+                    let idx = (start + 1..end)
+                        .rev()
+                        .find(|&idx| !lines[idx].trim().is_empty())
+                        .map_or(start + 1, |idx| idx + 1);
+                    lines.insert(idx, dependency);
+                }
+            }
+            None => push_section(&mut lines, "[dependencies]", &dependency),
+        }
+    }
+
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    Ok(contents)
+}
+
+/// Returns the path of the library of the crate set in the `[lib]` section of
+/// a Cargo manifest, if any.
+fn lib_path(contents: &str) -> Option<String> {
+    let lines: Vec<String> = contents.lines().map(String::from).collect();
+    let (start, end) = section(&lines, "lib")?;
+    let (_, value) = lines[start + 1..end]
+        .iter()
+        .find(|line| manifest_key(line) == Some("path"))?
+        .split_once('=')?;
+    let value = strip_comment(value).trim();
+    ['"', '\'']
+        .iter()
+        .find_map(|&quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .map(String::from)
+}
+
+/// Returns the index of the header of the given section of a Cargo manifest,
+/// and the index of the line following the section.
+fn section(lines: &[String], name: &str) -> Option<(usize, usize)> {
+    let start = lines
+        .iter()
+        .position(|line| section_name(line) == Some(name))?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| section_name(line).is_some())
+        .map_or(lines.len(), |idx| start + 1 + idx);
+    Some((start, end))
+}
+
+/// Returns the name of the section opened by a line of a Cargo manifest, if
+/// the line is a table or array of tables header.
+fn section_name(line: &str) -> Option<&str> {
+    let line = strip_comment(line).trim();
+    let name = match line.strip_prefix("[[") {
+        Some(line) => line.strip_suffix("]]")?,
+        None => line.strip_prefix('[')?.strip_suffix(']')?,
+    };
+    // Nested arrays of a multi-line array, such as `["a", "b"],`, are not
+    // headers, as keys cannot contain commas unless they are quoted.
+    if name.contains(',') {
+        return None;
+    }
+    Some(name.trim())
+}
+
+/// Returns the key set by a line of a Cargo manifest, if any, without quotes.
+fn manifest_key(line: &str) -> Option<&str> {
+    let (key, _) = line.split_once('=')?;
+    let key = key.trim();
+    let key = key.split('.').next().unwrap_or(key).trim();
+    Some(key.trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// Strips the comment at the end of a line of a Cargo manifest. `#` characters
+/// inside strings are kept.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (idx, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            ('#', None) => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Checks if the array starting on the first of the given lines of a Cargo
+/// manifest contains the given string. The array may span multiple lines.
+fn array_contains(lines: &[String], value: &str) -> bool {
+    let mut depth = 0;
+    for line in lines {
+        let line = strip_comment(line);
+        if line.contains(&format!("\"{}\"", value)) || line.contains(&format!("'{}'", value)) {
+            return true;
+        }
+        depth += line.matches('[').count() as isize - line.matches(']').count() as isize;
+        if depth <= 0 {
+            return false;
+        }
+    }
+    false
+}
+
+/// Appends a section with a single line to a Cargo manifest.
+fn push_section(lines: &mut Vec<String>, header: &str, line: &str) {
+    if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+        lines.push(String::new());
+    }
+    lines.push(header.into());
+    lines.push(line.into());
+}
+
+/// Comment opening the block of `php.ini` directives managed by `cargo php`.
+const INI_BLOCK_BEGIN: &str = "; BEGIN ext-php-rs";
+/// Comment closing the block of `php.ini` directives managed by `cargo php`.
+const INI_BLOCK_END: &str = "; END ext-php-rs";
+
+/// Reads the given `php.ini` file, which is treated as empty if it does not
+/// exist.
+fn read_ini(path: &Path) -> AResult<String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e).with_context(|| "Failed to read `php.ini`"),
+    }
+}
+
+/// Returns the contents of a `php.ini` file with an `extension=` directive
+/// loading the given extension, replacing any existing directive for the
+/// extension. The directive is commented out if `disable` is true.
+///
+/// The directive is appended to the end of the file, or if `block` is true,
+/// to the end of the block between the [`INI_BLOCK_BEGIN`] and
+/// [`INI_BLOCK_END`] comments, which is created at the end of the file if it
+/// does not exist.
+fn add_ini_extension(
+    contents: &str,
+    ext_name: &str,
+    disable: bool,
+    block: bool,
+) -> AResult<String> {
+    let ext_line = format!("extension={}", ext_name);
+    let mut lines: Vec<&str> = contents
+        .lines()
+        .filter(|line| !line.contains(&ext_line))
+        .collect();
+
+    let ext_line = if disable {
+        format!(";{}", ext_line)
+    } else {
+        ext_line
+    };
+
+    if block {
+        let begin = lines.iter().position(|line| line.trim() == INI_BLOCK_BEGIN);
+        let end = lines.iter().position(|line| line.trim() == INI_BLOCK_END);
+        match (begin, end) {
+            (Some(begin), Some(end)) if begin < end => lines.insert(end, &ext_line),
+            (None, None) => {
+                if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                    lines.push("");
+                }
+                lines.extend([INI_BLOCK_BEGIN, &ext_line, INI_BLOCK_END]);
+            }
+            _ => bail!(
+                "Expected a `{}` comment followed by a `{}` comment in `php.ini`.",
+                INI_BLOCK_BEGIN,
+                INI_BLOCK_END
+            ),
+        }
+    } else {
+        lines.push(&ext_line);
+    }
+
+    Ok(ini_contents(&lines))
+}
+
+/// Returns the contents of a `php.ini` file without the `extension=`
+/// directives loading the given extension file. The block between the
+/// [`INI_BLOCK_BEGIN`] and [`INI_BLOCK_END`] comments is removed if it no
+/// longer contains any directives.
+fn remove_ini_extension(contents: &str, ext_file: &str) -> String {
+    let mut lines: Vec<&str> = contents
+        .lines()
+        .filter(|line| !line.contains(ext_file))
+        .collect();
+
+    if let Some(begin) = lines.iter().position(|line| line.trim() == INI_BLOCK_BEGIN) {
+        if lines.get(begin + 1).map(|line| line.trim()) == Some(INI_BLOCK_END) {
+            lines.drain(begin..begin + 2);
+            // Also removes the blank line separating the block from the rest of
+            // the file when it was added.
+            if begin > 0 && begin == lines.len() && lines[begin - 1].trim().is_empty() {
+                lines.pop();
+            }
+        }
+    }
+
+    ini_contents(&lines)
+}
+
+/// Joins the lines of a `php.ini` file, ending the file with a newline.
+fn ini_contents(lines: &[&str]) -> String {
+    let mut contents = lines.join("\n");
+    if !contents.is_empty() {
+        contents.push('\n');
+    }
+    contents
+}
+
+/// Name of the file in the extension directory recording the file names of the
+/// extensions installed by `cargo php`.
+const INSTALL_RECORD: &str = ".cargo-php-installed";
+
+/// Returns the name an extension target is recorded under when installed, made
+/// of the names of the package and library target.
+fn record_key(ext: &ExtTarget) -> String {
+    format!("{}:{}", ext.package, ext.target.name)
+}
+
+/// Returns the path of the install record next to the given installed
+/// extension.
+fn record_path(ext_path: &Path) -> PathBuf {
+    ext_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(INSTALL_RECORD)
+}
+
+/// Reads the given install record, which is treated as empty if it does not
+/// exist.
+fn read_record(path: &Path) -> AResult<String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e).with_context(|| "Failed to read installed extensions"),
+    }
+}
+
+/// Returns the file name recorded for the given key in the install record of
+/// the given extension directory, if the extension was recorded when it was
+/// installed.
+fn installed_file(ext_dir: &Path, key: &str) -> AResult<Option<String>> {
+    let contents = read_record(&ext_dir.join(INSTALL_RECORD))?;
+    Ok(contents.lines().find_map(|line| {
+        let (line_key, file) = line.split_once('=')?;
+        (line_key.trim() == key).then(|| file.trim().to_string())
+    }))
+}
+
+/// Returns the contents of an install record with the given file name recorded
+/// under the given key, replacing any file name previously recorded under it.
+fn add_record(contents: &str, key: &str, ext_file: &str) -> String {
+    let record_line = format!("{}={}", key, ext_file);
+    let mut lines = remove_record_lines(contents, key);
+    if lines.is_empty() {
+        lines.push("# Extensions installed by `cargo php`, read by `cargo php remove`.");
+    }
+    lines.push(&record_line);
+    ini_contents(&lines)
+}
+
+/// Returns the contents of an install record without the given key, or an
+/// empty string if no other extensions are recorded.
+fn remove_record(contents: &str, key: &str) -> String {
+    let lines = remove_record_lines(contents, key);
+    if lines.iter().all(|line| line.starts_with('#')) {
+        return String::new();
+    }
+    ini_contents(&lines)
+}
+
+fn remove_record_lines<'a>(contents: &'a str, key: &str) -> Vec<&'a str> {
+    contents
+        .lines()
+        .filter(|line| line.split_once('=').map(|(k, _)| k.trim()) != Some(key))
+        .collect()
+}
+
+/// Removes an installed extension, the directives loading it from the given
+/// `php.ini` file and its entry from the install record.
+///
+/// # Parameters
+///
+/// * `ext_path` - Path to the installed extension.
+/// * `php_ini` - Path to the `php.ini` file loading the extension.
+/// * `key` - Name the extension is recorded under.
+fn remove_installed(ext_path: &Path, php_ini: Option<&Path>, key: &str) -> Result {
+    let ext_file = ext_path
+        .file_name()
+        .expect("ext path wasn't a filepath")
+        .to_string_lossy();
+
+    debug!("Removing `{}`", ext_path.display());
+    std::fs::remove_file(ext_path).with_context(|| "Failed to remove extension")?;
+
+    if let Some(php_ini) = php_ini.filter(|path| path.is_file()) {
+        debug!("Removing extension from `{}`", php_ini.display());
+        let contents = read_ini(php_ini)?;
+        let contents = remove_ini_extension(&contents, &ext_file);
+        std::fs::write(php_ini, contents).with_context(|| "Failed to update `php.ini`")?;
+    }
+
+    let record = record_path(ext_path);
+    if record.is_file() {
+        let contents = remove_record(&read_record(&record)?, key);
+        if contents.is_empty() {
+            std::fs::remove_file(&record)
+        } else {
+            std::fs::write(&record, contents)
+        }
+        .with_context(|| "Failed to update installed extensions")?;
+    }
+
+    Ok(())
+}
+
+/// Returns the `.ini` files in the given scan directory, in the order PHP reads
+/// them. A directory which does not exist contains no files.
+fn find_ini_files(dir: &Path) -> AResult<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read `{}`", dir.display())),
+    };
+
+    let mut files = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "ini") && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the extensions loaded by the `extension=` and `zend_extension=`
+/// directives of a `php.ini` file, as given in the directives.
+fn ini_extensions(contents: &str) -> Vec<&str> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            if !matches!(key.trim(), "extension" | "zend_extension") {
+                return None;
+            }
+            // Strips comments and quotes from the value.
+            let value = value.split(';').next()?.trim().trim_matches('"').trim();
+            (!value.is_empty()).then_some(value)
+        })
+        .collect()
+}
+
+/// Returns the path of an extension loaded by a `php.ini` directive, if it
+/// exists. Extensions given by name are searched for in the extension
+/// directory in the same way as PHP, with and without the library suffix and
+/// `php_` prefix.
+fn resolve_ext(ext_dir: &Path, ext: &str) -> Option<PathBuf> {
+    let path = Path::new(ext);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    [
+        ext.to_string(),
+        format!("{}{}", ext, std::env::consts::DLL_SUFFIX),
+        format!("php_{}{}", ext, std::env::consts::DLL_SUFFIX),
+    ]
+    .iter()
+    .map(|file| ext_dir.join(file))
+    .find(|path| path.is_file())
+}
+
+/// Checks that the given file can be written to, or created in its directory if
+/// it does not exist, without modifying it.
+fn check_writable(path: &Path) -> AResult<()> {
+    let result = if path.is_dir() {
+        let probe = path.join(format!(".cargo-php-{}", std::process::id()));
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&probe)
+            .and_then(|_| std::fs::remove_file(&probe))
+    } else if path.exists() {
+        OpenOptions::new().append(true).open(path).map(|_| ())
+    } else {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        if !dir.is_dir() {
+            bail!("Directory `{}` does not exist.", dir.display());
+        }
+        return check_writable(dir);
+    };
+
+    result.with_context(|| format!("Cannot write to `{}`", path.display()))
+}
+
+/// Checks if the given target is a dynamic library, which can be loaded by PHP
+/// as an extension.
+fn is_ext_target(target: &Target) -> bool {
+    target
+        .crate_types
+        .iter()
+        .any(|ty| ty == "dylib" || ty == "cdylib")
+}
+
+/// Searches the given directory and its parents for a Cargo manifest,
+/// returning the path to the nearest manifest.
+fn find_manifest(dir: &Path) -> AResult<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join("Cargo.toml"))
+        .find(|manifest| manifest.is_file())
+        .with_context(|| {
+            format!(
+                "Could not find `Cargo.toml` in `{}` or any parent directory.",
+                dir.display()
+            )
+        })
+}
+
+/// An extension compiled by [`build_ext`].
+struct Build {
+    /// Path to the extension dynamic library.
+    path: Utf8PathBuf,
+    /// Whether the extension was built for a debug build of PHP.
+    php_debug: bool,
+    /// Whether the extension was built for a thread-safe build of PHP.
+    php_zts: bool,
+}
+
+/// Creates a symbolic link at `link` pointing to the extension library at
+/// `ext`, replacing any existing file.
+fn link_ext(ext: &Path, link: &Path) -> std::io::Result<()> {
+    if link.exists() {
+        std::fs::remove_file(link)?;
+    }
+
+    #[cfg(unix)]
+    return std::os::unix::fs::symlink(ext, link);
+    #[cfg(windows)]
+    return std::os::windows::fs::symlink_file(ext, link);
+    #[cfg(not(any(unix, windows)))]
+    return Err(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "symbolic links are not supported on this platform",
+    ));
+}
+
+/// Checks if the given path is a symbolic link, without following it.
+fn is_symlink(path: &Path) -> bool {
+    std::fs::symlink_metadata(path)
+        .map(|meta| meta.file_type().is_symlink())
+        .unwrap_or(false)
 }
 
 /// Compiles the extension, searching for the given target artifact. If found,
 /// the path to the extension dynamic library is returned.
 ///
+/// The `php-config` executable given through the `PHP_CONFIG` variable is also
+/// used when compiling the extension, so the extension is built against the
+/// same PHP installation it is installed into.
+///
 /// # Parameters
 ///
-/// * `target` - The target to compile.
+/// * `ext` - The extension target to compile.
 /// * `release` - Whether to compile the target in release mode.
+/// * `target` - The target triple to compile the target for, passed to `cargo
+///   build --target`. Defaults to the host, or the `CARGO_BUILD_TARGET`
+///   variable if it is set.
 ///
 /// # Returns
 ///
-/// The path to the target artifact.
-fn build_ext(target: &Target, release: bool) -> AResult<Utf8PathBuf> {
+/// The path to the target artifact, and the configuration of the PHP build it
+/// was built for.
+fn build_ext(ext: &ExtTarget, release: bool, target: Option<&str>) -> AResult<Build> {
     let mut cmd = Command::new("cargo");
     cmd.arg("build")
-        .arg("--message-format=json-render-diagnostics");
+        .arg("--message-format=json-render-diagnostics")
+        .arg("--manifest-path")
+        .arg(&ext.manifest);
     if release {
         cmd.arg("--release");
     }
+    if let Some(target) = target {
+        cmd.arg("--target").arg(target);
+    }
+    if log::max_level() <= LevelFilter::Error {
+        cmd.arg("--quiet");
+    }
 
+    debug!("Running {:?}", cmd);
     let mut spawn = cmd
         .stdout(Stdio::piped())
         .spawn()
@@ -482,14 +2371,28 @@ fn build_ext(target: &Target, release: bool) -> AResult<Utf8PathBuf> {
     );
 
     let mut artifact = None;
+    let mut php_debug = false;
+    let mut php_zts = false;
     for message in cargo_metadata::Message::parse_stream(reader) {
         let message = message.with_context(|| "Invalid message received from `cargo build`")?;
         match message {
             cargo_metadata::Message::CompilerArtifact(a) => {
-                if &a.target == target {
+                trace!("Compiled `{}`", a.target.name);
+                if a.target == ext.target {
                     artifact = Some(a);
                 }
             }
+            // The build script of `ext-php-rs` sets the `php_debug` and `php_zts`
+            // cfgs when building against a debug or thread-safe build of PHP.
+            cargo_metadata::Message::BuildScriptExecuted(script) => {
+                for cfg in script.cfgs {
+                    match cfg.as_str() {
+                        "php_debug" => php_debug = true,
+                        "php_zts" => php_zts = true,
+                        _ => {}
+                    }
+                }
+            }
             cargo_metadata::Message::BuildFinished(b) => {
                 if !b.success {
                     bail!("Compilation failed, cancelling installation.")
@@ -502,11 +2405,551 @@ fn build_ext(target: &Target, release: bool) -> AResult<Utf8PathBuf> {
     }
 
     let artifact = artifact.with_context(|| "Extension artifact was not compiled")?;
+    let (_, suffix) = lib_affixes(target);
     for file in artifact.filenames {
-        if file.extension() == Some(std::env::consts::DLL_EXTENSION) {
-            return Ok(file);
+        if file.as_str().ends_with(suffix) {
+            debug!("Built extension `{}`", file);
+            return Ok(Build {
+                path: file,
+                php_debug,
+                php_zts,
+            });
         }
     }
 
     bail!("Failed to retrieve extension path from artifact")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_ini_extension, add_record, check_defines, check_writable, ext_path, ini_extensions,
+        init_manifest, installed_file, is_symlink, lib_path, percent_change, php_args,
+        quote_define, remove_ini_extension, remove_installed, remove_record, resolve_ext, Args,
+        BenchSample, BenchSummary, Commands, InstallFiles, Utf8Path,
+    };
+    use clap::{ErrorKind, Parser};
+    use log::LevelFilter;
+    use std::{ffi::OsStr, path::Path};
+
+    #[test]
+    fn test_ini_block_created() {
+        let ini = "memory_limit=128M\n";
+        let ini = add_ini_extension(ini, "libfoo.so", false, true).unwrap();
+        assert_eq!(
+            ini,
+            "memory_limit=128M\n\n; BEGIN ext-php-rs\nextension=libfoo.so\n; END ext-php-rs\n"
+        );
+
+        let ini = add_ini_extension(&ini, "libbar.so", true, true).unwrap();
+        assert_eq!(
+            ini,
+            "memory_limit=128M\n\n; BEGIN ext-php-rs\nextension=libfoo.so\n;extension=libbar.so\n; END ext-php-rs\n"
+        );
+    }
+
+    #[test]
+    fn test_ini_block_existing() {
+        let ini = "; BEGIN ext-php-rs\nextension=libfoo.so\n; END ext-php-rs\nmemory_limit=128M\n";
+        let ini = add_ini_extension(ini, "libbar.so", false, true).unwrap();
+        assert_eq!(
+            ini,
+            "; BEGIN ext-php-rs\nextension=libfoo.so\nextension=libbar.so\n; END ext-php-rs\nmemory_limit=128M\n"
+        );
+
+        // Reinstalling moves the directive into the block rather than adding it
+        // twice.
+        let ini = "extension=libfoo.so\n; BEGIN ext-php-rs\n; END ext-php-rs\n";
+        let ini = add_ini_extension(ini, "libfoo.so", false, true).unwrap();
+        assert_eq!(
+            ini,
+            "; BEGIN ext-php-rs\nextension=libfoo.so\n; END ext-php-rs\n"
+        );
+        assert_eq!(
+            add_ini_extension(&ini, "libfoo.so", false, true).unwrap(),
+            ini
+        );
+
+        assert!(add_ini_extension("; END ext-php-rs\n", "libfoo.so", false, true).is_err());
+    }
+
+    #[test]
+    fn test_ini_block_remove() {
+        let original = "memory_limit=128M\n";
+        let ini = add_ini_extension(original, "libfoo.so", false, true).unwrap();
+        let ini = add_ini_extension(&ini, "libbar.so", false, true).unwrap();
+
+        let ini = remove_ini_extension(&ini, "libfoo.so");
+        assert_eq!(
+            ini,
+            "memory_limit=128M\n\n; BEGIN ext-php-rs\nextension=libbar.so\n; END ext-php-rs\n"
+        );
+
+        let ini = remove_ini_extension(&ini, "libbar.so");
+        assert_eq!(ini, original);
+    }
+
+    #[test]
+    fn test_ini_without_block() {
+        let ini = "extension=libfoo.so\nmemory_limit=128M";
+        let ini = add_ini_extension(ini, "libfoo.so", false, false).unwrap();
+        assert_eq!(ini, "memory_limit=128M\nextension=libfoo.so\n");
+        assert_eq!(
+            remove_ini_extension(&ini, "libfoo.so"),
+            "memory_limit=128M\n"
+        );
+    }
+
+    #[test]
+    fn test_init_manifest() {
+        let manifest = "[package]\nname = \"hello\"\n\n[dependencies]\n";
+        let version = ext_php_rs::VERSION;
+        let expected = format!(
+            "[package]\nname = \"hello\"\n\n[dependencies]\next-php-rs = \"{}\"\n\n[lib]\ncrate-type = [\"cdylib\"]\n",
+            version
+        );
+        assert_eq!(init_manifest(manifest).unwrap(), expected);
+        // Running the command again leaves the manifest unchanged.
+        assert_eq!(init_manifest(&expected).unwrap(), expected);
+
+        let manifest = "[package]\nname = \"hello\"\n\n[lib]\ncrate-type = [\"rlib\"]\n\n[dependencies]\nlog = \"0.4\"\n\n[features]\n";
+        assert_eq!(
+            init_manifest(manifest).unwrap(),
+            format!(
+                "[package]\nname = \"hello\"\n\n[lib]\ncrate-type = [\"cdylib\", \"rlib\"]\n\n[dependencies]\nlog = \"0.4\"\next-php-rs = \"{}\"\n\n[features]\n",
+                version
+            )
+        );
+
+        let manifest = "[package]\nname = \"hello\"\n[lib]\npath = \"src/ext.rs\"\n[dependencies.ext-php-rs]\nversion = \"0.7\"\n";
+        assert_eq!(
+            init_manifest(manifest).unwrap(),
+            "[package]\nname = \"hello\"\n[lib]\ncrate-type = [\"cdylib\"]\npath = \"src/ext.rs\"\n[dependencies.ext-php-rs]\nversion = \"0.7\"\n"
+        );
+
+        assert!(init_manifest("[workspace]\nmembers = [\"ext\"]\n").is_err());
+    }
+
+    #[test]
+    fn test_init_manifest_formatting() {
+        // Headers with comments, single quotes and quoted keys.
+        let manifest = "[package] # the crate\nname = 'hello'\n\n[lib] # built as an extension\ncrate-type = ['cdylib']\n\n[dependencies]\n\"ext-php-rs\" = { path = \"../ext-php-rs\" }\n";
+        assert_eq!(init_manifest(manifest).unwrap(), manifest);
+
+        // Multi-line `crate-type` arrays.
+        let manifest = "[package]\nname = \"hello\"\n\n[lib]\ncrate-type = [\n    \"rlib\",\n    \"cdylib\", # for PHP\n]\n\n[dependencies]\next-php-rs = \"0.7\"\n";
+        assert_eq!(init_manifest(manifest).unwrap(), manifest);
+        let manifest = "[package]\nname = \"hello\"\n\n[lib]\ncrate-type = [\n    \"rlib\",\n]\n\n[dependencies]\next-php-rs = \"0.7\"\n";
+        assert_eq!(
+            init_manifest(manifest).unwrap(),
+            "[package]\nname = \"hello\"\n\n[lib]\ncrate-type = [\"cdylib\", \n    \"rlib\",\n]\n\n[dependencies]\next-php-rs = \"0.7\"\n"
+        );
+
+        // Lines of multi-line arrays and commented out sections are not
+        // headers.
+        let manifest = "[package]\nname = \"hello\"\nmetadata = [\n    [\"a\", \"b\"],\n    [\"c\", \"d\"]\n]\n# [lib]\n# crate-type = [\"rlib\"]\n\n[dependencies]\next-php-rs = \"0.7\"\n";
+        assert_eq!(
+            init_manifest(manifest).unwrap(),
+            format!("{}\n[lib]\ncrate-type = [\"cdylib\"]\n", manifest)
+        );
+    }
+
+    #[test]
+    fn test_lib_path() {
+        assert_eq!(lib_path("[package]\nname = \"hello\"\n"), None);
+        assert_eq!(
+            lib_path("[lib]\ncrate-type = [\"cdylib\"]\npath = \"src/ext.rs\"\n"),
+            Some("src/ext.rs".into())
+        );
+        assert_eq!(
+            lib_path("[lib] # extension\npath = 'ext/main.rs' # not src/lib.rs\n[features]\n"),
+            Some("ext/main.rs".into())
+        );
+        assert_eq!(
+            lib_path("[lib]\ncrate-type = [\"cdylib\"]\n\n[[bin]]\npath = \"src/main.rs\"\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_install_record() {
+        let record = add_record("", "hello:hello", "libhello.so");
+        assert_eq!(
+            record,
+            "# Extensions installed by `cargo php`, read by `cargo php remove`.\nhello:hello=libhello.so\n"
+        );
+
+        let record = add_record(&record, "other:other_ext", "other.so");
+        let record = add_record(&record, "hello:hello", "hello.so");
+        assert_eq!(
+            record,
+            "# Extensions installed by `cargo php`, read by `cargo php remove`.\nother:other_ext=other.so\nhello:hello=hello.so\n"
+        );
+
+        let record = remove_record(&record, "other:other_ext");
+        assert_eq!(
+            record,
+            "# Extensions installed by `cargo php`, read by `cargo php remove`.\nhello:hello=hello.so\n"
+        );
+        assert_eq!(remove_record(&record, "hello:hello"), "");
+    }
+
+    #[test]
+    fn test_install_remove_renamed() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let ext_dir = dir.join("ext");
+        std::fs::create_dir(&ext_dir).unwrap();
+
+        // The library is built as `libhello_impl.so` but installed as
+        // `hello.so`, so it cannot be found from the name of the target.
+        let built = dir.join("libhello_impl.so");
+        std::fs::write(&built, "").unwrap();
+        let php_ini = dir.join("php.ini");
+        std::fs::write(&php_ini, "memory_limit=128M\n").unwrap();
+
+        let installed = ext_dir.join("hello.so");
+        InstallFiles {
+            ext: built,
+            dest: installed.clone(),
+            ini_path: Some(php_ini.clone()),
+            disable: false,
+            block: false,
+            symlink: false,
+            record: Some("hello:hello_impl".into()),
+        }
+        .handle()
+        .unwrap();
+
+        assert!(installed.is_file());
+        assert_eq!(
+            std::fs::read_to_string(&php_ini).unwrap(),
+            "memory_limit=128M\nextension=hello.so\n"
+        );
+        assert_eq!(
+            installed_file(&ext_dir, "hello:hello_impl").unwrap(),
+            Some("hello.so".into())
+        );
+        assert_eq!(installed_file(&ext_dir, "hello:hello").unwrap(), None);
+
+        remove_installed(&installed, Some(&php_ini), "hello:hello_impl").unwrap();
+        assert!(!installed.exists());
+        assert_eq!(
+            std::fs::read_to_string(&php_ini).unwrap(),
+            "memory_limit=128M\n"
+        );
+        // The record is removed along with the last recorded extension.
+        assert_eq!(std::fs::read_dir(&ext_dir).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let built = dir.path().join("libhello.so");
+        std::fs::write(&built, "built").unwrap();
+        let installed = dir.path().join("hello.so");
+        let files = |symlink| InstallFiles {
+            ext: built.clone(),
+            dest: installed.clone(),
+            ini_path: None,
+            disable: false,
+            block: false,
+            symlink,
+            record: None,
+        };
+
+        files(true).handle().unwrap();
+        assert!(is_symlink(&installed));
+        assert_eq!(std::fs::read_link(&installed).unwrap(), built);
+
+        // Rebuilding the extension updates the installed extension.
+        std::fs::write(&built, "rebuilt").unwrap();
+        assert_eq!(std::fs::read_to_string(&installed).unwrap(), "rebuilt");
+
+        // Installing a link over a link replaces it.
+        files(true).handle().unwrap();
+        assert_eq!(std::fs::read_link(&installed).unwrap(), built);
+
+        // Copying over a link replaces the link instead of writing through it
+        // into the built library.
+        files(false).handle().unwrap();
+        assert!(!is_symlink(&installed));
+        std::fs::write(&installed, "installed").unwrap();
+        assert_eq!(std::fs::read_to_string(&built).unwrap(), "rebuilt");
+
+        // A link is removed even if the built library no longer exists.
+        files(true).handle().unwrap();
+        std::fs::remove_file(&built).unwrap();
+        assert!(!installed.is_file() && is_symlink(&installed));
+        remove_installed(&installed, None, "hello:hello").unwrap();
+        assert!(!is_symlink(&installed));
+    }
+
+    #[test]
+    fn test_log_level() {
+        let level = |args: &[&str]| {
+            Args::try_parse_from(args.iter().copied())
+                .and_then(|args| args.log_level())
+                .map_err(|e| e.kind)
+        };
+
+        assert_eq!(level(&["cargo-php", "list"]), Ok(LevelFilter::Info));
+        assert_eq!(level(&["cargo-php", "-v", "list"]), Ok(LevelFilter::Debug));
+        assert_eq!(level(&["cargo-php", "list", "-v"]), Ok(LevelFilter::Debug));
+        assert_eq!(level(&["cargo-php", "-vv", "list"]), Ok(LevelFilter::Trace));
+        assert_eq!(
+            level(&["cargo-php", "list", "-v", "--verbose", "-v"]),
+            Ok(LevelFilter::Trace)
+        );
+        assert_eq!(
+            level(&["cargo-php", "list", "--quiet"]),
+            Ok(LevelFilter::Error)
+        );
+        assert_eq!(level(&["cargo-php", "-q", "list"]), Ok(LevelFilter::Error));
+        assert_eq!(
+            level(&["cargo-php", "-q", "-v", "list"]),
+            Err(ErrorKind::ArgumentConflict)
+        );
+        assert_eq!(
+            level(&["cargo-php", "-q", "list", "-v"]),
+            Err(ErrorKind::ArgumentConflict)
+        );
+    }
+
+    #[test]
+    fn test_check_writable() {
+        let dir = tempfile::tempdir().unwrap();
+        let entries = || std::fs::read_dir(dir.path()).unwrap().count();
+
+        // The probe file written to check a directory is removed.
+        check_writable(dir.path()).unwrap();
+        assert_eq!(entries(), 0);
+
+        // Missing files are not created.
+        let ext = dir.path().join("hello.so");
+        check_writable(&ext).unwrap();
+        assert!(!ext.exists());
+
+        // Existing files are not modified.
+        let php_ini = dir.path().join("php.ini");
+        std::fs::write(&php_ini, "memory_limit=128M\n").unwrap();
+        check_writable(&php_ini).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&php_ini).unwrap(),
+            "memory_limit=128M\n"
+        );
+        assert_eq!(entries(), 1);
+
+        let err = check_writable(&dir.path().join("missing").join("hello.so")).unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+        assert!(check_writable(&php_ini.join("hello.so")).is_err());
+    }
+
+    #[test]
+    fn test_sudo_command() {
+        let parse = |files: &InstallFiles, level| {
+            let cmd = files.sudo_command(Path::new("/bin/cargo-php"), level);
+            assert_eq!(cmd.get_program(), "sudo");
+            let mut args = cmd.get_args();
+            assert_eq!(args.next().unwrap(), "/bin/cargo-php");
+
+            // The arguments are parsed in the same way as when run by `sudo`.
+            let args =
+                Args::try_parse_from(std::iter::once(OsStr::new("cargo-php")).chain(args)).unwrap();
+            let level = args.log_level().unwrap();
+            match args.command {
+                Commands::InstallFiles(files) => (files, level),
+                _ => panic!("expected the `install-files` subcommand"),
+            }
+        };
+
+        let files = InstallFiles {
+            ext: "/work/target/release/libhello.so".into(),
+            dest: "/usr/lib/php/hello.so".into(),
+            ini_path: Some("/etc/php/php.ini".into()),
+            disable: true,
+            block: true,
+            symlink: true,
+            record: Some("hello:hello".into()),
+        };
+        assert_eq!(
+            parse(&files, LevelFilter::Debug),
+            (files, LevelFilter::Debug)
+        );
+
+        let files = InstallFiles {
+            ext: "/work/target/debug/libhello world.so".into(),
+            dest: "/usr/lib/php/".into(),
+            ini_path: None,
+            disable: false,
+            block: false,
+            symlink: false,
+            record: None,
+        };
+        let (parsed, level) = parse(&files, LevelFilter::Info);
+        assert_eq!(parsed, files);
+        assert_eq!(level, LevelFilter::Info);
+
+        // The level of the messages printed is passed on.
+        assert_eq!(parse(&files, LevelFilter::Off).1, LevelFilter::Error);
+        assert_eq!(parse(&files, LevelFilter::Error).1, LevelFilter::Error);
+        assert_eq!(parse(&files, LevelFilter::Trace).1, LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_ini_extensions() {
+        let ini = "[PHP]\nextension=libhello.so\n;extension=disabled.so\nextension = \"mbstring\" ; comment\nzend_extension=/opt/php/opcache.so\nextension_dir=/opt/php/ext\nextension=\n";
+        assert_eq!(
+            ini_extensions(ini),
+            vec!["libhello.so", "mbstring", "/opt/php/opcache.so"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_ext() {
+        use std::path::Path;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let lib = format!("libhello{}", std::env::consts::DLL_SUFFIX);
+        let php_lib = format!("php_intl{}", std::env::consts::DLL_SUFFIX);
+        std::fs::write(dir.join(&lib), "").unwrap();
+        std::fs::write(dir.join(&php_lib), "").unwrap();
+
+        assert_eq!(resolve_ext(dir, &lib), Some(dir.join(&lib)));
+        assert_eq!(resolve_ext(dir, "libhello"), Some(dir.join(&lib)));
+        assert_eq!(resolve_ext(dir, "intl"), Some(dir.join(&php_lib)));
+        assert_eq!(
+            resolve_ext(Path::new("/nonexistent"), dir.join(&lib).to_str().unwrap()),
+            Some(dir.join(&lib))
+        );
+        assert_eq!(resolve_ext(dir, "missing"), None);
+    }
+
+    #[test]
+    fn test_ext_path() {
+        let target_dir = Utf8Path::new("/work/target");
+        assert_eq!(
+            ext_path(target_dir, "hello-world", false, None),
+            target_dir.join("debug").join(format!(
+                "{}hello_world{}",
+                std::env::consts::DLL_PREFIX,
+                std::env::consts::DLL_SUFFIX
+            ))
+        );
+        assert_eq!(
+            ext_path(target_dir, "hello", true, Some("x86_64-unknown-linux-gnu")),
+            "/work/target/x86_64-unknown-linux-gnu/release/libhello.so"
+        );
+        assert_eq!(
+            ext_path(target_dir, "hello", false, Some("aarch64-apple-darwin")),
+            "/work/target/aarch64-apple-darwin/debug/libhello.dylib"
+        );
+        assert_eq!(
+            ext_path(target_dir, "hello", true, Some("x86_64-pc-windows-msvc")),
+            "/work/target/x86_64-pc-windows-msvc/release/hello.dll"
+        );
+    }
+
+    #[test]
+    fn test_php_args() {
+        use std::{ffi::OsString, path::Path};
+
+        let args = php_args(
+            Path::new("/tmp/libhello.so"),
+            &[
+                "memory_limit=1G".into(),
+                "extension=intl".into(),
+                "include_path=a;b".into(),
+            ],
+        );
+        assert_eq!(
+            args,
+            [
+                "-n",
+                "-d",
+                "extension=/tmp/libhello.so",
+                "-d",
+                "memory_limit=1G",
+                "-d",
+                "extension=intl",
+                "-d",
+                "include_path=\"a;b\""
+            ]
+            .iter()
+            .map(OsString::from)
+            .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_check_defines() {
+        let defines = |defines: &[&str]| {
+            check_defines(&defines.iter().map(|d| d.to_string()).collect::<Vec<_>>())
+        };
+
+        assert!(defines(&[]).is_ok());
+        assert!(defines(&[
+            "memory_limit=-1",
+            "extension=intl",
+            "date.timezone=Europe/Paris",
+            "xdebug.mode=",
+            "error_reporting=E_ALL & ~E_DEPRECATED",
+            "include_path=.;/usr/share/php",
+            "include_path=\"a;b\"",
+            "user_agent=say \"hi\"",
+        ])
+        .is_ok());
+
+        assert!(defines(&["memory_limit"]).is_err());
+        assert!(defines(&["=1"]).is_err());
+        assert!(defines(&["memory limit=1"]).is_err());
+        assert!(defines(&["memory_limit=1\nextension=evil"]).is_err());
+        assert!(defines(&["user_agent=a;\"b\""]).is_err());
+    }
+
+    #[test]
+    fn test_quote_define() {
+        assert_eq!(quote_define("memory_limit=-1"), "memory_limit=-1");
+        assert_eq!(
+            quote_define("include_path=a;/usr/share/php"),
+            "include_path=\"a;/usr/share/php\""
+        );
+        // PHP quotes values starting with a character other than a letter or
+        // digit, and values which are already quoted are kept.
+        assert_eq!(
+            quote_define("include_path=.;/usr/share/php"),
+            "include_path=.;/usr/share/php"
+        );
+        assert_eq!(quote_define("include_path=\"a;b\""), "include_path=\"a;b\"");
+        assert_eq!(quote_define("user_agent=a=b"), "user_agent=a=b");
+    }
+
+    #[test]
+    fn test_bench_summary() {
+        use std::time::Duration;
+
+        let sample = |millis, memory| BenchSample {
+            time: Duration::from_millis(millis),
+            memory,
+        };
+        let summary = BenchSummary::new(&[sample(30, 1024), sample(10, 4096), sample(20, 2048)]);
+        assert!((summary.mean - 0.02).abs() < 1e-9);
+        assert!((summary.min - 0.01).abs() < 1e-9);
+        assert!((summary.max - 0.03).abs() < 1e-9);
+        assert_eq!(summary.memory, 4096);
+
+        let summary = BenchSummary::new(&[sample(5, 0)]);
+        assert_eq!(summary.mean, summary.min);
+        assert_eq!(summary.min, summary.max);
+    }
+
+    #[test]
+    fn test_percent_change() {
+        assert_eq!(percent_change(20.0, 15.0), "-25.00%");
+        assert_eq!(percent_change(20.0, 25.0), "+25.00%");
+        assert_eq!(percent_change(3.0, 3.0), "+0.00%");
+        assert_eq!(percent_change(0.0, 1.0), "n/a");
+    }
+}
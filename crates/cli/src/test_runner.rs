@@ -0,0 +1,199 @@
+//! Running of the `.php` and `.phpt` test files of an extension, used by
+//! `cargo php test`.
+
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{bail, Context, Result};
+
+/// Recursively collects the `.php` and `.phpt` files inside the given
+/// directory.
+pub fn find_tests(dir: &Path, tests: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_tests(&path, tests)?;
+        } else if matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("php") | Some("phpt")
+        ) {
+            tests.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs a single test file, returning an error describing the failure if the
+/// test did not pass.
+///
+/// The `--FILE--` section of a `.phpt` file is written next to the test, as
+/// `run-tests.php` does, so `__DIR__` and `__FILE__` refer to the directory of
+/// the test. The script is removed once the test has run.
+///
+/// # Parameters
+///
+/// * `php` - Path to the PHP binary.
+/// * `php_args` - Arguments loading the extension, passed to PHP before the
+///   script.
+/// * `test` - Path to the test file.
+pub fn run_test(php: &Path, php_args: &[OsString], test: &Path) -> Result<()> {
+    if test.extension() != Some(OsStr::new("phpt")) {
+        return run_script(php, php_args, test, None);
+    }
+
+    let contents = std::fs::read_to_string(test).with_context(|| "Failed to read test")?;
+    let sections = parse_phpt(&contents)?;
+    let script = test.with_extension("php");
+    if script.exists() {
+        bail!(
+            "The `--FILE--` section is written to `{}`, which already exists.",
+            script.display()
+        );
+    }
+    std::fs::write(&script, sections.file).with_context(|| "Failed to write test script")?;
+
+    let result = run_script(php, php_args, &script, sections.expect.as_deref());
+    let _ = std::fs::remove_file(&script);
+    result
+}
+
+/// Runs the given script, comparing its output with the expected output if
+/// given.
+fn run_script(
+    php: &Path,
+    php_args: &[OsString],
+    script: &Path,
+    expected: Option<&str>,
+) -> Result<()> {
+    let output = Command::new(php)
+        .args(php_args)
+        .arg(script)
+        .output()
+        .with_context(|| "Failed to run `php`")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    if !output.status.success() {
+        bail!(
+            "PHP exited with {}:\n{}{}",
+            output.status,
+            stdout,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    if let Some(expected) = expected {
+        if normalize_output(&stdout) != normalize_output(expected) {
+            bail!(
+                "Output did not match `--EXPECT--` section.\nExpected:\n{}\nActual:\n{}",
+                expected.trim_end(),
+                stdout.trim_end()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// The sections of a `.phpt` file used to run the test.
+#[derive(Debug, PartialEq)]
+struct Phpt {
+    /// The script to run.
+    file: String,
+    /// The expected output of the script, if any.
+    expect: Option<String>,
+}
+
+/// Parses a `.phpt` file. Only the `--TEST--`, `--FILE--` and `--EXPECT--`
+/// sections are supported.
+fn parse_phpt(contents: &str) -> Result<Phpt> {
+    let mut sections: Vec<(&str, String)> = vec![];
+    for line in contents.lines() {
+        let name = line
+            .strip_prefix("--")
+            .and_then(|line| line.strip_suffix("--"))
+            .filter(|name| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            });
+        match (name, sections.last_mut()) {
+            (Some(name), _) => sections.push((name, String::new())),
+            (None, Some((_, body))) => {
+                body.push_str(line);
+                body.push('\n');
+            }
+            (None, None) => bail!("Expected `.phpt` file to start with a section."),
+        }
+    }
+
+    let mut file = None;
+    let mut expect = None;
+    for (name, body) in sections {
+        match name {
+            "TEST" => {}
+            "FILE" => file = Some(body),
+            "EXPECT" => expect = Some(body),
+            _ => bail!("Unsupported `.phpt` section `--{}--`.", name),
+        }
+    }
+
+    Ok(Phpt {
+        file: file.with_context(|| "Missing `--FILE--` section in `.phpt` file")?,
+        expect,
+    })
+}
+
+/// Normalizes the output of a test so it can be compared with the expected
+/// output, ignoring line endings and trailing whitespace.
+fn normalize_output(output: &str) -> String {
+    output
+        .trim_end()
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_output, parse_phpt, Phpt};
+
+    #[test]
+    fn test_parse_phpt() {
+        let phpt = "--TEST--\nhello_world() greets\n--FILE--\n<?php\necho hello_world('PHP');\n--EXPECT--\nHello, PHP!\n";
+        assert_eq!(
+            parse_phpt(phpt).unwrap(),
+            Phpt {
+                file: "<?php\necho hello_world('PHP');\n".into(),
+                expect: Some("Hello, PHP!\n".into()),
+            }
+        );
+
+        // Lines which only look like sections are part of the body.
+        let phpt = "--FILE--\n<?php\n--lower--\n-- X --\n----\n";
+        assert_eq!(
+            parse_phpt(phpt).unwrap(),
+            Phpt {
+                file: "<?php\n--lower--\n-- X --\n----\n".into(),
+                expect: None,
+            }
+        );
+
+        assert!(parse_phpt("<?php\n--FILE--\n").is_err());
+        assert!(parse_phpt("--TEST--\nno script\n").is_err());
+        assert!(parse_phpt("--FILE--\n<?php\n--EXPECTF--\n%d\n").is_err());
+    }
+
+    #[test]
+    fn test_normalize_output() {
+        assert_eq!(normalize_output("a  \r\nb\t\n\n\n"), "a\nb");
+        assert_eq!(normalize_output("  indented\n"), "  indented");
+        assert_eq!(normalize_output(""), "");
+        assert_ne!(normalize_output("a\n\nb"), normalize_output("a\nb"));
+    }
+}
@@ -6,26 +6,72 @@ use darling::{FromMeta, ToTokens};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use syn::parse::ParseStream;
-use syn::{Attribute, AttributeArgs, Expr, Fields, FieldsNamed, ItemStruct, LitStr, Token};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, AttributeArgs, Expr, Fields, FieldsNamed, ItemStruct, LitStr, Path, Token};
 
 #[derive(Debug, Default)]
 pub struct Class {
     pub class_name: String,
     pub struct_path: String,
     pub parent: Option<String>,
+    pub extends: Option<String>,
     pub interfaces: Vec<String>,
+    pub implements: Vec<String>,
+    /// Rust identifiers of the traits declared with `#[php_trait]` used by the
+    /// class.
+    pub uses: Vec<String>,
+    /// Whether the class is abstract, and cannot be instantiated.
+    pub _abstract: bool,
+    /// Whether the class cannot be instantiated as its constructor is private,
+    /// for classes only holding static members.
+    pub uninstantiable: bool,
     pub docs: Vec<String>,
+    /// Deprecation message given through `#[php(deprecated = "...")]`.
+    pub deprecated: Option<String>,
+    /// PHP attributes given through `#[php(attribute = "...")]`.
+    pub attributes: Vec<String>,
+    /// Path to a `fn() -> bool` given through the `condition` option. The
+    /// class is only registered if it returns true on startup.
+    pub condition: Option<String>,
     pub methods: Vec<crate::method::Method>,
     pub constructor: Option<crate::method::Method>,
     pub constants: Vec<crate::constant::Constant>,
     pub properties: HashMap<String, Property>,
+    pub static_properties: Vec<StaticProperty>,
+    pub iterator: bool,
+    pub array_access: bool,
+    pub countable: bool,
+    pub stringable: bool,
+    pub json_serializable: bool,
+    pub serializable: bool,
+    pub debug_info: bool,
+    pub magic_properties: bool,
+    /// Whether the struct implements `Clone`, either derived or declared
+    /// through `#[cloneable]`, allowing objects to be cloned from PHP.
+    pub cloneable: bool,
+    /// Whether the struct implements `PartialOrd`, either derived or declared
+    /// through `#[comparable]`, allowing objects to be compared from PHP.
+    pub comparable: bool,
 }
 
 #[derive(Debug)]
 pub enum ParsedAttribute {
     Extends(Expr),
     Implements(Expr),
+    Uses(Ident),
+    Iterator,
+    ArrayAccess,
+    Countable,
+    Stringable,
+    JsonSerializable,
+    Serializable,
+    DebugInfo,
+    MagicProperties,
+    Cloneable,
+    Comparable,
     Property(PropertyAttr),
+    StaticProperty(Box<StaticPropertyAttr>),
+    Php(crate::impl_::PhpAttr),
     Comment(String),
 }
 
@@ -33,16 +79,73 @@ pub enum ParsedAttribute {
 #[darling(default)]
 pub struct AttrArgs {
     name: Option<String>,
+    extends: Option<String>,
+    implements: Option<Interfaces>,
+    #[darling(rename = "abstract")]
+    _abstract: bool,
+    uninstantiable: bool,
+    condition: Option<String>,
 }
 
+/// List of PHP interface names given through `implements = "..."` or
+/// `implements("...", "...")`.
+#[derive(Default, Debug)]
+pub struct Interfaces(Vec<String>);
+
+impl FromMeta for Interfaces {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(Self(vec![value.to_string()]))
+    }
+
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
+        items
+            .iter()
+            .map(|item| match item {
+                syn::NestedMeta::Lit(lit) => String::from_value(lit),
+                _ => Err(darling::Error::unexpected_type("non-string")),
+            })
+            .collect::<darling::Result<_>>()
+            .map(Self)
+    }
+}
+
+/// Methods which must be implemented by a class implementing one of the
+/// built-in PHP interfaces.
+const INTERFACE_METHODS: &[(&str, &[&str])] = &[
+    (
+        "ArrayAccess",
+        &["offsetExists", "offsetGet", "offsetSet", "offsetUnset"],
+    ),
+    ("Countable", &["count"]),
+    ("Iterator", &["current", "key", "next", "rewind", "valid"]),
+    ("IteratorAggregate", &["getIterator"]),
+    ("JsonSerializable", &["jsonSerialize"]),
+    ("Serializable", &["serialize", "unserialize"]),
+    ("Stringable", &["__toString"]),
+];
+
 pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream> {
     let args = AttrArgs::from_list(&args)
         .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
 
     let mut parent = None;
     let mut interfaces = vec![];
+    let mut uses = vec![];
+    let mut iterator = false;
+    let mut array_access = false;
+    let mut countable = false;
+    let mut stringable = false;
+    let mut json_serializable = false;
+    let mut serializable = false;
+    let mut debug_info = false;
+    let mut magic_properties = false;
+    let mut cloneable = input.attrs.iter().any(|attr| derives(attr, "Clone"));
+    let mut comparable = input.attrs.iter().any(|attr| derives(attr, "PartialOrd"));
     let mut properties = HashMap::new();
+    let mut static_properties = vec![];
     let mut comments = vec![];
+    let mut deprecated = None;
+    let mut php_attributes = vec![];
 
     input.attrs = {
         let mut unused = vec![];
@@ -55,6 +158,35 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
                     ParsedAttribute::Implements(class) => {
                         interfaces.push(class.to_token_stream().to_string());
                     }
+                    ParsedAttribute::Uses(ident) => uses.push(ident.to_string()),
+                    ParsedAttribute::Iterator => iterator = true,
+                    ParsedAttribute::ArrayAccess => array_access = true,
+                    ParsedAttribute::Countable => countable = true,
+                    ParsedAttribute::Stringable => stringable = true,
+                    ParsedAttribute::JsonSerializable => json_serializable = true,
+                    ParsedAttribute::Serializable => serializable = true,
+                    ParsedAttribute::DebugInfo => debug_info = true,
+                    ParsedAttribute::MagicProperties => magic_properties = true,
+                    ParsedAttribute::Cloneable => cloneable = true,
+                    ParsedAttribute::Comparable => comparable = true,
+                    ParsedAttribute::StaticProperty(prop) => {
+                        static_properties.push(StaticProperty {
+                            name: prop.name.to_string(),
+                            ty: prop.ty.to_token_stream().to_string(),
+                            default: prop.default.to_token_stream().to_string(),
+                        });
+                    }
+                    ParsedAttribute::Php(attr) => {
+                        if attr.name.is_some()
+                            || attr.emit_deprecated
+                            || attr.return_override.is_some()
+                            || attr.internal
+                        {
+                            bail!("Only the `deprecated` and `attribute` options of `#[php]` are valid for structs.");
+                        }
+                        deprecated = attr.deprecated.map(|d| d.0).or(deprecated);
+                        php_attributes.extend(attr.attributes);
+                    }
                     ParsedAttribute::Comment(comment) => {
                         comments.push(comment);
                     }
@@ -87,14 +219,21 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
                                 .ok_or_else(|| anyhow!("Only named fields can be properties."))?
                                 .to_string();
                             let prop_name = prop.rename.unwrap_or_else(|| field_name.clone());
-                            result_prop = Some((
-                                prop_name,
-                                Property::field(
-                                    field_name,
-                                    vec![],
-                                    prop.flags.map(|flags| flags.to_token_stream().to_string()),
-                                ),
-                            ));
+                            let mut property = Property::field(
+                                field_name,
+                                field.ty.to_token_stream().to_string(),
+                                vec![],
+                                prop.flags.map(|flags| flags.to_token_stream().to_string()),
+                            );
+                            if prop.readonly {
+                                property.set_readonly();
+                            }
+                            if let Some(ty) = prop.ty {
+                                property.php_ty = Some(PhpType::parse(&ty).map_err(|e| {
+                                    anyhow!("{} Given for property `{}`.", e, prop_name)
+                                })?);
+                            }
+                            result_prop = Some((prop_name, property));
                         }
                         ParsedAttribute::Comment(doc) => docs.push(doc),
                         _ => bail!("Attribute {:?} is not valid for struct fields.", attr),
@@ -110,16 +249,43 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
         }
     }
 
+    if parent.is_some() && args.extends.is_some() {
+        bail!("Only one of `#[extends]` and `extends` can be given.");
+    }
+
     let ItemStruct { ident, .. } = &input;
+    if let Some(condition) = &args.condition {
+        syn::parse_str::<syn::Path>(condition)
+            .map_err(|_| anyhow!("Invalid condition given for class `{}`.", ident))?;
+    }
     let class_name = args.name.unwrap_or_else(|| ident.to_string());
     let struct_path = ident.to_string();
     let class = Class {
         class_name,
         struct_path,
         parent,
+        extends: args.extends,
         interfaces,
+        implements: args.implements.unwrap_or_default().0,
+        uses,
+        _abstract: args._abstract,
+        uninstantiable: args.uninstantiable,
         docs: comments,
+        deprecated,
+        attributes: php_attributes,
+        condition: args.condition,
         properties,
+        static_properties,
+        iterator,
+        array_access,
+        countable,
+        stringable,
+        json_serializable,
+        serializable,
+        debug_info,
+        magic_properties,
+        cloneable,
+        comparable,
         ..Default::default()
     };
 
@@ -129,7 +295,7 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
         bail!("The `#[php_module]` macro must be called last to ensure functions and classes are registered.");
     }
 
-    if state.startup_function.is_some() {
+    if !state.startup_functions.is_empty() {
         bail!("The `#[php_startup]` macro must be called after all the classes have been defined.");
     }
 
@@ -142,12 +308,151 @@ pub fn parser(args: AttributeArgs, mut input: ItemStruct) -> Result<TokenStream>
     })
 }
 
+impl Class {
+    /// Checks that the class implements the methods required by the PHP
+    /// interfaces given through `implements`, and does not declare the magic
+    /// methods registered by struct attributes. Must be called once all impls
+    /// of the class have been parsed.
+    pub fn check_interfaces(&self) -> Result<()> {
+        for interface in &self.implements {
+            let lookup = interface.trim_start_matches('\\');
+            let provided_by_attr = match lookup.to_lowercase().as_str() {
+                "iterator" => self.iterator,
+                "arrayaccess" => self.array_access,
+                "countable" => self.countable,
+                "stringable" => self.stringable,
+                "jsonserializable" => self.json_serializable,
+                _ => false,
+            };
+            if provided_by_attr {
+                bail!(
+                    "Class `{}` already implements `{}` through a struct attribute.",
+                    self.class_name,
+                    interface
+                );
+            }
+
+            let required = INTERFACE_METHODS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(lookup))
+                .map(|(_, methods)| *methods)
+                .unwrap_or_default();
+
+            for method in required {
+                // PHP method names are case-insensitive.
+                if !self
+                    .methods
+                    .iter()
+                    .any(|m| m.name.eq_ignore_ascii_case(method))
+                {
+                    bail!(
+                        "Class `{}` implements `{}` but is missing the `{}` method.",
+                        self.class_name,
+                        interface,
+                        method
+                    );
+                }
+            }
+        }
+
+        let registered = self
+            .serializable
+            .then(|| ("serializable", &["__serialize", "__unserialize"][..]))
+            .into_iter()
+            .chain(
+                self.debug_info
+                    .then(|| ("debug_info", &["__debugInfo"][..])),
+            );
+        for (attr, magic_methods) in registered {
+            if let Some(method) = self.methods.iter().find(|m| {
+                magic_methods
+                    .iter()
+                    .any(|magic| m.name.eq_ignore_ascii_case(magic))
+            }) {
+                bail!(
+                    "Class `{}` is `#[{}]` and cannot declare the `{}` method.",
+                    self.class_name,
+                    attr,
+                    method.name
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct Property {
     pub ty: PropertyType,
     pub docs: Vec<String>,
     #[allow(dead_code)]
     pub flags: Option<String>,
+    pub readonly: bool,
+    /// Rust type of the field, used to derive the PHP type of field
+    /// properties.
+    pub field_ty: Option<String>,
+    /// PHP type given through `#[prop(ty = "...")]`, overriding the type
+    /// derived from the Rust type of the field.
+    pub php_ty: Option<PhpType>,
+}
+
+/// A PHP type declaration, e.g. `?int` or `int|string|null`.
+#[derive(Debug)]
+pub struct PhpType {
+    pub types: Vec<String>,
+    pub nullable: bool,
+}
+
+impl PhpType {
+    pub fn parse(ty: &str) -> Result<Self> {
+        let (members, short_nullable) = match ty.trim().strip_prefix('?') {
+            Some(members) => (members, true),
+            None => (ty.trim(), false),
+        };
+        let mut types = vec![];
+        let mut nullable = short_nullable;
+
+        for member in members.split('|').map(str::trim) {
+            match member.to_ascii_lowercase().as_str() {
+                "" => bail!("Invalid PHP type `{}`.", ty),
+                "null" => nullable = true,
+                _ => types.push(member.to_string()),
+            }
+        }
+
+        if types.is_empty() || (short_nullable && types.len() > 1) {
+            bail!("Invalid PHP type `{}`.", ty);
+        }
+
+        Ok(Self { types, nullable })
+    }
+
+    /// Returns the `DataType` of each member of the type.
+    pub fn data_types(&self) -> Vec<TokenStream> {
+        self.types
+            .iter()
+            .map(|ty| {
+                let variant = match ty.to_ascii_lowercase().as_str() {
+                    "int" => quote! { Long },
+                    "float" => quote! { Double },
+                    "string" => quote! { String },
+                    "bool" => quote! { Bool },
+                    "true" => quote! { True },
+                    "false" => quote! { False },
+                    "array" => quote! { Array },
+                    "object" => quote! { Object(None) },
+                    "mixed" => quote! { Mixed },
+                    "callable" => quote! { Callable },
+                    _ => {
+                        let class = ty.trim_start_matches('\\');
+                        quote! { Object(Some(#class)) }
+                    }
+                };
+                quote! { ::ext_php_rs::flags::DataType::#variant }
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -158,14 +463,19 @@ pub enum PropertyType {
     Method {
         getter: Option<String>,
         setter: Option<String>,
+        /// Whether the getter returns a `Result`, which is thrown as an
+        /// exception when reading the property fails.
+        fallible: bool,
     },
 }
 
 impl Property {
-    pub fn add_getter(&mut self, new_getter: String) -> Result<()> {
+    pub fn add_getter(&mut self, new_getter: String, is_fallible: bool) -> Result<()> {
         match &mut self.ty {
             PropertyType::Field { .. } => bail!("Cannot add getter to field property."),
-            PropertyType::Method { getter, setter: _ } => match getter {
+            PropertyType::Method {
+                getter, fallible, ..
+            } => match getter {
                 Some(getter) => bail!(
                     "Attempted to add getter `{}` to property that already has a getter `{}`.",
                     new_getter,
@@ -173,6 +483,7 @@ impl Property {
                 ),
                 None => {
                     getter.replace(new_getter);
+                    *fallible = is_fallible;
                     Ok(())
                 }
             },
@@ -182,7 +493,7 @@ impl Property {
     pub fn add_setter(&mut self, new_setter: String) -> Result<()> {
         match &mut self.ty {
             PropertyType::Field { .. } => bail!("Cannot add setter to field property."),
-            PropertyType::Method { getter: _, setter } => match setter {
+            PropertyType::Method { setter, .. } => match setter {
                 Some(getter) => bail!(
                     "Attempted to add setter `{}` to property that already has a setter `{}`.",
                     new_setter,
@@ -196,11 +507,19 @@ impl Property {
         }
     }
 
-    pub fn field(field_name: String, docs: Vec<String>, flags: Option<String>) -> Self {
+    pub fn field(
+        field_name: String,
+        field_ty: String,
+        docs: Vec<String>,
+        flags: Option<String>,
+    ) -> Self {
         Self {
             ty: PropertyType::Field { field_name },
             docs,
             flags,
+            readonly: false,
+            field_ty: Some(field_ty),
+            php_ty: None,
         }
     }
 
@@ -209,21 +528,38 @@ impl Property {
             ty: PropertyType::Method {
                 getter: None,
                 setter: None,
+                fallible: false,
             },
             docs,
             flags,
+            readonly: false,
+            field_ty: None,
+            php_ty: None,
         }
     }
 
+    pub fn set_readonly(&mut self) {
+        self.readonly = true;
+    }
+
     pub fn as_prop_tuple(&self, name: &str) -> TokenStream {
         match &self.ty {
             PropertyType::Field { field_name } => {
                 let field_name = Ident::new(field_name, Span::call_site());
+                let ctor = if self.readonly {
+                    quote! { readonly_field }
+                } else {
+                    quote! { field }
+                };
                 quote! {
-                    (#name, ::ext_php_rs::props::Property::field(|obj: &mut Self| &mut obj.#field_name)),
+                    (#name, ::ext_php_rs::props::Property::#ctor(|obj: &mut Self| &mut obj.#field_name)),
                 }
             }
-            PropertyType::Method { getter, setter } => {
+            PropertyType::Method {
+                getter,
+                setter,
+                fallible,
+            } => {
                 let getter = if let Some(getter) = getter {
                     let ident = Ident::new(getter, Span::call_site());
                     quote! { Some(Self::#ident) }
@@ -236,18 +572,82 @@ impl Property {
                 } else {
                     quote! { None }
                 };
+                let ctor = if *fallible {
+                    quote! { fallible_method }
+                } else {
+                    quote! { method }
+                };
                 quote! {
-                    (#name, ::ext_php_rs::props::Property::method(#getter, #setter)),
+                    (#name, ::ext_php_rs::props::Property::#ctor(#getter, #setter)),
                 }
             }
         }
     }
 }
 
+#[derive(Debug)]
+pub struct StaticProperty {
+    pub name: String,
+    pub ty: String,
+    pub default: String,
+}
+
+impl StaticProperty {
+    pub fn as_builder_call(&self) -> Result<TokenStream> {
+        let name = &self.name;
+        let ty: syn::Type = syn::parse_str(&self.ty)
+            .map_err(|_| anyhow!("Invalid type given for static property `{}`", name))?;
+        let default: Expr = syn::parse_str(&self.default)
+            .map_err(|_| anyhow!("Invalid default given for static property `{}`", name))?;
+
+        let nullable = is_option(&ty);
+
+        Ok(quote! {
+            .typed_property(
+                #name,
+                { let default: #ty = #default; default },
+                #nullable,
+                PropertyFlags::Public | PropertyFlags::Static,
+            )
+        })
+    }
+}
+
+/// Returns true if the given type is an `Option`, which is converted into a
+/// nullable PHP type.
+pub fn is_option(ty: &syn::Type) -> bool {
+    matches!(
+        ty,
+        syn::Type::Path(path) if path.path.segments.last().is_some_and(|seg| seg.ident == "Option")
+    )
+}
+
+/// Parsed from `#[static_prop(name: Type = default)]`.
+#[derive(Debug)]
+pub struct StaticPropertyAttr {
+    pub name: Ident,
+    pub ty: syn::Type,
+    pub default: Expr,
+}
+
+impl syn::parse::Parse for StaticPropertyAttr {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let default = input.parse()?;
+
+        Ok(Self { name, ty, default })
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct PropertyAttr {
     pub rename: Option<String>,
     pub flags: Option<Expr>,
+    pub readonly: bool,
+    pub ty: Option<String>,
 }
 
 impl syn::parse::Parse for PropertyAttr {
@@ -255,6 +655,11 @@ impl syn::parse::Parse for PropertyAttr {
         let mut this = Self::default();
         while !input.is_empty() {
             let field = input.parse::<Ident>()?.to_string();
+            if field == "readonly" {
+                this.readonly = true;
+                let _ = input.parse::<Token![,]>();
+                continue;
+            }
             input.parse::<Token![=]>()?;
 
             match field.as_str() {
@@ -264,6 +669,9 @@ impl syn::parse::Parse for PropertyAttr {
                 "flags" => {
                     this.flags.replace(input.parse::<Expr>()?);
                 }
+                "ty" => {
+                    this.ty.replace(input.parse::<LitStr>()?.value());
+                }
                 _ => return Err(input.error("invalid attribute field")),
             }
 
@@ -290,6 +698,22 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
                 .map_err(|_| anyhow!("Unable to parse `#[{}]` attribute.", name))?;
             Some(ParsedAttribute::Implements(meta))
         }
+        "uses" => {
+            let ident: Ident = attr
+                .parse_args()
+                .map_err(|_| anyhow!("Unable to parse `#[{}]` attribute.", name))?;
+            Some(ParsedAttribute::Uses(ident))
+        }
+        "iterator" => Some(ParsedAttribute::Iterator),
+        "array_access" => Some(ParsedAttribute::ArrayAccess),
+        "countable" => Some(ParsedAttribute::Countable),
+        "stringable" => Some(ParsedAttribute::Stringable),
+        "json_serializable" => Some(ParsedAttribute::JsonSerializable),
+        "serializable" => Some(ParsedAttribute::Serializable),
+        "debug_info" => Some(ParsedAttribute::DebugInfo),
+        "magic_properties" => Some(ParsedAttribute::MagicProperties),
+        "cloneable" => Some(ParsedAttribute::Cloneable),
+        "comparable" => Some(ParsedAttribute::Comparable),
         "doc" => {
             struct DocComment(pub String);
 
@@ -315,6 +739,64 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
 
             Some(ParsedAttribute::Property(attr))
         }
+        "static_prop" | "static_property" => {
+            let attr = attr
+                .parse_args()
+                .map_err(|e| anyhow!("Unable to parse `#[{}]` attribute: {}", name, e))?;
+
+            Some(ParsedAttribute::StaticProperty(attr))
+        }
+        "php" => {
+            let meta = attr
+                .parse_meta()
+                .map_err(|e| anyhow!("Unable to parse `#[php]` attribute: {}", e))?;
+            let attr = crate::impl_::PhpAttr::from_meta(&meta)
+                .map_err(|e| anyhow!("Unable to parse `#[php]` attribute: {}", e))?;
+
+            Some(ParsedAttribute::Php(attr))
+        }
         _ => None,
     })
 }
+
+/// Returns true if the attribute is a `#[derive]` attribute which derives the
+/// trait with the given name.
+fn derives(attr: &Attribute, name: &str) -> bool {
+    if !attr.path.is_ident("derive") {
+        return false;
+    }
+
+    attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+        .is_ok_and(|paths| {
+            paths.iter().any(|path| {
+                path.segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == name)
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::derives;
+    use syn::{parse_quote, ItemStruct};
+
+    #[test]
+    fn test_derives() {
+        let input: ItemStruct = parse_quote! {
+            #[derive(Debug, std::clone::Clone, PartialEq, PartialOrd)]
+            #[doc = "Clone"]
+            struct Counter;
+        };
+        assert!(derives(&input.attrs[0], "Clone"));
+        assert!(derives(&input.attrs[0], "PartialOrd"));
+        assert!(!derives(&input.attrs[0], "Ord"));
+        assert!(!derives(&input.attrs[1], "Clone"));
+
+        let input: ItemStruct = parse_quote! {
+            #[derive(Debug, Default)]
+            struct Counter;
+        };
+        assert!(!derives(&input.attrs[0], "Clone"));
+    }
+}
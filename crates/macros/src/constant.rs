@@ -1,9 +1,11 @@
-use crate::helpers::get_docs;
+use std::convert::TryInto;
+
+use crate::helpers::{get_docs, php_string};
 use anyhow::{bail, Result};
 use darling::ToTokens;
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Expr, ItemConst};
+use syn::{BinOp, Expr, ExprArray, ExprPath, ItemConst, Lit, Type, UnOp};
 
 use crate::STATE;
 
@@ -13,20 +15,32 @@ pub struct Constant {
     // pub visibility: Visibility,
     pub docs: Vec<String>,
     pub value: String,
+    /// The value of the constant as a PHP literal, to be used in stubs. [`None`]
+    /// if the value could not be resolved from the expression.
+    pub literal: Option<String>,
+    /// The Rust type of the constant. Only set on class constants, as constants
+    /// outside of classes cannot be typed in PHP.
+    pub ty: Option<String>,
+    /// The scalar value of the constant, used when evaluating the constants
+    /// which refer to it.
+    scalar: Option<Value>,
 }
 
 pub fn parser(input: ItemConst) -> Result<TokenStream> {
     let mut state = STATE.lock();
 
-    if state.startup_function.is_some() {
+    if !state.startup_functions.is_empty() {
         bail!("Constants must be declared before you declare your startup function and module function.");
     }
 
-    state.constants.push(Constant {
-        name: input.ident.to_string(),
-        docs: get_docs(&input.attrs),
-        value: input.expr.to_token_stream().to_string(),
-    });
+    let constant = Constant::new(
+        input.ident.to_string(),
+        get_docs(&input.attrs),
+        &input.expr,
+        &input.ty,
+        &state.constants,
+    );
+    state.constants.push(constant);
 
     Ok(quote! {
         #[allow(dead_code)]
@@ -35,6 +49,28 @@ pub fn parser(input: ItemConst) -> Result<TokenStream> {
 }
 
 impl Constant {
+    /// Creates a constant of the given type from the Rust expression of its
+    /// value. The expression may refer to the previously declared `constants`,
+    /// which are replaced by their values when resolving the PHP literal of the
+    /// constant.
+    pub fn new(
+        name: String,
+        docs: Vec<String>,
+        expr: &Expr,
+        ty: &Type,
+        constants: &[Constant],
+    ) -> Self {
+        let scalar_ty = scalar_type(ty);
+        Self {
+            name,
+            docs,
+            value: expr.to_token_stream().to_string(),
+            literal: php_literal(expr, scalar_ty.as_deref(), constants),
+            ty: None,
+            scalar: evaluate(expr, scalar_ty.as_deref(), constants),
+        }
+    }
+
     pub fn val_tokens(&self) -> TokenStream {
         let expr: Expr =
             syn::parse_str(&self.value).expect("failed to parse previously parsed expr");
@@ -51,3 +87,366 @@ impl Constant {
     //     quote! { ::ext_php_rs::flags::ConstantFlags}
     // }
 }
+
+/// Returns the name of the scalar type making up the given type, looking
+/// through references, slices, arrays and the values of `(&str, T)` pairs.
+fn scalar_type(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.get_ident().map(ToString::to_string),
+        Type::Reference(reference) => scalar_type(&reference.elem),
+        Type::Slice(slice) => scalar_type(&slice.elem),
+        Type::Array(array) => scalar_type(&array.elem),
+        Type::Tuple(tuple) => scalar_type(tuple.elems.last()?),
+        Type::Paren(paren) => scalar_type(&paren.elem),
+        Type::Group(group) => scalar_type(&group.elem),
+        _ => None,
+    }
+}
+
+/// Converts a Rust expression made up of literals into the equivalent PHP
+/// literal. Slices of `(&str, T)` pairs are converted into associative arrays,
+/// in the same way as they are registered. Arithmetic on numbers and references
+/// to the given constants are evaluated.
+///
+/// `ty` is the scalar type of the numbers in the expression, as declared on the
+/// constant.
+fn php_literal(expr: &Expr, ty: Option<&str>, constants: &[Constant]) -> Option<String> {
+    if let Some(value) = evaluate(expr, ty, constants) {
+        return value.php_literal();
+    }
+
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(s) => Some(php_string(&s.value())),
+            _ => None,
+        },
+        Expr::Path(path) => constants[find_constant(path, constants)?].literal.clone(),
+        Expr::Reference(reference) => php_literal(&reference.expr, ty, constants),
+        Expr::Paren(paren) => php_literal(&paren.expr, ty, constants),
+        Expr::Group(group) => php_literal(&group.expr, ty, constants),
+        Expr::Array(array) => php_array(array, ty, constants),
+        Expr::Tuple(tuple) => Some(format!(
+            "[{}]",
+            tuple
+                .elems
+                .iter()
+                .map(|elem| php_literal(elem, ty, constants))
+                .collect::<Option<Vec<_>>>()?
+                .join(", ")
+        )),
+        _ => None,
+    }
+}
+
+/// A scalar value evaluated from a constant expression. Only the types PHP
+/// represents numbers with are evaluated, so the result of each operation is
+/// the same as in Rust.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Value {
+    fn php_literal(self) -> Option<String> {
+        match self {
+            Value::Int(i) => Some(i.to_string()),
+            // Floats are formatted with a decimal point, so whole numbers are not
+            // read as integers by PHP.
+            Value::Float(f) if f.is_finite() => Some(format!("{:?}", f)),
+            Value::Float(_) => None,
+            Value::Bool(b) => Some(b.to_string()),
+        }
+    }
+}
+
+/// Evaluates an expression made up of scalar literals, arithmetic, casts and
+/// references to the given constants. Returns [`None`] if the expression
+/// cannot be evaluated, or overflows.
+///
+/// `ty` is the type of the unsuffixed literals in the expression. Numbers are
+/// only evaluated when their suffix or type is `i64` or `f64`, as the result of
+/// operations on other types depends on their width, such as `!0u8` or
+/// `300 as u8`. For the same reason, casts are only evaluated when they do not
+/// change the type of the value.
+fn evaluate(expr: &Expr, ty: Option<&str>, constants: &[Constant]) -> Option<Value> {
+    match expr {
+        Expr::Lit(lit) => {
+            let is_type = |suffix: &str, name: &str| match suffix {
+                "" => ty == Some(name),
+                suffix => suffix == name,
+            };
+            match &lit.lit {
+                Lit::Int(i) if is_type(i.suffix(), "i64") => i.base10_parse().ok().map(Value::Int),
+                Lit::Float(f) if is_type(f.suffix(), "f64") => {
+                    f.base10_parse().ok().map(Value::Float)
+                }
+                Lit::Bool(b) => Some(Value::Bool(b.value)),
+                _ => None,
+            }
+        }
+        Expr::Path(path) => constants[find_constant(path, constants)?].scalar,
+        Expr::Unary(unary) => match (unary.op, evaluate(&unary.expr, ty, constants)?) {
+            (UnOp::Neg(_), Value::Int(i)) => i.checked_neg().map(Value::Int),
+            (UnOp::Neg(_), Value::Float(f)) => Some(Value::Float(-f)),
+            (UnOp::Not(_), Value::Int(i)) => Some(Value::Int(!i)),
+            (UnOp::Not(_), Value::Bool(b)) => Some(Value::Bool(!b)),
+            _ => None,
+        },
+        // The type of the shift amount does not have to match the shifted value.
+        Expr::Binary(binary) => binary_op(
+            &binary.op,
+            evaluate(&binary.left, ty, constants)?,
+            match binary.op {
+                BinOp::Shl(_) | BinOp::Shr(_) => evaluate(&binary.right, Some("i64"), constants)?,
+                _ => evaluate(&binary.right, ty, constants)?,
+            },
+        ),
+        Expr::Cast(cast) => {
+            let ty = match &*cast.ty {
+                Type::Path(path) => path.path.get_ident()?.to_string(),
+                _ => return None,
+            };
+            // The type of unsuffixed literals in a cast is not inferred from
+            // the type they are cast to.
+            match (ty.as_str(), evaluate(&cast.expr, None, constants)?) {
+                ("i64", value @ Value::Int(_)) | ("f64", value @ Value::Float(_)) => Some(value),
+                _ => None,
+            }
+        }
+        Expr::Paren(paren) => evaluate(&paren.expr, ty, constants),
+        Expr::Group(group) => evaluate(&group.expr, ty, constants),
+        _ => None,
+    }
+}
+
+/// Applies a binary operator to two evaluated values of the same type, as
+/// Rust does not mix types in arithmetic.
+fn binary_op(op: &BinOp, left: Value, right: Value) -> Option<Value> {
+    Some(match (left, right) {
+        (Value::Int(l), Value::Int(r)) => Value::Int(match op {
+            BinOp::Add(_) => l.checked_add(r)?,
+            BinOp::Sub(_) => l.checked_sub(r)?,
+            BinOp::Mul(_) => l.checked_mul(r)?,
+            BinOp::Div(_) => l.checked_div(r)?,
+            BinOp::Rem(_) => l.checked_rem(r)?,
+            BinOp::BitAnd(_) => l & r,
+            BinOp::BitOr(_) => l | r,
+            BinOp::BitXor(_) => l ^ r,
+            BinOp::Shl(_) => l.checked_shl(r.try_into().ok()?)?,
+            BinOp::Shr(_) => l.checked_shr(r.try_into().ok()?)?,
+            _ => return None,
+        }),
+        (Value::Float(l), Value::Float(r)) => Value::Float(match op {
+            BinOp::Add(_) => l + r,
+            BinOp::Sub(_) => l - r,
+            BinOp::Mul(_) => l * r,
+            BinOp::Div(_) => l / r,
+            BinOp::Rem(_) => l % r,
+            _ => return None,
+        }),
+        (Value::Bool(l), Value::Bool(r)) => Value::Bool(match op {
+            BinOp::And(_) | BinOp::BitAnd(_) => l && r,
+            BinOp::Or(_) | BinOp::BitOr(_) => l || r,
+            BinOp::BitXor(_) => l ^ r,
+            _ => return None,
+        }),
+        _ => return None,
+    })
+}
+
+/// Returns the index of the constant referred to by a path, either by name or
+/// through `Self`.
+fn find_constant(path: &ExprPath, constants: &[Constant]) -> Option<usize> {
+    let segments = &path.path.segments;
+    let name = match segments.len() {
+        1 => &segments[0].ident,
+        2 if segments[0].ident == "Self" => &segments[1].ident,
+        _ => return None,
+    };
+    constants.iter().rposition(|c| name == c.name.as_str())
+}
+
+/// Converts a Rust array expression into a PHP array literal.
+fn php_array(array: &ExprArray, ty: Option<&str>, constants: &[Constant]) -> Option<String> {
+    let pairs = array
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            Expr::Tuple(tuple) if tuple.elems.len() == 2 => match &tuple.elems[0] {
+                Expr::Lit(lit) if matches!(lit.lit, Lit::Str(_)) => {
+                    Some((&tuple.elems[0], &tuple.elems[1]))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+
+    let elems = match pairs {
+        Some(pairs) if !pairs.is_empty() => pairs
+            .into_iter()
+            .map(|(key, val)| {
+                Some(format!(
+                    "{} => {}",
+                    php_literal(key, ty, constants)?,
+                    php_literal(val, ty, constants)?
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?,
+        _ => array
+            .elems
+            .iter()
+            .map(|elem| php_literal(elem, ty, constants))
+            .collect::<Option<Vec<_>>>()?,
+    };
+
+    Some(format!("[{}]", elems.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{php_literal, Constant};
+    use syn::parse_quote;
+
+    #[test]
+    fn test_php_literal() {
+        let i64 = Some("i64");
+        assert_eq!(
+            php_literal(&parse_quote!(100), i64, &[]),
+            Some("100".into())
+        );
+        assert_eq!(
+            php_literal(&parse_quote!(-1.5f64), None, &[]),
+            Some("-1.5".into())
+        );
+        assert_eq!(
+            php_literal(&parse_quote!("it's \\ here"), None, &[]),
+            Some("'it\\'s \\\\ here'".into())
+        );
+        assert_eq!(
+            php_literal(&parse_quote!(&[1, 2, 3]), i64, &[]),
+            Some("[1, 2, 3]".into())
+        );
+        assert_eq!(
+            php_literal(
+                &parse_quote!(&[("timeout", 30), ("verbose", false)]),
+                i64,
+                &[]
+            ),
+            Some("['timeout' => 30, 'verbose' => false]".into())
+        );
+        assert_eq!(
+            php_literal(&parse_quote!(&[]), None, &[]),
+            Some("[]".into())
+        );
+        assert_eq!(php_literal(&parse_quote!(OTHER + 1), i64, &[]), None);
+        assert_eq!(php_literal(&parse_quote!(&[("a", OTHER)]), i64, &[]), None);
+    }
+
+    #[test]
+    fn test_php_literal_widths() {
+        let literal = |expr, ty| php_literal(&expr, ty, &[]);
+
+        assert_eq!(literal(parse_quote!(!0), Some("i64")), Some("-1".into()));
+        assert_eq!(literal(parse_quote!(!0i64), None), Some("-1".into()));
+        assert_eq!(
+            literal(parse_quote!(1 << 63), Some("i64")),
+            Some((i64::MIN).to_string())
+        );
+        assert_eq!(literal(parse_quote!(1.5), Some("f64")), Some("1.5".into()));
+        assert_eq!(literal(parse_quote!(5i64 as i64), None), Some("5".into()));
+        assert_eq!(
+            literal(parse_quote!(0.5f64 as f64), None),
+            Some("0.5".into())
+        );
+
+        // The result of operations on other types depends on their width.
+        assert_eq!(literal(parse_quote!(!0u8), None), None);
+        assert_eq!(literal(parse_quote!(!0), Some("u32")), None);
+        assert_eq!(literal(parse_quote!(0.1), Some("f32")), None);
+        assert_eq!(literal(parse_quote!(0.1f32), None), None);
+        assert_eq!(literal(parse_quote!(&[1, 2]), Some("u8")), None);
+        assert_eq!(literal(parse_quote!(5), None), None);
+
+        // Casts which narrow or change the type of the value.
+        assert_eq!(literal(parse_quote!(300 as u8), Some("u8")), None);
+        assert_eq!(literal(parse_quote!(-1i64 as u64), Some("u64")), None);
+        assert_eq!(literal(parse_quote!(1i64 as i128), Some("i128")), None);
+        assert_eq!(literal(parse_quote!(5i64 as f64), Some("f64")), None);
+        assert_eq!(literal(parse_quote!(1.5f64 as i64), Some("i64")), None);
+        assert_eq!(literal(parse_quote!(1.5f64 as f32), Some("f32")), None);
+        assert_eq!(literal(parse_quote!(true as i64), Some("i64")), None);
+        assert_eq!(literal(parse_quote!(5 as i64), Some("i64")), None);
+    }
+
+    #[test]
+    fn test_interdependent_constants() {
+        let mut constants: Vec<Constant> = vec![];
+        let mut add = |name: &str, expr, ty| {
+            let constant = Constant::new(name.into(), vec![], &expr, &ty, &constants);
+            let literal = constant.literal.clone();
+            constants.push(constant);
+            literal
+        };
+
+        assert_eq!(
+            add("A", parse_quote!(1), parse_quote!(i64)),
+            Some("1".into())
+        );
+        assert_eq!(
+            add("B", parse_quote!(Self::A + 1), parse_quote!(i64)),
+            Some("2".into())
+        );
+        assert_eq!(
+            add("C", parse_quote!((B * 4) << A), parse_quote!(i64)),
+            Some("16".into())
+        );
+        assert_eq!(
+            add("D", parse_quote!(Self::C as i64 / 8), parse_quote!(i64)),
+            Some("2".into())
+        );
+        assert_eq!(
+            add("E", parse_quote!(-Self::A), parse_quote!(i64)),
+            Some("-1".into())
+        );
+        assert_eq!(
+            add("NAME", parse_quote!("test"), parse_quote!(&str)),
+            Some("'test'".into())
+        );
+        assert_eq!(
+            add(
+                "NAMES",
+                parse_quote!(&[Self::NAME, "other"]),
+                parse_quote!(&[&str])
+            ),
+            Some("['test', 'other']".into())
+        );
+        assert_eq!(add("F", parse_quote!(Self::G + 1), parse_quote!(i64)), None);
+        assert_eq!(add("G", parse_quote!(Self::A / 0), parse_quote!(i64)), None);
+        assert_eq!(
+            add("H", parse_quote!(Self::A + 1.5), parse_quote!(i64)),
+            None
+        );
+        assert_eq!(
+            add("I", parse_quote!(Other::A + 1), parse_quote!(i64)),
+            None
+        );
+
+        // Constants of other types are not evaluated, even when cast.
+        assert_eq!(add("MASK", parse_quote!(!0), parse_quote!(u32)), None);
+        assert_eq!(
+            add("J", parse_quote!(Self::MASK as i64), parse_quote!(i64)),
+            None
+        );
+        assert_eq!(
+            add(
+                "K",
+                parse_quote!(&[("a", Self::A), ("b", 2)]),
+                parse_quote!(&[(&str, i64)])
+            ),
+            Some("['a' => 1, 'b' => 2]".into())
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use anyhow::{anyhow, bail, Result};
+use darling::FromMeta;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{AttributeArgs, Expr, Fields, ItemEnum, Lit, UnOp};
+
+use crate::{helpers::get_docs, STATE};
+
+#[derive(Debug)]
+pub struct Enum {
+    pub enum_name: String,
+    pub struct_path: String,
+    pub docs: Vec<String>,
+    pub cases: Vec<Case>,
+}
+
+#[derive(Debug)]
+pub struct Case {
+    pub name: String,
+    pub docs: Vec<String>,
+    pub value: CaseValue,
+}
+
+/// Value of a case, given through the discriminant of an int-backed enum or
+/// `#[php(value = "...")]` for a string-backed enum.
+#[derive(Debug, PartialEq)]
+pub enum CaseValue {
+    Pure,
+    Long(i64),
+    String(String),
+}
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    name: Option<String>,
+}
+
+/// Options given to a variant through `#[php(...)]`.
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct VariantAttr {
+    name: Option<String>,
+    value: Option<String>,
+}
+
+pub fn parser(args: AttributeArgs, mut input: ItemEnum) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
+
+    let ident = input.ident.clone();
+    let enum_name = args.name.unwrap_or_else(|| ident.to_string());
+    let meta = Ident::new(&format!("_{}_ENUM_META", ident), Span::call_site());
+
+    let (idents, cases) = parse_cases(&enum_name, &mut input)?;
+
+    let names: Vec<_> = cases.iter().map(|case| case.name.clone()).collect();
+    let enum_ = Enum {
+        enum_name: enum_name.clone(),
+        struct_path: ident.to_string(),
+        docs: get_docs(&input.attrs),
+        cases,
+    };
+
+    let mut state = STATE.lock();
+
+    if state.built_module {
+        bail!("The `#[php_module]` macro must be called last to ensure functions and classes are registered.");
+    }
+
+    if !state.startup_functions.is_empty() {
+        bail!("The `#[php_startup]` macro must be called after all the enums have been defined.");
+    }
+
+    state.enums.push(enum_);
+
+    Ok(quote! {
+        #input
+
+        static #meta: ::ext_php_rs::enum_::EnumMetadata = ::ext_php_rs::enum_::EnumMetadata::new();
+
+        impl ::ext_php_rs::enum_::RegisteredEnum for #ident {
+            const ENUM_NAME: &'static str = #enum_name;
+
+            fn get_metadata() -> &'static ::ext_php_rs::enum_::EnumMetadata {
+                &#meta
+            }
+
+            fn case_name(&self) -> &'static str {
+                match *self {
+                    #(Self::#idents => #names,)*
+                }
+            }
+
+            fn from_case_name(name: &str) -> ::std::option::Option<Self> {
+                match name {
+                    #(#names => ::std::option::Option::Some(Self::#idents),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+
+        ::ext_php_rs::enum_derives!(#ident);
+    })
+}
+
+/// Parses the variants of the enum into cases, removing the `#[php]` attributes
+/// from the variants. Returns the identifiers of the variants along with the
+/// cases.
+fn parse_cases(enum_name: &str, input: &mut ItemEnum) -> Result<(Vec<Ident>, Vec<Case>)> {
+    let mut cases = Vec::with_capacity(input.variants.len());
+    let mut idents = Vec::with_capacity(input.variants.len());
+    let mut next_discriminant = 0i64;
+
+    for variant in input.variants.iter_mut() {
+        if !matches!(variant.fields, Fields::Unit) {
+            bail!(
+                "Variant `{}` has fields - only unit variants can be exported as enum cases.",
+                variant.ident
+            );
+        }
+
+        let mut attr = VariantAttr::default();
+        let mut attrs = vec![];
+        for a in variant.attrs.drain(..) {
+            if a.path.is_ident("php") {
+                attr = VariantAttr::from_meta(&a.parse_meta()?)
+                    .map_err(|e| anyhow!("Unable to parse `#[php]` attribute: {:?}", e))?;
+            } else {
+                attrs.push(a);
+            }
+        }
+        variant.attrs = attrs;
+
+        let value = match (&variant.discriminant, attr.value) {
+            (Some(_), Some(_)) => bail!(
+                "Variant `{}` cannot have both a discriminant and a string value.",
+                variant.ident
+            ),
+            (Some((_, expr)), None) => {
+                let value = discriminant(expr).ok_or_else(|| {
+                    anyhow!(
+                        "The discriminant of variant `{}` must be an integer literal.",
+                        variant.ident
+                    )
+                })?;
+                next_discriminant = value.wrapping_add(1);
+                CaseValue::Long(value)
+            }
+            (None, Some(value)) => CaseValue::String(value),
+            (None, None) => {
+                let value = next_discriminant;
+                next_discriminant = value.wrapping_add(1);
+                CaseValue::Long(value)
+            }
+        };
+
+        cases.push(Case {
+            name: attr.name.unwrap_or_else(|| variant.ident.to_string()),
+            docs: get_docs(&variant.attrs),
+            value,
+        });
+        idents.push(variant.ident.clone());
+    }
+
+    let cases = backed_cases(
+        cases,
+        input.variants.iter().any(|v| v.discriminant.is_some()),
+    )?;
+    for (i, case) in cases.iter().enumerate() {
+        if cases[..i].iter().any(|other| other.name == case.name) {
+            bail!(
+                "Enum `{}` has more than one case named `{}`.",
+                enum_name,
+                case.name
+            );
+        }
+        if case.value != CaseValue::Pure && cases[..i].iter().any(|other| other.value == case.value)
+        {
+            bail!(
+                "Case `{}` of enum `{}` has the same value as another case.",
+                case.name,
+                enum_name
+            );
+        }
+    }
+
+    Ok((idents, cases))
+}
+
+/// Determines the kind of enum from the values of its cases. An enum without
+/// discriminants or string values is a pure enum, so the implicit
+/// discriminants are removed.
+fn backed_cases(mut cases: Vec<Case>, has_discriminants: bool) -> Result<Vec<Case>> {
+    let strings = cases
+        .iter()
+        .filter(|case| matches!(case.value, CaseValue::String(_)))
+        .count();
+
+    if strings == 0 && !has_discriminants {
+        for case in cases.iter_mut() {
+            case.value = CaseValue::Pure;
+        }
+    } else if strings != 0 && strings != cases.len() {
+        bail!("Either all or none of the variants of an enum must have a string value.");
+    }
+
+    Ok(cases)
+}
+
+/// Returns the value of an integer literal discriminant.
+fn discriminant(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(i) => i.base10_parse().ok(),
+            _ => None,
+        },
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+            discriminant(&unary.expr).map(|value| -value)
+        }
+        Expr::Paren(paren) => discriminant(&paren.expr),
+        Expr::Group(group) => discriminant(&group.expr),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{discriminant, parse_cases, CaseValue};
+    use syn::{parse_quote, ItemEnum};
+
+    #[test]
+    fn test_discriminant() {
+        assert_eq!(discriminant(&parse_quote!(3)), Some(3));
+        assert_eq!(discriminant(&parse_quote!(-2)), Some(-2));
+        assert_eq!(discriminant(&parse_quote!(1 << 2)), None);
+    }
+
+    #[test]
+    fn test_case_values() {
+        let values = |mut input: ItemEnum| {
+            parse_cases("Test", &mut input).map(|(_, cases)| {
+                cases
+                    .into_iter()
+                    .map(|case| (case.name, case.value))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(
+            values(parse_quote! { enum Status { Draft, Published } }).unwrap(),
+            vec![
+                ("Draft".into(), CaseValue::Pure),
+                ("Published".into(), CaseValue::Pure)
+            ]
+        );
+        assert_eq!(
+            values(parse_quote! { enum Level { Low = 1, Medium, High = 10 } }).unwrap(),
+            vec![
+                ("Low".into(), CaseValue::Long(1)),
+                ("Medium".into(), CaseValue::Long(2)),
+                ("High".into(), CaseValue::Long(10))
+            ]
+        );
+        assert_eq!(
+            values(parse_quote! {
+                enum Suit {
+                    #[php(value = "H")]
+                    Hearts,
+                    #[php(name = "Spade", value = "S")]
+                    Spades,
+                }
+            })
+            .unwrap(),
+            vec![
+                ("Hearts".into(), CaseValue::String("H".into())),
+                ("Spade".into(), CaseValue::String("S".into()))
+            ]
+        );
+
+        assert!(values(parse_quote! { enum Mixed { #[php(value = "a")] A, B } }).is_err());
+        assert!(values(parse_quote! { enum Duplicate { A = 1, B = 1 } }).is_err());
+        assert!(values(parse_quote! { enum Fields { A(i32) } }).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::{anyhow, bail, Result};
+use darling::FromMeta;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{AttributeArgs, Data, DeriveInput};
+
+use crate::{helpers::get_docs, STATE};
+
+#[derive(Debug)]
+pub struct Exception {
+    pub class_name: String,
+    pub struct_path: String,
+    pub parent: String,
+    pub docs: Vec<String>,
+}
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    name: Option<String>,
+    extends: Option<String>,
+    code: Option<i32>,
+}
+
+pub fn parser(args: AttributeArgs, input: DeriveInput) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
+
+    if let Data::Union(_) = &input.data {
+        bail!("Only structs and enums can be exported as exceptions.");
+    }
+
+    let DeriveInput { ident, attrs, .. } = &input;
+    let class_name = args.name.unwrap_or_else(|| ident.to_string());
+    let parent = args.extends.unwrap_or_else(|| "Exception".into());
+    let code = args.code.unwrap_or(0);
+    let meta = Ident::new(&format!("_{}_EXCEPTION_META", ident), Span::call_site());
+
+    let exception = Exception {
+        class_name: class_name.clone(),
+        struct_path: ident.to_string(),
+        parent: parent.clone(),
+        docs: get_docs(attrs),
+    };
+
+    let mut state = STATE.lock();
+
+    if state.built_module {
+        bail!("The `#[php_module]` macro must be called last to ensure functions and classes are registered.");
+    }
+
+    if !state.startup_functions.is_empty() {
+        bail!(
+            "The `#[php_startup]` macro must be called after all the exceptions have been defined."
+        );
+    }
+
+    state.exceptions.push(exception);
+
+    Ok(quote! {
+        #input
+
+        static #meta: ::ext_php_rs::exception::ExceptionMetadata =
+            ::ext_php_rs::exception::ExceptionMetadata::new();
+
+        impl ::ext_php_rs::exception::RegisteredException for #ident {
+            const CLASS_NAME: &'static str = #class_name;
+            const PARENT: &'static str = #parent;
+            const CODE: i32 = #code;
+
+            fn get_metadata() -> &'static ::ext_php_rs::exception::ExceptionMetadata {
+                &#meta
+            }
+        }
+
+        impl ::std::convert::From<#ident> for ::ext_php_rs::exception::PhpException {
+            fn from(err: #ident) -> Self {
+                ::ext_php_rs::exception::PhpException::from_exception(err)
+            }
+        }
+    })
+}
@@ -1,14 +1,15 @@
 use std::collections::HashMap;
 
 use crate::helpers::get_docs;
+use crate::impl_::{parse_attribute, ParsedAttribute, PhpAttr, ReturnOverride};
 use crate::{syn_ext::DropLifetimes, STATE};
 use anyhow::{anyhow, bail, Result};
 use darling::{FromMeta, ToTokens};
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::quote;
 use syn::{
-    punctuated::Punctuated, AttributeArgs, FnArg, GenericArgument, ItemFn, Lit, PathArguments,
-    ReturnType, Signature, Token, Type, TypePath,
+    punctuated::Punctuated, Attribute, AttributeArgs, FnArg, GenericArgument, ItemFn, Lit,
+    PathArguments, ReturnType, Signature, Token, Type, TypePath,
 };
 
 #[derive(Default, Debug, FromMeta)]
@@ -17,6 +18,43 @@ pub struct AttrArgs {
     optional: Option<String>,
     ignore_module: bool,
     defaults: HashMap<String, Lit>,
+    executor: Option<String>,
+    variadic: Option<String>,
+    panic: Option<PanicMode>,
+    never: bool,
+    namespace: Option<String>,
+    condition: Option<String>,
+}
+
+/// What happens when a Rust panic reaches a function, given through the
+/// `panic` option.
+#[derive(Debug, Clone, Copy, FromMeta)]
+pub enum PanicMode {
+    #[darling(rename = "abort")]
+    Abort,
+    #[darling(rename = "exception")]
+    Exception,
+}
+
+impl PanicMode {
+    pub fn tokens(self) -> TokenStream {
+        match self {
+            PanicMode::Abort => quote! { ::ext_php_rs::exception::PanicMode::Abort },
+            PanicMode::Exception => quote! { ::ext_php_rs::exception::PanicMode::Exception },
+        }
+    }
+}
+
+/// Returns the panic mode given to `catch_panic` in the generated function. The
+/// module panic mode is used if no mode is given.
+pub fn panic_mode_tokens(mode: Option<PanicMode>) -> TokenStream {
+    match mode {
+        Some(mode) => {
+            let mode = mode.tokens();
+            quote! { Some(#mode) }
+        }
+        None => quote! { None },
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -25,35 +63,76 @@ pub struct Arg {
     pub ty: String,
     pub nullable: bool,
     pub default: Option<String>,
+    pub by_ref: bool,
+    pub variadic: bool,
+    /// Whether the parameter is an `Omittable`, which distinguishes an
+    /// argument given as `null` from an argument which was not given.
+    pub omittable: bool,
+    /// Whether the parameter comes before the first optional parameter, in
+    /// which case it has no default value in PHP.
+    pub required: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct Function {
     pub name: String,
+    /// Whether the name was given explicitly through `#[php(name = "...")]`,
+    /// in which case it is not renamed by the module.
+    pub named: bool,
     pub docs: Vec<String>,
     pub ident: String,
     pub args: Vec<Arg>,
     pub optional: Option<String>,
     pub output: Option<(String, bool)>,
+    /// Whether the function never returns, in which case it is given the
+    /// `never` return type.
+    pub never: bool,
+    /// Namespace given through the `namespace` option, which overrides the
+    /// namespace given to the module.
+    pub namespace: Option<String>,
+    /// Deprecation message given through `#[php(deprecated = "...")]`.
+    pub deprecated: Option<String>,
+    /// Whether PHP raises an `E_DEPRECATED` error when the function is called.
+    pub emit_deprecated: bool,
+    /// Return type override emitted into the `.phpstorm.meta.php` file.
+    pub return_override: Option<ReturnOverride>,
+    /// Path to a `fn() -> bool` given through the `condition` option. The
+    /// function is only registered if it returns true on startup.
+    pub condition: Option<String>,
+    /// Whether the function is hidden from the generated stubs, given through
+    /// `#[php(internal)]`.
+    pub internal: bool,
+    /// PHP attributes given through `#[php(attribute = "...")]`.
+    pub attributes: Vec<String>,
 }
 
-pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Function)> {
+pub fn parser(args: AttributeArgs, mut input: ItemFn) -> Result<(TokenStream, Function)> {
     let attr_args = match AttrArgs::from_list(&args) {
         Ok(args) => args,
         Err(e) => bail!("Unable to parse attribute arguments: {:?}", e),
     };
 
+    let php_attr = take_php_attr(&mut input.attrs)?;
+    let php_name = php_attr.name;
+    let deprecated = php_attr.deprecated.map(|d| d.0);
+    if php_attr.emit_deprecated && deprecated.is_none() {
+        bail!("The `emit_deprecated` option requires the `deprecated` option.");
+    }
+    let mut args = build_args(&mut input.sig.inputs, &attr_args.defaults)?;
+    if let Some(return_override) = &php_attr.return_override {
+        return_override.validate(&input.sig.ident.to_string(), args.len())?;
+    }
     let ItemFn { sig, .. } = &input;
     let Signature {
         ident,
         output,
-        inputs,
+        asyncness,
         ..
     } = &sig;
 
     let internal_ident = Ident::new(&format!("_internal_php_{}", ident), Span::call_site());
-    let args = build_args(inputs, &attr_args.defaults)?;
-    let optional = find_optional_parameter(args.iter(), attr_args.optional);
+    set_variadic_parameter(args.iter_mut(), attr_args.variadic)?;
+    let optional = find_optional_parameter(args.iter_mut(), attr_args.optional)?;
     let arg_definitions = build_arg_definitions(&args);
     let arg_parser = build_arg_parser(
         args.iter(),
@@ -64,7 +143,60 @@ pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Functi
     let arg_accessors = build_arg_accessors(&args);
 
     let return_type = get_return_type(output)?;
+    let call = quote! { #ident(#(#arg_accessors, )*) };
+    let call = if asyncness.is_some() {
+        let executor: TypePath = match attr_args.executor {
+            Some(executor) => syn::parse_str(&executor)
+                .map_err(|_| anyhow!("Invalid executor given for function `{}`.", ident))?,
+            None => syn::parse_quote! { ::ext_php_rs::executor::LocalExecutor },
+        };
+        quote! { ::ext_php_rs::executor::run::<#executor, _>(#call) }
+    } else if attr_args.executor.is_some() {
+        bail!("The `executor` option can only be used on `async` functions.");
+    } else {
+        call
+    };
+
+    let never = matches!(output, ReturnType::Type(_, ty) if matches!(**ty, Type::Never(_)));
+    let ret = if never {
+        // The function diverges, so there is no result to convert.
+        quote! {
+            let _ = retval;
+            #call;
+        }
+    } else if attr_args.never {
+        if asyncness.is_some() {
+            bail!("The `never` option cannot be used on `async` functions.");
+        }
+
+        let message = format!(
+            "{}(): never-returning function must not implicitly return",
+            php_name.as_deref().unwrap_or(&ident.to_string())
+        );
+        quote! {
+            let _ = retval;
+            let e: ::ext_php_rs::exception::PhpException = match #call {
+                Err(e) => e.into(),
+                Ok(_) => ::ext_php_rs::exception::PhpException::new(
+                    #message.into(),
+                    0,
+                    ::ext_php_rs::zend::ce::type_error(),
+                ),
+            };
+            e.throw().expect("Failed to throw exception");
+        }
+    } else {
+        quote! {
+            let result = #call;
+
+            if let Err(e) = result.set_zval(retval, false) {
+                let e: ::ext_php_rs::exception::PhpException = e.into();
+                e.throw().expect("Failed to throw exception");
+            }
+        }
+    };
 
+    let panic_mode = panic_mode_tokens(attr_args.panic);
     let func = quote! {
         #input
 
@@ -72,18 +204,23 @@ pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Functi
         pub extern "C" fn #internal_ident(ex: &mut ::ext_php_rs::zend::ExecuteData, retval: &mut ::ext_php_rs::types::Zval) {
             use ::ext_php_rs::convert::IntoZval;
 
-            #(#arg_definitions)*
-            #arg_parser
-
-            let result = #ident(#(#arg_accessors, )*);
+            let result = ::ext_php_rs::internal::catch_panic(#panic_mode, || {
+                #(#arg_definitions)*
+                #arg_parser
+                #ret
+            });
 
-            if let Err(e) = result.set_zval(retval, false) {
-                let e: ::ext_php_rs::exception::PhpException = e.into();
+            if let Err(e) = result {
                 e.throw().expect("Failed to throw exception");
             }
         }
     };
 
+    if let Some(condition) = &attr_args.condition {
+        syn::parse_str::<syn::Path>(condition)
+            .map_err(|_| anyhow!("Invalid condition given for function `{}`.", ident))?;
+    }
+
     let mut state = STATE.lock();
 
     if state.built_module && !attr_args.ignore_module {
@@ -91,12 +228,21 @@ pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Functi
     }
 
     let function = Function {
-        name: ident.to_string(),
+        named: php_name.is_some(),
+        name: php_name.unwrap_or_else(|| ident.to_string()),
         docs: get_docs(&input.attrs),
         ident: internal_ident.to_string(),
         args,
         optional,
         output: return_type,
+        never: never || attr_args.never,
+        namespace: attr_args.namespace,
+        deprecated,
+        emit_deprecated: php_attr.emit_deprecated,
+        return_override: php_attr.return_override,
+        condition: attr_args.condition,
+        internal: php_attr.internal,
+        attributes: php_attr.attributes,
     };
 
     state.functions.push(function.clone());
@@ -104,12 +250,35 @@ pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<(TokenStream, Functi
     Ok((func, function))
 }
 
+/// Removes the `#[php(...)]` attributes from a function, returning the options
+/// given through them.
+fn take_php_attr(attrs: &mut Vec<Attribute>) -> Result<PhpAttr> {
+    let mut php_attr = PhpAttr::default();
+
+    for attr in std::mem::take(attrs) {
+        if attr.path.is_ident("php") {
+            if let Some(ParsedAttribute::Php(attr)) = parse_attribute(&attr)? {
+                php_attr.name = attr.name.or(php_attr.name);
+                php_attr.deprecated = attr.deprecated.or(php_attr.deprecated);
+                php_attr.emit_deprecated |= attr.emit_deprecated;
+                php_attr.return_override = attr.return_override.or(php_attr.return_override);
+                php_attr.internal |= attr.internal;
+                php_attr.attributes.extend(attr.attributes);
+            }
+        } else {
+            attrs.push(attr);
+        }
+    }
+
+    Ok(php_attr)
+}
+
 fn build_args(
-    inputs: &Punctuated<FnArg, Token![,]>,
+    inputs: &mut Punctuated<FnArg, Token![,]>,
     defaults: &HashMap<String, Lit>,
 ) -> Result<Vec<Arg>> {
     inputs
-        .iter()
+        .iter_mut()
         .map(|arg| match arg {
             FnArg::Receiver(_) => bail!(
                 "`self` is not permitted in PHP functions. See the `#[php_method]` attribute."
@@ -119,13 +288,42 @@ fn build_args(
                     syn::Pat::Ident(pat) => pat.ident.to_string(),
                     _ => bail!("Invalid parameter type."),
                 };
-                Arg::from_type(name.clone(), &ty.ty, defaults.get(&name), false)
+
+                let mut param_default = None;
+                let attrs = std::mem::take(&mut ty.attrs);
+                for attr in attrs.into_iter() {
+                    match parse_attribute(&attr)? {
+                        Some(ParsedAttribute::ParamDefault(lit)) => param_default = Some(lit),
+                        Some(_) => bail!("Invalid attribute for parameter `{}`.", name),
+                        None => ty.attrs.push(attr),
+                    }
+                }
+
+                let default = get_param_default(&name, param_default.as_ref(), defaults)?;
+                Arg::from_type(name.clone(), &ty.ty, default, false)
                     .ok_or_else(|| anyhow!("Invalid parameter type for parameter `{}`.", name))
             }
         })
         .collect::<Result<Vec<_>>>()
 }
 
+/// Returns the default value of a parameter, given either through a
+/// `#[default(...)]` attribute on the parameter or through the `defaults`
+/// attribute option, but not both.
+pub fn get_param_default<'a>(
+    name: &str,
+    param_default: Option<&'a Lit>,
+    defaults: &'a HashMap<String, Lit>,
+) -> Result<Option<&'a Lit>> {
+    match (param_default, defaults.get(name)) {
+        (Some(_), Some(_)) => bail!(
+            "Parameter `{}` has a default value given by both `#[default]` and `defaults`.",
+            name
+        ),
+        (param_default, default) => Ok(param_default.or(default)),
+    }
+}
+
 fn build_arg_definitions(args: &[Arg]) -> Vec<TokenStream> {
     args.iter()
         .map(|ty| {
@@ -138,25 +336,98 @@ fn build_arg_definitions(args: &[Arg]) -> Vec<TokenStream> {
         .collect()
 }
 
+pub fn set_variadic_parameter<'a>(
+    args: impl DoubleEndedIterator<Item = &'a mut Arg>,
+    variadic: Option<String>,
+) -> Result<()> {
+    let variadic = match variadic {
+        Some(variadic) => variadic,
+        None => return Ok(()),
+    };
+
+    let arg = match args.last() {
+        Some(arg) if arg.name == variadic => arg,
+        _ => bail!(
+            "The variadic parameter `{}` must be the last parameter.",
+            variadic
+        ),
+    };
+
+    // The type of the parameter is the type of each value given.
+    let ty: Type = syn::parse_str(&arg.ty)?;
+    let elem = match &ty {
+        Type::Path(TypePath { path, .. }) => {
+            path.segments
+                .last()
+                .and_then(|seg| match (&seg.arguments, seg.ident == "Vec") {
+                    (PathArguments::AngleBracketed(args), true) => args.args.first(),
+                    _ => None,
+                })
+        }
+        _ => None,
+    }
+    .ok_or_else(|| anyhow!("The variadic parameter `{}` must be a `Vec`.", variadic))?;
+
+    arg.ty = elem.to_token_stream().to_string();
+    arg.variadic = true;
+    Ok(())
+}
+
+/// Finds the first optional parameter, either given explicitly through the
+/// `optional` option or inferred from the trailing `Option` parameters and
+/// parameters with default values. The parameters before it are marked as
+/// required, so they are registered without a default value.
 pub fn find_optional_parameter<'a>(
-    args: impl DoubleEndedIterator<Item = &'a Arg>,
+    args: impl Iterator<Item = &'a mut Arg>,
     optional: Option<String>,
-) -> Option<String> {
-    if optional.is_some() {
-        return optional;
-    }
+) -> Result<Option<String>> {
+    let mut args = args.collect::<Vec<_>>();
 
-    let mut optional = None;
+    let optional = match optional {
+        Some(optional) => {
+            if !args.iter().any(|arg| arg.name == optional) {
+                bail!(
+                    "The optional parameter `{}` is not a parameter of the function.",
+                    optional
+                );
+            }
+            Some(optional)
+        }
+        None => {
+            let mut optional = None;
+
+            for arg in args.iter().rev() {
+                if arg.variadic {
+                    continue;
+                } else if arg.nullable {
+                    optional.replace(arg.name.clone());
+                } else {
+                    break;
+                }
+            }
 
-    for arg in args.rev() {
-        if arg.nullable {
-            optional.replace(arg.name.clone());
-        } else {
-            break;
+            optional
         }
+    };
+
+    let boundary = args
+        .iter()
+        .position(|arg| optional.as_ref() == Some(&arg.name))
+        .unwrap_or(args.len());
+    for arg in &mut args[..boundary] {
+        arg.required = !arg.variadic;
+    }
+    if let Some(arg) = args[boundary..]
+        .iter()
+        .find(|arg| !arg.nullable && arg.default.is_none() && !arg.variadic)
+    {
+        bail!(
+            "Parameter `{}` must be a variant of `Option` or have a default value as it is optional.",
+            arg.name
+        );
     }
 
-    optional
+    Ok(optional)
 }
 
 pub enum ParserType {
@@ -171,21 +442,20 @@ pub fn build_arg_parser<'a>(
     ret: &TokenStream,
     ty: ParserType,
 ) -> Result<TokenStream> {
-    let mut rest_optional = false;
-
     let args = args
         .map(|arg| {
             let name = arg.get_name_ident();
-            let prelude = optional.as_ref().and_then(|opt| if *opt == arg.name {
-                rest_optional = true;
-                Some(quote! { .not_required() })
-            } else {
-                None
+            let prelude = optional.as_ref().and_then(|opt| {
+                if *opt == arg.name {
+                    Some(quote! { .not_required() })
+                } else {
+                    None
+                }
             });
 
-            if rest_optional && !arg.nullable && arg.default.is_none() {
+            if arg.omittable && arg.default.is_some() {
                 bail!(
-                    "Parameter `{}` must be a variant of `Option` or have a default value as it is optional.",
+                    "Parameter `{}` cannot have a default value as it is `Omittable`.",
                     arg.name
                 )
             } else {
@@ -236,9 +506,12 @@ fn build_arg_accessors(args: &[Arg]) -> Vec<TokenStream> {
         .collect()
 }
 
+/// Returns the type returned by a function and whether it is nullable.
+/// Functions without a return type return `()`, which is declared as `void` in
+/// PHP.
 pub fn get_return_type(output_type: &ReturnType) -> Result<Option<(String, bool)>> {
     Ok(match output_type {
-        ReturnType::Default => None,
+        ReturnType::Default => Some(("()".to_string(), false)),
         ReturnType::Type(_, ty) => {
             Arg::from_type("".to_string(), ty, None, true).map(|arg| (arg.ty, arg.nullable))
         }
@@ -252,6 +525,10 @@ impl Arg {
             ty,
             nullable,
             default,
+            by_ref: false,
+            variadic: false,
+            omittable: false,
+            required: false,
         }
     }
 
@@ -285,13 +562,27 @@ impl Arg {
                     _ => path.to_token_stream().to_string(),
                 };
 
-                Some(Arg::new(
-                    name,
-                    stringified,
-                    seg.ident == "Option" || default.is_some(),
-                    default,
-                ))
+                Some(Arg {
+                    by_ref: !is_return && seg.ident == "ZvalRef",
+                    omittable: !is_return && seg.ident == "Omittable",
+                    ..Arg::new(
+                        name,
+                        stringified,
+                        seg.ident == "Option" || seg.ident == "Omittable" || default.is_some(),
+                        default,
+                    )
+                })
             }
+            // `()` can only be returned, as `void`.
+            Type::Tuple(tuple) if tuple.elems.is_empty() && is_return => {
+                Some(Arg::new(name, "()".to_string(), false, default))
+            }
+            Type::Tuple(tuple) if !tuple.elems.is_empty() => Some(Arg::new(
+                name,
+                tuple.to_token_stream().to_string(),
+                false,
+                default,
+            )),
             Type::Reference(ref_) => {
                 // Returning references is invalid, so let's just create our arg
                 Some(Arg::new(
@@ -318,13 +609,58 @@ impl Arg {
         Ident::new(&self.name, Span::call_site())
     }
 
+    /// Returns the default value of the argument as seen by PHP. Nullable
+    /// arguments without a default value default to `null`, so that they can be
+    /// skipped when calling the function with named arguments. Required
+    /// arguments never have a default value.
+    pub fn php_default(&self) -> Option<String> {
+        match &self.default {
+            _ if self.required => None,
+            Some(default) => Some(default.clone()),
+            None if self.nullable && !self.by_ref && !self.variadic => Some("null".into()),
+            None => None,
+        }
+    }
+
     /// Returns a [`TokenStream`] containing the line required to retrieve the
     /// value from the argument.
     pub fn get_accessor(&self, ret: &TokenStream) -> TokenStream {
         let name = &self.name;
         let name_ident = self.get_name_ident();
 
-        if let Some(default) = self.default.as_ref() {
+        if self.variadic {
+            quote! {
+                match #name_ident.variadic_vals() {
+                    Ok(vals) => vals,
+                    Err(position) => {
+                        ::ext_php_rs::exception::PhpException::default(
+                            format!("Invalid value given for argument {} of `{}`.", position, #name)
+                        )
+                        .throw()
+                        .expect("Failed to throw exception: Invalid value given for variadic argument.");
+                        #ret
+                    }
+                }
+            }
+        } else if self.omittable {
+            quote! {
+                if #name_ident.zval().is_none() {
+                    ::ext_php_rs::types::Omittable::Absent
+                } else {
+                    match #name_ident.val() {
+                        Some(val) => val,
+                        None => {
+                            ::ext_php_rs::exception::PhpException::default(
+                                concat!("Invalid value given for argument `", #name, "`.").into()
+                            )
+                            .throw()
+                            .expect(concat!("Failed to throw exception: Invalid value given for argument `", #name, "`."));
+                            #ret
+                        }
+                    }
+                }
+            }
+        } else if let Some(default) = self.default.as_ref() {
             // `bool`s are not literals - need to use Ident.
             let val = syn::parse_str::<Literal>(default)
                 .map(|lit| lit.to_token_stream())
@@ -358,14 +694,17 @@ impl Arg {
         let ty = self.get_type_ident();
 
         let null = self.nullable.then(|| quote! { .allow_null() });
-        let default = self.default.as_ref().map(|val| {
+        let default = self.php_default().map(|val| {
             quote! {
                 .default(#val)
             }
         });
 
+        let by_ref = self.by_ref.then(|| quote! { .as_ref() });
+        let variadic = self.variadic.then(|| quote! { .is_variadic() });
+
         quote! {
-            ::ext_php_rs::args::Arg::new(#name, #ty) #null #default
+            ::ext_php_rs::args::Arg::new(#name, #ty) #null #default #by_ref #variadic
         }
     }
 }
@@ -394,20 +733,108 @@ impl Function {
                 quote! { #prelude.arg(#def) }
             })
             .collect::<Vec<_>>();
-        let output = self.output.as_ref().map(|(ty, nullable)| {
-            let ty: Type = syn::parse_str(ty).expect("failed to parse ty");
+        let output = if self.never {
+            Some(quote! {
+                .returns(::ext_php_rs::flags::DataType::Never, false, false)
+            })
+        } else {
+            self.output.as_ref().map(|(ty, nullable)| {
+                let ty: Type = syn::parse_str(ty).expect("failed to parse ty");
 
-            // TODO allow reference returns?
-            quote! {
-                .returns(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE, false, #nullable)
-            }
-        });
+                // TODO allow reference returns?
+                quote! {
+                    .returns(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE, false, #nullable)
+                }
+            })
+        };
+
+        let deprecated = if self.emit_deprecated {
+            Some(quote! { .deprecated() })
+        } else {
+            None
+        };
 
         quote! {
             ::ext_php_rs::builders::FunctionBuilder::new(#name, #name_ident)
                 #(#args)*
                 #output
+                #deprecated
                 .build()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{find_optional_parameter, Arg};
+    use syn::parse_quote;
+
+    fn arg(name: &str, nullable: bool, default: Option<&str>) -> Arg {
+        Arg::new(name.into(), "i64".into(), nullable, default.map(Into::into))
+    }
+
+    #[test]
+    fn test_inferred_optional_parameter() {
+        let mut args = [
+            arg("a", true, None),
+            arg("b", false, None),
+            arg("c", true, None),
+            arg("d", true, Some("5")),
+        ];
+        let optional = find_optional_parameter(args.iter_mut(), None).unwrap();
+
+        assert_eq!(optional.as_deref(), Some("c"));
+        assert_eq!(args[0].php_default(), None);
+        assert_eq!(args[1].php_default(), None);
+        assert_eq!(args[2].php_default().as_deref(), Some("null"));
+        assert_eq!(args[3].php_default().as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn test_explicit_optional_parameter() {
+        let mut args = [
+            arg("a", true, Some("1")),
+            arg("b", true, None),
+            arg("c", true, None),
+        ];
+        let optional = find_optional_parameter(args.iter_mut(), Some("b".into())).unwrap();
+
+        assert_eq!(optional.as_deref(), Some("b"));
+        assert!(args[0].required);
+        assert_eq!(args[0].php_default(), None);
+        assert!(!args[1].required);
+        assert_eq!(args[1].php_default().as_deref(), Some("null"));
+        assert_eq!(args[2].php_default().as_deref(), Some("null"));
+    }
+
+    #[test]
+    fn test_required_after_optional_parameter() {
+        let mut args = [
+            arg("a", true, None),
+            arg("b", false, Some("1")),
+            arg("c", false, None),
+        ];
+        let err = find_optional_parameter(args.iter_mut(), Some("a".into())).unwrap_err();
+        assert!(err.to_string().starts_with("Parameter `c` must be"));
+
+        let mut args = [arg("a", false, None), arg("b", false, Some("1"))];
+        assert!(find_optional_parameter(args.iter_mut(), Some("b".into())).is_ok());
+    }
+
+    #[test]
+    fn test_omittable_parameter() {
+        let arg = Arg::from_type("a".into(), &parse_quote!(Omittable<i64>), None, false).unwrap();
+        assert!(arg.omittable);
+        assert!(arg.nullable);
+        assert_eq!(arg.php_default().as_deref(), Some("null"));
+
+        let arg = Arg::from_type("a".into(), &parse_quote!(Option<i64>), None, false).unwrap();
+        assert!(!arg.omittable);
+    }
+
+    #[test]
+    fn test_unknown_optional_parameter() {
+        let mut args = [arg("a", true, None)];
+        assert!(find_optional_parameter(args.iter_mut(), Some("b".into())).is_err());
+    }
+}
@@ -14,3 +14,8 @@ pub fn get_docs(attrs: &[Attribute]) -> Vec<String> {
 
     docs
 }
+
+/// Quotes the given value as a single-quoted PHP string literal.
+pub fn php_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
@@ -10,6 +10,7 @@ use crate::helpers::get_docs;
 use crate::{
     class::{Property, PropertyAttr},
     constant::Constant,
+    function::PanicMode,
     method,
 };
 
@@ -76,9 +77,12 @@ impl RenameRule {
 #[derive(Debug)]
 pub enum ParsedAttribute {
     Default(HashMap<String, Lit>),
+    ParamDefault(Lit),
     Optional(String),
+    Variadic(String),
     Visibility(Visibility),
     Rename(String),
+    Php(PhpAttr),
     Property {
         prop_name: Option<String>,
         ty: PropAttrTy,
@@ -91,6 +95,167 @@ pub enum ParsedAttribute {
 #[darling(default)]
 pub struct AttrArgs {
     rename_methods: Option<RenameRule>,
+    rename_all: Option<RenameRule>,
+    panic: Option<PanicMode>,
+}
+
+/// Options given through the `#[php(...)]` attribute on functions, methods and
+/// classes.
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct PhpAttr {
+    pub name: Option<String>,
+    pub deprecated: Option<Deprecated>,
+    /// Whether PHP raises an `E_DEPRECATED` error when the function or method
+    /// is called.
+    pub emit_deprecated: bool,
+    pub return_override: Option<ReturnOverride>,
+    /// Registers the method as a PHP magic method, given through
+    /// `#[php(magic = "...")]`.
+    pub magic: Option<MagicMethod>,
+    /// Hides the function from the generated stubs.
+    pub internal: bool,
+    /// PHP attributes written above the declaration in the stubs, given
+    /// through `#[php(attribute = "...")]`.
+    #[darling(multiple, rename = "attribute")]
+    pub attributes: Vec<String>,
+}
+
+/// PHP magic methods which can be implemented by a method with any Rust name,
+/// given through `#[php(magic = "...")]`.
+#[derive(Debug, Clone, Copy, FromMeta)]
+pub enum MagicMethod {
+    /// `__invoke`, called when the object is called as a function.
+    #[darling(rename = "invoke")]
+    Invoke,
+}
+
+impl MagicMethod {
+    /// Returns the PHP name of the magic method.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Invoke => "__invoke",
+        }
+    }
+}
+
+/// Return type of a function or method which depends on one of its arguments,
+/// given through `#[php(return_override(...))]`. Emitted into the
+/// `.phpstorm.meta.php` file generated by `cargo php stubs --meta`.
+#[derive(Default, Debug, Clone, FromMeta)]
+#[darling(default)]
+pub struct ReturnOverride {
+    /// Zero-based position of the argument.
+    pub arg: u32,
+    /// The argument is the name of the returned class, with `@` in the pattern
+    /// replaced by the argument.
+    pub class: Option<ClassPattern>,
+    /// Returns a value of the same type as the argument.
+    pub same_type: bool,
+    /// Returns an element of the array given as the argument.
+    pub element_type: bool,
+    /// Maps the values of the argument to the returned classes.
+    pub map: Option<OverrideMap>,
+}
+
+impl ReturnOverride {
+    /// Ensures exactly one kind of override is given, for an argument of the
+    /// function or method.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the function or method.
+    /// * `params` - The number of parameters of the function or method.
+    pub fn validate(&self, name: &str, params: usize) -> Result<()> {
+        let kinds = [
+            self.class.is_some(),
+            self.same_type,
+            self.element_type,
+            self.map.is_some(),
+        ];
+        if kinds.iter().filter(|kind| **kind).count() != 1 {
+            bail!(
+                "The `return_override` option of `{}` requires exactly one of `class`, `same_type`, `element_type` or `map`.",
+                name
+            );
+        }
+        if self.arg as usize >= params {
+            bail!(
+                "The `return_override` option of `{}` refers to argument {}, but it only has {} argument(s).",
+                name,
+                self.arg,
+                params
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Pattern given through `return_override(class = "...")`, or `@` for
+/// `return_override(class)`.
+#[derive(Debug, Clone)]
+pub struct ClassPattern(pub String);
+
+impl FromMeta for ClassPattern {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self("@".to_string()))
+    }
+
+    fn from_string(value: &str) -> darling::Result<Self> {
+        if !value.contains('@') {
+            return Err(darling::Error::custom(
+                "class pattern must contain `@`, which is replaced by the argument",
+            ));
+        }
+        Ok(Self(value.to_string()))
+    }
+}
+
+/// Values of an argument and the classes returned for them, given through
+/// `return_override(map(key = "Class", ...))`, in the order they were given.
+#[derive(Debug, Clone)]
+pub struct OverrideMap(pub Vec<(String, String)>);
+
+impl FromMeta for OverrideMap {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        items
+            .iter()
+            .map(|item| match item {
+                NestedMeta::Meta(Meta::NameValue(nv)) => {
+                    let key = nv
+                        .path
+                        .get_ident()
+                        .ok_or_else(|| darling::Error::custom("expected identifier").with_span(nv))?
+                        .to_string();
+                    match &nv.lit {
+                        Lit::Str(class) => Ok((key, class.value())),
+                        lit => Err(darling::Error::unexpected_lit_type(lit)),
+                    }
+                }
+                _ => Err(
+                    darling::Error::unsupported_format("expected `key = \"Class\"`")
+                        .with_span(item),
+                ),
+            })
+            .collect::<darling::Result<_>>()
+            .map(Self)
+    }
+}
+
+/// Deprecation message given through `#[php(deprecated = "...")]`, or an empty
+/// message for `#[php(deprecated)]`.
+#[derive(Debug, Clone)]
+pub struct Deprecated(pub String);
+
+impl FromMeta for Deprecated {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self(String::new()))
+    }
+
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(Self(value.to_string()))
+    }
 }
 
 #[derive(Debug)]
@@ -103,6 +268,13 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
     let args = AttrArgs::from_list(&args)
         .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
 
+    let rename_rule = match (args.rename_methods, args.rename_all) {
+        (Some(_), Some(_)) => {
+            bail!("Only one of `rename_methods` and `rename_all` can be given.")
+        }
+        (rule, rule_all) => rule.or(rule_all).unwrap_or_default(),
+    };
+
     let ItemImpl { self_ty, items, .. } = input;
     let class_name = self_ty.to_token_stream().to_string();
 
@@ -112,7 +284,7 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
 
     let mut state = crate::STATE.lock();
 
-    if state.startup_function.is_some() {
+    if !state.startup_functions.is_empty() {
         bail!(
             "Impls must be declared before you declare your startup function and module function."
         );
@@ -129,12 +301,17 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
         .map(|item| {
             Ok(match item {
                 syn::ImplItem::Const(constant) => {
-                    class.constants.push(Constant {
-                        name: constant.ident.to_string(),
-                        // visibility: Visibility::Public,
-                        docs: get_docs(&constant.attrs),
-                        value: constant.expr.to_token_stream().to_string(),
-                    });
+                    let mut ty = constant.ty.clone();
+                    method::replace_self(&self_ty, &mut ty);
+                    let mut parsed = Constant::new(
+                        constant.ident.to_string(),
+                        get_docs(&constant.attrs),
+                        &constant.expr,
+                        &ty,
+                        &class.constants,
+                    );
+                    parsed.ty = Some(ty.to_token_stream().to_string());
+                    class.constants.push(parsed);
 
                     quote! {
                         #[allow(dead_code)]
@@ -142,8 +319,13 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
                     }
                 }
                 syn::ImplItem::Method(method) => {
-                    let parsed_method =
-                        method::parser(&self_ty, method, args.rename_methods.unwrap_or_default())?;
+                    let parsed_method = method::parser(&self_ty, method, rename_rule, args.panic)?;
+                    if parsed_method.method._abstract && !class._abstract {
+                        bail!(
+                            "Abstract method `{}` can only be declared on a class declared with `#[php_class(abstract)]`.",
+                            parsed_method.method.name
+                        );
+                    }
 
                     // TODO(david): How do we handle comments for getter/setter? Take the comments
                     // from the methods??
@@ -155,7 +337,10 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
                         let ident = parsed_method.method.orig_ident.clone();
 
                         match ty {
-                            PropAttrTy::Getter => prop.add_getter(ident)?,
+                            PropAttrTy::Getter => prop.add_getter(
+                                ident,
+                                method::returns_result(&parsed_method.item.sig.output),
+                            )?,
                             PropAttrTy::Setter => prop.add_setter(ident)?,
                         }
                     }
@@ -163,8 +348,27 @@ pub fn parser(args: AttributeArgs, input: ItemImpl) -> Result<TokenStream> {
                         if class.constructor.is_some() {
                             bail!("You cannot have two constructors on the same class.");
                         }
+                        if class.uninstantiable {
+                            bail!(
+                                "Class `{}` declared with `#[php_class(uninstantiable)]` cannot have a constructor.",
+                                class.class_name
+                            );
+                        }
                         class.constructor = Some(parsed_method.method);
                     } else {
+                        // PHP method names are case-insensitive.
+                        let name = parsed_method.method.name.to_lowercase();
+                        if class
+                            .methods
+                            .iter()
+                            .any(|method| method.name.to_lowercase() == name)
+                        {
+                            bail!(
+                                "Method `{}` has the same PHP name as another method on `{}`.",
+                                parsed_method.method.name,
+                                class_name
+                            );
+                        }
                         class.methods.push(parsed_method.method);
                     }
                     parsed_method.tokens
@@ -195,6 +399,20 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
                 .map_err(|_| anyhow!("Unable to parse `#[default]` macro."))?;
             ParsedAttribute::Default(defaults)
         }
+        "default" => {
+            let lit = if let Meta::List(list) = meta {
+                if let Some(NestedMeta::Lit(lit)) = list.nested.first() {
+                    Some(lit.clone())
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+            .ok_or_else(|| anyhow!("Invalid argument given for `#[default]` macro."))?;
+
+            ParsedAttribute::ParamDefault(lit)
+        }
         "optional" => {
             let name = if let Meta::List(list) = meta {
                 if let Some(NestedMeta::Meta(meta)) = list.nested.first() {
@@ -209,6 +427,20 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
 
             ParsedAttribute::Optional(name)
         }
+        "variadic" => {
+            let name = if let Meta::List(list) = meta {
+                if let Some(NestedMeta::Meta(meta)) = list.nested.first() {
+                    Some(meta.to_token_stream().to_string())
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+            .ok_or_else(|| anyhow!("Invalid argument given for `#[variadic]` macro."))?;
+
+            ParsedAttribute::Variadic(name)
+        }
         "public" => ParsedAttribute::Visibility(Visibility::Public),
         "protected" => ParsedAttribute::Visibility(Visibility::Protected),
         "private" => ParsedAttribute::Visibility(Visibility::Private),
@@ -226,6 +458,12 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
 
             ParsedAttribute::Rename(ident)
         }
+        "php" => {
+            let attr = PhpAttr::from_meta(&meta)
+                .map_err(|e| anyhow!("Unable to parse `#[php]` attribute: {}", e))?;
+
+            ParsedAttribute::Php(attr)
+        }
         "getter" => {
             let prop_name = if attr.tokens.is_empty() {
                 None
@@ -262,7 +500,9 @@ pub fn parse_attribute(attr: &Attribute) -> Result<Option<ParsedAttribute>> {
 
 #[cfg(test)]
 mod tests {
-    use super::RenameRule;
+    use super::{MagicMethod, PhpAttr, RenameRule};
+    use darling::FromMeta;
+    use syn::{parse_quote, Attribute};
 
     #[test]
     fn test_rename_magic() {
@@ -299,4 +539,15 @@ mod tests {
             assert_eq!(snake, RenameRule::Snake.rename(original));
         }
     }
+
+    #[test]
+    fn test_magic_method() {
+        let attr: Attribute = parse_quote!(#[php(magic = "invoke")]);
+        let attr = PhpAttr::from_meta(&attr.parse_meta().unwrap()).unwrap();
+        assert!(matches!(attr.magic, Some(MagicMethod::Invoke)));
+        assert_eq!(attr.magic.unwrap().name(), "__invoke");
+
+        let attr: Attribute = parse_quote!(#[php(magic = "call")]);
+        assert!(PhpAttr::from_meta(&attr.parse_meta().unwrap()).is_err());
+    }
 }
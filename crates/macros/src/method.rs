@@ -5,12 +5,12 @@ use syn::ReturnType;
 
 use crate::helpers::get_docs;
 use crate::{
-    function::{self, ParserType},
-    impl_::{parse_attribute, ParsedAttribute, PropAttrTy, RenameRule, Visibility},
+    function::{self, panic_mode_tokens, PanicMode, ParserType},
+    impl_::{parse_attribute, ParsedAttribute, PropAttrTy, RenameRule, ReturnOverride, Visibility},
 };
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{punctuated::Punctuated, FnArg, ImplItemMethod, Lit, Pat, Token, Type};
+use syn::{punctuated::Punctuated, FnArg, ImplItemMethod, Item, Lit, Pat, Stmt, Token, Type};
 
 #[derive(Debug, Clone)]
 pub enum Arg {
@@ -38,11 +38,26 @@ pub struct Method {
     pub optional: Option<String>,
     pub output: Option<(String, bool)>,
     pub _static: bool,
+    /// Whether the method is registered without a handler, to be implemented
+    /// by the classes inheriting it.
+    pub _abstract: bool,
     pub visibility: Visibility,
+    /// Deprecation message given through `#[php(deprecated = "...")]`.
+    pub deprecated: Option<String>,
+    /// Whether PHP raises an `E_DEPRECATED` error when the method is called.
+    pub emit_deprecated: bool,
+    /// Return type override emitted into the `.phpstorm.meta.php` file.
+    pub return_override: Option<ReturnOverride>,
+    /// PHP attributes given through `#[php(attribute = "...")]`.
+    pub attributes: Vec<String>,
 }
 
 pub struct ParsedMethod {
     pub tokens: TokenStream,
+    /// The function called by PHP, without a visibility.
+    pub handler: TokenStream,
+    /// The method with the attributes used by the macro removed.
+    pub item: ImplItemMethod,
     pub method: Method,
     pub property: Option<(String, PropAttrTy)>,
     pub constructor: bool,
@@ -57,13 +72,28 @@ pub enum MethodType {
 
 impl ParsedMethod {
     pub fn new(
-        tokens: TokenStream,
+        handler: TokenStream,
+        item: ImplItemMethod,
         method: Method,
         property: Option<(String, PropAttrTy)>,
         constructor: bool,
     ) -> Self {
+        // Abstract methods have no body, so they are not kept in the impl block.
+        let tokens = if method._abstract {
+            TokenStream::new()
+        } else {
+            quote! {
+                #item
+
+                #[doc(hidden)]
+                pub #handler
+            }
+        };
+
         Self {
             tokens,
+            handler,
+            item,
             method,
             property,
             constructor,
@@ -75,13 +105,20 @@ pub fn parser(
     struct_ty: &Type,
     mut input: ImplItemMethod,
     rename_rule: RenameRule,
+    panic_mode: Option<PanicMode>,
 ) -> Result<ParsedMethod> {
     let mut defaults = HashMap::new();
     let mut optional = None;
+    let mut variadic = None;
     let mut visibility = Visibility::Public;
     let mut as_prop = None;
     let mut identifier = None;
     let mut is_constructor = false;
+    let mut deprecated = None;
+    let mut emit_deprecated = false;
+    let mut return_override = None;
+    let mut magic = None;
+    let mut attributes = vec![];
     let docs = get_docs(&input.attrs);
 
     for attr in input.attrs.iter() {
@@ -89,8 +126,20 @@ pub fn parser(
             match attr {
                 ParsedAttribute::Default(list) => defaults = list,
                 ParsedAttribute::Optional(name) => optional = Some(name),
+                ParsedAttribute::Variadic(name) => variadic = Some(name),
                 ParsedAttribute::Visibility(vis) => visibility = vis,
                 ParsedAttribute::Rename(ident) => identifier = Some(ident),
+                ParsedAttribute::Php(attr) => {
+                    identifier = attr.name.or(identifier);
+                    deprecated = attr.deprecated.map(|d| d.0).or(deprecated);
+                    emit_deprecated |= attr.emit_deprecated;
+                    return_override = attr.return_override.or(return_override);
+                    magic = attr.magic.or(magic);
+                    attributes.extend(attr.attributes);
+                    if attr.internal {
+                        bail!("The `internal` option of `#[php]` is only valid for functions.");
+                    }
+                }
                 ParsedAttribute::Property { prop_name, ty } => {
                     if as_prop.is_some() {
                         bail!(
@@ -119,6 +168,23 @@ pub fn parser(
     input.attrs.clear();
 
     let ident = &input.sig.ident;
+    if let Some(magic) = magic {
+        if identifier.is_some() || is_constructor || as_prop.is_some() {
+            bail!(
+                "Method `{}` is the `{}` magic method, and cannot be renamed, a constructor, getter or setter.",
+                ident,
+                magic.name()
+            );
+        }
+        if !matches!(visibility, Visibility::Public) {
+            bail!(
+                "Method `{}` is the `{}` magic method, and must be public.",
+                ident,
+                magic.name()
+            );
+        }
+        identifier = Some(magic.name().to_string());
+    }
     let name = identifier.unwrap_or_else(|| rename_rule.rename(ident.to_string()));
     if name == "__construct" {
         is_constructor = true;
@@ -128,21 +194,60 @@ pub fn parser(
         bail!("`#[constructor]` attribute cannot be combined with the visibility or getter/setter attributes.");
     }
 
+    if emit_deprecated && deprecated.is_none() {
+        bail!(
+            "The `emit_deprecated` option of method `{}` requires the `deprecated` option.",
+            name
+        );
+    }
+
+    let _abstract = is_abstract(&input);
+    if _abstract && (is_constructor || as_prop.is_some()) {
+        bail!(
+            "Abstract method `{}` cannot be a constructor, getter or setter.",
+            name
+        );
+    }
+    if _abstract && matches!(visibility, Visibility::Private) {
+        bail!("Abstract method `{}` cannot be private.", name);
+    }
+
     let bail = if is_constructor {
         quote! { return ConstructorResult::ArgError; }
     } else {
         quote! { return; }
     };
     let internal_ident = Ident::new(&format!("_internal_php_{}", ident), Span::call_site());
-    let args = build_args(struct_ty, &mut input.sig.inputs, &defaults)?;
+    let mut args = build_args(struct_ty, &mut input.sig.inputs, &defaults)?;
+    function::set_variadic_parameter(
+        args.iter_mut().filter_map(|arg| match arg {
+            Arg::Typed(arg) => Some(arg),
+            _ => None,
+        }),
+        variadic,
+    )?;
     let optional = function::find_optional_parameter(
-        args.iter().filter_map(|arg| match arg {
+        args.iter_mut().filter_map(|arg| match arg {
             Arg::Typed(arg) => Some(arg),
             _ => None,
         }),
         optional,
-    );
+    )?;
+    if let Some(return_override) = &return_override {
+        let params = args
+            .iter()
+            .filter(|arg| matches!(arg, Arg::Typed(_)))
+            .count();
+        return_override.validate(&name, params)?;
+    }
     let (arg_definitions, method_type) = build_arg_definitions(&args);
+    if let (Some(magic), MethodType::Static) = (magic, &method_type) {
+        bail!(
+            "Method `{}` is the `{}` magic method, and cannot be static.",
+            ident,
+            magic.name()
+        );
+    }
     let arg_parser = build_arg_parser(
         args.iter(),
         &optional,
@@ -153,22 +258,27 @@ pub fn parser(
         },
     )?;
     let arg_accessors = build_arg_accessors(&args, &bail);
+    let panic_mode = panic_mode_tokens(panic_mode);
 
-    let func = if is_constructor {
+    let handler = if is_constructor {
         quote! {
-            #input
-
-            #[doc(hidden)]
-            pub fn #internal_ident(
+            fn #internal_ident(
                 ex: &mut ::ext_php_rs::zend::ExecuteData
             ) -> ::ext_php_rs::class::ConstructorResult<Self> {
                 use ::ext_php_rs::convert::IntoZval;
                 use ::ext_php_rs::class::ConstructorResult;
 
-                #(#arg_definitions)*
-                #arg_parser
+                let result = ::ext_php_rs::internal::catch_panic(#panic_mode, || {
+                    #(#arg_definitions)*
+                    #arg_parser
 
-                Self::#ident(#(#arg_accessors,)*).into()
+                    Self::#ident(#(#arg_accessors,)*).into()
+                });
+
+                match result {
+                    Ok(result) => result,
+                    Err(e) => ConstructorResult::Exception(e),
+                }
             }
         }
     } else {
@@ -178,28 +288,38 @@ pub fn parser(
         };
 
         quote! {
-            #input
-
-            #[doc(hidden)]
-            pub extern "C" fn #internal_ident(
+            extern "C" fn #internal_ident(
                 ex: &mut ::ext_php_rs::zend::ExecuteData,
                 retval: &mut ::ext_php_rs::types::Zval
             ) {
                 use ::ext_php_rs::convert::IntoZval;
 
-                #(#arg_definitions)*
-                #arg_parser
+                let result = ::ext_php_rs::internal::catch_panic(#panic_mode, || {
+                    #(#arg_definitions)*
+                    #arg_parser
+
+                    let result = #this #ident(#(#arg_accessors,)*);
 
-                let result = #this #ident(#(#arg_accessors,)*);
+                    if let Err(e) = result.set_zval(retval, false) {
+                        let e: ::ext_php_rs::exception::PhpException = e.into();
+                        e.throw().expect("Failed to throw exception");
+                    }
+                });
 
-                if let Err(e) = result.set_zval(retval, false) {
-                    let e: ::ext_php_rs::exception::PhpException = e.into();
+                if let Err(e) = result {
                     e.throw().expect("Failed to throw exception");
                 }
             }
         }
     };
 
+    // PHP does not allow destructors to declare a return type.
+    let output = if name == "__destruct" {
+        None
+    } else {
+        get_return_type(struct_ty, &input.sig.output)?
+    };
+
     let method = Method {
         name,
         ident: internal_ident.to_string(),
@@ -207,17 +327,42 @@ pub fn parser(
         docs,
         args,
         optional,
-        output: get_return_type(struct_ty, &input.sig.output)?,
+        output,
         _static: matches!(method_type, MethodType::Static),
+        _abstract,
         visibility,
+        deprecated,
+        emit_deprecated,
+        return_override,
+        attributes,
     };
 
-    Ok(ParsedMethod::new(func, method, as_prop, is_constructor))
+    Ok(ParsedMethod::new(
+        handler,
+        input,
+        method,
+        as_prop,
+        is_constructor,
+    ))
 }
 
+/// Returns true if the method was declared without a body, e.g.
+/// `fn speak(&self) -> String;`, in which case it is registered as an abstract
+/// method.
+fn is_abstract(input: &ImplItemMethod) -> bool {
+    // `syn` parses methods without a body into a block containing a single
+    // verbatim semicolon.
+    match input.block.stmts.as_slice() {
+        [Stmt::Item(Item::Verbatim(tokens))] => tokens.to_string() == ";",
+        _ => false,
+    }
+}
+
+/// Returns the type returned by a method and whether it is nullable. Methods
+/// without a return type return `()`, which is declared as `void` in PHP.
 pub fn get_return_type(self_ty: &Type, output_type: &ReturnType) -> Result<Option<(String, bool)>> {
     Ok(match output_type {
-        ReturnType::Default => None,
+        ReturnType::Default => Some(("()".to_string(), false)),
         ReturnType::Type(_, ty) => {
             let mut ty = ty.clone();
             replace_self(self_ty, &mut ty);
@@ -227,9 +372,24 @@ pub fn get_return_type(self_ty: &Type, output_type: &ReturnType) -> Result<Optio
     })
 }
 
+/// Returns true if the given return type is a `Result` or `PhpResult`.
+pub fn returns_result(output_type: &ReturnType) -> bool {
+    match output_type {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(syn::TypePath { path, .. }) => path
+                .segments
+                .last()
+                .map(|seg| seg.ident == "Result" || seg.ident == "PhpResult")
+                .unwrap_or(false),
+            _ => false,
+        },
+        ReturnType::Default => false,
+    }
+}
+
 /// Takes a type `ty` and replaces all instances of `Self` with the type
 /// `self_ty`.
-fn replace_self(self_ty: &Type, ty: &mut Type) {
+pub fn replace_self(self_ty: &Type, ty: &mut Type) {
     match ty {
         Type::Array(syn::TypeArray { elem, .. }) => replace_self(self_ty, elem),
         Type::BareFn(syn::TypeBareFn { inputs, output, .. }) => {
@@ -309,11 +469,13 @@ fn build_args(
             }
             FnArg::Typed(ty) => {
                 let mut this = false;
+                let mut param_default = None;
                 let attrs = std::mem::take(&mut ty.attrs);
                 for attr in attrs.into_iter() {
                     if let Some(attr) = parse_attribute(&attr)? {
                         match attr {
                             ParsedAttribute::This => this = true,
+                            ParsedAttribute::ParamDefault(lit) => param_default = Some(lit),
                             _ => bail!("Invalid attribute for argument."),
                         }
                     }
@@ -326,7 +488,8 @@ fn build_args(
                         Pat::Ident(pat) => pat.ident.to_string(),
                         _ => bail!("Invalid parameter type."),
                     };
-                    let default = defaults.get(&name);
+                    let default =
+                        function::get_param_default(&name, param_default.as_ref(), defaults)?;
                     let mut ty = ty.ty.clone();
                     replace_self(struct_ty, &mut ty);
 
@@ -416,6 +579,24 @@ impl Method {
     pub fn get_builder(&self, class_path: &Ident) -> TokenStream {
         let name = &self.name;
         let name_ident = self.get_name_ident();
+        self.build_function(quote! { new(#name, #class_path :: #name_ident) })
+    }
+
+    /// Returns a builder for the method implemented by a class through a trait
+    /// declared with `#[php_trait]`.
+    pub fn get_trait_builder(&self, class_path: &Ident, trait_path: &Ident) -> TokenStream {
+        let name = &self.name;
+        let name_ident = self.get_name_ident();
+        self.build_function(quote! { new(#name, <#class_path as #trait_path>::#name_ident) })
+    }
+
+    /// Returns a builder for the method registered without a handler.
+    pub fn get_abstract_builder(&self) -> TokenStream {
+        let name = &self.name;
+        self.build_function(quote! { new_abstract(#name) })
+    }
+
+    fn build_function(&self, constructor: TokenStream) -> TokenStream {
         let args = self.get_arg_definitions();
         let output = self.output.as_ref().map(|(ty, nullable)| {
             let ty: Type = syn::parse_str(ty).unwrap();
@@ -427,7 +608,7 @@ impl Method {
         });
 
         quote! {
-            ::ext_php_rs::builders::FunctionBuilder::new(#name, #class_path :: #name_ident)
+            ::ext_php_rs::builders::FunctionBuilder::#constructor
                 #(#args)*
                 #output
                 .build()
@@ -445,6 +626,14 @@ impl Method {
             flags.push(quote! { Static });
         }
 
+        if self._abstract {
+            flags.push(quote! { Abstract });
+        }
+
+        if self.emit_deprecated {
+            flags.push(quote! { Deprecated });
+        }
+
         flags
             .iter()
             .map(|flag| quote! { ::ext_php_rs::flags::MethodFlags::#flag })
@@ -1,17 +1,36 @@
-use std::sync::MutexGuard;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::MutexGuard,
+};
 
 use anyhow::{anyhow, bail, Result};
+use darling::FromMeta;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{ItemFn, Signature, Type};
+use syn::{AttributeArgs, ItemFn, Signature, Type};
 
 use crate::{
-    class::{Class, Property},
-    function::{Arg, Function},
-    startup_function, State, STATE,
+    class::{is_option, Class, Property, StaticProperty},
+    enum_::CaseValue,
+    function::{Arg, Function, PanicMode},
+    helpers::php_string,
+    impl_::{ClassPattern, OverrideMap, RenameRule, ReturnOverride},
+    startup_function,
+    trait_::Trait,
+    State, STATE,
 };
 
-pub fn parser(input: ItemFn) -> Result<TokenStream> {
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    rename_all: Option<RenameRule>,
+    panic: Option<PanicMode>,
+    namespace: Option<String>,
+}
+
+pub fn parser(args: AttributeArgs, input: ItemFn) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
     let ItemFn { sig, block, .. } = input;
     let Signature { output, inputs, .. } = sig;
     let stmts = &block.stmts;
@@ -24,20 +43,46 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
 
     state.built_module = true;
 
-    // Generate startup function if one hasn't already been tagged with the macro.
-    let startup_fn = if (!state.classes.is_empty() || !state.constants.is_empty())
-        && state.startup_function.is_none()
-    {
-        drop(state);
+    let rename_rule = args.rename_all.unwrap_or(RenameRule::None);
+    let mut names = HashSet::new();
+    for func in state.functions.iter_mut() {
+        if !func.named {
+            func.name = rename_rule.rename(&func.name);
+        }
 
-        let parsed = syn::parse2(quote! {
-            fn php_module_startup() {}
-        })
-        .map_err(|_| anyhow!("Unable to generate PHP module startup function."))?;
-        let startup = startup_function::parser(parsed)?;
+        // An empty namespace given to the function places it in the global
+        // namespace, ignoring the namespace of the module.
+        match (&func.namespace, &args.namespace) {
+            (Some(_), _) if func.name.contains('\\') => bail!(
+                "Function `{}` cannot be given a namespace, as its name already contains one.",
+                func.name
+            ),
+            (Some(namespace), _) => func.name = namespaced(namespace, &func.name),
+            (None, Some(namespace)) if !func.name.contains('\\') => {
+                func.name = namespaced(namespace, &func.name)
+            }
+            _ => {}
+        }
+
+        // PHP function names are case-insensitive.
+        if !names.insert(func.name.to_lowercase()) {
+            bail!(
+                "Function `{}` has the same PHP name as another function.",
+                func.name
+            );
+        }
+    }
 
-        state = STATE.lock();
-        Some(startup)
+    // Generate the function registering classes and constants, which is called
+    // before the startup functions tagged with the macro.
+    let startup_fn = if !state.classes.is_empty()
+        || !state.traits.is_empty()
+        || !state.constants.is_empty()
+        || !state.exceptions.is_empty()
+        || !state.enums.is_empty()
+        || !state.startup_functions.is_empty()
+    {
+        Some(startup_function::build_register_function(&state)?)
     } else {
         None
     };
@@ -45,12 +90,46 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
     let functions = state
         .functions
         .iter()
+        .filter(|func| func.condition.is_none())
         .map(|func| func.get_builder())
         .collect::<Vec<_>>();
-    let startup = state.startup_function.as_ref().map(|ident| {
-        let ident = Ident::new(ident, Span::call_site());
+    let conditional_functions = state
+        .functions
+        .iter()
+        .filter_map(|func| {
+            let condition: syn::Path = syn::parse_str(func.condition.as_ref()?)
+                .expect("failed to parse previously parsed condition");
+            let builder = func.get_builder();
+            Some(quote! { .conditional_function(#builder.unwrap(), #condition) })
+        })
+        .collect::<Vec<_>>();
+
+    // Startup functions are called in ascending order of priority, and shutdown
+    // functions in descending order. Functions with the same priority are
+    // called in the order they were declared, and shut down in reverse.
+    state.startup_functions.sort_by_key(|hook| hook.priority);
+    state.shutdown_functions.sort_by_key(|hook| hook.priority);
+    let startup = startup_fn
+        .as_ref()
+        .map(|_| startup_function::REGISTER_FUNCTION)
+        .into_iter()
+        .chain(
+            state
+                .startup_functions
+                .iter()
+                .map(|hook| hook.ident.as_str()),
+        )
+        .map(|ident| {
+            let ident = Ident::new(ident, Span::call_site());
+            quote! {
+                .startup_function(#ident)
+            }
+        })
+        .collect::<Vec<_>>();
+    let shutdown = state.shutdown_functions.iter().map(|hook| {
+        let ident = Ident::new(&hook.ident, Span::call_site());
         quote! {
-            .startup_function(#ident)
+            .shutdown_function(#ident)
         }
     });
     let registered_classes_impls = state
@@ -59,6 +138,10 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
         .map(|(_, class)| generate_registered_class_impl(class))
         .collect::<Result<Vec<_>>>()?;
     let describe_fn = generate_stubs(&state);
+    let panic_mode = args.panic.map(|mode| {
+        let mode = mode.tokens();
+        quote! { .panic_mode(#mode) }
+    });
 
     let result = quote! {
         #(#registered_classes_impls)*
@@ -76,8 +159,11 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
                 env!("CARGO_PKG_NAME"),
                 env!("CARGO_PKG_VERSION")
             )
-            #startup
-            #(.function(#functions.unwrap()))*
+            #panic_mode
+            #(#startup)*
+            #(#shutdown)*
+            .functions(::std::vec![#(#functions.unwrap()),*])
+            #(#conditional_functions)*
             ;
 
             // TODO allow result return types
@@ -94,6 +180,15 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
     Ok(result)
 }
 
+/// Prefixes the name of a function with a namespace. Leading and trailing
+/// backslashes are removed from the namespace.
+fn namespaced(namespace: &str, name: &str) -> String {
+    match namespace.trim_matches('\\') {
+        "" => name.to_string(),
+        namespace => format!("{}\\{}", namespace, name),
+    }
+}
+
 /// Generates an implementation for `RegisteredClass` on the given class.
 pub fn generate_registered_class_impl(class: &Class) -> Result<TokenStream> {
     let self_ty = Ident::new(&class.struct_path, Span::call_site());
@@ -122,6 +217,33 @@ pub fn generate_registered_class_impl(class: &Class) -> Result<TokenStream> {
     } else {
         quote! { None }
     };
+    let handler_overrides = vec![
+        class
+            .countable
+            .then(|| quote! { handlers.countable::<Self>(); }),
+        class
+            .stringable
+            .then(|| quote! { handlers.stringable::<Self>(); }),
+        class
+            .debug_info
+            .then(|| quote! { handlers.debug_info::<Self>(); }),
+        class
+            .cloneable
+            .then(|| quote! { handlers.cloneable::<Self>(); }),
+        class
+            .comparable
+            .then(|| quote! { handlers.comparable::<Self>(); }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    let override_handlers = (!handler_overrides.is_empty()).then(|| {
+        quote! {
+            fn override_handlers(handlers: &mut ::ext_php_rs::zend::ZendObjectHandlers) {
+                #(#handler_overrides)*
+            }
+        }
+    });
 
     Ok(quote! {
         static #meta: ::ext_php_rs::class::ClassMetadata<#self_ty> = ::ext_php_rs::class::ClassMetadata::new();
@@ -143,6 +265,8 @@ pub fn generate_registered_class_impl(class: &Class) -> Result<TokenStream> {
                     #(#prop_tuples)*
                 ])
             }
+
+            #override_handlers
         }
     })
 }
@@ -162,13 +286,26 @@ fn generate_stubs(state: &MutexGuard<State>) -> TokenStream {
 
             Description::new(#module)
         }
+
+        #[cfg(debug_assertions)]
+        #[no_mangle]
+        pub extern "C" fn ext_php_rs_describe_abi_version() -> u32 {
+            ::ext_php_rs::describe::ABI_VERSION
+        }
     }
 }
 
 impl Describe for Function {
     fn describe(&self) -> TokenStream {
         let name = &self.name;
-        let ret = if let Some((ty, null)) = &self.output {
+        let ret = if self.never {
+            quote! {
+                Some(Retval {
+                    ty: ::ext_php_rs::flags::DataType::Never,
+                    nullable: false,
+                })
+            }
+        } else if let Some((ty, null)) = &self.output {
             let ty: Type = syn::parse_str(ty)
                 .expect("unreachable - failed to parse previosuly parsed function return type");
             quote! {
@@ -186,11 +323,21 @@ impl Describe for Function {
                 #doc.into()
             }
         });
+        let deprecated = describe_optional_str(&self.deprecated);
+        let attributes = describe_attributes(&self.attributes);
+        let return_override = describe_return_override(&self.return_override);
+        let condition = describe_optional_str(&self.condition);
+        let internal = self.internal;
 
         quote! {
             Function {
                 name: #name.into(),
                 docs: DocBlock(vec![#(#docs,)*].into()),
+                deprecated: #deprecated,
+                attributes: vec![#(#attributes,)*].into(),
+                return_override: #return_override,
+                condition: #condition,
+                internal: #internal,
                 ret: abi::Option::#ret,
                 params: vec![#(#params,)*].into(),
             }
@@ -200,9 +347,15 @@ impl Describe for Function {
 
 impl Describe for Arg {
     fn describe(&self) -> TokenStream {
-        let Arg { name, nullable, .. } = self;
+        let Arg {
+            name,
+            nullable,
+            by_ref,
+            variadic,
+            ..
+        } = self;
         let ty: Type = syn::parse_str(&self.ty).expect("failed to parse previously parsed type");
-        let default = if let Some(default) = &self.default {
+        let default = if let Some(default) = self.php_default() {
             quote! { Some(#default.into()) }
         } else {
             quote! { None }
@@ -214,15 +367,18 @@ impl Describe for Arg {
                 ty: abi::Option::Some(<#ty as ::ext_php_rs::convert::FromZvalMut>::TYPE),
                 nullable: #nullable,
                 default: abi::Option::#default,
+                by_ref: #by_ref,
+                variadic: #variadic,
             }
         }
     }
 }
 
-impl Describe for Class {
-    fn describe(&self) -> TokenStream {
+impl Class {
+    /// Describes the class, looking up the PHP names of the traits it uses.
+    fn describe(&self, traits: &HashMap<String, Trait>) -> TokenStream {
         let name = &self.class_name;
-        let extends = if let Some(parent) = &self.parent {
+        let extends = if let Some(parent) = self.extends.as_ref().or(self.parent.as_ref()) {
             quote! { Some(#parent.into()) }
         } else {
             quote! { None }
@@ -230,8 +386,29 @@ impl Describe for Class {
         let interfaces = self
             .interfaces
             .iter()
-            .map(|iface| quote! { #iface.into(), });
-        let properties = self.properties.iter().map(|d| d.describe());
+            .chain(&self.implements)
+            .map(|iface| quote! { #iface.into() })
+            .chain(self.iterator.then(|| quote! { "Iterator".into() }))
+            .chain(self.array_access.then(|| quote! { "ArrayAccess".into() }))
+            .chain(self.countable.then(|| quote! { "Countable".into() }))
+            .chain(self.stringable.then(|| quote! { "Stringable".into() }))
+            .chain(
+                self.json_serializable
+                    .then(|| quote! { "JsonSerializable".into() }),
+            );
+        let properties = self
+            .properties
+            .iter()
+            .map(|d| d.describe())
+            .chain(self.static_properties.iter().map(Describe::describe));
+        let uses = self
+            .uses
+            .iter()
+            .filter_map(|ident| traits.get(ident))
+            .map(|php_trait| {
+                let name = &php_trait.trait_name;
+                quote! { #name.into() }
+            });
         let mut methods: Vec<_> = self.methods.iter().map(Describe::describe).collect();
         let docs = self.docs.iter().map(|c| {
             quote! {
@@ -240,16 +417,73 @@ impl Describe for Class {
         });
         let constants = self.constants.iter().map(Describe::describe);
 
+        let ty = if self._abstract {
+            quote! { ClassType::Abstract }
+        } else {
+            quote! { ClassType::Class }
+        };
+
         if let Some(ctor) = &self.constructor {
             methods.insert(0, ctor.describe());
+        } else if self.uninstantiable {
+            methods.insert(0, describe_private_constructor());
+        }
+
+        if self.iterator {
+            methods.extend(describe_iterator_methods());
+        }
+
+        if self.array_access {
+            methods.extend(describe_array_access_methods());
+        }
+
+        if self.countable {
+            methods.push(describe_interface_method("count", &[], &quote! { Long }));
         }
 
+        if self.serializable {
+            methods.extend(describe_serialize_methods());
+        }
+
+        if self.debug_info {
+            methods.push(describe_method("__debugInfo", &[], &quote! { Array }));
+        }
+
+        if self.magic_properties {
+            methods.extend(describe_magic_property_methods());
+        }
+
+        if self.stringable {
+            methods.push(describe_interface_method(
+                "__toString",
+                &[],
+                &quote! { String },
+            ));
+        }
+
+        if self.json_serializable {
+            methods.push(describe_interface_method(
+                "jsonSerialize",
+                &[],
+                &quote! { Mixed },
+            ));
+        }
+
+        let deprecated = describe_optional_str(&self.deprecated);
+        let attributes = describe_attributes(&self.attributes);
+        let condition = describe_optional_str(&self.condition);
+
         quote! {
             Class {
                 name: #name.into(),
                 docs: DocBlock(vec![#(#docs,)*].into()),
+                deprecated: #deprecated,
+                attributes: vec![#(#attributes,)*].into(),
+                condition: #condition,
+                ty: #ty,
                 extends: abi::Option::#extends,
                 implements: vec![#(#interfaces,)*].into(),
+                uses: vec![#(#uses,)*].into(),
                 properties: vec![#(#properties,)*].into(),
                 methods: vec![#(#methods,)*].into(),
                 constants: vec![#(#constants,)*].into(),
@@ -258,6 +492,306 @@ impl Describe for Class {
     }
 }
 
+/// Describes an optional string, e.g. the deprecation message of a function,
+/// method or class.
+fn describe_optional_str(value: &Option<String>) -> TokenStream {
+    match value {
+        Some(message) => quote! { abi::Option::Some(#message.into()) },
+        None => quote! { abi::Option::None },
+    }
+}
+
+/// Describes the PHP attributes written above a declaration in the stubs.
+fn describe_attributes(attributes: &[String]) -> impl Iterator<Item = TokenStream> + '_ {
+    attributes.iter().map(|attr| quote! { #attr.into() })
+}
+
+/// Describes the return type override of a function or method.
+fn describe_return_override(return_override: &Option<ReturnOverride>) -> TokenStream {
+    let return_override = match return_override {
+        Some(return_override) => return_override,
+        None => return quote! { abi::Option::None },
+    };
+    let arg = return_override.arg;
+    let kind = if let Some(ClassPattern(pattern)) = &return_override.class {
+        quote! { OverrideKind::Class(#pattern.into()) }
+    } else if return_override.same_type {
+        quote! { OverrideKind::SameType }
+    } else if return_override.element_type {
+        quote! { OverrideKind::ElementType }
+    } else if let Some(OverrideMap(entries)) = &return_override.map {
+        let entries = entries.iter().map(|(key, class)| {
+            quote! {
+                OverrideMapEntry {
+                    key: #key.into(),
+                    class: #class.into(),
+                }
+            }
+        });
+        quote! { OverrideKind::Map(vec![#(#entries,)*].into()) }
+    } else {
+        unreachable!("return override validated when parsed")
+    };
+
+    quote! {
+        abi::Option::Some(ReturnOverride {
+            arg: #arg,
+            kind: #kind,
+        })
+    }
+}
+
+/// Describes the methods registered on classes which implement `Iterator`
+/// through the `#[iterator]` attribute.
+fn describe_iterator_methods() -> Vec<TokenStream> {
+    [
+        ("current", &[][..], quote! { Mixed }),
+        ("key", &[], quote! { Mixed }),
+        ("next", &[], quote! { Void }),
+        ("rewind", &[], quote! { Void }),
+        ("valid", &[], quote! { Bool }),
+    ]
+    .iter()
+    .map(|(name, params, ty)| describe_interface_method(name, params, ty))
+    .collect()
+}
+
+/// Describes the methods registered on classes which implement `ArrayAccess`
+/// through the `#[array_access]` attribute.
+fn describe_array_access_methods() -> Vec<TokenStream> {
+    [
+        ("offsetExists", &["offset"][..], quote! { Bool }),
+        ("offsetGet", &["offset"], quote! { Mixed }),
+        ("offsetSet", &["offset", "value"], quote! { Void }),
+        ("offsetUnset", &["offset"], quote! { Void }),
+    ]
+    .iter()
+    .map(|(name, params, ty)| describe_interface_method(name, params, ty))
+    .collect()
+}
+
+/// Describes the methods registered on classes with the `#[magic_properties]`
+/// attribute.
+fn describe_magic_property_methods() -> Vec<TokenStream> {
+    [
+        ("__get", &["name"][..], quote! { Mixed }),
+        ("__set", &["name", "value"], quote! { Void }),
+        ("__isset", &["name"], quote! { Bool }),
+        ("__unset", &["name"], quote! { Void }),
+    ]
+    .iter()
+    .map(|(name, params, ty)| describe_interface_method(name, params, ty))
+    .collect()
+}
+
+/// Describes the magic methods registered on classes with the `#[serializable]`
+/// attribute.
+fn describe_serialize_methods() -> Vec<TokenStream> {
+    vec![
+        describe_method("__serialize", &[], &quote! { Array }),
+        describe_method(
+            "__unserialize",
+            &[("data", quote! { Array })],
+            &quote! { Void },
+        ),
+    ]
+}
+
+/// Describes a public method with untyped parameters, registered on a class
+/// when implementing a built-in interface.
+fn describe_interface_method(name: &str, params: &[&str], ty: &TokenStream) -> TokenStream {
+    let params: Vec<_> = params
+        .iter()
+        .map(|param| (*param, quote! { Mixed }))
+        .collect();
+    describe_method(name, &params, ty)
+}
+
+/// Describes a public method with the given parameters and their types,
+/// registered on a class by the library.
+fn describe_method(name: &str, params: &[(&str, TokenStream)], ty: &TokenStream) -> TokenStream {
+    let params = params.iter().map(|(param, param_ty)| {
+        quote! {
+            Parameter {
+                name: #param.into(),
+                ty: abi::Option::Some(::ext_php_rs::flags::DataType::#param_ty),
+                nullable: false,
+                default: abi::Option::None,
+                by_ref: false,
+                variadic: false,
+            }
+        }
+    });
+
+    quote! {
+        Method {
+            name: #name.into(),
+            docs: DocBlock(vec![].into()),
+            deprecated: abi::Option::None,
+            attributes: vec![].into(),
+            return_override: abi::Option::None,
+            ty: MethodType::Member,
+            params: vec![#(#params,)*].into(),
+            retval: abi::Option::Some(Retval {
+                ty: ::ext_php_rs::flags::DataType::#ty,
+                nullable: false,
+            }),
+            _static: false,
+            _abstract: false,
+            visibility: Visibility::Public,
+        }
+    }
+}
+
+/// Describes the private constructor registered on a class which cannot be
+/// instantiated.
+fn describe_private_constructor() -> TokenStream {
+    quote! {
+        Method {
+            name: "__construct".into(),
+            docs: DocBlock(vec![].into()),
+            deprecated: abi::Option::None,
+            attributes: vec![].into(),
+            return_override: abi::Option::None,
+            ty: MethodType::Constructor,
+            params: vec![].into(),
+            retval: abi::Option::None,
+            _static: false,
+            _abstract: false,
+            visibility: Visibility::Private,
+        }
+    }
+}
+
+impl Describe for crate::exception::Exception {
+    fn describe(&self) -> TokenStream {
+        let name = &self.class_name;
+        let parent = &self.parent;
+        let docs = self.docs.iter().map(|c| {
+            quote! {
+                #c.into()
+            }
+        });
+
+        quote! {
+            Class {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                deprecated: abi::Option::None,
+                attributes: vec![].into(),
+                condition: abi::Option::None,
+                ty: ClassType::Class,
+                extends: abi::Option::Some(#parent.into()),
+                implements: vec![].into(),
+                uses: vec![].into(),
+                properties: vec![].into(),
+                methods: vec![].into(),
+                constants: vec![].into(),
+            }
+        }
+    }
+}
+
+impl Describe for crate::enum_::Enum {
+    fn describe(&self) -> TokenStream {
+        let name = &self.enum_name;
+        let docs = self.docs.iter().map(|c| {
+            quote! {
+                #c.into()
+            }
+        });
+        let ty = match self.cases.first().map(|case| &case.value) {
+            Some(CaseValue::Long(_)) => {
+                quote! { ClassType::BackedEnum(::ext_php_rs::flags::DataType::Long) }
+            }
+            Some(CaseValue::String(_)) => {
+                quote! { ClassType::BackedEnum(::ext_php_rs::flags::DataType::String) }
+            }
+            _ => quote! { ClassType::Enum },
+        };
+        let cases = self.cases.iter().map(|case| {
+            let name = &case.name;
+            let docs = case.docs.iter().map(|doc| {
+                quote! {
+                    #doc.into()
+                }
+            });
+            let value = match &case.value {
+                CaseValue::Pure => quote! { abi::Option::None },
+                CaseValue::Long(value) => {
+                    let value = value.to_string();
+                    quote! { abi::Option::Some(#value.into()) }
+                }
+                CaseValue::String(value) => {
+                    let value = php_string(value);
+                    quote! { abi::Option::Some(#value.into()) }
+                }
+            };
+
+            quote! {
+                Constant {
+                    name: #name.into(),
+                    docs: DocBlock(vec![#(#docs,)*].into()),
+                    value: #value,
+                    ty: abi::Option::None,
+                }
+            }
+        });
+
+        quote! {
+            Class {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                deprecated: abi::Option::None,
+                attributes: vec![].into(),
+                condition: abi::Option::None,
+                ty: #ty,
+                extends: abi::Option::None,
+                implements: vec![].into(),
+                uses: vec![].into(),
+                properties: vec![].into(),
+                methods: vec![].into(),
+                constants: vec![#(#cases,)*].into(),
+            }
+        }
+    }
+}
+
+impl Describe for Trait {
+    fn describe(&self) -> TokenStream {
+        let name = &self.trait_name;
+        let docs = self.docs.iter().map(|c| {
+            quote! {
+                #c.into()
+            }
+        });
+        let methods = self.methods.iter().map(|method| {
+            crate::method::Method {
+                _abstract: true,
+                ..method.clone()
+            }
+            .describe()
+        });
+
+        quote! {
+            Class {
+                name: #name.into(),
+                docs: DocBlock(vec![#(#docs,)*].into()),
+                deprecated: abi::Option::None,
+                attributes: vec![].into(),
+                condition: abi::Option::None,
+                ty: ClassType::Trait,
+                extends: abi::Option::None,
+                implements: vec![].into(),
+                uses: vec![].into(),
+                properties: vec![].into(),
+                methods: vec![#(#methods,)*].into(),
+                constants: vec![].into(),
+            }
+        }
+    }
+}
+
 impl Describe for (&String, &Property) {
     fn describe(&self) -> TokenStream {
         let name = self.0;
@@ -267,24 +801,76 @@ impl Describe for (&String, &Property) {
             }
         });
 
-        // TODO(david): store metadata for ty, vis, static, null, default
+        let readonly = self.1.readonly;
+
+        let (ty, union, nullable) = match (&self.1.php_ty, &self.1.field_ty) {
+            (Some(php_ty), _) => {
+                let mut types = php_ty.data_types().into_iter();
+                let ty = types.next();
+                (
+                    quote! { abi::Option::Some(#ty) },
+                    types.collect(),
+                    php_ty.nullable,
+                )
+            }
+            (None, Some(ty)) => {
+                let ty: syn::Type = syn::parse_str(ty).expect("failed to parse property type");
+                (
+                    quote! { abi::Option::Some(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE) },
+                    vec![],
+                    is_option(&ty),
+                )
+            }
+            (None, None) => (quote! { abi::Option::None }, vec![], false),
+        };
+
+        // TODO(david): store metadata for vis, static, default
         quote! {
             Property {
                 name: #name.into(),
                 docs: DocBlock(vec![#(#docs,)*].into()),
-                ty: abi::Option::None,
+                ty: #ty,
+                union: vec![#(#union,)*].into(),
                 vis: Visibility::Public,
                 static_: false,
-                nullable: false,
+                readonly: #readonly,
+                nullable: #nullable,
                 default: abi::Option::None,
             }
         }
     }
 }
 
+impl Describe for StaticProperty {
+    fn describe(&self) -> TokenStream {
+        let StaticProperty { name, default, .. } = self;
+        let ty: Type = syn::parse_str(&self.ty).expect("failed to parse previously parsed type");
+        let nullable = is_option(&ty);
+
+        quote! {
+            Property {
+                name: #name.into(),
+                docs: DocBlock(vec![].into()),
+                ty: abi::Option::Some(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE),
+                union: vec![].into(),
+                vis: Visibility::Public,
+                static_: true,
+                readonly: false,
+                nullable: #nullable,
+                default: abi::Option::Some(#default.into()),
+            }
+        }
+    }
+}
+
 impl Describe for crate::method::Method {
     fn describe(&self) -> TokenStream {
-        let crate::method::Method { name, _static, .. } = &self;
+        let crate::method::Method {
+            name,
+            _static,
+            _abstract,
+            ..
+        } = &self;
         let ty = if self.name == "__construct" {
             quote! { MethodType::Constructor }
         } else if self._static {
@@ -316,15 +902,22 @@ impl Describe for crate::method::Method {
                 #doc.into()
             }
         });
+        let deprecated = describe_optional_str(&self.deprecated);
+        let attributes = describe_attributes(&self.attributes);
+        let return_override = describe_return_override(&self.return_override);
 
         quote! {
             Method {
                 name: #name.into(),
                 docs: DocBlock(vec![#(#docs,)*].into()),
+                deprecated: #deprecated,
+                attributes: vec![#(#attributes,)*].into(),
+                return_override: #return_override,
                 ty: #ty,
                 params: vec![#(#parameters,)*].into(),
                 retval: abi::Option::#ret,
                 _static: #_static,
+                _abstract: #_abstract,
                 visibility: #vis,
             }
         }
@@ -350,11 +943,25 @@ impl Describe for crate::constant::Constant {
             }
         });
 
+        let ty = if let Some(ty) = &self.ty {
+            let ty: Type = syn::parse_str(ty).expect("failed to parse previously parsed type");
+            quote! { abi::Option::Some(<#ty as ::ext_php_rs::convert::IntoZval>::TYPE) }
+        } else {
+            quote! { abi::Option::None }
+        };
+
+        let value = if let Some(value) = &self.literal {
+            quote! { abi::Option::Some(#value.into()) }
+        } else {
+            quote! { abi::Option::None }
+        };
+
         quote! {
             Constant {
                 name: #name.into(),
                 docs: DocBlock(vec![#(#docs,)*].into()),
-                value: abi::Option::None,
+                value: #value,
+                ty: #ty,
             }
         }
     }
@@ -363,7 +970,17 @@ impl Describe for crate::constant::Constant {
 impl Describe for State {
     fn describe(&self) -> TokenStream {
         let functs = self.functions.iter().map(Describe::describe);
-        let classes = self.classes.iter().map(|(_, class)| class.describe());
+        let classes = self
+            .exceptions
+            .iter()
+            .map(Describe::describe)
+            .chain(self.enums.iter().map(Describe::describe))
+            .chain(self.traits.values().map(Describe::describe))
+            .chain(
+                self.classes
+                    .iter()
+                    .map(|(_, class)| class.describe(&self.traits)),
+            );
         let constants = self.constants.iter().map(Describe::describe);
 
         quote! {
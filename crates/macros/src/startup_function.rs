@@ -1,38 +1,68 @@
 use std::collections::HashMap;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
+use darling::FromMeta;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{Expr, ItemFn, Signature};
+use syn::{AttributeArgs, Expr, ItemFn, Signature};
 
-use crate::{class::Class, constant::Constant, STATE};
+use crate::{
+    class::Class,
+    constant::Constant,
+    enum_::{CaseValue, Enum},
+    exception::Exception,
+    trait_::Trait,
+    State, STATE,
+};
 
-pub fn parser(input: ItemFn) -> Result<TokenStream> {
+/// Name of the generated startup function which registers the classes,
+/// traits, exceptions, enums and constants of the extension.
+pub const REGISTER_FUNCTION: &str = "ext_php_rs_module_register";
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    priority: i32,
+}
+
+/// A startup or shutdown function of the extension.
+#[derive(Debug)]
+pub struct Hook {
+    pub ident: String,
+    pub priority: i32,
+}
+
+/// Parses a function annotated with `#[php_startup]` or `#[php_shutdown]`.
+pub fn parser(args: AttributeArgs, input: ItemFn, shutdown: bool) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
     let ItemFn { sig, block, .. } = input;
     let Signature { ident, .. } = sig;
     let stmts = &block.stmts;
 
     let mut state = STATE.lock();
-    state.startup_function = Some(ident.to_string());
 
-    let classes = build_classes(&state.classes)?;
-    let constants = build_constants(&state.constants);
+    if state.built_module {
+        bail!("The `#[php_module]` macro must be called last to ensure startup and shutdown functions are registered.");
+    }
+
+    let hook = Hook {
+        ident: ident.to_string(),
+        priority: args.priority,
+    };
+    if shutdown {
+        state.shutdown_functions.push(hook);
+    } else {
+        state.startup_functions.push(hook);
+    }
 
     let func = quote! {
         #[doc(hidden)]
         pub extern "C" fn #ident(ty: i32, module_number: i32) -> i32 {
-            use ::ext_php_rs::constant::IntoConst;
-            use ::ext_php_rs::flags::PropertyFlags;
-
             fn internal() {
                 #(#stmts)*
             }
 
-            ::ext_php_rs::internal::ext_php_rs_startup();
-
-            #(#classes)*
-            #(#constants)*
-
             // TODO return result?
             internal();
 
@@ -43,23 +73,157 @@ pub fn parser(input: ItemFn) -> Result<TokenStream> {
     Ok(func)
 }
 
-/// Returns a vector of `ClassBuilder`s for each class.
-fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
-    classes
-        .iter()
+/// Generates the startup function which registers the classes, traits,
+/// exceptions, enums and constants of the extension. The function is called before any function
+/// annotated with `#[php_startup]`.
+pub fn build_register_function(state: &State) -> Result<TokenStream> {
+    let ident = Ident::new(REGISTER_FUNCTION, Span::call_site());
+    let exceptions = build_exceptions(&state.exceptions);
+    let enums = build_enums(&state.enums);
+    let traits = build_traits(&state.traits);
+    let classes = build_classes(&state.classes, &state.traits)?;
+    let constants = build_constants(&state.constants);
+
+    Ok(quote! {
+        #[doc(hidden)]
+        pub extern "C" fn #ident(ty: i32, module_number: i32) -> i32 {
+            use ::ext_php_rs::constant::IntoConst;
+            use ::ext_php_rs::flags::PropertyFlags;
+
+            ::ext_php_rs::internal::ext_php_rs_startup();
+
+            #(#exceptions)*
+            #(#enums)*
+            #(#traits)*
+            #(#classes)*
+            #(#constants)*
+
+            0
+        }
+    })
+}
+
+/// Returns the classes in the order they must be registered, so that classes
+/// extending another class of the extension are registered after their parent.
+fn order_classes(classes: &HashMap<String, Class>) -> Result<Vec<(&String, &Class)>> {
+    let mut pending: Vec<_> = classes.iter().collect();
+    pending.sort_by_key(|(name, _)| *name);
+
+    let mut ordered: Vec<(&String, &Class)> = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let (ready, waiting): (Vec<_>, Vec<_>) = pending.into_iter().partition(|(_, class)| {
+            let parent = match &class.extends {
+                Some(parent) => parent.trim_start_matches('\\'),
+                None => return true,
+            };
+            let is_local = |c: &Class| c.class_name.eq_ignore_ascii_case(parent);
+
+            !classes.values().any(is_local) || ordered.iter().any(|(_, c)| is_local(c))
+        });
+
+        if ready.is_empty() {
+            bail!(
+                "Class `{}` has a circular inheritance chain.",
+                waiting[0].1.class_name
+            );
+        }
+
+        ordered.extend(ready);
+        pending = waiting;
+    }
+
+    Ok(ordered)
+}
+
+/// Returns a vector of `ClassBuilder`s for each trait. The methods of a trait
+/// are registered as abstract methods, and are implemented by the classes using
+/// the trait.
+fn build_traits(traits: &HashMap<String, Trait>) -> Vec<TokenStream> {
+    let mut traits: Vec<_> = traits.values().collect();
+    traits.sort_by(|l, r| l.trait_name.cmp(&r.trait_name));
+
+    traits
+        .into_iter()
+        .map(|php_trait| {
+            let Trait { trait_name, .. } = php_trait;
+            let methods = php_trait.methods.iter().map(|method| {
+                let method = crate::method::Method {
+                    _abstract: true,
+                    ..method.clone()
+                };
+                let builder = method.get_abstract_builder();
+                let flags = method.get_flags();
+                quote! { .method(#builder.unwrap(), #flags) }
+            });
+
+            quote! {{
+                ::ext_php_rs::builders::ClassBuilder::new(#trait_name)
+                    .flags(::ext_php_rs::flags::ClassFlags::Trait)
+                    #(#methods)*
+                    .build()
+                    .expect(concat!("Unable to build trait `", #trait_name, "`"));
+            }}
+        })
+        .collect()
+}
+
+/// Returns a vector of `ClassBuilder`s for each class, with parent classes
+/// registered before the classes extending them.
+fn build_classes(
+    classes: &HashMap<String, Class>,
+    traits: &HashMap<String, Trait>,
+) -> Result<Vec<TokenStream>> {
+    order_classes(classes)?
+        .into_iter()
         .map(|(name, class)| {
             let Class { class_name, .. } = &class;
+            class.check_interfaces()?;
             let ident = Ident::new(name, Span::call_site());
             let meta = Ident::new(&format!("_{}_META", name), Span::call_site());
             let methods = class.methods.iter().map(|method| {
-                let builder = method.get_builder(&ident);
+                let builder = if method._abstract {
+                    method.get_abstract_builder()
+                } else {
+                    method.get_builder(&ident)
+                };
                 let flags = method.get_flags();
                 quote! { .method(#builder.unwrap(), #flags) }
             });
+            let mut names: Vec<_> = class.methods.iter().map(|m| m.name.to_lowercase()).collect();
+            let mut trait_methods = vec![];
+            for used in &class.uses {
+                let php_trait = traits.get(used).ok_or_else(|| {
+                    anyhow!(
+                        "Trait `{}` used by `{}` must be declared with `#[php_trait]`.",
+                        used,
+                        class_name
+                    )
+                })?;
+                let trait_ident = Ident::new(used, Span::call_site());
+                for method in &php_trait.methods {
+                    // PHP method names are case-insensitive.
+                    let name = method.name.to_lowercase();
+                    if names.contains(&name) {
+                        bail!(
+                            "Method `{}` of trait `{}` has the same PHP name as another method on `{}`.",
+                            method.name,
+                            used,
+                            class_name
+                        );
+                    }
+                    names.push(name);
+
+                    let builder = method.get_trait_builder(&ident, &trait_ident);
+                    let flags = method.get_flags();
+                    trait_methods.push(quote! { .method(#builder.unwrap(), #flags) });
+                }
+            }
+            // Constants are registered with the value of the Rust constant, so they can be
+            // defined in terms of other constants of the class.
             let constants = class.constants.iter().map(|constant| {
                 let name = &constant.name;
-                let val = constant.val_tokens();
-                quote! { .constant(#name, #val).unwrap() }
+                let const_ident = Ident::new(name, Span::call_site());
+                quote! { .typed_constant(#name, #ident::#const_ident).unwrap() }
             });
             let parent = {
                 if let Some(parent) = &class.parent {
@@ -67,6 +231,14 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
                         anyhow!("Invalid expression given for `{}` parent", class_name)
                     })?;
                     Some(quote! { .extends(#expr) })
+                } else if let Some(parent) = &class.extends {
+                    let lookup = parent.trim_start_matches('\\');
+                    Some(quote! {
+                        .extends(
+                            ::ext_php_rs::zend::ClassEntry::try_find(#lookup)
+                                .expect(concat!("Unable to find parent class `", #parent, "` of class `", #class_name, "`"))
+                        )
+                    })
                 } else {
                     None
                 }
@@ -85,6 +257,44 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
                     Ok(quote! { .implements(#expr) })
                 })
                 .collect::<Result<Vec<_>>>()?;
+            let implements = class.implements.iter().map(|interface| {
+                let lookup = interface.trim_start_matches('\\');
+                quote! {
+                    .implements(
+                        ::ext_php_rs::zend::ClassEntry::try_find(#lookup)
+                            .expect(concat!("Unable to find interface `", #interface, "` of class `", #class_name, "`"))
+                    )
+                }
+            });
+            let flags = class
+                ._abstract
+                .then(|| quote! { .flags(::ext_php_rs::flags::ClassFlags::Abstract) });
+            let uninstantiable = class
+                .uninstantiable
+                .then(|| quote! { .uninstantiable() });
+            let iterator = class.iterator.then(|| quote! { .iterator::<#ident>() });
+            let array_access = class
+                .array_access
+                .then(|| quote! { .array_access::<#ident>() });
+            let countable = class.countable.then(|| quote! { .countable::<#ident>() });
+            let stringable = class.stringable.then(|| quote! { .stringable::<#ident>() });
+            let json_serializable = class
+                .json_serializable
+                .then(|| quote! { .json_serializable::<#ident>() });
+            let serializable = class
+                .serializable
+                .then(|| quote! { .serializable::<#ident>() });
+            let debug_info = class
+                .debug_info
+                .then(|| quote! { .debug_info::<#ident>() });
+            let magic_properties = class
+                .magic_properties
+                .then(|| quote! { .magic_properties::<#ident>() });
+            let static_properties = class
+                .static_properties
+                .iter()
+                .map(|prop| prop.as_builder_call())
+                .collect::<Result<Vec<_>>>()?;
             // TODO(david): register properties for reflection (somehow)
             // let properties = class
             //     .properties
@@ -115,11 +325,33 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
             //     })
             //     .collect::<Result<Vec<_>>>()?;
 
-            Ok(quote! {{
+            let condition = match &class.condition {
+                Some(condition) => {
+                    let condition: syn::Path = syn::parse_str(condition)
+                        .map_err(|_| anyhow!("Invalid condition given for class `{}`", class_name))?;
+                    quote! { #condition() }
+                }
+                None => quote! { true },
+            };
+
+            Ok(quote! {if #condition {
                 let class = ::ext_php_rs::builders::ClassBuilder::new(#class_name)
+                    #flags
+                    #uninstantiable
                     #(#methods)*
+                    #(#trait_methods)*
                     #(#constants)*
                     #(#interfaces)*
+                    #(#implements)*
+                    #iterator
+                    #array_access
+                    #countable
+                    #stringable
+                    #json_serializable
+                    #serializable
+                    #debug_info
+                    #magic_properties
+                    #(#static_properties)*
                     // #(#properties)*
                     #parent
                     .object_override::<#ident>()
@@ -132,6 +364,66 @@ fn build_classes(classes: &HashMap<String, Class>) -> Result<Vec<TokenStream>> {
         .collect::<Result<Vec<_>>>()
 }
 
+/// Returns a vector of `ClassBuilder`s for each exception, in the order they
+/// were declared so exceptions can extend previously declared exceptions.
+fn build_exceptions(exceptions: &[Exception]) -> Vec<TokenStream> {
+    exceptions
+        .iter()
+        .map(|exception| {
+            let Exception {
+                class_name, parent, ..
+            } = &exception;
+            let meta = Ident::new(
+                &format!("_{}_EXCEPTION_META", exception.struct_path),
+                Span::call_site(),
+            );
+            let lookup = parent.trim_start_matches('\\');
+
+            quote! {{
+                let parent = ::ext_php_rs::zend::ClassEntry::try_find(#lookup)
+                    .expect(concat!("Unable to find parent class `", #parent, "` of exception `", #class_name, "`"));
+                let class = ::ext_php_rs::builders::ClassBuilder::new(#class_name)
+                    .extends(parent)
+                    .build()
+                    .expect(concat!("Unable to build exception `", #class_name, "`"));
+
+                #meta.set_ce(class);
+            }}
+        })
+        .collect()
+}
+
+/// Returns a vector of `EnumBuilder`s for each enum.
+fn build_enums(enums: &[Enum]) -> Vec<TokenStream> {
+    enums
+        .iter()
+        .map(|enum_| {
+            let name = &enum_.enum_name;
+            let meta = Ident::new(
+                &format!("_{}_ENUM_META", enum_.struct_path),
+                Span::call_site(),
+            );
+            let cases = enum_.cases.iter().map(|case| {
+                let case_name = &case.name;
+                match &case.value {
+                    CaseValue::Pure => quote! { .case(#case_name) },
+                    CaseValue::Long(value) => quote! { .int_case(#case_name, #value) },
+                    CaseValue::String(value) => quote! { .string_case(#case_name, #value) },
+                }
+            });
+
+            quote! {{
+                let ce = ::ext_php_rs::builders::EnumBuilder::new(#name)
+                    #(#cases)*
+                    .build()
+                    .expect(concat!("Unable to build enum `", #name, "`"));
+
+                #meta.set_ce(ce);
+            }}
+        })
+        .collect()
+}
+
 fn build_constants(constants: &[Constant]) -> Vec<TokenStream> {
     constants
         .iter()
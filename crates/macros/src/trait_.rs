@@ -0,0 +1,155 @@
+use anyhow::{anyhow, bail, Result};
+use darling::{FromMeta, ToTokens};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{AttributeArgs, ImplItemMethod, ItemTrait, TraitItem, TraitItemMethod, Type, Visibility};
+
+use crate::{
+    helpers::get_docs,
+    impl_::{parse_attribute, RenameRule},
+    method::{self, Arg, Method},
+    STATE,
+};
+
+#[derive(Debug)]
+pub struct Trait {
+    pub trait_name: String,
+    pub docs: Vec<String>,
+    pub methods: Vec<Method>,
+}
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    name: Option<String>,
+    rename_methods: Option<RenameRule>,
+}
+
+pub fn parser(args: AttributeArgs, mut input: ItemTrait) -> Result<TokenStream> {
+    let args = AttrArgs::from_list(&args)
+        .map_err(|e| anyhow!("Unable to parse attribute arguments: {:?}", e))?;
+
+    if !input.generics.params.is_empty() {
+        bail!("Traits exported to PHP cannot be generic.");
+    }
+
+    let self_ty: Type = syn::parse_quote! { Self };
+    let rename_rule = args.rename_methods.unwrap_or_default();
+    let mut methods = vec![];
+    let mut handlers = vec![];
+
+    for item in input.items.iter_mut() {
+        let item = match item {
+            TraitItem::Method(item) => item,
+            _ => continue,
+        };
+
+        let method = ImplItemMethod {
+            attrs: item.attrs.clone(),
+            vis: Visibility::Inherited,
+            defaultness: None,
+            sig: item.sig.clone(),
+            block: item
+                .default
+                .clone()
+                .unwrap_or_else(|| syn::parse_quote! { {} }),
+        };
+        let parsed = method::parser(&self_ty, method, rename_rule, None)?;
+        let name = &parsed.method.name;
+
+        if parsed.constructor || parsed.property.is_some() {
+            bail!(
+                "Method `{}` of a trait cannot be a constructor, getter or setter.",
+                name
+            );
+        }
+        if parsed.method._static {
+            bail!("Method `{}` of a trait cannot be static.", name);
+        }
+        if uses_self(&parsed.method) {
+            bail!(
+                "Method `{}` of a trait cannot use `Self` in its parameter or return types.",
+                name
+            );
+        }
+        // PHP method names are case-insensitive.
+        if methods
+            .iter()
+            .any(|m: &Method| m.name.eq_ignore_ascii_case(name))
+        {
+            bail!("Method `{}` has the same PHP name as another method.", name);
+        }
+
+        // Keep the attributes which were not used by the macro, such as
+        // documentation comments.
+        let mut attrs = vec![];
+        for attr in std::mem::take(&mut item.attrs) {
+            if parse_attribute(&attr)?.is_none() {
+                attrs.push(attr);
+            }
+        }
+        *item = TraitItemMethod {
+            attrs,
+            sig: parsed.item.sig,
+            ..item.clone()
+        };
+
+        let handler = parsed.handler;
+        handlers.push(quote! {
+            #[doc(hidden)]
+            #handler
+        });
+        methods.push(parsed.method);
+    }
+
+    // The handlers retrieve `$this` as `Self`, so the trait can only be
+    // implemented by PHP classes.
+    input
+        .supertraits
+        .push(syn::parse_quote! { ::ext_php_rs::class::RegisteredClass });
+
+    let ident = &input.ident;
+    let php_trait = Trait {
+        trait_name: args.name.unwrap_or_else(|| ident.to_string()),
+        docs: get_docs(&input.attrs),
+        methods,
+    };
+
+    let mut state = STATE.lock();
+
+    if state.built_module {
+        bail!("The `#[php_module]` macro must be called last to ensure functions and classes are registered.");
+    }
+
+    if !state.startup_functions.is_empty() {
+        bail!("The `#[php_startup]` macro must be called after all the traits have been defined.");
+    }
+
+    state.traits.insert(ident.to_string(), php_trait);
+
+    input.items.extend(
+        handlers
+            .into_iter()
+            .map(|handler| syn::parse2(handler).expect("failed to parse trait method handler")),
+    );
+
+    Ok(input.to_token_stream())
+}
+
+/// Returns true if the parameter or return types of the method refer to
+/// `Self`, which would refer to the class using the trait.
+fn uses_self(method: &Method) -> bool {
+    let is_self = |ty: &str| {
+        ty.split(|c: char| !c.is_alphanumeric())
+            .any(|t| t == "Self")
+    };
+
+    method.args.iter().any(|arg| match arg {
+        Arg::Typed(arg) => is_self(&arg.ty),
+        Arg::Receiver(_) => false,
+    }) || method
+        .output
+        .as_ref()
+        .map(|(ty, _)| is_self(ty))
+        .unwrap_or(false)
+}
@@ -1,18 +1,98 @@
-use anyhow::{bail, Context, Result};
-use darling::ToTokens;
+use anyhow::{anyhow, bail, Context, Result};
+use darling::{FromMeta, ToTokens};
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{
-    token::Where, DataEnum, DataStruct, DeriveInput, GenericParam, Generics, Ident, ImplGenerics,
-    Lifetime, LifetimeDef, TypeGenerics, Variant, WhereClause,
+    token::Where, Attribute, DataEnum, DataStruct, DeriveInput, GenericParam, Generics, Ident,
+    ImplGenerics, Lifetime, LifetimeDef, Meta, NestedMeta, TypeGenerics, Variant, WhereClause,
 };
 
+use crate::class::is_option;
+
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+pub struct AttrArgs {
+    tagged: bool,
+    tag: Option<String>,
+    content: Option<String>,
+}
+
+impl AttrArgs {
+    fn parse(attrs: &[Attribute]) -> Result<Option<Self>> {
+        let attr = match attrs.iter().find(|attr| attr.path.is_ident("zval_convert")) {
+            Some(attr) => attr,
+            None => return Ok(None),
+        };
+        let nested = match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested.into_iter().collect::<Vec<NestedMeta>>(),
+            _ => bail!("Invalid `#[zval_convert]` attribute."),
+        };
+
+        Self::from_list(&nested)
+            .map(Some)
+            .map_err(|e| anyhow!("Unable to parse `#[zval_convert]` attribute: {:?}", e))
+    }
+
+    fn is_tagged(&self) -> bool {
+        self.tagged || self.tag.is_some() || self.content.is_some()
+    }
+}
+
 pub fn parser(input: DeriveInput) -> Result<TokenStream> {
     let DeriveInput {
-        generics, ident, ..
+        attrs,
+        generics,
+        ident,
+        ..
     } = input;
+    let attr_args = AttrArgs::parse(&attrs)?;
+
+    let (into_impl_generics, ty_generics, _) = generics.split_for_impl();
+    let (from_impl_generics, into_where_clause, from_where_clause) = conversion_generics(&generics);
+
+    match input.data {
+        syn::Data::Struct(_) if attr_args.is_some() => {
+            bail!("The `#[zval_convert]` attribute can only be used on enums.")
+        }
+        syn::Data::Enum(data) if attr_args.as_ref().is_some_and(AttrArgs::is_tagged) => {
+            parse_tagged_enum(
+                data,
+                ident,
+                attr_args.unwrap_or_default(),
+                into_impl_generics,
+                from_impl_generics,
+                into_where_clause,
+                from_where_clause,
+                ty_generics,
+            )
+        }
+        syn::Data::Struct(data) => parse_struct(
+            data,
+            ident,
+            into_impl_generics,
+            from_impl_generics,
+            into_where_clause,
+            from_where_clause,
+            ty_generics,
+        ),
+        syn::Data::Enum(data) => parse_enum(
+            data,
+            ident,
+            into_impl_generics,
+            from_impl_generics,
+            into_where_clause,
+            from_where_clause,
+            ty_generics,
+        ),
+        _ => bail!("Only structs and enums are supported by the `#[derive(ZvalConvert)]` macro."),
+    }
+}
 
-    let (into_impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+/// Returns the generics of the `FromZval` implementations, which add the
+/// `'_zval` lifetime, along with the where clauses of the `IntoZval` and
+/// `FromZval` implementations, which bound each type parameter by the trait.
+fn conversion_generics(generics: &Generics) -> (Generics, WhereClause, WhereClause) {
+    let (into_impl_generics, _, where_clause) = generics.split_for_impl();
 
     let mut into_where_clause = where_clause.cloned().unwrap_or_else(|| WhereClause {
         where_token: Where {
@@ -64,27 +144,7 @@ pub fn parser(input: DeriveInput) -> Result<TokenStream> {
         }
     }
 
-    match input.data {
-        syn::Data::Struct(data) => parse_struct(
-            data,
-            ident,
-            into_impl_generics,
-            from_impl_generics,
-            into_where_clause,
-            from_where_clause,
-            ty_generics,
-        ),
-        syn::Data::Enum(data) => parse_enum(
-            data,
-            ident,
-            into_impl_generics,
-            from_impl_generics,
-            into_where_clause,
-            from_where_clause,
-            ty_generics,
-        ),
-        _ => bail!("Only structs and enums are supported by the `#[derive(ZvalConvert)]` macro."),
-    }
+    (from_impl_generics, into_where_clause, from_where_clause)
 }
 
 fn parse_struct(
@@ -260,3 +320,560 @@ fn parse_enum(
         }
     })
 }
+
+#[allow(clippy::too_many_arguments)]
+fn parse_tagged_enum(
+    data: DataEnum,
+    ident: Ident,
+    attr_args: AttrArgs,
+    into_impl_generics: ImplGenerics,
+    from_impl_generics: Generics,
+    into_where_clause: WhereClause,
+    from_where_clause: WhereClause,
+    ty_generics: TypeGenerics,
+) -> Result<TokenStream> {
+    let tag = attr_args.tag.unwrap_or_else(|| "type".into());
+    let content = attr_args.content.unwrap_or_else(|| "data".into());
+    let from_field = |ty: &syn::Type, zv: TokenStream| {
+        quote! {
+            match #zv {
+                ::std::option::Option::Some(zv) => <#ty>::from_zval(zv)
+                    .ok_or_else(|| ::ext_php_rs::error::Error::ZvalConversion(zv.get_type()))?,
+                ::std::option::Option::None => return ::ext_php_rs::error::Result::Err(
+                    ::ext_php_rs::error::Error::ZvalConversion(::ext_php_rs::flags::DataType::Undef)
+                ),
+            }
+        }
+    };
+    let data_array = quote! {
+        data.and_then(|zv| zv.array())
+            .ok_or(::ext_php_rs::error::Error::ZvalConversion(::ext_php_rs::flags::DataType::Undef))?
+    };
+
+    let (into_variants, from_variants): (Vec<_>, Vec<_>) = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let variant_ident = &variant.ident;
+            let name = variant_ident.to_string();
+
+            match &variant.fields {
+                syn::Fields::Unit => (
+                    quote! {
+                        #ident::#variant_ident => {
+                            ht.insert(#tag, #name)?;
+                        }
+                    },
+                    quote! {
+                        #name => Self::#variant_ident
+                    },
+                ),
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    let ty = &fields.unnamed.first().unwrap().ty;
+                    let val = from_field(ty, quote! { data });
+
+                    (
+                        quote! {
+                            #ident::#variant_ident(val) => {
+                                ht.insert(#tag, #name)?;
+                                ht.insert(#content, val)?;
+                            }
+                        },
+                        quote! {
+                            #name => Self::#variant_ident(#val)
+                        },
+                    )
+                }
+                syn::Fields::Unnamed(fields) => {
+                    let binds = (0..fields.unnamed.len())
+                        .map(|i| Ident::new(&format!("val_{}", i), Span::call_site()))
+                        .collect::<Vec<_>>();
+                    let vals = fields.unnamed.iter().enumerate().map(|(i, field)| {
+                        let i = i as u64;
+                        from_field(&field.ty, quote! { data.get_index(#i) })
+                    });
+
+                    (
+                        quote! {
+                            #ident::#variant_ident(#(#binds),*) => {
+                                let mut data = ::ext_php_rs::types::ZendHashTable::new();
+                                #(data.push(#binds)?;)*
+                                ht.insert(#tag, #name)?;
+                                ht.insert(#content, data)?;
+                            }
+                        },
+                        quote! {
+                            #name => {
+                                let data = #data_array;
+                                Self::#variant_ident(#(#vals),*)
+                            }
+                        },
+                    )
+                }
+                syn::Fields::Named(fields) => {
+                    let binds = fields
+                        .named
+                        .iter()
+                        .map(|field| field.ident.as_ref().unwrap())
+                        .collect::<Vec<_>>();
+                    let keys = binds
+                        .iter()
+                        .map(|bind| bind.to_string())
+                        .collect::<Vec<_>>();
+                    let vals = fields.named.iter().zip(keys.iter()).map(|(field, key)| {
+                        let ident = field.ident.as_ref().unwrap();
+                        let val = from_field(&field.ty, quote! { data.get(#key) });
+                        quote! { #ident: #val }
+                    });
+
+                    (
+                        quote! {
+                            #ident::#variant_ident { #(#binds),* } => {
+                                let mut data = ::ext_php_rs::types::ZendHashTable::new();
+                                #(data.insert(#keys, #binds)?;)*
+                                ht.insert(#tag, #name)?;
+                                ht.insert(#content, data)?;
+                            }
+                        },
+                        quote! {
+                            #name => {
+                                let data = #data_array;
+                                Self::#variant_ident { #(#vals),* }
+                            }
+                        },
+                    )
+                }
+            }
+        })
+        .unzip();
+
+    Ok(quote! {
+        impl #into_impl_generics ::ext_php_rs::convert::IntoZval for #ident #ty_generics #into_where_clause {
+            const TYPE: ::ext_php_rs::flags::DataType = ::ext_php_rs::flags::DataType::Array;
+
+            fn set_zval(
+                self,
+                zv: &mut ::ext_php_rs::types::Zval,
+                persistent: bool,
+            ) -> ::ext_php_rs::error::Result<()> {
+                use ::ext_php_rs::convert::IntoZval;
+
+                let mut ht = ::ext_php_rs::types::ZendHashTable::new();
+                match self {
+                    #(#into_variants)*
+                }
+                ht.set_zval(zv, persistent)
+            }
+        }
+
+        impl #from_impl_generics ::std::convert::TryFrom<&'_zval ::ext_php_rs::types::ZendHashTable> for #ident #ty_generics #from_where_clause {
+            type Error = ::ext_php_rs::error::Error;
+
+            fn try_from(ht: &'_zval ::ext_php_rs::types::ZendHashTable) -> ::ext_php_rs::error::Result<Self> {
+                use ::ext_php_rs::convert::FromZval;
+
+                let tag = ht
+                    .get(#tag)
+                    .and_then(|zv| zv.str())
+                    .ok_or(::ext_php_rs::error::Error::InvalidEnumTag(None))?;
+                #[allow(unused_variables)]
+                let data = ht.get(#content);
+
+                ::ext_php_rs::error::Result::Ok(match tag {
+                    #(#from_variants,)*
+                    tag => return ::ext_php_rs::error::Result::Err(
+                        ::ext_php_rs::error::Error::InvalidEnumTag(Some(tag.to_string()))
+                    ),
+                })
+            }
+        }
+
+        impl #from_impl_generics ::ext_php_rs::convert::FromZval<'_zval> for #ident #ty_generics #from_where_clause {
+            const TYPE: ::ext_php_rs::flags::DataType = ::ext_php_rs::flags::DataType::Array;
+
+            fn from_zval(zval: &'_zval ::ext_php_rs::types::Zval) -> ::std::option::Option<Self> {
+                use ::std::convert::TryFrom;
+
+                Self::try_from(zval.array()?).ok()
+            }
+        }
+    })
+}
+
+/// Parsed from the `#[php]` attribute on a struct deriving `FromZval` or
+/// `IntoZval`.
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+struct StructAttr {
+    object: bool,
+    skip_none: bool,
+}
+
+/// Parsed from the `#[php]` attribute on a field of a struct deriving
+/// `FromZval` or `IntoZval`.
+#[derive(Default, Debug, FromMeta)]
+#[darling(default)]
+struct FieldAttr {
+    rename: Option<String>,
+    default: bool,
+    skip_none: bool,
+}
+
+/// Parses the `#[php]` attributes in the given list of attributes, of which
+/// there may be more than one.
+fn parse_php_attrs<T: FromMeta>(attrs: &[Attribute]) -> Result<Vec<T>> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("php"))
+        .map(|attr| {
+            T::from_meta(&attr.parse_meta()?)
+                .map_err(|e| anyhow!("Unable to parse `#[php]` attribute: {:?}", e))
+        })
+        .collect()
+}
+
+impl StructAttr {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        Ok(parse_php_attrs::<Self>(attrs)?
+            .into_iter()
+            .fold(Self::default(), |acc, attr| Self {
+                object: acc.object || attr.object,
+                skip_none: acc.skip_none || attr.skip_none,
+            }))
+    }
+}
+
+impl FieldAttr {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        Ok(parse_php_attrs::<Self>(attrs)?
+            .into_iter()
+            .fold(Self::default(), |acc, attr| Self {
+                rename: attr.rename.or(acc.rename),
+                default: acc.default || attr.default,
+                skip_none: acc.skip_none || attr.skip_none,
+            }))
+    }
+}
+
+/// A field of a struct, stored in the array element or property with the
+/// given key.
+struct StructField<'a> {
+    ident: &'a Ident,
+    key: String,
+    default: bool,
+    skip_none: bool,
+}
+
+fn parse_fields<'a>(data: &'a DataStruct, attr: &StructAttr) -> Result<Vec<StructField<'a>>> {
+    data.fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.as_ref().with_context(|| {
+                "Fields require names when using the `#[derive(FromZval)]` and `#[derive(IntoZval)]` macros on a struct."
+            })?;
+            let field_attr = FieldAttr::parse(&field.attrs)?;
+            let is_option = is_option(&field.ty);
+            if field_attr.skip_none && !is_option {
+                bail!(
+                    "The `skip_none` attribute can only be used on `Option` fields, which `{}` is not.",
+                    ident
+                );
+            }
+
+            Ok(StructField {
+                ident,
+                key: field_attr.rename.unwrap_or_else(|| ident.to_string()),
+                // A missing `Option` is read as `None`.
+                default: field_attr.default || is_option,
+                skip_none: field_attr.skip_none || (attr.skip_none && is_option),
+            })
+        })
+        .collect()
+}
+
+fn struct_data(data: syn::Data, macro_name: &str) -> Result<DataStruct> {
+    match data {
+        syn::Data::Struct(data) => Ok(data),
+        _ => bail!(
+            "Only structs are supported by the `#[derive({})]` macro.",
+            macro_name
+        ),
+    }
+}
+
+pub fn from_zval_parser(input: DeriveInput) -> Result<TokenStream> {
+    let DeriveInput {
+        attrs,
+        generics,
+        ident,
+        data,
+        ..
+    } = input;
+    let attr = StructAttr::parse(&attrs)?;
+    let data = struct_data(data, "FromZval")?;
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (from_impl_generics, _, from_where_clause) = conversion_generics(&generics);
+
+    let fields = parse_fields(&data, &attr)?.into_iter().map(|field| {
+        let StructField {
+            ident,
+            key,
+            default,
+            ..
+        } = field;
+        let default = if default {
+            quote! { ::std::option::Option::Some(::std::default::Default::default) }
+        } else {
+            quote! { ::std::option::Option::None }
+        };
+
+        quote! {
+            #ident: ::ext_php_rs::internal::array_field(ht.get(#key), #key, #default)?,
+        }
+    });
+
+    // Objects are read from their property table.
+    let (ty, ht, ht_opt) = if attr.object {
+        (
+            quote! { ::ext_php_rs::flags::DataType::Object(None) },
+            quote! { zv.object().and_then(|obj| obj.get_properties().ok()) },
+            quote! { zv.object()?.get_properties().ok()? },
+        )
+    } else {
+        (
+            quote! { ::ext_php_rs::flags::DataType::Array },
+            quote! { zv.array() },
+            quote! { zv.array()? },
+        )
+    };
+
+    Ok(quote! {
+        impl #from_impl_generics ::std::convert::TryFrom<&'_zval ::ext_php_rs::types::ZendHashTable> for #ident #ty_generics #from_where_clause {
+            type Error = ::ext_php_rs::error::ConversionError;
+
+            fn try_from(
+                ht: &'_zval ::ext_php_rs::types::ZendHashTable,
+            ) -> ::std::result::Result<Self, ::ext_php_rs::error::ConversionError> {
+                ::std::result::Result::Ok(Self {
+                    #(#fields)*
+                })
+            }
+        }
+
+        impl #from_impl_generics ::std::convert::TryFrom<&'_zval ::ext_php_rs::types::Zval> for #ident #ty_generics #from_where_clause {
+            type Error = ::ext_php_rs::error::ConversionError;
+
+            fn try_from(
+                zv: &'_zval ::ext_php_rs::types::Zval,
+            ) -> ::std::result::Result<Self, ::ext_php_rs::error::ConversionError> {
+                let ht = #ht.ok_or_else(|| ::ext_php_rs::error::ConversionError::new(#ty, zv))?;
+
+                Self::try_from(ht)
+            }
+        }
+
+        impl #from_impl_generics ::ext_php_rs::convert::FromZval<'_zval> for #ident #ty_generics #from_where_clause {
+            const TYPE: ::ext_php_rs::flags::DataType = #ty;
+
+            fn from_zval(zv: &'_zval ::ext_php_rs::types::Zval) -> ::std::option::Option<Self> {
+                use ::std::convert::TryFrom;
+
+                Self::try_from(#ht_opt).ok()
+            }
+        }
+    })
+}
+
+pub fn into_zval_parser(input: DeriveInput) -> Result<TokenStream> {
+    let DeriveInput {
+        attrs,
+        generics,
+        ident,
+        data,
+        ..
+    } = input;
+    let attr = StructAttr::parse(&attrs)?;
+    let data = struct_data(data, "IntoZval")?;
+
+    let (into_impl_generics, ty_generics, _) = generics.split_for_impl();
+    let (_, into_where_clause, _) = conversion_generics(&generics);
+
+    let set = if attr.object {
+        quote! { set_property }
+    } else {
+        quote! { insert }
+    };
+    let fields = parse_fields(&data, &attr)?.into_iter().map(|field| {
+        let StructField {
+            ident,
+            key,
+            skip_none,
+            ..
+        } = field;
+
+        if skip_none {
+            quote! {
+                if let ::std::option::Option::Some(val) = self.#ident {
+                    container.#set(#key, val)?;
+                }
+            }
+        } else {
+            quote! {
+                container.#set(#key, self.#ident)?;
+            }
+        }
+    });
+
+    let (ty, container) = if attr.object {
+        (
+            quote! { ::ext_php_rs::flags::DataType::Object(None) },
+            quote! { ::ext_php_rs::types::ZendObject::new_stdclass() },
+        )
+    } else {
+        (
+            quote! { ::ext_php_rs::flags::DataType::Array },
+            quote! { ::ext_php_rs::types::ZendHashTable::new() },
+        )
+    };
+
+    Ok(quote! {
+        impl #into_impl_generics ::ext_php_rs::convert::IntoZval for #ident #ty_generics #into_where_clause {
+            const TYPE: ::ext_php_rs::flags::DataType = #ty;
+
+            fn set_zval(
+                self,
+                zv: &mut ::ext_php_rs::types::Zval,
+                persistent: bool,
+            ) -> ::ext_php_rs::error::Result<()> {
+                use ::ext_php_rs::convert::IntoZval;
+
+                let mut container = #container;
+                #(#fields)*
+                container.set_zval(zv, persistent)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{into_zval_parser, parse_fields, StructAttr};
+    use syn::{parse_quote, DeriveInput};
+
+    /// Returns the fields of the struct as `(ident, key, default, skip_none)`.
+    fn fields(input: DeriveInput) -> anyhow::Result<Vec<(String, String, bool, bool)>> {
+        let attr = StructAttr::parse(&input.attrs)?;
+        let data = match input.data {
+            syn::Data::Struct(data) => data,
+            _ => unreachable!(),
+        };
+
+        Ok(parse_fields(&data, &attr)?
+            .into_iter()
+            .map(|field| {
+                (
+                    field.ident.to_string(),
+                    field.key,
+                    field.default,
+                    field.skip_none,
+                )
+            })
+            .collect())
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        let input: DeriveInput = parse_quote! {
+            struct Options {
+                verbose: bool,
+                #[php(rename = "max-depth")]
+                max_depth: u32,
+                #[php(default)]
+                retries: u32,
+                timeout: Option<f64>,
+            }
+        };
+
+        assert_eq!(
+            fields(input).unwrap(),
+            vec![
+                ("verbose".into(), "verbose".into(), false, false),
+                ("max_depth".into(), "max-depth".into(), false, false),
+                ("retries".into(), "retries".into(), true, false),
+                ("timeout".into(), "timeout".into(), true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_struct_fields_skip_none() {
+        let input: DeriveInput = parse_quote! {
+            #[php(object, skip_none)]
+            struct User {
+                name: String,
+                email: Option<String>,
+                #[php(rename = "displayName")]
+                #[php(skip_none)]
+                display_name: Option<String>,
+            }
+        };
+        let attr = StructAttr::parse(&input.attrs).unwrap();
+        assert!(attr.object);
+
+        assert_eq!(
+            fields(input).unwrap(),
+            vec![
+                ("name".into(), "name".into(), false, false),
+                ("email".into(), "email".into(), true, true),
+                ("display_name".into(), "displayName".into(), true, true),
+            ]
+        );
+
+        let input: DeriveInput = parse_quote! {
+            struct User {
+                #[php(skip_none)]
+                name: String,
+            }
+        };
+        assert!(fields(input).is_err());
+    }
+
+    #[test]
+    fn test_struct_fields_unnamed() {
+        let input: DeriveInput = parse_quote! {
+            struct Pair(i64, i64);
+        };
+        assert!(fields(input).is_err());
+    }
+
+    #[test]
+    fn test_into_zval_nested() {
+        let into_zval = |input: DeriveInput| into_zval_parser(input).unwrap().to_string();
+
+        let inner = into_zval(parse_quote! {
+            #[php(object)]
+            struct Retry {
+                attempts: u32,
+            }
+        });
+        assert!(inner.contains("ZendObject :: new_stdclass ()"));
+        assert!(inner.contains(r#"container . set_property ("attempts" , self . attempts) ?"#));
+
+        let outer = into_zval(parse_quote! {
+            struct Options {
+                url: String,
+                #[php(rename = "retry-policy")]
+                retry: Retry,
+                #[php(skip_none)]
+                timeout: Option<f64>,
+            }
+        });
+        assert!(outer.contains("ZendHashTable :: new ()"));
+        assert!(outer.contains(r#"container . insert ("url" , self . url) ?"#));
+        assert!(outer.contains(r#"container . insert ("retry-policy" , self . retry) ?"#));
+        assert!(outer.contains(
+            r#"if let :: std :: option :: Option :: Some (val) = self . timeout { container . insert ("timeout" , val) ? ; }"#
+        ));
+    }
+}
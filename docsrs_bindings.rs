@@ -4,6 +4,7 @@ pub const ZEND_DEBUG: u32 = 1;
 pub const ZEND_MM_ALIGNMENT: u32 = 8;
 pub const _ZEND_TYPE_NAME_BIT: u32 = 8388608;
 pub const _ZEND_TYPE_NULLABLE_BIT: u32 = 2;
+pub const HASH_FLAG_PACKED: u32 = 4;
 pub const HT_MIN_SIZE: u32 = 8;
 pub const IS_UNDEF: u32 = 0;
 pub const IS_NULL: u32 = 1;
@@ -81,9 +82,19 @@ pub const ZEND_ACC_DONE_PASS_TWO: u32 = 33554432;
 pub const ZEND_ACC_HEAP_RT_CACHE: u32 = 67108864;
 pub const ZEND_ACC_STRICT_TYPES: u32 = 2147483648;
 pub const ZEND_ISEMPTY: u32 = 1;
+pub const ZEND_UNCOMPARABLE: u32 = 1;
 pub const _ZEND_SEND_MODE_SHIFT: u32 = 24;
 pub const _ZEND_IS_VARIADIC_BIT: u32 = 67108864;
+pub const ZEND_INI_USER: u32 = 1;
+pub const ZEND_INI_PERDIR: u32 = 2;
+pub const ZEND_INI_SYSTEM: u32 = 4;
+pub const ZEND_INI_ALL: u32 = 7;
 pub const ZEND_MODULE_API_NO: u32 = 20200930;
+pub const MODULE_DEP_REQUIRED: u32 = 1;
+pub const MODULE_DEP_CONFLICTS: u32 = 2;
+pub const MODULE_DEP_OPTIONAL: u32 = 3;
+pub const PHP_MAJOR_VERSION: u32 = 8;
+pub const PHP_MINOR_VERSION: u32 = 0;
 pub const USING_ZTS: u32 = 0;
 pub const MAY_BE_BOOL: u32 = 12;
 pub const MAY_BE_ANY: u32 = 1022;
@@ -123,6 +134,7 @@ pub type zend_resource = _zend_resource;
 pub type zend_reference = _zend_reference;
 pub type zend_ast_ref = _zend_ast_ref;
 pub type dtor_func_t = ::std::option::Option<unsafe extern "C" fn(pDest: *mut zval)>;
+pub type rsrc_dtor_func_t = ::std::option::Option<unsafe extern "C" fn(res: *mut zend_resource)>;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct zend_type {
@@ -332,6 +344,9 @@ extern "C" {
         pData: *mut zval,
     ) -> *mut zval;
 }
+extern "C" {
+    pub fn zend_hash_extend(ht: *mut HashTable, nSize: u32, packed: bool);
+}
 extern "C" {
     pub fn zend_hash_index_update(ht: *mut HashTable, h: zend_ulong, pData: *mut zval)
         -> *mut zval;
@@ -356,6 +371,9 @@ extern "C" {
         len: size_t,
     ) -> *mut zval;
 }
+extern "C" {
+    pub fn zend_is_auto_global_str(name: *const ::std::os::raw::c_char, len: size_t) -> bool;
+}
 extern "C" {
     pub fn zend_hash_index_find(ht: *const HashTable, h: zend_ulong) -> *mut zval;
 }
@@ -780,6 +798,13 @@ extern "C" {
         cache_slot: *mut *mut ::std::os::raw::c_void,
     ) -> ::std::os::raw::c_int;
 }
+extern "C" {
+    pub fn zend_std_cast_object_tostring(
+        readobj: *mut zend_object,
+        writeobj: *mut zval,
+        type_: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
 extern "C" {
     pub fn zend_is_true(op: *mut zval) -> ::std::os::raw::c_int;
 }
@@ -837,6 +862,7 @@ pub struct _zend_property_info {
     pub ce: *mut zend_class_entry,
     pub type_: zend_type,
 }
+pub type zend_property_info = _zend_property_info;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _zend_internal_arg_info {
@@ -1115,6 +1141,7 @@ pub struct _zend_module_dep {
     pub version: *const ::std::os::raw::c_char,
     pub type_: ::std::os::raw::c_uchar,
 }
+pub type zend_module_dep = _zend_module_dep;
 extern "C" {
     pub fn zend_lookup_class_ex(
         name: *mut zend_string,
@@ -1139,12 +1166,50 @@ pub struct _zend_function_entry {
     pub flags: u32,
 }
 pub type zend_function_entry = _zend_function_entry;
+extern "C" {
+    pub fn zend_register_functions(
+        scope: *mut zend_class_entry,
+        functions: *const zend_function_entry,
+        function_table: *mut HashTable,
+        type_: ::std::os::raw::c_int,
+    ) -> zend_result;
+}
+extern "C" {
+    pub fn zend_unregister_functions(
+        functions: *const zend_function_entry,
+        count: ::std::os::raw::c_int,
+        function_table: *mut HashTable,
+    );
+}
+extern "C" {
+    pub static mut module_registry: HashTable;
+}
 extern "C" {
     pub fn zend_register_internal_class_ex(
         class_entry: *mut zend_class_entry,
         parent_ce: *mut zend_class_entry,
     ) -> *mut zend_class_entry;
 }
+extern "C" {
+    pub fn zend_register_internal_enum(
+        name: *const ::std::os::raw::c_char,
+        type_: zend_uchar,
+        functions: *const zend_function_entry,
+    ) -> *mut zend_class_entry;
+}
+extern "C" {
+    pub fn zend_enum_add_case_cstr(
+        ce: *mut zend_class_entry,
+        name: *const ::std::os::raw::c_char,
+        value: *mut zval,
+    );
+}
+extern "C" {
+    pub fn zend_enum_get_case_cstr(
+        ce: *mut zend_class_entry,
+        name: *const ::std::os::raw::c_char,
+    ) -> *mut zend_object;
+}
 extern "C" {
     pub fn zend_is_callable(
         callable: *mut zval,
@@ -1161,6 +1226,16 @@ extern "C" {
         access_type: ::std::os::raw::c_int,
     );
 }
+extern "C" {
+    pub fn zend_declare_typed_property(
+        ce: *mut zend_class_entry,
+        name: *mut zend_string,
+        property: *mut zval,
+        access_type: ::std::os::raw::c_int,
+        doc_comment: *mut zend_string,
+        type_: zend_type,
+    ) -> *mut zend_property_info;
+}
 extern "C" {
     pub fn zend_declare_class_constant(
         ce: *mut zend_class_entry,
@@ -1169,6 +1244,20 @@ extern "C" {
         value: *mut zval,
     );
 }
+extern "C" {
+    pub fn zend_update_static_property_ex(
+        scope: *mut zend_class_entry,
+        name: *mut zend_string,
+        value: *mut zval,
+    ) -> zend_result;
+}
+extern "C" {
+    pub fn zend_read_static_property_ex(
+        scope: *mut zend_class_entry,
+        name: *mut zend_string,
+        silent: bool,
+    ) -> *mut zval;
+}
 extern "C" {
     pub fn object_properties_init(object: *mut zend_object, class_type: *mut zend_class_entry);
 }
@@ -1193,6 +1282,15 @@ extern "C" {
         named_params: *mut HashTable,
     );
 }
+extern "C" {
+    pub fn zend_create_fake_closure(
+        res: *mut zval,
+        func: *mut zend_function,
+        scope: *mut zend_class_entry,
+        called_scope: *mut zend_class_entry,
+        this_ptr: *mut zval,
+    );
+}
 pub const _zend_expected_type_Z_EXPECTED_LONG: _zend_expected_type = 0;
 pub const _zend_expected_type_Z_EXPECTED_LONG_OR_NULL: _zend_expected_type = 1;
 pub const _zend_expected_type_Z_EXPECTED_BOOL: _zend_expected_type = 2;
@@ -1232,6 +1330,32 @@ extern "C" {
 }
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+pub struct _zend_ini_entry_def {
+    pub name: *const ::std::os::raw::c_char,
+    pub on_modify: ::std::option::Option<
+        unsafe extern "C" fn(
+            entry: *mut zend_ini_entry,
+            new_value: *mut zend_string,
+            mh_arg1: *mut ::std::os::raw::c_void,
+            mh_arg2: *mut ::std::os::raw::c_void,
+            mh_arg3: *mut ::std::os::raw::c_void,
+            stage: ::std::os::raw::c_int,
+        ) -> ::std::os::raw::c_int,
+    >,
+    pub mh_arg1: *mut ::std::os::raw::c_void,
+    pub mh_arg2: *mut ::std::os::raw::c_void,
+    pub mh_arg3: *mut ::std::os::raw::c_void,
+    pub value: *const ::std::os::raw::c_char,
+    pub displayer: ::std::option::Option<
+        unsafe extern "C" fn(ini_entry: *mut zend_ini_entry, type_: ::std::os::raw::c_int),
+    >,
+    pub value_length: u32,
+    pub name_length: u16,
+    pub modifiable: u8,
+}
+pub type zend_ini_entry_def = _zend_ini_entry_def;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
 pub struct _zend_ini_entry {
     pub name: *mut zend_string,
     pub on_modify: ::std::option::Option<
@@ -1257,6 +1381,30 @@ pub struct _zend_ini_entry {
     pub orig_modifiable: u8,
     pub modified: u8,
 }
+extern "C" {
+    pub fn zend_register_ini_entries(
+        ini_entry: *const zend_ini_entry_def,
+        module_number: ::std::os::raw::c_int,
+    ) -> zend_result;
+}
+extern "C" {
+    pub fn zend_unregister_ini_entries(module_number: ::std::os::raw::c_int);
+}
+extern "C" {
+    pub fn zend_ini_long(
+        name: *const ::std::os::raw::c_char,
+        name_length: size_t,
+        orig: ::std::os::raw::c_int,
+    ) -> zend_long;
+}
+extern "C" {
+    pub fn zend_ini_string_ex(
+        name: *const ::std::os::raw::c_char,
+        name_length: size_t,
+        orig: ::std::os::raw::c_int,
+        exists: *mut zend_bool,
+    ) -> *mut ::std::os::raw::c_char;
+}
 extern "C" {
     pub fn zend_register_bool_constant(
         name: *const ::std::os::raw::c_char,
@@ -1314,6 +1462,9 @@ extern "C" {
 extern "C" {
     pub static mut zend_ce_error_exception: *mut zend_class_entry;
 }
+extern "C" {
+    pub static mut zend_ce_error: *mut zend_class_entry;
+}
 extern "C" {
     pub static mut zend_ce_compile_error: *mut zend_class_entry;
 }
@@ -1349,6 +1500,21 @@ extern "C" {
 extern "C" {
     pub fn zend_do_implement_interface(ce: *mut zend_class_entry, iface: *mut zend_class_entry);
 }
+extern "C" {
+    pub static mut zend_ce_iterator: *mut zend_class_entry;
+}
+extern "C" {
+    pub static mut zend_ce_arrayaccess: *mut zend_class_entry;
+}
+extern "C" {
+    pub static mut zend_ce_countable: *mut zend_class_entry;
+}
+extern "C" {
+    pub static mut zend_ce_stringable: *mut zend_class_entry;
+}
+extern "C" {
+    pub static mut php_json_serializable_ce: *mut zend_class_entry;
+}
 extern "C" {
     pub fn zend_class_serialize_deny(
         object: *mut zval,
@@ -1366,6 +1532,29 @@ extern "C" {
         data: *mut zend_unserialize_data,
     ) -> ::std::os::raw::c_int;
 }
+extern "C" {
+    pub fn zend_list_delete(res: *mut zend_resource);
+}
+extern "C" {
+    pub fn zend_list_close(res: *mut zend_resource);
+}
+extern "C" {
+    pub fn zend_rsrc_list_get_rsrc_type(res: *mut zend_resource) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn zend_register_resource(
+        rsrc_pointer: *mut ::std::os::raw::c_void,
+        rsrc_type: ::std::os::raw::c_int,
+    ) -> *mut zend_resource;
+}
+extern "C" {
+    pub fn zend_register_list_destructors_ex(
+        ld: rsrc_dtor_func_t,
+        pld: rsrc_dtor_func_t,
+        type_name: *const ::std::os::raw::c_char,
+        module_number: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
 extern "C" {
     pub fn ext_php_rs_zend_string_init(
         str_: *const ::std::os::raw::c_char,
@@ -1373,6 +1562,16 @@ extern "C" {
         persistent: bool,
     ) -> *mut zend_string;
 }
+extern "C" {
+    pub fn ext_php_rs_zend_string_alloc(len: size_t, persistent: bool) -> *mut zend_string;
+}
+extern "C" {
+    pub fn ext_php_rs_zend_string_realloc(
+        zs: *mut zend_string,
+        len: size_t,
+        persistent: bool,
+    ) -> *mut zend_string;
+}
 extern "C" {
     pub fn ext_php_rs_zend_string_release(zs: *mut zend_string);
 }
@@ -1391,3 +1590,17 @@ extern "C" {
 extern "C" {
     pub fn ext_php_rs_executor_globals() -> *mut zend_executor_globals;
 }
+extern "C" {
+    pub fn ext_php_rs_zts_module_globals(
+        id: ::std::os::raw::c_int,
+    ) -> *mut ::std::os::raw::c_void;
+}
+extern "C" {
+    pub fn ext_php_rs_zend_register_array_constant(
+        name: *const ::std::os::raw::c_char,
+        name_len: size_t,
+        arr: *mut zend_array,
+        flags: ::std::os::raw::c_int,
+        module_number: ::std::os::raw::c_int,
+    );
+}
@@ -24,9 +24,11 @@ pub struct Arg<'a> {
     _type: DataType,
     as_ref: bool,
     allow_null: bool,
-    variadic: bool,
+    pub(crate) variadic: bool,
     default_value: Option<String>,
     zval: Option<&'a mut Zval>,
+    variadic_zvals: Vec<&'a mut Zval>,
+    position: usize,
 }
 
 impl<'a> Arg<'a> {
@@ -45,6 +47,8 @@ impl<'a> Arg<'a> {
             variadic: false,
             default_value: None,
             zval: None,
+            variadic_zvals: vec![],
+            position: 0,
         }
     }
 
@@ -101,6 +105,26 @@ impl<'a> Arg<'a> {
         self.zval.as_mut().and_then(|zv| T::from_zval_mut(zv))
     }
 
+    /// Attempts to retrieve the values given for a variadic argument,
+    /// converting each value into `T`. This will be empty until the ArgParser
+    /// is used to parse the arguments, or if no values were given.
+    ///
+    /// If a value could not be converted, the position of the value in the
+    /// arguments given to the function, starting from one, is returned in the
+    /// [`Err`] variant.
+    pub fn variadic_vals<T>(&'a mut self) -> Result<Vec<T>, usize>
+    where
+        T: FromZvalMut<'a>,
+    {
+        let position = self.position;
+
+        self.variadic_zvals
+            .iter_mut()
+            .enumerate()
+            .map(|(i, zv)| T::from_zval_mut(zv).ok_or(position + i + 1))
+            .collect()
+    }
+
     /// Attempts to return a reference to the arguments internal Zval.
     ///
     /// # Returns
@@ -220,23 +244,40 @@ impl<'a, 'b> ArgParser<'a, 'b> {
     /// passed to the function. The user has already been notified so you
     /// should break execution after seeing an error type.
     pub fn parse(mut self) -> Result<()> {
-        let max_num_args = self.args.len();
-        let min_num_args = self.min_num_args.unwrap_or(max_num_args);
+        // A variadic argument must be the last argument, and accepts any number of
+        // values, including none.
+        let variadic = matches!(self.args.last(), Some(arg) if arg.variadic);
+        let num_fixed_args = self.args.len() - variadic as usize;
+        let max_num_args = if variadic { None } else { Some(num_fixed_args) };
+        let min_num_args = self.min_num_args.unwrap_or(num_fixed_args);
         let num_args = self.arg_zvals.len();
 
-        if num_args < min_num_args || num_args > max_num_args {
+        if num_args < min_num_args || matches!(max_num_args, Some(max) if num_args > max) {
             // SAFETY: Exported C function is safe, return value is unused and parameters
             // are copied.
-            unsafe { zend_wrong_parameters_count_error(min_num_args as _, max_num_args as _) };
+            unsafe {
+                zend_wrong_parameters_count_error(
+                    min_num_args as _,
+                    max_num_args.map_or(u32::MAX, |max| max as _),
+                )
+            };
             return Err(Error::IncorrectArguments(num_args, min_num_args));
         }
 
         for (i, arg_zval) in self.arg_zvals.into_iter().enumerate() {
-            if let Some(arg) = self.args.get_mut(i) {
-                arg.zval = arg_zval;
+            if i < num_fixed_args {
+                if let Some(arg) = self.args.get_mut(i) {
+                    arg.zval = arg_zval;
+                }
+            } else if let (Some(arg), Some(zval)) = (self.args.last_mut(), arg_zval) {
+                arg.variadic_zvals.push(zval);
             }
         }
 
+        if let Some(arg) = self.args.last_mut().filter(|_| variadic) {
+            arg.position = num_fixed_args;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,102 @@
+//! Traits used for exporting Rust types to PHP as classes which implement the
+//! `ArrayAccess` interface, allowing objects to be used with the array
+//! subscript syntax (`$obj['key']`).
+
+use crate::{class::RegisteredClass, convert::IntoZval, exception::PhpResult, types::Zval};
+
+/// Implemented on Rust types which are exported to PHP as classes which
+/// implement the `ArrayAccess` interface.
+///
+/// The class must be registered with the `#[array_access]` attribute underneath
+/// the [`macro@php_class`] attribute, which implements the interface and
+/// registers the `offsetGet`, `offsetSet`, `offsetExists` and `offsetUnset`
+/// methods on the class.
+///
+/// Offsets are given as an untyped [`Zval`], as PHP allows any type to be used
+/// as an offset, although they are generally integers or strings. The offset
+/// given to [`offset_set`] is null when appending to the object
+/// (`$obj[] = $value`).
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use std::collections::HashMap;
+/// use ext_php_rs::{array_access::PhpArrayAccess, types::Zval};
+///
+/// #[php_class]
+/// #[array_access]
+/// #[derive(Default)]
+/// pub struct Config {
+///     values: HashMap<String, String>,
+/// }
+///
+/// impl PhpArrayAccess for Config {
+///     type Value = Option<String>;
+///
+///     fn offset_exists(&mut self, offset: &Zval) -> PhpResult<bool> {
+///         Ok(offset.str().map_or(false, |key| self.values.contains_key(key)))
+///     }
+///
+///     fn offset_get(&mut self, offset: &Zval) -> PhpResult<Option<String>> {
+///         Ok(offset.str().and_then(|key| self.values.get(key).cloned()))
+///     }
+///
+///     fn offset_set(&mut self, offset: &Zval, value: &Zval) -> PhpResult {
+///         let key = offset.string().ok_or("Offset must be a string.")?;
+///         let value = value.string().ok_or("Value must be a string.")?;
+///         self.values.insert(key, value);
+///         Ok(())
+///     }
+///
+///     fn offset_unset(&mut self, offset: &Zval) -> PhpResult {
+///         if let Some(key) = offset.str() {
+///             self.values.remove(key);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`macro@php_class`]: crate::php_class
+/// [`offset_set`]: PhpArrayAccess::offset_set
+pub trait PhpArrayAccess: RegisteredClass {
+    /// The type returned when reading an offset.
+    type Value: IntoZval;
+
+    /// Returns whether the given offset exists. Called when using `isset()` or
+    /// `empty()` on an offset.
+    ///
+    /// # Parameters
+    ///
+    /// * `offset` - The offset to check.
+    fn offset_exists(&mut self, offset: &Zval) -> PhpResult<bool>;
+
+    /// Returns the value at the given offset.
+    ///
+    /// # Parameters
+    ///
+    /// * `offset` - The offset to retrieve.
+    fn offset_get(&mut self, offset: &Zval) -> PhpResult<Self::Value>;
+
+    /// Sets the value at the given offset.
+    ///
+    /// # Parameters
+    ///
+    /// * `offset` - The offset to set. Null when appending a value.
+    /// * `value` - The new value.
+    fn offset_set(&mut self, offset: &Zval, value: &Zval) -> PhpResult;
+
+    /// Removes the value at the given offset. Called when using `unset()` on an
+    /// offset.
+    ///
+    /// # Parameters
+    ///
+    /// * `offset` - The offset to remove.
+    fn offset_unset(&mut self, offset: &Zval) -> PhpResult;
+}
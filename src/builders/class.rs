@@ -1,20 +1,35 @@
-use std::{ffi::CString, mem::MaybeUninit};
+use std::{
+    ffi::{CStr, CString},
+    mem::MaybeUninit,
+};
 
 use crate::{
+    args::Arg,
+    array_access::PhpArrayAccess,
     builders::FunctionBuilder,
     class::{ConstructorMeta, ConstructorResult, RegisteredClass},
     convert::IntoZval,
+    countable::PhpCountable,
     error::{Error, Result},
-    exception::PhpException,
+    exception::{PhpException, PhpResult},
     ffi::{
-        zend_declare_class_constant, zend_declare_property, zend_do_implement_interface,
-        zend_register_internal_class_ex,
+        zend_declare_class_constant, zend_declare_property, zend_declare_typed_property,
+        zend_do_implement_interface, zend_register_internal_class_ex,
     },
-    flags::{ClassFlags, MethodFlags, PropertyFlags},
-    types::{ZendClassObject, ZendObject, ZendStr, Zval},
-    zend::{ClassEntry, ExecuteData, FunctionEntry},
+    flags::{ClassFlags, DataType, MethodFlags, PropertyFlags},
+    iterator::{IterState, PhpIterator},
+    json::PhpJsonSerialize,
+    magic::{PhpDebugInfo, PhpMagicProperties},
+    serialize::PhpSerialize,
+    stringable::PhpToString,
+    types::{ZendClassObject, ZendHashTable, ZendObject, ZendStr, Zval},
+    zend::{ce, ClassEntry, ExecuteData, FunctionEntry, ZendType},
 };
 
+/// A property declared on a class, with its name, default value and flags,
+/// along with its type and whether it is nullable if the property is typed.
+type ClassProperty = (String, Zval, PropertyFlags, Option<(DataType, bool)>);
+
 /// Builder for registering a class in PHP.
 pub struct ClassBuilder {
     name: String,
@@ -23,8 +38,10 @@ pub struct ClassBuilder {
     interfaces: Vec<&'static ClassEntry>,
     methods: Vec<FunctionEntry>,
     object_override: Option<unsafe extern "C" fn(class_type: *mut ClassEntry) -> *mut ZendObject>,
-    properties: Vec<(String, Zval, PropertyFlags)>,
-    constants: Vec<(String, Zval)>,
+    properties: Vec<ClassProperty>,
+    constants: Vec<(String, Zval, Option<DataType>)>,
+    serializable: bool,
+    instantiable: bool,
 }
 
 impl ClassBuilder {
@@ -46,6 +63,8 @@ impl ClassBuilder {
             object_override: None,
             properties: vec![],
             constants: vec![],
+            serializable: false,
+            instantiable: true,
         }
     }
 
@@ -114,7 +133,70 @@ impl ClassBuilder {
             Err(_) => panic!("Invalid default value for property `{}`.", name.into()),
         };
 
-        self.properties.push((name.into(), default, flags));
+        self.properties.push((name.into(), default, flags, None));
+        self
+    }
+
+    /// Adds a typed property to the class. The type of the property is given
+    /// by the type of the given default, and PHP throws a `TypeError` when a
+    /// value of another type is assigned to the property.
+    ///
+    /// Properties of a type which PHP does not allow properties to be declared
+    /// with, such as `callable`, are added without a type.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property to add to the class.
+    /// * `default` - The default value of the property.
+    /// * `nullable` - Whether the property can be set to null.
+    /// * `flags` - Flags relating to the property. See [`PropertyFlags`].
+    ///
+    /// # Panics
+    ///
+    /// Function will panic if the given `default` cannot be converted into a
+    /// [`Zval`].
+    pub fn typed_property<N: Into<String>, T: IntoZval>(
+        mut self,
+        name: N,
+        default: T,
+        nullable: bool,
+        flags: PropertyFlags,
+    ) -> Self {
+        let name = name.into();
+        let default = match default.into_zval(true) {
+            Ok(default) => default,
+            Err(_) => panic!("Invalid default value for property `{}`.", name),
+        };
+
+        self.properties
+            .push((name, default, flags, Some((T::TYPE, nullable))));
+        self
+    }
+
+    /// Adds a readonly property to the class. The type of the property is given
+    /// by `T`. The property has no default value, and can only be initialized
+    /// once from inside the class, after which PHP throws an `Error` when the
+    /// property is modified.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property to add to the class.
+    /// * `nullable` - Whether the property can be set to null.
+    /// * `flags` - Flags relating to the property. See [`PropertyFlags`]. The
+    ///   [`PropertyFlags::Readonly`] flag is always added.
+    #[cfg(php81)]
+    pub fn readonly_property<N: Into<String>, T: IntoZval>(
+        mut self,
+        name: N,
+        nullable: bool,
+        flags: PropertyFlags,
+    ) -> Self {
+        self.properties.push((
+            name.into(),
+            Zval::undef(),
+            flags | PropertyFlags::Readonly,
+            Some((T::TYPE, nullable)),
+        ));
         self
     }
 
@@ -131,7 +213,29 @@ impl ClassBuilder {
     pub fn constant<T: Into<String>>(mut self, name: T, value: impl IntoZval) -> Result<Self> {
         let value = value.into_zval(true)?;
 
-        self.constants.push((name.into(), value));
+        self.constants.push((name.into(), value, None));
+        Ok(self)
+    }
+
+    /// Adds a constant to the class, declared with the type of the given
+    /// value. The type is only declared when built against PHP 8.3 or later,
+    /// which introduced typed class constants, and is otherwise left out.
+    ///
+    /// Returns a result containing the class builder if the constant was
+    /// successfully added.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the constant to add to the class.
+    /// * `value` - The value of the constant.
+    pub fn typed_constant<T: Into<String>, V: IntoZval>(
+        mut self,
+        name: T,
+        value: V,
+    ) -> Result<Self> {
+        let value = value.into_zval(true)?;
+
+        self.constants.push((name.into(), value, Some(V::TYPE)));
         Ok(self)
     }
 
@@ -145,6 +249,18 @@ impl ClassBuilder {
         self
     }
 
+    /// Prevents the class from being instantiated, for classes which only hold
+    /// static methods, properties and constants.
+    ///
+    /// The constructor of the class is registered as private, so `new` throws
+    /// an `Error` as it would for a PHP class declaring a private constructor,
+    /// e.g. `Call to private Foo::__construct() from global scope`. A private
+    /// constructor is added to the class if it has none.
+    pub fn uninstantiable(mut self) -> Self {
+        self.instantiable = false;
+        self
+    }
+
     /// Overrides the creation of the Zend object which will represent an
     /// instance of this class.
     ///
@@ -217,6 +333,545 @@ impl ClassBuilder {
         )
     }
 
+    /// Implements the `Iterator` interface on the class, registering the
+    /// iterator methods which are backed by the Rust iterator returned from
+    /// [`PhpIterator::iter_state`].
+    ///
+    /// # Parameters
+    ///
+    /// * `T` - The type which implements [`PhpIterator`]. Must be the same type
+    ///   given to [`ClassBuilder::object_override`].
+    pub fn iterator<T>(self) -> Self
+    where
+        T: PhpIterator,
+        <T::Iter as Iterator>::Item: IntoZval + Clone,
+    {
+        fn state<T: PhpIterator>(ex: &mut ExecuteData) -> Option<&mut IterState<T::Iter>> {
+            match ex.get_object::<T>() {
+                Some(this) => Some(this.iter_state()),
+                None => {
+                    PhpException::default("Failed to retrieve reference to `$this`".into())
+                        .throw()
+                        .expect("Failed to throw exception");
+                    None
+                }
+            }
+        }
+
+        extern "C" fn current<T>(ex: &mut ExecuteData, retval: &mut Zval)
+        where
+            T: PhpIterator,
+            <T::Iter as Iterator>::Item: IntoZval + Clone,
+        {
+            if let Some(state) = state::<T>(ex) {
+                let result = match state.current().cloned() {
+                    Some(item) => item.set_zval(retval, false),
+                    None => {
+                        retval.set_null();
+                        Ok(())
+                    }
+                };
+
+                if let Err(e) = result {
+                    let e: PhpException = e.into();
+                    e.throw().expect("Failed to throw exception");
+                }
+            }
+        }
+
+        extern "C" fn key<T: PhpIterator>(ex: &mut ExecuteData, retval: &mut Zval) {
+            if let Some(state) = state::<T>(ex) {
+                match state.key() {
+                    Some(key) => retval.set_long(key),
+                    None => retval.set_null(),
+                }
+            }
+        }
+
+        extern "C" fn next<T: PhpIterator>(ex: &mut ExecuteData, _: &mut Zval) {
+            if let Some(state) = state::<T>(ex) {
+                state.next();
+            }
+        }
+
+        extern "C" fn rewind<T: PhpIterator>(ex: &mut ExecuteData, _: &mut Zval) {
+            if let Some(state) = state::<T>(ex) {
+                if let Err(e) = state.rewind() {
+                    e.throw().expect("Failed to throw exception");
+                }
+            }
+        }
+
+        extern "C" fn valid<T: PhpIterator>(ex: &mut ExecuteData, retval: &mut Zval) {
+            if let Some(state) = state::<T>(ex) {
+                retval.set_bool(state.valid());
+            }
+        }
+
+        let method = |name, handler, ty| {
+            FunctionBuilder::new(name, handler)
+                .returns(ty, false, false)
+                .build()
+                .expect("Failed to build iterator method")
+        };
+
+        self.implements(ce::iterator())
+            .method(
+                method("current", current::<T>, DataType::Mixed),
+                MethodFlags::Public,
+            )
+            .method(
+                method("key", key::<T>, DataType::Mixed),
+                MethodFlags::Public,
+            )
+            .method(
+                method("next", next::<T>, DataType::Void),
+                MethodFlags::Public,
+            )
+            .method(
+                method("rewind", rewind::<T>, DataType::Void),
+                MethodFlags::Public,
+            )
+            .method(
+                method("valid", valid::<T>, DataType::Bool),
+                MethodFlags::Public,
+            )
+    }
+
+    /// Implements the `ArrayAccess` interface on the class, registering the
+    /// offset methods which call the methods of [`PhpArrayAccess`].
+    ///
+    /// # Parameters
+    ///
+    /// * `T` - The type which implements [`PhpArrayAccess`]. Must be the same
+    ///   type given to [`ClassBuilder::object_override`].
+    pub fn array_access<T: PhpArrayAccess>(self) -> Self {
+        extern "C" fn offset_exists<T: PhpArrayAccess>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut offset = Arg::new("offset", DataType::Mixed);
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.arg(&mut offset).parse().is_err() {
+                return;
+            }
+
+            if let (Some(this), Some(offset)) = (this(this_obj), offset.zval()) {
+                set_retval(this.offset_exists(offset), retval);
+            }
+        }
+
+        extern "C" fn offset_get<T: PhpArrayAccess>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut offset = Arg::new("offset", DataType::Mixed);
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.arg(&mut offset).parse().is_err() {
+                return;
+            }
+
+            if let (Some(this), Some(offset)) = (this(this_obj), offset.zval()) {
+                set_retval(this.offset_get(offset), retval);
+            }
+        }
+
+        extern "C" fn offset_set<T: PhpArrayAccess>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut offset = Arg::new("offset", DataType::Mixed);
+            let mut value = Arg::new("value", DataType::Mixed);
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.arg(&mut offset).arg(&mut value).parse().is_err() {
+                return;
+            }
+
+            if let (Some(this), Some(offset), Some(value)) =
+                (this(this_obj), offset.zval(), value.zval())
+            {
+                set_retval(this.offset_set(offset, value), retval);
+            }
+        }
+
+        extern "C" fn offset_unset<T: PhpArrayAccess>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut offset = Arg::new("offset", DataType::Mixed);
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.arg(&mut offset).parse().is_err() {
+                return;
+            }
+
+            if let (Some(this), Some(offset)) = (this(this_obj), offset.zval()) {
+                set_retval(this.offset_unset(offset), retval);
+            }
+        }
+
+        let offset = || Arg::new("offset", DataType::Mixed);
+        let method = |func: FunctionBuilder<'static>, ty| {
+            func.returns(ty, false, false)
+                .build()
+                .expect("Failed to build array access method")
+        };
+
+        self.implements(ce::arrayaccess())
+            .method(
+                method(
+                    FunctionBuilder::new("offsetExists", offset_exists::<T>).arg(offset()),
+                    DataType::Bool,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    FunctionBuilder::new("offsetGet", offset_get::<T>).arg(offset()),
+                    DataType::Mixed,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    FunctionBuilder::new("offsetSet", offset_set::<T>)
+                        .arg(offset())
+                        .arg(Arg::new("value", DataType::Mixed)),
+                    DataType::Void,
+                ),
+                MethodFlags::Public,
+            )
+            .method(
+                method(
+                    FunctionBuilder::new("offsetUnset", offset_unset::<T>).arg(offset()),
+                    DataType::Void,
+                ),
+                MethodFlags::Public,
+            )
+    }
+
+    /// Implements the `Countable` interface on the class, registering the
+    /// `count` method which calls [`PhpCountable::count`]. The
+    /// `count_elements` handler must also be overridden by
+    /// [`RegisteredClass::override_handlers`], which is done by the
+    /// [`php_class`](crate::php_class) attribute macro.
+    ///
+    /// # Parameters
+    ///
+    /// * `T` - The type which implements [`PhpCountable`]. Must be the same type
+    ///   given to [`ClassBuilder::object_override`].
+    pub fn countable<T: PhpCountable>(self) -> Self {
+        extern "C" fn count<T: PhpCountable>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let this = match ex.get_object::<T>() {
+                Some(this) => this,
+                None => {
+                    PhpException::default("Failed to retrieve reference to `$this`".into())
+                        .throw()
+                        .expect("Failed to throw exception");
+                    return;
+                }
+            };
+            let count = this.count();
+
+            if count < 0 {
+                PhpException::new(
+                    format!(
+                        "{}::count() must return a non-negative integer",
+                        T::CLASS_NAME
+                    ),
+                    0,
+                    ce::value_error(),
+                )
+                .throw()
+                .expect("Failed to throw exception");
+                return;
+            }
+
+            retval.set_long(count);
+        }
+
+        self.implements(ce::countable()).method(
+            FunctionBuilder::new("count", count::<T>)
+                .returns(DataType::Long, false, false)
+                .build()
+                .expect("Failed to build countable method"),
+            MethodFlags::Public,
+        )
+    }
+
+    /// Implements the `Stringable` interface on the class, registering the
+    /// `__toString` method which calls [`PhpToString::to_php_string`]. The
+    /// `cast_object` handler must also be overridden by
+    /// [`RegisteredClass::override_handlers`], which is done by the
+    /// [`php_class`](crate::php_class) attribute macro.
+    ///
+    /// # Parameters
+    ///
+    /// * `T` - The type which implements [`PhpToString`]. Must be the same type
+    ///   given to [`ClassBuilder::object_override`].
+    pub fn stringable<T: PhpToString>(self) -> Self {
+        extern "C" fn to_string<T: PhpToString>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let this = match ex.get_object::<T>() {
+                Some(this) => this,
+                None => {
+                    PhpException::default("Failed to retrieve reference to `$this`".into())
+                        .throw()
+                        .expect("Failed to throw exception");
+                    return;
+                }
+            };
+
+            if let Err(e) = retval.set_string(&this.to_php_string(), false) {
+                let e: PhpException = e.into();
+                e.throw().expect("Failed to throw exception");
+            }
+        }
+
+        self.implements(ce::stringable()).method(
+            FunctionBuilder::new("__toString", to_string::<T>)
+                .returns(DataType::String, false, false)
+                .build()
+                .expect("Failed to build stringable method"),
+            MethodFlags::Public,
+        )
+    }
+
+    /// Implements the `JsonSerializable` interface on the class, registering
+    /// the `jsonSerialize` method which calls
+    /// [`PhpJsonSerialize::json_serialize`]. Errors are thrown as exceptions.
+    ///
+    /// # Parameters
+    ///
+    /// * `T` - The type which implements [`PhpJsonSerialize`]. Must be the
+    ///   same type given to [`ClassBuilder::object_override`].
+    pub fn json_serializable<T: PhpJsonSerialize>(self) -> Self {
+        extern "C" fn json_serialize<T: PhpJsonSerialize>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let this = match ex.get_object::<T>() {
+                Some(this) => this,
+                None => {
+                    PhpException::default("Failed to retrieve reference to `$this`".into())
+                        .throw()
+                        .expect("Failed to throw exception");
+                    return;
+                }
+            };
+
+            match this.json_serialize() {
+                Ok(value) => *retval = value,
+                Err(e) => e.throw().expect("Failed to throw exception"),
+            }
+        }
+
+        self.implements(ce::json_serializable()).method(
+            FunctionBuilder::new("jsonSerialize", json_serialize::<T>)
+                .returns(DataType::Mixed, false, false)
+                .build()
+                .expect("Failed to build json serializable method"),
+            MethodFlags::Public,
+        )
+    }
+
+    /// Registers the `__serialize` and `__unserialize` magic methods on the
+    /// class, which call the methods of [`PhpSerialize`], and allows the
+    /// objects of the class to be serialized.
+    ///
+    /// `__unserialize` is called by PHP on a new object whose constructor has
+    /// not been called, so the object is initialized with the value returned
+    /// by [`PhpSerialize::unserialize`]. Calling `__unserialize` on an object
+    /// which has already been initialized throws an `Error`.
+    ///
+    /// # Parameters
+    ///
+    /// * `T` - The type which implements [`PhpSerialize`]. Must be the same
+    ///   type given to [`ClassBuilder::object_override`].
+    pub fn serializable<T: PhpSerialize>(mut self) -> Self {
+        extern "C" fn serialize<T: PhpSerialize>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.parse().is_err() {
+                return;
+            }
+
+            if let Some(this) = this(this_obj) {
+                set_retval(this.serialize(), retval);
+            }
+        }
+
+        extern "C" fn unserialize<T: PhpSerialize>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut data = Arg::new("data", DataType::Array);
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.arg(&mut data).parse().is_err() {
+                return;
+            }
+
+            let this_obj = match this_obj {
+                Some(this_obj) => this_obj,
+                None => {
+                    PhpException::default("Failed to retrieve reference to `$this`".into())
+                        .throw()
+                        .expect("Failed to throw exception");
+                    return;
+                }
+            };
+
+            // Calling `__unserialize` directly on a constructed object would replace its
+            // Rust value while references into it may still be held.
+            if this_obj.obj.is_some() {
+                PhpException::new(
+                    format!(
+                        "{}::__unserialize() cannot be called on an initialized object",
+                        T::CLASS_NAME
+                    ),
+                    0,
+                    ce::error(),
+                )
+                .throw()
+                .expect("Failed to throw exception");
+                return;
+            }
+
+            if let Some(data) = data.val::<&ZendHashTable>() {
+                // The object has not been constructed when it is being unserialized, so it
+                // is initialized here rather than through `$this`.
+                let result = T::unserialize(data).map(|val| {
+                    this_obj.initialize(val);
+                });
+                set_retval(result, retval);
+            }
+        }
+
+        self.serializable = true;
+        self.method(
+            FunctionBuilder::new("__serialize", serialize::<T>)
+                .returns(DataType::Array, false, false)
+                .build()
+                .expect("Failed to build serialize method"),
+            MethodFlags::Public,
+        )
+        .method(
+            FunctionBuilder::new("__unserialize", unserialize::<T>)
+                .arg(Arg::new("data", DataType::Array))
+                .returns(DataType::Void, false, false)
+                .build()
+                .expect("Failed to build unserialize method"),
+            MethodFlags::Public,
+        )
+    }
+
+    /// Registers the `__debugInfo` magic method on the class, which calls
+    /// [`PhpDebugInfo::debug_info`]. The `get_debug_info` handler must also be
+    /// overridden by [`RegisteredClass::override_handlers`], which is done by
+    /// the [`php_class`](crate::php_class) attribute macro.
+    ///
+    /// # Parameters
+    ///
+    /// * `T` - The type which implements [`PhpDebugInfo`]. Must be the same
+    ///   type given to [`ClassBuilder::object_override`].
+    pub fn debug_info<T: PhpDebugInfo>(self) -> Self {
+        extern "C" fn debug_info<T: PhpDebugInfo>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.parse().is_err() {
+                return;
+            }
+
+            if let Some(this) = this(this_obj) {
+                set_retval(this.debug_info(), retval);
+            }
+        }
+
+        self.method(
+            FunctionBuilder::new("__debugInfo", debug_info::<T>)
+                .returns(DataType::Array, false, false)
+                .build()
+                .expect("Failed to build debug info method"),
+            MethodFlags::Public,
+        )
+    }
+
+    /// Registers the `__get`, `__set`, `__isset` and `__unset` magic methods on
+    /// the class, which call the methods of [`PhpMagicProperties`].
+    ///
+    /// # Parameters
+    ///
+    /// * `T` - The type which implements [`PhpMagicProperties`]. Must be the
+    ///   same type given to [`ClassBuilder::object_override`].
+    pub fn magic_properties<T: PhpMagicProperties>(self) -> Self {
+        extern "C" fn get<T: PhpMagicProperties>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut name = Arg::new("name", DataType::String);
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.arg(&mut name).parse().is_err() {
+                return;
+            }
+
+            if let (Some(this), Some(name)) = (this(this_obj), name.val::<&str>()) {
+                set_retval(this.get_property(name), retval);
+            }
+        }
+
+        extern "C" fn set<T: PhpMagicProperties>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut name = Arg::new("name", DataType::String);
+            let mut value = Arg::new("value", DataType::Mixed);
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.arg(&mut name).arg(&mut value).parse().is_err() {
+                return;
+            }
+
+            if let (Some(this), Some(name), Some(value)) =
+                (this(this_obj), name.val::<&str>(), value.zval())
+            {
+                set_retval(this.set_property(name, value), retval);
+            }
+        }
+
+        extern "C" fn isset<T: PhpMagicProperties>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut name = Arg::new("name", DataType::String);
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.arg(&mut name).parse().is_err() {
+                return;
+            }
+
+            if let (Some(this), Some(name)) = (this(this_obj), name.val::<&str>()) {
+                set_retval(this.isset_property(name), retval);
+            }
+        }
+
+        extern "C" fn unset<T: PhpMagicProperties>(ex: &mut ExecuteData, retval: &mut Zval) {
+            let mut name = Arg::new("name", DataType::String);
+            let (parser, this_obj) = ex.parser_method::<T>();
+            if parser.arg(&mut name).parse().is_err() {
+                return;
+            }
+
+            if let (Some(this), Some(name)) = (this(this_obj), name.val::<&str>()) {
+                set_retval(this.unset_property(name), retval);
+            }
+        }
+
+        let name = || Arg::new("name", DataType::String);
+        let method = |func: FunctionBuilder<'static>, ty| {
+            func.returns(ty, false, false)
+                .build()
+                .expect("Failed to build magic property method")
+        };
+
+        self.method(
+            method(
+                FunctionBuilder::new("__get", get::<T>).arg(name()),
+                DataType::Mixed,
+            ),
+            MethodFlags::Public,
+        )
+        .method(
+            method(
+                FunctionBuilder::new("__set", set::<T>)
+                    .arg(name())
+                    .arg(Arg::new("value", DataType::Mixed)),
+                DataType::Void,
+            ),
+            MethodFlags::Public,
+        )
+        .method(
+            method(
+                FunctionBuilder::new("__isset", isset::<T>).arg(name()),
+                DataType::Bool,
+            ),
+            MethodFlags::Public,
+        )
+        .method(
+            method(
+                FunctionBuilder::new("__unset", unset::<T>).arg(name()),
+                DataType::Void,
+            ),
+            MethodFlags::Public,
+        )
+    }
+
     /// Builds the class, returning a reference to the class entry.
     ///
     /// # Errors
@@ -225,6 +880,21 @@ impl ClassBuilder {
     pub fn build(mut self) -> Result<&'static mut ClassEntry> {
         self.ce.name = ZendStr::new_interned(&self.name, true)?.into_raw();
 
+        if !self.instantiable {
+            extern "C" fn constructor(_: &mut ExecuteData, _: &mut Zval) {}
+
+            let mut has_constructor = false;
+            for func in self.methods.iter_mut().filter(|func| is_constructor(func)) {
+                func.flags = MethodFlags::Private.bits();
+                has_constructor = true;
+            }
+            if !has_constructor {
+                let mut func = FunctionBuilder::new("__construct", constructor).build()?;
+                func.flags = MethodFlags::Private.bits();
+                self.methods.push(func);
+            }
+        }
+
         self.methods.push(FunctionEntry::end());
         let func = Box::into_raw(self.methods.into_boxed_slice()) as *const FunctionEntry;
         self.ce.info.internal.builtin_functions = func;
@@ -241,8 +911,9 @@ impl ClassBuilder {
             .ok_or(Error::InvalidPointer)?
         };
 
-        // disable serialization if the class has an associated object
-        if self.object_override.is_some() {
+        // disable serialization if the class has an associated object, unless the
+        // object provides its own serialization
+        if self.object_override.is_some() && !self.serializable {
             cfg_if::cfg_if! {
                 if #[cfg(php81)] {
                     class.ce_flags |= ClassFlags::NotSerializable.bits();
@@ -257,19 +928,62 @@ impl ClassBuilder {
             unsafe { zend_do_implement_interface(class, std::mem::transmute(iface)) };
         }
 
-        for (name, mut default, flags) in self.properties {
-            unsafe {
-                zend_declare_property(
-                    class,
-                    CString::new(name.as_str())?.as_ptr(),
-                    name.len() as _,
-                    &mut default,
-                    flags.bits() as _,
-                );
+        for (name, mut default, flags, ty) in self.properties {
+            let ty = match ty {
+                Some((ty, nullable)) => ZendType::property_type(ty, nullable)?,
+                None => None,
+            };
+
+            // PHP only allows typed properties to be readonly.
+            #[cfg(php81)]
+            if ty.is_none() && flags.contains(PropertyFlags::Readonly) {
+                return Err(Error::InvalidProperty);
+            }
+
+            match ty {
+                Some(ty) => unsafe {
+                    zend_declare_typed_property(
+                        class,
+                        ZendStr::new_interned(&name, true)?.into_raw(),
+                        &mut default,
+                        flags.bits() as _,
+                        std::ptr::null_mut(),
+                        ty,
+                    );
+                },
+                None => unsafe {
+                    zend_declare_property(
+                        class,
+                        CString::new(name.as_str())?.as_ptr(),
+                        name.len() as _,
+                        &mut default,
+                        flags.bits() as _,
+                    );
+                },
             }
         }
 
-        for (name, value) in self.constants {
+        for (name, value, ty) in self.constants {
+            #[cfg(php83)]
+            if let Some(ty) = ty {
+                if let Some(ty) = ZendType::property_type(ty, value.is_null())? {
+                    let value = Box::into_raw(Box::new(value));
+                    unsafe {
+                        crate::ffi::zend_declare_typed_class_constant(
+                            class,
+                            ZendStr::new_interned(&name, true)?.into_raw(),
+                            value,
+                            crate::ffi::ZEND_ACC_PUBLIC as _,
+                            std::ptr::null_mut(),
+                            ty,
+                        )
+                    };
+                    continue;
+                }
+            }
+            #[cfg(not(php83))]
+            let _ = ty;
+
             let value = Box::into_raw(Box::new(value));
             unsafe {
                 zend_declare_class_constant(
@@ -288,3 +1002,35 @@ impl ClassBuilder {
         Ok(class)
     }
 }
+
+/// Checks if the given method is the constructor of a class.
+fn is_constructor(func: &FunctionEntry) -> bool {
+    // SAFETY: Methods added to the builder have a valid name.
+    unsafe { CStr::from_ptr(func.fname) }
+        .to_bytes()
+        .eq_ignore_ascii_case(b"__construct")
+}
+
+/// Returns a mutable reference to the Rust object from the object `this`,
+/// throwing an exception and returning [`None`] if the object is missing.
+fn this<T: RegisteredClass>(this: Option<&mut ZendClassObject<T>>) -> Option<&mut T> {
+    match this {
+        Some(this) => Some(&mut **this),
+        None => {
+            PhpException::default("Failed to retrieve reference to `$this`".into())
+                .throw()
+                .expect("Failed to throw exception");
+            None
+        }
+    }
+}
+
+/// Sets the return value of a method to the result of a method, throwing the
+/// exception if the method or conversion failed.
+fn set_retval(result: PhpResult<impl IntoZval>, retval: &mut Zval) {
+    let result = result.and_then(|val| val.set_zval(retval, false).map_err(Into::into));
+
+    if let Err(e) = result {
+        e.throw().expect("Failed to throw exception");
+    }
+}
@@ -0,0 +1,219 @@
+use std::{collections::HashSet, ffi::CString, ptr};
+
+use crate::{
+    convert::IntoZval,
+    error::{Error, Result},
+    ffi::{zend_enum_add_case_cstr, zend_register_internal_enum, IS_LONG, IS_STRING, IS_UNDEF},
+    types::Zval,
+    zend::ClassEntry,
+};
+
+/// Value of a case of an enum.
+#[derive(Debug, Clone, PartialEq)]
+enum CaseValue {
+    Pure,
+    Long(i64),
+    String(String),
+}
+
+/// Builder for registering an enum in PHP. Requires PHP 8.1.
+///
+/// An enum is either pure, where cases have no value, or backed by integer or
+/// string values. The backing type is given by the cases added to the enum, so
+/// all cases must be of the same kind.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ext_php_rs::builders::EnumBuilder;
+///
+/// let ce = EnumBuilder::new("Suit")
+///     .string_case("Hearts", "H")
+///     .string_case("Spades", "S")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct EnumBuilder {
+    name: String,
+    cases: Vec<(String, CaseValue)>,
+}
+
+impl EnumBuilder {
+    /// Creates a new enum builder, used to build enums to be exported to PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the enum.
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            cases: vec![],
+        }
+    }
+
+    /// Adds a case without a value to a pure enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the case.
+    pub fn case<T: Into<String>>(mut self, name: T) -> Self {
+        self.cases.push((name.into(), CaseValue::Pure));
+        self
+    }
+
+    /// Adds a case to an integer-backed enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the case.
+    /// * `value` - The value of the case.
+    pub fn int_case<T: Into<String>>(mut self, name: T, value: i64) -> Self {
+        self.cases.push((name.into(), CaseValue::Long(value)));
+        self
+    }
+
+    /// Adds a case to a string-backed enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the case.
+    /// * `value` - The value of the case.
+    pub fn string_case<T: Into<String>, V: Into<String>>(mut self, name: T, value: V) -> Self {
+        self.cases
+            .push((name.into(), CaseValue::String(value.into())));
+        self
+    }
+
+    /// Builds the enum, registering it with PHP.
+    ///
+    /// Returns a result containing the class entry of the enum if successful.
+    /// Returns an error if a case was declared twice, if two cases have the
+    /// same value, or if the cases are not all of the same kind.
+    pub fn build(self) -> Result<&'static mut ClassEntry> {
+        let backing = backing_type(&self.cases)?;
+        let name = CString::new(self.name)?;
+
+        let ce = unsafe {
+            zend_register_internal_enum(name.as_ptr(), backing as _, ptr::null())
+                .as_mut()
+                .ok_or(Error::InvalidPointer)?
+        };
+
+        for (name, value) in self.cases {
+            let mut value = match value {
+                CaseValue::Pure => None,
+                CaseValue::Long(value) => Some(value.into_zval(true)?),
+                // Case values must be interned strings.
+                CaseValue::String(value) => {
+                    let mut zv = Zval::new();
+                    zv.set_interned_string(&value, true)?;
+                    Some(zv)
+                }
+            };
+
+            let name = CString::new(name)?;
+            let value = value.as_mut().map_or(ptr::null_mut(), |zv| zv as *mut Zval);
+            unsafe { zend_enum_add_case_cstr(ce, name.as_ptr(), value) };
+        }
+
+        Ok(ce)
+    }
+}
+
+/// Returns the backing type of an enum with the given cases, `IS_UNDEF` for a
+/// pure enum. Checks that the names and values of the cases are unique, and
+/// that all the cases are of the same kind.
+fn backing_type(cases: &[(String, CaseValue)]) -> Result<u32> {
+    let mut names = HashSet::new();
+    let mut values = Vec::with_capacity(cases.len());
+    let mut backing = None;
+
+    for (name, value) in cases {
+        let ty = match value {
+            CaseValue::Pure => IS_UNDEF,
+            CaseValue::Long(_) => IS_LONG,
+            CaseValue::String(_) => IS_STRING,
+        };
+
+        if !names.insert(name.as_str())
+            || *backing.get_or_insert(ty) != ty
+            || (ty != IS_UNDEF && values.contains(&value))
+        {
+            return Err(Error::InvalidEnumCase(name.clone()));
+        }
+        values.push(value);
+    }
+
+    Ok(backing.unwrap_or(IS_UNDEF))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::{backing_type, CaseValue};
+    use crate::{
+        error::Error,
+        ffi::{IS_LONG, IS_STRING, IS_UNDEF},
+    };
+
+    fn cases(cases: &[(&str, CaseValue)]) -> Vec<(String, CaseValue)> {
+        cases
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_backing_type() {
+        assert_eq!(backing_type(&[]).unwrap(), IS_UNDEF);
+        assert_eq!(
+            backing_type(&cases(&[
+                ("Draft", CaseValue::Pure),
+                ("Published", CaseValue::Pure)
+            ]))
+            .unwrap(),
+            IS_UNDEF
+        );
+        assert_eq!(
+            backing_type(&cases(&[
+                ("Low", CaseValue::Long(1)),
+                ("High", CaseValue::Long(2))
+            ]))
+            .unwrap(),
+            IS_LONG
+        );
+        assert_eq!(
+            backing_type(&cases(&[("Hearts", CaseValue::String("H".into()))])).unwrap(),
+            IS_STRING
+        );
+    }
+
+    #[test]
+    fn test_invalid_cases() {
+        let invalid = |input: &[(&str, CaseValue)]| match backing_type(&cases(input)) {
+            Err(Error::InvalidEnumCase(name)) => name,
+            _ => panic!("expected invalid enum case"),
+        };
+
+        assert_eq!(
+            invalid(&[("Low", CaseValue::Long(1)), ("Low", CaseValue::Long(2))]),
+            "Low"
+        );
+        assert_eq!(
+            invalid(&[("Low", CaseValue::Long(1)), ("High", CaseValue::Long(1))]),
+            "High"
+        );
+        assert_eq!(
+            invalid(&[("Low", CaseValue::Long(1)), ("High", CaseValue::Pure)]),
+            "High"
+        );
+        assert_eq!(
+            invalid(&[
+                ("Low", CaseValue::Long(1)),
+                ("High", CaseValue::String("1".into()))
+            ]),
+            "High"
+        );
+    }
+}
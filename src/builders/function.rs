@@ -1,7 +1,7 @@
 use crate::{
     args::{Arg, ArgInfo},
     error::{Error, Result},
-    flags::DataType,
+    flags::{DataType, MethodFlags},
     types::Zval,
     zend::{ExecuteData, FunctionEntry, ZendType},
 };
@@ -56,6 +56,33 @@ impl<'a> FunctionBuilder<'a> {
         }
     }
 
+    /// Creates a new builder for an abstract method, which has no handler and
+    /// must be implemented by the classes inheriting it. The method must be
+    /// added to a class with the [`MethodFlags::Abstract`] flag.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    ///
+    /// [`MethodFlags::Abstract`]: crate::flags::MethodFlags::Abstract
+    pub fn new_abstract<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            function: FunctionEntry {
+                fname: ptr::null(),
+                handler: None,
+                arg_info: ptr::null(),
+                num_args: 0,
+                flags: 0,
+            },
+            args: vec![],
+            n_req: None,
+            retval: None,
+            ret_as_ref: false,
+            ret_as_null: false,
+        }
+    }
+
     /// Creates a constructor builder, used to build the constructor
     /// for classes.
     ///
@@ -67,6 +94,47 @@ impl<'a> FunctionBuilder<'a> {
         Self::new("__construct", handler)
     }
 
+    /// Places the function inside a PHP namespace, by prefixing the name of the
+    /// function with the namespace, in the same way as the `ZEND_NS_FE` macro.
+    /// Leading and trailing backslashes are removed from the namespace.
+    ///
+    /// Note that PHP looks up unqualified function calls inside a namespace
+    /// before falling back to the global namespace, so a namespaced function
+    /// does not shadow a global function with the same name outside of the
+    /// namespace.
+    ///
+    /// # Parameters
+    ///
+    /// * `namespace` - The namespace of the function, e.g. `MyExt\Utils`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ext_php_rs::{builders::FunctionBuilder, types::Zval, zend::ExecuteData};
+    /// extern "C" fn handler(_: &mut ExecuteData, retval: &mut Zval) {
+    ///     retval.set_null();
+    /// }
+    ///
+    /// let entry = FunctionBuilder::new("hello", handler)
+    ///     .namespace("MyExt")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn namespace<T: AsRef<str>>(mut self, namespace: T) -> Self {
+        let namespace = namespace.as_ref().trim_matches('\\');
+        if !namespace.is_empty() {
+            self.name = format!("{}\\{}", namespace, self.name);
+        }
+        self
+    }
+
+    /// Marks the function as deprecated, causing PHP to raise an `E_DEPRECATED`
+    /// error each time the function is called.
+    pub fn deprecated(mut self) -> Self {
+        self.function.flags |= MethodFlags::Deprecated.bits();
+        self
+    }
+
     /// Adds an argument to the function.
     ///
     /// # Parameters
@@ -105,7 +173,11 @@ impl<'a> FunctionBuilder<'a> {
 
         // argument header, retval etc
         args.push(ArgInfo {
-            name: self.n_req.unwrap_or(self.args.len()) as *const _,
+            name: self.n_req.unwrap_or_else(|| match self.args.last() {
+                // Variadic arguments accept no values, so are never required.
+                Some(arg) if arg.variadic => self.args.len() - 1,
+                _ => self.args.len(),
+            }) as *const _,
             type_: match self.retval {
                 Some(retval) => {
                     ZendType::empty_from_type(retval, self.ret_as_ref, false, self.ret_as_null)
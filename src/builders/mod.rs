@@ -2,9 +2,14 @@
 //! Generally zero-cost abstractions.
 
 mod class;
+#[cfg(any(docs, feature = "enum"))]
+mod enum_;
 mod function;
 mod module;
 
 pub use class::ClassBuilder;
+#[cfg(any(docs, feature = "enum"))]
+#[cfg_attr(docs, doc(cfg(feature = "enum")))]
+pub use enum_::EnumBuilder;
 pub use function::FunctionBuilder;
 pub use module::ModuleBuilder;
@@ -1,11 +1,58 @@
 use crate::{
-    error::Result,
-    ffi::{ext_php_rs_php_build_id, USING_ZTS, ZEND_DEBUG, ZEND_MODULE_API_NO},
+    error::{Error, Result},
+    exception::PanicMode,
+    ffi::{
+        ext_php_rs_php_build_id, zend_module_dep, zend_register_functions,
+        zend_unregister_functions, USING_ZTS, ZEND_DEBUG, ZEND_MODULE_API_NO,
+    },
+    flags::{ModuleDependency, ZendResult},
+    ini::{self, IniEntry},
+    request::{self, RequestGlobals},
+    resource::{self, ResourceType},
     zend::{FunctionEntry, ModuleEntry},
 };
 
 use std::{ffi::CString, mem, ptr};
 
+use parking_lot::{const_mutex, Mutex};
+
+/// The startup and shutdown functions of the extension, when more than one
+/// has been given. Only one module can be built per extension, so only one set
+/// of functions is stored.
+static MODULE_HOOKS: Mutex<ModuleHooks> = const_mutex(ModuleHooks {
+    startup: Vec::new(),
+    shutdown: Vec::new(),
+});
+
+struct ModuleHooks {
+    startup: Vec<StartupShutdownFunc>,
+    shutdown: Vec<StartupShutdownFunc>,
+}
+
+/// The functions of the extension which are only registered when their
+/// condition holds, along with the startup and shutdown functions they wrap.
+static MODULE_CONDITIONAL: Mutex<Option<ConditionalFunctions>> = const_mutex(None);
+
+struct ConditionalFunctions {
+    functions: Vec<ConditionalFunction>,
+    /// The functions registered when the module started up, terminated by an
+    /// empty entry. Empty if the module has not started up.
+    registered: Vec<FunctionEntry>,
+    startup: Option<ModuleFunc>,
+    shutdown: Option<ModuleFunc>,
+}
+
+/// A function entry, along with the condition which must hold for the function
+/// to be registered.
+type ConditionalFunction = (FunctionEntry, fn() -> bool);
+
+/// Startup or shutdown function stored inside a module entry.
+type ModuleFunc = unsafe extern "C" fn(i32, i32) -> i32;
+
+// SAFETY: The function entries are only read by the startup and shutdown
+// functions of the module, which PHP calls from a single thread.
+unsafe impl Send for ConditionalFunctions {}
+
 /// Builds a Zend module extension to be registered with PHP. Must be called
 /// from within an external function called `get_module`, returning a mutable
 /// pointer to a `ModuleEntry`.
@@ -39,6 +86,14 @@ pub struct ModuleBuilder {
     version: String,
     module: ModuleEntry,
     functions: Vec<FunctionEntry>,
+    conditional_functions: Vec<ConditionalFunction>,
+    dependencies: Vec<(String, ModuleDependency)>,
+    ini_entries: Vec<IniEntry>,
+    resources: Vec<ResourceType>,
+    request_globals: bool,
+    startup_functions: Vec<StartupShutdownFunc>,
+    shutdown_functions: Vec<StartupShutdownFunc>,
+    panic_mode: PanicMode,
 }
 
 impl ModuleBuilder {
@@ -82,46 +137,66 @@ impl ModuleBuilder {
                 build_id: unsafe { ext_php_rs_php_build_id() },
             },
             functions: vec![],
+            conditional_functions: vec![],
+            dependencies: vec![],
+            ini_entries: vec![],
+            resources: vec![],
+            request_globals: false,
+            startup_functions: vec![],
+            shutdown_functions: vec![],
+            panic_mode: PanicMode::default(),
         }
     }
 
-    /// Sets the startup function for the extension.
+    /// Adds a startup function to the extension, called when the module is
+    /// loaded (`MINIT`). Can be called more than once, in which case the
+    /// functions are called in the order they were added. If a function
+    /// returns a failure, the remaining functions are not called and the
+    /// extension fails to load.
     ///
     /// # Arguments
     ///
     /// * `func` - The function to be called on startup.
     pub fn startup_function(mut self, func: StartupShutdownFunc) -> Self {
-        self.module.module_startup_func = Some(func);
+        self.startup_functions.push(func);
         self
     }
 
-    /// Sets the shutdown function for the extension.
+    /// Adds a shutdown function to the extension, called when the module is
+    /// unloaded (`MSHUTDOWN`). Can be called more than once, in which case the
+    /// functions are called in the reverse order they were added, so resources
+    /// are released in the reverse order of startup.
     ///
     /// # Arguments
     ///
     /// * `func` - The function to be called on shutdown.
     pub fn shutdown_function(mut self, func: StartupShutdownFunc) -> Self {
-        self.module.module_shutdown_func = Some(func);
+        self.shutdown_functions.push(func);
         self
     }
 
-    /// Sets the request startup function for the extension.
+    /// Sets the request startup function for the extension, called at the
+    /// start of every request (`RINIT`).
     ///
     /// # Arguments
     ///
     /// * `func` - The function to be called when startup is requested.
     pub fn request_startup_function(mut self, func: StartupShutdownFunc) -> Self {
-        self.module.module_startup_func = Some(func);
+        self.module.request_startup_func = Some(func);
         self
     }
 
-    /// Sets the request shutdown function for the extension.
+    /// Sets the request shutdown function for the extension, called at the end
+    /// of every request (`RSHUTDOWN`). [`RequestGlobals`] are still accessible
+    /// from the function.
     ///
     /// # Arguments
     ///
     /// * `func` - The function to be called when shutdown is requested.
+    ///
+    /// [`RequestGlobals`]: crate::request::RequestGlobals
     pub fn request_shutdown_function(mut self, func: StartupShutdownFunc) -> Self {
-        self.module.module_shutdown_func = Some(func);
+        self.module.request_shutdown_func = Some(func);
         self
     }
 
@@ -136,6 +211,18 @@ impl ModuleBuilder {
         self
     }
 
+    /// Sets what happens when a Rust panic reaches a function or method of the
+    /// extension which does not set its own panic mode. By default, panics
+    /// abort the PHP process.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The panic mode of the extension.
+    pub fn panic_mode(mut self, mode: PanicMode) -> Self {
+        self.panic_mode = mode;
+        self
+    }
+
     /// Adds a function to the extension.
     ///
     /// # Arguments
@@ -146,16 +233,184 @@ impl ModuleBuilder {
         self
     }
 
+    /// Adds a batch of functions to the extension, reserving space for all of
+    /// them at once. This is how the [`php_module`] macro registers the
+    /// functions annotated with [`php_function`].
+    ///
+    /// Functions cannot be registered lazily: PHP copies every function entry
+    /// into its function table when the extension starts up, and resolves
+    /// calls to functions when a script is compiled, without an autoloading
+    /// mechanism like the one for classes. The entries are built once when the
+    /// extension is loaded, so the cost of registering a function is mostly
+    /// spent inside PHP.
+    ///
+    /// # Arguments
+    ///
+    /// * `funcs` - The functions to be added to the extension.
+    ///
+    /// [`php_module`]: macro@crate::php_module
+    /// [`php_function`]: macro@crate::php_function
+    pub fn functions<I: IntoIterator<Item = FunctionEntry>>(mut self, funcs: I) -> Self {
+        let funcs = funcs.into_iter();
+        self.functions.reserve(funcs.size_hint().0);
+        self.functions.extend(funcs);
+        self
+    }
+
+    /// Adds a function to the extension which is only registered if the given
+    /// condition returns true when the extension starts up (`MINIT`), for
+    /// example when the function depends on another extension being loaded.
+    ///
+    /// The condition is called after the INI entries of the extension are
+    /// registered, and before the startup functions are called. Functions
+    /// which are registered are unregistered when the extension shuts down.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - The function to be added to the extension.
+    /// * `condition` - Returns whether the function should be registered.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{builders::{FunctionBuilder, ModuleBuilder}, types::Zval, zend::{self, ExecuteData}};
+    ///
+    /// extern "C" fn fetch(_: &mut ExecuteData, retval: &mut Zval) {
+    ///     retval.set_null();
+    /// }
+    ///
+    /// let module = ModuleBuilder::new("myext", "0.1.0").conditional_function(
+    ///     FunctionBuilder::new("myext_fetch", fetch).build().unwrap(),
+    ///     || zend::extension_loaded("curl"),
+    /// );
+    /// ```
+    pub fn conditional_function(mut self, func: FunctionEntry, condition: fn() -> bool) -> Self {
+        self.conditional_functions.push((func, condition));
+        self
+    }
+
+    /// Declares a dependency of the extension on another extension.
+    ///
+    /// PHP starts up the required and optional dependencies of an extension
+    /// before the extension itself, whatever the order of the `extension`
+    /// directives in `php.ini`. If a required dependency is not loaded, the
+    /// extension fails to start up. A conflicting extension is only detected
+    /// if it is loaded before the extension, so it should be listed first in
+    /// `php.ini`.
+    ///
+    /// The names of the dependencies are validated when the module is built.
+    /// Building fails if a name is empty, is the name of the extension itself,
+    /// or is declared more than once.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the other extension, e.g. `json`.
+    /// * `kind` - How the extension depends on the other extension.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{builders::ModuleBuilder, flags::ModuleDependency};
+    ///
+    /// let module = ModuleBuilder::new("myext", "0.1.0")
+    ///     .dependency("json", ModuleDependency::Required)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn dependency<T: Into<String>>(mut self, name: T, kind: ModuleDependency) -> Self {
+        self.dependencies.push((name.into(), kind));
+        self
+    }
+
+    /// Adds an INI entry to the extension. The entry is registered when the
+    /// extension starts up and unregistered when it shuts down. See the
+    /// [`ini`](crate::ini) module for reading the value of the entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The INI entry to be added to the extension.
+    pub fn ini_entry(mut self, entry: IniEntry) -> Self {
+        self.ini_entries.push(entry);
+        self
+    }
+
+    /// Declares a resource type for values of type `T`, which can then be
+    /// stored inside a [`Resource`]. The type is registered when the extension
+    /// starts up, before the startup functions are called.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the resource type, returned by
+    ///   `get_resource_type()` in PHP.
+    ///
+    /// [`Resource`]: crate::resource::Resource
+    pub fn resource<T: 'static>(mut self, name: impl Into<String>) -> Self {
+        self.resources.push(ResourceType::new::<T>(name.into()));
+        self
+    }
+
+    /// Registers a [`RequestGlobals`] static with the extension. The value of
+    /// the request global can only be accessed during requests once it is
+    /// registered, and is dropped at the end of each request.
+    ///
+    /// # Arguments
+    ///
+    /// * `global` - The request global to register.
+    pub fn request_global<T>(mut self, global: &'static RequestGlobals<T>) -> Self {
+        global.register();
+        self.request_globals = true;
+        self
+    }
+
     /// Builds the extension and returns a `ModuleEntry`.
     ///
     /// Returns a result containing the module entry if successful.
     pub fn build(mut self) -> Result<ModuleEntry> {
+        // Avoids growing the vector past its length when the end entry is
+        // pushed, as the boxed slice would otherwise be reallocated.
+        self.functions.reserve_exact(1);
         self.functions.push(FunctionEntry::end());
         self.module.functions =
             Box::into_raw(self.functions.into_boxed_slice()) as *const FunctionEntry;
+        if !self.dependencies.is_empty() {
+            let deps = module_deps(&self.name, &self.dependencies)?;
+            self.module.deps = Box::into_raw(deps.into_boxed_slice()) as *const zend_module_dep;
+        }
         self.module.name = CString::new(self.name)?.into_raw();
         self.module.version = CString::new(self.version)?.into_raw();
 
+        if self.startup_functions.len() > 1 || self.shutdown_functions.len() > 1 {
+            let mut hooks = MODULE_HOOKS.lock();
+            hooks.startup = self.startup_functions;
+            hooks.shutdown = self.shutdown_functions;
+            self.module.module_startup_func = Some(startup_hooks);
+            self.module.module_shutdown_func = Some(shutdown_hooks);
+        } else {
+            if let Some(func) = self.startup_functions.pop() {
+                self.module.module_startup_func = Some(func);
+            }
+            if let Some(func) = self.shutdown_functions.pop() {
+                self.module.module_shutdown_func = Some(func);
+            }
+        }
+
+        if !self.conditional_functions.is_empty() {
+            wrap_conditional(&mut self.module, self.conditional_functions);
+        }
+
+        if !self.resources.is_empty() {
+            resource::wrap_module(&mut self.module, self.resources)?;
+        }
+
+        if !self.ini_entries.is_empty() {
+            ini::wrap_module(&mut self.module, self.ini_entries);
+        }
+
+        if self.request_globals {
+            request::wrap_module(&mut self.module);
+        }
+        PanicMode::set_module(self.panic_mode);
+
         Ok(self.module)
     }
 }
@@ -165,3 +420,258 @@ pub type StartupShutdownFunc = extern "C" fn(_type: i32, _module_number: i32) ->
 
 /// A function to be called when `phpinfo();` is called.
 pub type InfoFunc = extern "C" fn(zend_module: *mut ModuleEntry);
+
+/// Validates the given dependencies of a module, and converts them into
+/// dependency entries terminated by an empty entry.
+///
+/// # Arguments
+///
+/// * `module` - The name of the module.
+/// * `dependencies` - The names of the dependencies of the module, along with
+///   how the module depends on them.
+fn module_deps(
+    module: &str,
+    dependencies: &[(String, ModuleDependency)],
+) -> Result<Vec<zend_module_dep>> {
+    let mut deps = Vec::with_capacity(dependencies.len() + 1);
+    for (i, (name, kind)) in dependencies.iter().enumerate() {
+        // PHP compares the names of extensions case-insensitively.
+        let invalid = name.trim().is_empty()
+            || name.eq_ignore_ascii_case(module)
+            || dependencies[..i]
+                .iter()
+                .any(|(other, _)| other.eq_ignore_ascii_case(name));
+        if invalid {
+            return Err(Error::InvalidModuleDependency(name.clone()));
+        }
+
+        deps.push(zend_module_dep {
+            name: CString::new(name.as_str())?.into_raw(),
+            rel: ptr::null(),
+            version: ptr::null(),
+            type_: kind.as_u8(),
+        });
+    }
+
+    deps.push(zend_module_dep {
+        name: ptr::null(),
+        rel: ptr::null(),
+        version: ptr::null(),
+        type_: 0,
+    });
+    Ok(deps)
+}
+
+/// Calls the given startup functions in order, stopping at the first function
+/// which fails.
+fn run_startup(funcs: &[StartupShutdownFunc], ty: i32, module_number: i32) -> i32 {
+    for func in funcs {
+        let result = func(ty, module_number);
+        if result != ZendResult::Success.bits() {
+            return result;
+        }
+    }
+
+    ZendResult::Success.bits()
+}
+
+/// Calls the given shutdown functions in reverse order. All functions are
+/// called even if one fails, in which case the failure is returned.
+fn run_shutdown(funcs: &[StartupShutdownFunc], ty: i32, module_number: i32) -> i32 {
+    let mut result = ZendResult::Success.bits();
+    for func in funcs.iter().rev() {
+        let func_result = func(ty, module_number);
+        if func_result != ZendResult::Success.bits() {
+            result = func_result;
+        }
+    }
+
+    result
+}
+
+extern "C" fn startup_hooks(ty: i32, module_number: i32) -> i32 {
+    let funcs = MODULE_HOOKS.lock().startup.clone();
+    run_startup(&funcs, ty, module_number)
+}
+
+extern "C" fn shutdown_hooks(ty: i32, module_number: i32) -> i32 {
+    let funcs = MODULE_HOOKS.lock().shutdown.clone();
+    run_shutdown(&funcs, ty, module_number)
+}
+
+/// Stores the given conditional functions, and replaces the startup and
+/// shutdown functions of the module with functions which register and
+/// unregister them.
+fn wrap_conditional(module: &mut ModuleEntry, functions: Vec<ConditionalFunction>) {
+    MODULE_CONDITIONAL.lock().replace(ConditionalFunctions {
+        functions,
+        registered: vec![],
+        startup: module.module_startup_func,
+        shutdown: module.module_shutdown_func,
+    });
+    module.module_startup_func = Some(conditional_startup);
+    module.module_shutdown_func = Some(conditional_shutdown);
+}
+
+/// Returns the entries of the functions whose condition holds, terminated by
+/// an empty entry.
+fn enabled_functions(functions: &[ConditionalFunction]) -> Vec<FunctionEntry> {
+    functions
+        .iter()
+        .filter(|(_, condition)| condition())
+        .map(|(func, _)| *func)
+        .chain(std::iter::once(FunctionEntry::end()))
+        .collect()
+}
+
+extern "C" fn conditional_startup(ty: i32, module_number: i32) -> i32 {
+    // The conditions are called without holding the lock, as they may call back
+    // into the extension.
+    let (functions, startup) = match MODULE_CONDITIONAL.lock().as_ref() {
+        Some(conditional) => (conditional.functions.clone(), conditional.startup),
+        None => return ZendResult::Failure.bits(),
+    };
+    let registered = enabled_functions(&functions);
+
+    let result = unsafe {
+        zend_register_functions(ptr::null_mut(), registered.as_ptr(), ptr::null_mut(), ty)
+    };
+    if result != ZendResult::Success.bits() {
+        return ZendResult::Failure.bits();
+    }
+    if let Some(conditional) = MODULE_CONDITIONAL.lock().as_mut() {
+        conditional.registered = registered;
+    }
+
+    match startup {
+        Some(startup) => unsafe { startup(ty, module_number) },
+        None => ZendResult::Success.bits(),
+    }
+}
+
+extern "C" fn conditional_shutdown(ty: i32, module_number: i32) -> i32 {
+    let (shutdown, registered) = match MODULE_CONDITIONAL.lock().as_mut() {
+        Some(conditional) => (
+            conditional.shutdown,
+            std::mem::take(&mut conditional.registered),
+        ),
+        None => return ZendResult::Failure.bits(),
+    };
+    let result = match shutdown {
+        Some(shutdown) => unsafe { shutdown(ty, module_number) },
+        None => ZendResult::Success.bits(),
+    };
+
+    if registered.len() > 1 {
+        unsafe {
+            zend_unregister_functions(
+                registered.as_ptr(),
+                (registered.len() - 1) as _,
+                ptr::null_mut(),
+            )
+        };
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        enabled_functions, module_deps, run_shutdown, run_startup, ConditionalFunction,
+        StartupShutdownFunc,
+    };
+    use crate::{
+        error::Error,
+        flags::{ModuleDependency, ZendResult},
+        zend::FunctionEntry,
+    };
+    use parking_lot::{const_mutex, Mutex};
+    use std::ffi::CStr;
+
+    static CALLS: Mutex<Vec<u8>> = const_mutex(Vec::new());
+
+    extern "C" fn first(_: i32, _: i32) -> i32 {
+        CALLS.lock().push(1);
+        ZendResult::Success.bits()
+    }
+
+    extern "C" fn second(_: i32, _: i32) -> i32 {
+        CALLS.lock().push(2);
+        ZendResult::Failure.bits()
+    }
+
+    extern "C" fn third(_: i32, _: i32) -> i32 {
+        CALLS.lock().push(3);
+        ZendResult::Success.bits()
+    }
+
+    #[test]
+    fn test_hook_order() {
+        let funcs: &[StartupShutdownFunc] = &[first, second, third];
+
+        assert_eq!(run_startup(funcs, 0, 0), ZendResult::Failure.bits());
+        assert_eq!(std::mem::take(&mut *CALLS.lock()), vec![1, 2]);
+
+        assert_eq!(run_shutdown(funcs, 0, 0), ZendResult::Failure.bits());
+        assert_eq!(std::mem::take(&mut *CALLS.lock()), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_enabled_functions() {
+        let func = |num_args| FunctionEntry {
+            num_args,
+            ..FunctionEntry::end()
+        };
+        let functions: &[ConditionalFunction] =
+            &[(func(1), || true), (func(2), || false), (func(3), || true)];
+
+        let enabled = enabled_functions(functions);
+        assert_eq!(
+            enabled.iter().map(|func| func.num_args).collect::<Vec<_>>(),
+            vec![1, 3, 0]
+        );
+        assert!(enabled[2].fname.is_null());
+    }
+
+    #[test]
+    fn test_module_deps() {
+        let deps = module_deps(
+            "myext",
+            &[
+                ("json".into(), ModuleDependency::Required),
+                ("Zend OPcache".into(), ModuleDependency::Conflicts),
+                ("curl".into(), ModuleDependency::Optional),
+            ],
+        )
+        .expect("failed to build module dependencies");
+
+        let names: Vec<_> = deps[..3]
+            .iter()
+            .map(|dep| {
+                unsafe { CStr::from_ptr(dep.name) }
+                    .to_str()
+                    .expect("dependency name is not UTF-8")
+            })
+            .collect();
+        assert_eq!(names, vec!["json", "Zend OPcache", "curl"]);
+        assert_eq!(
+            deps.iter().map(|dep| dep.type_).collect::<Vec<_>>(),
+            vec![1, 2, 3, 0]
+        );
+        assert!(deps[3].name.is_null());
+
+        for invalid in [
+            vec![(" ".to_string(), ModuleDependency::Required)],
+            vec![("MyExt".to_string(), ModuleDependency::Optional)],
+            vec![
+                ("json".to_string(), ModuleDependency::Required),
+                ("JSON".to_string(), ModuleDependency::Optional),
+            ],
+        ] {
+            assert!(matches!(
+                module_deps("myext", &invalid),
+                Err(Error::InvalidModuleDependency(_))
+            ));
+        }
+    }
+}
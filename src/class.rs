@@ -11,7 +11,8 @@ use crate::{
     builders::FunctionBuilder,
     exception::PhpException,
     props::Property,
-    zend::{ClassEntry, ExecuteData, ZendObjectHandlers},
+    types::ZendObject,
+    zend::{ClassEntry, ExecuteData, ExecutorGlobals, ZendObjectHandlers},
 };
 
 /// Implemented on Rust types which are exported to PHP. Allows users to get and
@@ -38,6 +39,50 @@ pub trait RegisteredClass: Sized + 'static {
     /// reference to the property with reference to `self`. The value is a
     /// [`Property`].
     fn get_properties<'a>() -> HashMap<&'static str, Property<'a, Self>>;
+
+    /// Modifies the object handlers of the class after they have been
+    /// initialized with the default handlers. Used to override handlers when
+    /// the class implements interfaces such as `Countable`.
+    ///
+    /// By default, the handlers are left untouched.
+    ///
+    /// # Parameters
+    ///
+    /// * `handlers` - The initialized object handlers of the class.
+    fn override_handlers(_handlers: &mut ZendObjectHandlers) {}
+
+    /// Returns a pointer to the PHP object which contains `self` while a
+    /// method of the class is being called, allowing the method to read and
+    /// write the dynamic properties of the object.
+    ///
+    /// A pointer is returned rather than a reference as the object owns
+    /// `self`. Reading or writing a property of the object can call back into
+    /// the class, such as through a property handler or a `__set` method,
+    /// which would alias `self`.
+    ///
+    /// Returns [`None`] if `self` is not the object the current method was
+    /// called on, such as when called from a static method or on a different
+    /// instance of the class.
+    ///
+    /// # Safety
+    ///
+    /// The pointer is valid for the duration of the current method call.
+    /// `self` must not be used while a reference created from the pointer is
+    /// alive, and the caller must ensure the PHP code run while accessing
+    /// the object does not access the Rust value, such as through a property
+    /// declared on the struct or a method of the class.
+    fn this(&mut self) -> Option<*mut ZendObject> {
+        // SAFETY: The current execute data is either null or valid while a
+        // function is being called.
+        let ex = unsafe { ExecutorGlobals::get().current_execute_data.as_mut() }?;
+        let obj = ex.get_object::<Self>()?;
+
+        if !std::ptr::eq(obj.obj.as_ref()?, self) {
+            return None;
+        }
+
+        Some(&mut obj.std)
+    }
 }
 
 /// Stores metadata about a classes Rust constructor, including the function
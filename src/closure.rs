@@ -6,8 +6,10 @@ use crate::{
     args::{Arg, ArgParser},
     builders::{ClassBuilder, FunctionBuilder},
     class::{ClassMetadata, RegisteredClass},
-    convert::{FromZval, IntoZval},
+    convert::{FromZval, IntoZendObject, IntoZval},
+    error::{Error, Result},
     exception::PhpException,
+    ffi::{zend_create_fake_closure, zend_function},
     flags::{DataType, MethodFlags},
     props::Property,
     types::Zval,
@@ -45,6 +47,15 @@ static CLOSURE_META: ClassMetadata<Closure> = ClassMetadata::new();
 /// When the `__invoke` method is called from PHP, the `invoke` method is called
 /// on the `dyn PhpClosure`\ trait object, and from there everything is
 /// basically the same as a regular PHP function.
+///
+/// When returned to PHP, the `RustClosure` object is bound to a regular PHP
+/// `Closure` which calls the `__invoke` method, so it can be used anywhere a
+/// `Closure` or `callable` is accepted. The `Closure` holds a reference to the
+/// object, so the Rust closure is dropped once PHP releases the `Closure`.
+///
+/// Closures returning a [`Result`](std::result::Result) throw the error as an
+/// exception when called. The error type must implement
+/// [`Into<PhpException>`].
 pub struct Closure(Box<dyn PhpClosure>);
 
 unsafe impl Send for Closure {}
@@ -158,7 +169,30 @@ impl RegisteredClass for Closure {
     }
 }
 
-class_derives!(Closure);
+class_derives!(@object Closure);
+
+impl IntoZval for Closure {
+    const TYPE: DataType = DataType::Object(Some("Closure"));
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        let ce = CLOSURE_META.ce();
+        let invoke = ce
+            .function_table
+            .get("__invoke")
+            .and_then(|func| unsafe { func.ptr::<zend_function>() })
+            .ok_or(Error::InvalidPointer)?;
+
+        let mut this = Zval::new();
+        self.into_zend_object()?.set_zval(&mut this, persistent)?;
+
+        // The fake closure takes a reference to the `RustClosure` object, so the
+        // Rust closure and anything it captures live for as long as PHP holds
+        // the `Closure`. Our own reference is released when `this` is dropped.
+        let ce = ce as *const _ as *mut _;
+        unsafe { zend_create_fake_closure(zv, invoke, ce, ce, &mut this) };
+        Ok(())
+    }
+}
 
 /// Implemented on types which can be used as PHP closures.
 ///
@@ -1,13 +1,17 @@
 //! Types and traits for registering constants in PHP.
 
-use std::ffi::CString;
+use std::{convert::TryInto, ffi::CString};
 
 use super::flags::GlobalConstantFlags;
-use crate::error::Result;
+use crate::boxed::ZBox;
+use crate::convert::IntoZval;
+use crate::error::{Error, Result};
 use crate::ffi::{
-    zend_register_bool_constant, zend_register_double_constant, zend_register_long_constant,
-    zend_register_string_constant,
+    ext_php_rs_zend_register_array_constant, zend_register_bool_constant,
+    zend_register_double_constant, zend_register_long_constant, zend_register_string_constant,
 };
+use crate::flags::DataType;
+use crate::types::{ZendHashTable, Zval};
 
 /// Implemented on types which can be registered as a constant in PHP.
 pub trait IntoConst: Sized {
@@ -165,3 +169,151 @@ into_const_num!(i32, zend_register_long_constant);
 into_const_num!(i64, zend_register_long_constant);
 into_const_num!(f32, zend_register_double_constant);
 into_const_num!(f64, zend_register_double_constant);
+
+/// Implements the `IntoConst` trait for slices and arrays of the given types,
+/// registering the constant as a list.
+macro_rules! into_const_list {
+    ($($type: ty),*) => {
+        $(
+            impl IntoConst for &[$type] {
+                fn register_constant_flags(
+                    &self,
+                    name: &str,
+                    module_number: i32,
+                    flags: GlobalConstantFlags,
+                ) -> Result<()> {
+                    let mut ht = ZendHashTable::with_capacity(
+                        self.len().try_into().map_err(|_| Error::IntegerOverflow)?,
+                    );
+                    for val in self.iter() {
+                        ht.push(*val)?;
+                    }
+                    ht.register_constant_flags(name, module_number, flags)
+                }
+            }
+
+            impl<const N: usize> IntoConst for [$type; N] {
+                fn register_constant_flags(
+                    &self,
+                    name: &str,
+                    module_number: i32,
+                    flags: GlobalConstantFlags,
+                ) -> Result<()> {
+                    (&self[..]).register_constant_flags(name, module_number, flags)
+                }
+            }
+        )*
+    };
+}
+
+into_const_list!(i8, i16, i32, i64, f32, f64, bool, &str);
+
+/// Registers the constant as an associative array, with the first element of
+/// each pair as the key. Pairs are used over a [`HashMap`] as they can be
+/// declared in a Rust constant.
+///
+/// [`HashMap`]: std::collections::HashMap
+impl<T: IntoZval + Clone> IntoConst for &[(&str, T)] {
+    fn register_constant_flags(
+        &self,
+        name: &str,
+        module_number: i32,
+        flags: GlobalConstantFlags,
+    ) -> Result<()> {
+        let mut ht = ZendHashTable::with_capacity(
+            self.len().try_into().map_err(|_| Error::IntegerOverflow)?,
+        );
+        for (key, val) in self.iter() {
+            ht.insert(*key, val.clone())?;
+        }
+        ht.register_constant_flags(name, module_number, flags)
+    }
+}
+
+impl<T: IntoZval + Clone, const N: usize> IntoConst for [(&str, T); N] {
+    fn register_constant_flags(
+        &self,
+        name: &str,
+        module_number: i32,
+        flags: GlobalConstantFlags,
+    ) -> Result<()> {
+        (&self[..]).register_constant_flags(name, module_number, flags)
+    }
+}
+
+impl IntoConst for ZBox<ZendHashTable> {
+    fn register_constant_flags(
+        &self,
+        name: &str,
+        module_number: i32,
+        flags: GlobalConstantFlags,
+    ) -> Result<()> {
+        (&**self).register_constant_flags(name, module_number, flags)
+    }
+}
+
+/// Registers an array constant. Arrays may only contain scalars, strings and
+/// other arrays - objects and resources cannot be stored in a constant.
+///
+/// The array is copied when registering the constant, so it can be dropped
+/// afterwards. Persistent constants are copied into persistent memory, and
+/// cannot be modified once registered.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ext_php_rs::{constant::IntoConst, types::ZendHashTable};
+///
+/// pub extern "C" fn startup_function(_type: i32, module_number: i32) -> i32 {
+///     let mut defaults = ZendHashTable::new();
+///     defaults.insert("timeout", 30).unwrap();
+///     defaults.insert("host", "localhost").unwrap();
+///     defaults.register_constant("MYEXT_DEFAULTS", module_number).unwrap();
+///     0
+/// }
+/// ```
+impl IntoConst for &ZendHashTable {
+    fn register_constant_flags(
+        &self,
+        name: &str,
+        module_number: i32,
+        flags: GlobalConstantFlags,
+    ) -> Result<()> {
+        check_constant_array(self)?;
+        unsafe {
+            ext_php_rs_zend_register_array_constant(
+                CString::new(name)?.as_ptr(),
+                name.len() as _,
+                *self as *const ZendHashTable as *mut ZendHashTable,
+                flags.bits() as _,
+                module_number,
+            )
+        };
+        Ok(())
+    }
+}
+
+/// Checks that the given array only contains values which can be stored in a
+/// constant.
+fn check_constant_array(ht: &ZendHashTable) -> Result<()> {
+    ht.values().try_for_each(check_constant_value)
+}
+
+/// Checks that the given value can be stored in a constant.
+fn check_constant_value(val: &Zval) -> Result<()> {
+    if let Some(val) = val.reference() {
+        return check_constant_value(val);
+    }
+
+    match val.get_type() {
+        DataType::Null
+        | DataType::False
+        | DataType::True
+        | DataType::Bool
+        | DataType::Long
+        | DataType::Double
+        | DataType::String => Ok(()),
+        DataType::Array => check_constant_array(val.array().ok_or(Error::InvalidPointer)?),
+        ty => Err(Error::InvalidConstant(ty)),
+    }
+}
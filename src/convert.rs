@@ -10,6 +10,25 @@ use crate::{
 
 /// Allows zvals to be converted into Rust types in a fallible way. Reciprocal
 /// of the [`IntoZval`] trait.
+///
+/// The primitive types, [`String`], [`Vec<T>`] and
+/// [`HashMap<String, T>`](std::collections::HashMap) also implement
+/// `TryFrom<&Zval>`, which performs the same conversion as this trait but
+/// returns a [`ConversionError`] describing the expected and actual PHP types
+/// on failure, in the same way as [`Zval::try_into_type`]. This is more
+/// convenient for conversions inside function bodies:
+///
+/// ```no_run
+/// use std::convert::TryInto;
+/// use ext_php_rs::{exception::PhpResult, types::Zval};
+///
+/// fn double(zval: &Zval) -> PhpResult<i64> {
+///     let x: i64 = zval.try_into()?;
+///     Ok(x * 2)
+/// }
+/// ```
+///
+/// [`ConversionError`]: crate::error::ConversionError
 pub trait FromZval<'a>: Sized {
     /// The corresponding type of the implemented value in PHP.
     const TYPE: DataType;
@@ -218,3 +237,19 @@ impl<T: IntoZval + Clone> IntoZvalDyn for T {
         Self::TYPE
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::IntoZval;
+    use crate::{flags::DataType, types::Zval};
+
+    #[test]
+    fn test_unit_into_null() {
+        assert_eq!(<() as IntoZval>::TYPE, DataType::Void);
+
+        let mut zv = Zval::new();
+        zv.set_long(5);
+        ().set_zval(&mut zv, false).expect("failed to set unit");
+        assert!(zv.is_null());
+    }
+}
@@ -0,0 +1,45 @@
+//! Traits used for exporting Rust types to PHP as classes which implement the
+//! `Countable` interface, allowing objects to be passed to `count()`.
+
+use crate::{class::RegisteredClass, types::ZendLong};
+
+/// Implemented on Rust types which are exported to PHP as classes which
+/// implement the `Countable` interface.
+///
+/// The class must be registered with the `#[countable]` attribute underneath
+/// the [`macro@php_class`] attribute, which implements the interface, registers
+/// the `count` method and overrides the `count_elements` object handler, so
+/// `count($obj)` calls [`PhpCountable::count`] directly.
+///
+/// PHP requires counts to be non-negative. If a negative count is returned,
+/// `count($obj)` and `$obj->count()` throw a `ValueError`.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use ext_php_rs::countable::PhpCountable;
+///
+/// #[php_class]
+/// #[countable]
+/// pub struct Collection {
+///     items: Vec<String>,
+/// }
+///
+/// impl PhpCountable for Collection {
+///     fn count(&self) -> i64 {
+///         self.items.len() as i64
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`macro@php_class`]: crate::php_class
+pub trait PhpCountable: RegisteredClass {
+    /// Returns the number of elements in the object.
+    fn count(&self) -> ZendLong;
+}
@@ -13,7 +13,7 @@
 //! [`usize`] should not be in use, but rather `size_t` or a similar type,
 //! however these are currently unstable.
 
-use std::{fmt::Display, ops::Deref, vec::Vec as StdVec};
+use std::{fmt::Display, mem::ManuallyDrop, ops::Deref, vec::Vec as StdVec};
 
 /// An immutable, ABI-stable [`Vec`][std::vec::Vec].
 #[repr(C)]
@@ -30,12 +30,34 @@ impl<T> Deref for Vec<T> {
     }
 }
 
+impl<T> Vec<T> {
+    /// Retains only the elements for which the given predicate returns true,
+    /// in the same way as [`Vec::retain`][std::vec::Vec::retain].
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        let mut vec = std::mem::replace(self, StdVec::new().into()).into_std();
+        vec.retain(f);
+        *self = vec.into();
+    }
+
+    /// Converts the vector back into a standard library vector.
+    fn into_std(self) -> StdVec<T> {
+        let this = ManuallyDrop::new(self);
+        unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(this.ptr, this.len)) }.into_vec()
+    }
+}
+
 impl<T> Drop for Vec<T> {
     fn drop(&mut self) {
         unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(self.ptr, self.len)) };
     }
 }
 
+impl<T> Default for Vec<T> {
+    fn default() -> Self {
+        StdVec::new().into()
+    }
+}
+
 impl<T> From<StdVec<T>> for Vec<T> {
     fn from(vec: StdVec<T>) -> Self {
         let vec = vec.into_boxed_slice();
@@ -71,6 +93,12 @@ impl From<&'static str> for Str {
     }
 }
 
+impl Default for Str {
+    fn default() -> Self {
+        "".into()
+    }
+}
+
 impl AsRef<str> for Str {
     fn as_ref(&self) -> &str {
         self.str()
@@ -85,7 +113,43 @@ impl Display for Str {
 
 /// An ABI-stable [`Option`][std::option::Option].
 #[repr(C, u8)]
+#[derive(Default)]
 pub enum Option<T> {
     Some(T),
+    #[default]
     None,
 }
+
+impl<T> Option<T> {
+    /// Converts from `&Option<T>` to a standard library `Option<&T>`.
+    pub fn as_ref(&self) -> std::option::Option<&T> {
+        match self {
+            Self::Some(val) => std::option::Option::Some(val),
+            Self::None => std::option::Option::None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::{Option, Str, Vec};
+    use serde::{Serialize, Serializer};
+
+    impl<T: Serialize> Serialize for Vec<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl Serialize for Str {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.str())
+        }
+    }
+
+    impl<T: Serialize> Serialize for Option<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.as_ref().serialize(serializer)
+        }
+    }
+}
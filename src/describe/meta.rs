@@ -0,0 +1,213 @@
+//! Generation of `.phpstorm.meta.php` files, which tell PhpStorm the return
+//! types of functions and methods which depend on their arguments.
+//!
+//! See the [PhpStorm documentation](https://www.jetbrains.com/help/phpstorm/ide-advanced-metadata.html)
+//! for the format of the file.
+
+use super::{Module, OverrideKind, ReturnOverride};
+use std::fmt::{Error as FmtError, Result as FmtResult, Write};
+use std::option::Option as StdOption;
+
+impl Module {
+    /// Converts the return type overrides of the functions and methods of the
+    /// module into a `.phpstorm.meta.php` file.
+    ///
+    /// # Returns
+    ///
+    /// Returns the contents of the file on success, or [`None`] if no function
+    /// or method of the module has a return type override. Returns an error if
+    /// there was an error writing into the string.
+    ///
+    /// [`None`]: std::option::Option::None
+    pub fn to_phpstorm_meta(&self) -> Result<StdOption<String>, FmtError> {
+        let mut overrides = String::new();
+
+        for func in &*self.functions {
+            if let Some(return_override) = func.return_override.as_ref() {
+                let target = format!("\\{}", func.name.str().trim_start_matches('\\'));
+                fmt_override(&mut overrides, &target, return_override)?;
+            }
+        }
+
+        for class in &*self.classes {
+            for method in &*class.methods {
+                if let Some(return_override) = method.return_override.as_ref() {
+                    let target = format!(
+                        "\\{}::{}",
+                        class.name.str().trim_start_matches('\\'),
+                        method.name
+                    );
+                    fmt_override(&mut overrides, &target, return_override)?;
+                }
+            }
+        }
+
+        if overrides.is_empty() {
+            return Ok(None);
+        }
+
+        let mut buf = String::new();
+        writeln!(buf, "<?php")?;
+        writeln!(buf)?;
+        writeln!(buf, "// PhpStorm meta for {}", self.name)?;
+        writeln!(buf)?;
+        writeln!(buf, "namespace PHPSTORM_META {{")?;
+        buf.push_str(&overrides);
+        writeln!(buf, "}}")?;
+
+        Ok(Some(buf))
+    }
+}
+
+/// Writes an `override()` call for the given function or method.
+///
+/// # Parameters
+///
+/// * `buf` - The buffer to write into.
+/// * `target` - The fully qualified name of the function, or the fully
+///   qualified name of the class and the name of the method separated by `::`.
+/// * `return_override` - The return type override.
+fn fmt_override(buf: &mut String, target: &str, return_override: &ReturnOverride) -> FmtResult {
+    let arg = return_override.arg;
+    write!(buf, "    override({}({}), ", target, arg)?;
+
+    match &return_override.kind {
+        OverrideKind::Class(pattern) => {
+            writeln!(buf, "map([")?;
+            writeln!(buf, "        '' => {},", php_string(pattern.str()))?;
+            write!(buf, "    ])")?;
+        }
+        OverrideKind::SameType => write!(buf, "type({})", arg)?,
+        OverrideKind::ElementType => write!(buf, "elementType({})", arg)?,
+        OverrideKind::Map(entries) => {
+            writeln!(buf, "map([")?;
+            for entry in &**entries {
+                writeln!(
+                    buf,
+                    "        {} => \\{}::class,",
+                    php_string(entry.key.str()),
+                    entry.class.str().trim_start_matches('\\')
+                )?;
+            }
+            write!(buf, "    ])")?;
+        }
+    }
+
+    writeln!(buf, ");")
+}
+
+/// Quotes the given value as a single-quoted PHP string, escaping backslashes
+/// and single quotes. Also used by `cargo-php` to write PHP scripts.
+pub fn php_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use crate::describe::{
+        abi::Option, Class, Function, Method, Module, OverrideKind, OverrideMapEntry,
+        ReturnOverride,
+    };
+
+    fn function(name: &'static str, return_override: Option<ReturnOverride>) -> Function {
+        Function {
+            name: name.into(),
+            return_override,
+            ..Default::default()
+        }
+    }
+
+    fn module(functions: Vec<Function>, classes: Vec<Class>) -> Module {
+        Module {
+            name: "ext".into(),
+            functions: functions.into(),
+            classes: classes.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn test_no_overrides() {
+        let module = module(vec![function("plain", Option::None)], vec![]);
+        assert_eq!(module.to_phpstorm_meta().unwrap(), None);
+    }
+
+    #[test]
+    pub fn test_function_overrides() {
+        let module = module(
+            vec![
+                function("plain", Option::None),
+                function(
+                    "MyExt\\make",
+                    Option::Some(ReturnOverride {
+                        arg: 0,
+                        kind: OverrideKind::Class("@".into()),
+                    }),
+                ),
+                function(
+                    "repository",
+                    Option::Some(ReturnOverride {
+                        arg: 1,
+                        kind: OverrideKind::Class("App\\@Repository".into()),
+                    }),
+                ),
+                function(
+                    "identity",
+                    Option::Some(ReturnOverride {
+                        arg: 0,
+                        kind: OverrideKind::SameType,
+                    }),
+                ),
+                function(
+                    "first",
+                    Option::Some(ReturnOverride {
+                        arg: 0,
+                        kind: OverrideKind::ElementType,
+                    }),
+                ),
+            ],
+            vec![],
+        );
+
+        assert_eq!(
+            module.to_phpstorm_meta().unwrap().unwrap(),
+            "<?php\n\n// PhpStorm meta for ext\n\nnamespace PHPSTORM_META {\n    override(\\MyExt\\make(0), map([\n        '' => '@',\n    ]));\n    override(\\repository(1), map([\n        '' => 'App\\\\@Repository',\n    ]));\n    override(\\identity(0), type(0));\n    override(\\first(0), elementType(0));\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_method_map_override() {
+        let class = Class {
+            name: "MyExt\\Container".into(),
+            methods: vec![Method {
+                name: "get".into(),
+                return_override: Option::Some(ReturnOverride {
+                    arg: 0,
+                    kind: OverrideKind::Map(
+                        vec![
+                            OverrideMapEntry {
+                                key: "cache".into(),
+                                class: "\\MyExt\\Cache".into(),
+                            },
+                            OverrideMapEntry {
+                                key: "db".into(),
+                                class: "PDO".into(),
+                            },
+                        ]
+                        .into(),
+                    ),
+                }),
+                ..Default::default()
+            }]
+            .into(),
+            ..Default::default()
+        };
+        let module = module(vec![], vec![class]);
+
+        assert_eq!(
+            module.to_phpstorm_meta().unwrap().unwrap(),
+            "<?php\n\n// PhpStorm meta for ext\n\nnamespace PHPSTORM_META {\n    override(\\MyExt\\Container::get(0), map([\n        'cache' => \\MyExt\\Cache::class,\n        'db' => \\PDO::class,\n    ]));\n}\n"
+        );
+    }
+}
@@ -1,20 +1,42 @@
 //! Types used to describe downstream extensions. Used by the `cargo-php`
 //! CLI application to generate PHP stub files used by IDEs.
+//!
+//! With the `serde` feature enabled, the types implement `Serialize`, allowing
+//! other tools to consume the description of an extension, for example through
+//! the JSON output of `cargo php describe --json`.
 
 pub mod abi;
+mod meta;
 mod stub;
 
-use crate::flags::DataType;
+use crate::{
+    ffi::{PHP_MAJOR_VERSION, PHP_MINOR_VERSION},
+    flags::DataType,
+};
 use abi::*;
 
-pub use stub::ToStub;
+pub use meta::php_string;
+pub use stub::{PhpVersion, StubOptions, ToStub};
 
+/// Version of the layout of the types in this module. It is returned by the
+/// ABI version function exported from the extension by the `#[php_module]`
+/// macro, which `cargo-php` calls before reading the description, as reading a
+/// description laid out differently than expected is undefined behaviour.
+///
+/// Must be incremented whenever the layout of any of the types changes.
+pub const ABI_VERSION: u32 = 1;
+
+/// The description of an extension, returned by the describe function which is
+/// exported from the extension by the `#[php_module]` macro.
 #[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Description {
     /// Extension description.
     pub module: Module,
     /// ext-php-rs version.
     pub version: &'static str,
+    /// Version of PHP the extension was built against.
+    pub php_version: PhpVersion,
 }
 
 impl Description {
@@ -27,12 +49,15 @@ impl Description {
         Self {
             module,
             version: crate::VERSION,
+            php_version: PhpVersion::new(PHP_MAJOR_VERSION as u8, PHP_MINOR_VERSION as u8),
         }
     }
 }
 
 /// Represents an extension containing a set of exports.
 #[repr(C)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Module {
     pub name: Str,
     pub functions: Vec<Function>,
@@ -40,35 +65,83 @@ pub struct Module {
     pub constants: Vec<Constant>,
 }
 
+impl Module {
+    /// Removes the functions and classes which are only registered when a
+    /// condition holds when the extension starts up.
+    pub fn remove_conditional(&mut self) {
+        self.functions
+            .retain(|func| func.condition.as_ref().is_none());
+        self.classes
+            .retain(|class| class.condition.as_ref().is_none());
+    }
+}
+
 /// Represents a set of comments on an export.
 #[repr(C)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DocBlock(pub Vec<Str>);
 
 /// Represents an exported function.
 #[repr(C)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Function {
     pub name: Str,
     pub docs: DocBlock,
+    /// Deprecation message, empty if the function is deprecated without a
+    /// message.
+    pub deprecated: Option<Str>,
+    /// PHP attributes written above the declaration in the stubs, e.g.
+    /// `Deprecated`.
+    pub attributes: Vec<Str>,
+    /// Return type depending on an argument, emitted into the
+    /// `.phpstorm.meta.php` file.
+    pub return_override: Option<ReturnOverride>,
+    /// Condition which must hold when the extension starts up for the
+    /// function to be registered.
+    pub condition: Option<Str>,
+    /// Whether the function is hidden from the generated stubs, given through
+    /// `#[php(internal)]`. The function is still registered with PHP.
+    pub internal: bool,
     pub ret: Option<Retval>,
     pub params: Vec<Parameter>,
 }
 
 /// Represents a parameter attached to an exported function or method.
 #[repr(C)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Parameter {
     pub name: Str,
     pub ty: Option<DataType>,
     pub nullable: bool,
     pub default: Option<Str>,
+    pub by_ref: bool,
+    pub variadic: bool,
 }
 
 /// Represents an exported class.
 #[repr(C)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Class {
     pub name: Str,
     pub docs: DocBlock,
+    /// Deprecation message, empty if the class is deprecated without a
+    /// message.
+    pub deprecated: Option<Str>,
+    /// PHP attributes written above the declaration in the stubs, e.g.
+    /// `Deprecated`.
+    pub attributes: Vec<Str>,
+    /// Condition which must hold when the extension starts up for the class
+    /// to be registered.
+    pub condition: Option<Str>,
+    pub ty: ClassType,
     pub extends: Option<Str>,
     pub implements: Vec<Str>,
+    /// Traits used by the class.
+    pub uses: Vec<Str>,
     pub properties: Vec<Property>,
     pub methods: Vec<Method>,
     pub constants: Vec<Constant>,
@@ -76,39 +149,112 @@ pub struct Class {
 
 /// Represents a property attached to an exported class.
 #[repr(C)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Property {
     pub name: Str,
     pub docs: DocBlock,
     pub ty: Option<DataType>,
+    /// The remaining members of a union type, e.g. `string` for `int|string`.
+    /// Empty if the property does not have a union type.
+    pub union: Vec<DataType>,
     pub vis: Visibility,
     pub static_: bool,
+    pub readonly: bool,
     pub nullable: bool,
     pub default: Option<Str>,
 }
 
 /// Represents a method attached to an exported class.
 #[repr(C)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Method {
     pub name: Str,
     pub docs: DocBlock,
+    /// Deprecation message, empty if the method is deprecated without a
+    /// message.
+    pub deprecated: Option<Str>,
+    /// PHP attributes written above the declaration in the stubs, e.g.
+    /// `Deprecated`.
+    pub attributes: Vec<Str>,
+    /// Return type depending on an argument, emitted into the
+    /// `.phpstorm.meta.php` file.
+    pub return_override: Option<ReturnOverride>,
     pub ty: MethodType,
     pub params: Vec<Parameter>,
     pub retval: Option<Retval>,
     pub _static: bool,
+    pub _abstract: bool,
     pub visibility: Visibility,
 }
 
 /// Represents a value returned from a function or method.
 #[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Retval {
     pub ty: DataType,
     pub nullable: bool,
 }
 
+/// Represents a return type of a function or method which depends on the
+/// value of one of its arguments.
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReturnOverride {
+    /// Zero-based position of the argument.
+    pub arg: u32,
+    pub kind: OverrideKind,
+}
+
+/// Enumerator used to differentiate between the ways a return type can depend
+/// on an argument.
+#[repr(C, u8)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum OverrideKind {
+    /// Returns an instance of the class named by the argument. `@` in the
+    /// pattern is replaced by the argument.
+    Class(Str),
+    /// Returns a value of the same type as the argument.
+    SameType,
+    /// Returns an element of the array given as the argument.
+    ElementType,
+    /// Returns an instance of the class mapped to the argument.
+    Map(Vec<OverrideMapEntry>),
+}
+
+/// Represents a value of an argument mapped to the class which is returned.
+#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OverrideMapEntry {
+    pub key: Str,
+    pub class: Str,
+}
+
+/// Enumerator used to differentiate between classes, abstract classes, traits
+/// and enums.
+///
+/// The cases of an enum are described as the constants of the class.
+#[repr(C, u8)]
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ClassType {
+    #[default]
+    Class,
+    Abstract,
+    Trait,
+    /// An enum whose cases do not have values.
+    Enum,
+    /// An enum whose cases have values of the given type.
+    BackedEnum(DataType),
+}
+
 /// Enumerator used to differentiate between methods.
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum MethodType {
+    #[default]
     Member,
     Static,
     Constructor,
@@ -117,17 +263,23 @@ pub enum MethodType {
 /// Enumerator used to differentiate between different method and property
 /// visibilties.
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Visibility {
     Private,
     Protected,
+    #[default]
     Public,
 }
 
 /// Represents an exported constant, stand alone or attached to a class.
 #[repr(C)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Constant {
     pub name: Str,
     pub docs: DocBlock,
     pub value: Option<Str>,
+    /// Declared type of the constant. Only class constants can be typed.
+    pub ty: Option<DataType>,
 }
@@ -1,27 +1,159 @@
 //! Traits and implementations to convert describe units into PHP stub code.
 
 use crate::flags::DataType;
-use std::{cmp::Ordering, collections::HashMap};
+use std::{borrow::Cow, cmp::Ordering, collections::HashMap};
 
 use super::{
-    abi::*, Class, Constant, DocBlock, Function, Method, MethodType, Module, Parameter, Property,
-    Visibility,
+    abi::*, Class, ClassType, Constant, DocBlock, Function, Method, MethodType, Module, Parameter,
+    Property, Retval, Visibility,
 };
-use std::fmt::{Error as FmtError, Result as FmtResult, Write};
+use std::fmt::{Display, Error as FmtError, Result as FmtResult, Write};
 use std::{option::Option as StdOption, vec::Vec as StdVec};
 
+/// A version of PHP targeted by generated stubs. Syntax which is not available
+/// in the targeted version is left out of the stubs, and types which cannot be
+/// declared are documented with PHPDoc tags instead.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PhpVersion {
+    major: u8,
+    minor: u8,
+}
+
+impl PhpVersion {
+    /// The latest version of PHP with syntax supported by the stub generator.
+    pub const LATEST: Self = Self::new(8, 3);
+
+    /// Scalar parameter types and return types.
+    const TYPE_DECLARATIONS: Self = Self::new(7, 0);
+    /// Nullable types, e.g. `?int`, and the `void` return type.
+    const NULLABLE_TYPES: Self = Self::new(7, 1);
+    /// The `object` type.
+    const OBJECT_TYPE: Self = Self::new(7, 2);
+    /// Typed properties, e.g. `public int $foo;`.
+    const TYPED_PROPERTIES: Self = Self::new(7, 4);
+    /// The `mixed` type.
+    const MIXED_TYPE: Self = Self::new(8, 0);
+    /// Union types, e.g. `int|string`.
+    const UNION_TYPES: Self = Self::new(8, 0);
+    /// Readonly properties.
+    const READONLY_PROPERTIES: Self = Self::new(8, 1);
+    /// Enums, e.g. `enum Suit: string {}`.
+    const ENUMS: Self = Self::new(8, 1);
+    /// The `never` return type.
+    const NEVER_TYPE: Self = Self::new(8, 1);
+    /// Typed class constants, e.g. `const int FOO = 1;`.
+    const TYPED_CONSTANTS: Self = Self::new(8, 3);
+
+    /// Creates a new PHP version.
+    ///
+    /// # Parameters
+    ///
+    /// * `major` - The major version, e.g. `8` for PHP 8.3.
+    /// * `minor` - The minor version, e.g. `3` for PHP 8.3.
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+
+    /// Returns the major version.
+    pub fn major(&self) -> u8 {
+        self.major
+    }
+
+    /// Returns the minor version.
+    pub fn minor(&self) -> u8 {
+        self.minor
+    }
+}
+
+impl Display for PhpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> FmtResult {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Options controlling the generated stubs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StubOptions {
+    /// The version of PHP the stubs are generated for.
+    pub version: PhpVersion,
+    /// Whether a module is preceded by a preamble asserting that the extension
+    /// is loaded. Static analysis tools which understand assertions then treat
+    /// calls to `extension_loaded()` with the name of the module as always
+    /// true.
+    pub declare_loaded: bool,
+    /// Whether class names are written fully-qualified, e.g. `\MyExt\Foo`, in
+    /// types and in the `extends`, `implements` and `use` clauses of classes.
+    /// Otherwise, class names are written as they were registered, which PHP
+    /// resolves relative to the namespace of the stub.
+    pub fully_qualified: bool,
+}
+
+impl StubOptions {
+    /// Creates stub options targeting the given version of PHP, with the
+    /// remaining options disabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `version` - The version of PHP the stubs are generated for.
+    pub const fn new(version: PhpVersion) -> Self {
+        Self {
+            version,
+            declare_loaded: false,
+            fully_qualified: false,
+        }
+    }
+}
+
+impl From<PhpVersion> for StubOptions {
+    fn from(version: PhpVersion) -> Self {
+        Self::new(version)
+    }
+}
+
 /// Implemented on types which can be converted into PHP stubs.
 pub trait ToStub {
-    /// Converts the implementor into PHP code, represented as a PHP stub.
-    /// Returned as a string.
+    /// Converts the implementor into PHP code, represented as a PHP stub,
+    /// targeting the latest version of PHP. Returned as a string.
     ///
     /// # Returns
     ///
     /// Returns a string on success. Returns an error if there was an error
     /// writing into the string.
     fn to_stub(&self) -> Result<String, FmtError> {
+        self.to_stub_for(PhpVersion::LATEST)
+    }
+
+    /// Converts the implementor into PHP code, represented as a PHP stub,
+    /// targeting the given version of PHP. Returned as a string.
+    ///
+    /// # Parameters
+    ///
+    /// * `version` - The version of PHP the stub is generated for.
+    ///
+    /// # Returns
+    ///
+    /// Returns a string on success. Returns an error if there was an error
+    /// writing into the string.
+    fn to_stub_for(&self, version: PhpVersion) -> Result<String, FmtError> {
+        self.to_stub_with(StubOptions::new(version))
+    }
+
+    /// Converts the implementor into PHP code, represented as a PHP stub,
+    /// generated with the given options. Returned as a string.
+    ///
+    /// # Parameters
+    ///
+    /// * `opts` - The options the stub is generated with.
+    ///
+    /// # Returns
+    ///
+    /// Returns a string on success. Returns an error if there was an error
+    /// writing into the string.
+    fn to_stub_with(&self, opts: StubOptions) -> Result<String, FmtError> {
         let mut buf = String::new();
-        self.fmt_stub(&mut buf)?;
+        self.fmt_stub(&mut buf, opts)?;
         Ok(buf)
     }
 
@@ -30,21 +162,51 @@ pub trait ToStub {
     /// # Parameters
     ///
     /// * `buf` - The buffer to write the PHP code into.
+    /// * `opts` - The options the stub is generated with.
     ///
     /// # Returns
     ///
     /// Returns nothing on success. Returns an error if there was an error
     /// writing into the buffer.
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult;
+    fn fmt_stub(&self, buf: &mut String, opts: StubOptions) -> FmtResult;
+}
+
+impl Module {
+    /// Converts the module into PHP stubs targeting the given version of PHP,
+    /// along with a preamble asserting that the extension is loaded. Static
+    /// analysis tools which understand assertions then treat calls to
+    /// `extension_loaded()` with the name of the module as always true.
+    ///
+    /// # Parameters
+    ///
+    /// * `version` - The version of PHP the stubs are generated for.
+    ///
+    /// # Returns
+    ///
+    /// Returns a string on success. Returns an error if there was an error
+    /// writing into the string.
+    pub fn to_stub_declaring_loaded(&self, version: PhpVersion) -> Result<String, FmtError> {
+        self.to_stub_with(StubOptions {
+            declare_loaded: true,
+            ..StubOptions::new(version)
+        })
+    }
 }
 
 impl ToStub for Module {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+    fn fmt_stub(&self, buf: &mut String, opts: StubOptions) -> FmtResult {
         writeln!(buf, "<?php")?;
         writeln!(buf)?;
         writeln!(buf, "// Stubs for {}", self.name)?;
         writeln!(buf)?;
 
+        if opts.declare_loaded {
+            writeln!(buf, "namespace {{")?;
+            writeln!(buf, "    \\assert(\\extension_loaded('{}'));", self.name)?;
+            writeln!(buf, "}}")?;
+            write!(buf, "{}", NEW_LINE_SEPARATOR)?;
+        }
+
         // To account for namespaces we need to group by them. [`None`] as the key
         // represents no namespace, while [`Some`] represents a namespace.
         let mut entries: HashMap<StdOption<&str>, StdVec<String>> = HashMap::new();
@@ -58,17 +220,18 @@ impl ToStub for Module {
 
         for c in &*self.constants {
             let (ns, _) = split_namespace(c.name.as_ref());
-            insert(ns, c.to_stub()?);
+            insert(ns, c.to_stub_with(opts)?);
         }
 
-        for func in &*self.functions {
+        // Internal functions are registered with PHP but hidden from the stubs.
+        for func in self.functions.iter().filter(|func| !func.internal) {
             let (ns, _) = split_namespace(func.name.as_ref());
-            insert(ns, func.to_stub()?);
+            insert(ns, func.to_stub_with(opts)?);
         }
 
         for class in &*self.classes {
             let (ns, _) = split_namespace(class.name.as_ref());
-            insert(ns, class.to_stub()?);
+            insert(ns, class.to_stub_with(opts)?);
         }
 
         let mut entries: StdVec<_> = entries.iter().collect();
@@ -109,8 +272,15 @@ impl ToStub for Module {
 }
 
 impl ToStub for Function {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        self.docs.fmt_stub(buf)?;
+    fn fmt_stub(&self, buf: &mut String, opts: StubOptions) -> FmtResult {
+        let mut tags: StdVec<_> = condition_note(&self.condition).into_iter().collect();
+        tags.extend(signature_tags(&self.params, self.ret.as_ref(), opts)?);
+        tags.extend(deprecated_tag(&self.deprecated));
+        if self.internal {
+            tags.push("@internal".into());
+        }
+        fmt_doc_block(buf, &self.docs, &tags)?;
+        fmt_attributes(buf, &self.attributes)?;
 
         let (_, name) = split_namespace(self.name.as_ref());
         write!(
@@ -119,17 +289,16 @@ impl ToStub for Function {
             name,
             self.params
                 .iter()
-                .map(ToStub::to_stub)
+                .map(|param| param.to_stub_with(opts))
                 .collect::<Result<StdVec<_>, FmtError>>()?
                 .join(", ")
         )?;
 
         if let Option::Some(retval) = &self.ret {
-            write!(buf, ": ")?;
-            if retval.nullable {
-                write!(buf, "?")?;
+            if can_declare(&retval.ty, retval.nullable, opts) {
+                write!(buf, ": ")?;
+                fmt_type(buf, &retval.ty, retval.nullable, opts)?;
             }
-            retval.ty.fmt_stub(buf)?;
         }
 
         writeln!(buf, " {{}}")
@@ -137,22 +306,38 @@ impl ToStub for Function {
 }
 
 impl ToStub for Parameter {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+    fn fmt_stub(&self, buf: &mut String, opts: StubOptions) -> FmtResult {
         if let Option::Some(ty) = &self.ty {
-            if self.nullable {
-                write!(buf, "?")?;
+            if can_declare(ty, self.nullable, opts) {
+                fmt_type(buf, ty, self.nullable, opts)?;
+                write!(buf, " ")?;
             }
+        }
 
-            ty.fmt_stub(buf)?;
-            write!(buf, " ")?;
+        if self.by_ref {
+            write!(buf, "&")?;
+        }
+
+        if self.variadic {
+            write!(buf, "...")?;
         }
 
-        write!(buf, "${}", self.name)
+        write!(buf, "${}", self.name)?;
+
+        if let Option::Some(default) = &self.default {
+            write!(buf, " = {}", default)?;
+        }
+
+        Ok(())
     }
 }
 
 impl ToStub for DataType {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+    fn fmt_stub(&self, buf: &mut String, opts: StubOptions) -> FmtResult {
+        if let DataType::Object(Some(ty)) = self {
+            return write!(buf, "{}", class_name(ty, opts));
+        }
+
         write!(
             buf,
             "{}",
@@ -162,12 +347,13 @@ impl ToStub for DataType {
                 DataType::Double => "float",
                 DataType::String => "string",
                 DataType::Array => "array",
-                DataType::Object(Some(ty)) => ty,
-                DataType::Object(None) => "object",
+                DataType::Object(_) => "object",
                 DataType::Resource => "resource",
                 DataType::Reference => "reference",
                 DataType::Callable => "callable",
                 DataType::Bool => "bool",
+                DataType::Never => "never",
+                DataType::Void => "void",
                 _ => "mixed",
             }
         )
@@ -175,27 +361,41 @@ impl ToStub for DataType {
 }
 
 impl ToStub for DocBlock {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        if !self.0.is_empty() {
-            writeln!(buf, "/**")?;
-            for comment in self.0.iter() {
-                writeln!(buf, " *{}", comment)?;
-            }
-            writeln!(buf, " */")?;
-        }
-        Ok(())
+    fn fmt_stub(&self, buf: &mut String, _: StubOptions) -> FmtResult {
+        fmt_doc_block(buf, self, &[])
     }
 }
 
 impl ToStub for Class {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        self.docs.fmt_stub(buf)?;
+    fn fmt_stub(&self, buf: &mut String, opts: StubOptions) -> FmtResult {
+        // Enums cannot be declared before PHP 8.1, so they are declared as a
+        // final class with a constant for each case instead.
+        let is_enum = matches!(self.ty, ClassType::Enum | ClassType::BackedEnum(_));
+        let enum_class = is_enum && opts.version < PhpVersion::ENUMS;
+        let tags: StdVec<_> = condition_note(&self.condition)
+            .into_iter()
+            .chain(deprecated_tag(&self.deprecated))
+            .chain(enum_class.then(|| enum_note(&self.ty, opts)).transpose()?)
+            .collect();
+        fmt_doc_block(buf, &self.docs, &tags)?;
+        fmt_attributes(buf, &self.attributes)?;
 
         let (_, name) = split_namespace(self.name.as_ref());
-        write!(buf, "class {} ", name)?;
+        match self.ty {
+            ClassType::Class => write!(buf, "class {} ", name)?,
+            ClassType::Abstract => write!(buf, "abstract class {} ", name)?,
+            ClassType::Trait => write!(buf, "trait {} ", name)?,
+            _ if enum_class => write!(buf, "final class {} ", name)?,
+            ClassType::Enum => write!(buf, "enum {} ", name)?,
+            ClassType::BackedEnum(ty) => {
+                write!(buf, "enum {}: ", name)?;
+                ty.fmt_stub(buf, opts)?;
+                write!(buf, " ")?;
+            }
+        }
 
         if let Option::Some(extends) = &self.extends {
-            write!(buf, "extends {} ", extends)?;
+            write!(buf, "extends {} ", class_name(extends.str(), opts))?;
         }
 
         if !self.implements.is_empty() {
@@ -204,24 +404,50 @@ impl ToStub for Class {
                 "implements {} ",
                 self.implements
                     .iter()
-                    .map(|s| s.str())
+                    .map(|s| class_name(s.str(), opts))
                     .collect::<StdVec<_>>()
                     .join(", ")
             )?;
         }
 
+        let has_members =
+            !self.constants.is_empty() || !self.properties.is_empty() || !self.methods.is_empty();
+        if self.uses.is_empty() && !has_members {
+            return writeln!(buf, "{{}}");
+        }
+
         writeln!(buf, "{{")?;
 
-        fn stub<T: ToStub>(items: &[T]) -> impl Iterator<Item = Result<String, FmtError>> + '_ {
+        for trait_ in &*self.uses {
+            writeln!(buf, "    use {};", class_name(trait_.str(), opts))?;
+        }
+        if !self.uses.is_empty() && has_members {
+            write!(buf, "{}", NEW_LINE_SEPARATOR)?;
+        }
+
+        fn stub<T: ToStub>(
+            items: &[T],
+            opts: StubOptions,
+        ) -> impl Iterator<Item = Result<String, FmtError>> + '_ {
             items
                 .iter()
-                .map(|item| item.to_stub().map(|stub| indent(&stub, 4)))
+                .map(move |item| item.to_stub_with(opts).map(|stub| indent(&stub, 4)))
         }
 
+        let constants: StdVec<_> = if is_enum && !enum_class {
+            self.constants
+                .iter()
+                .map(|case| fmt_case(case, opts).map(|stub| indent(&stub, 4)))
+                .collect()
+        } else {
+            stub(&self.constants, opts).collect()
+        };
+
         buf.push_str(
-            &stub(&self.constants)
-                .chain(stub(&self.properties))
-                .chain(stub(&self.methods))
+            &constants
+                .into_iter()
+                .chain(stub(&self.properties, opts))
+                .chain(stub(&self.methods, opts))
                 .collect::<Result<StdVec<_>, FmtError>>()?
                 .join(NEW_LINE_SEPARATOR),
         );
@@ -231,17 +457,39 @@ impl ToStub for Class {
 }
 
 impl ToStub for Property {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        self.docs.fmt_stub(buf)?;
-        self.vis.fmt_stub(buf)?;
+    fn fmt_stub(&self, buf: &mut String, opts: StubOptions) -> FmtResult {
+        let readonly = self.readonly && opts.version >= PhpVersion::READONLY_PROPERTIES;
+        let ty = self.ty.as_ref().map(|ty| {
+            let types: StdVec<_> = std::iter::once(ty).chain(self.union.iter()).collect();
+            let declare = opts.version >= PhpVersion::TYPED_PROPERTIES
+                && can_declare_property(&types, self.nullable, opts);
+            (types, declare)
+        });
+
+        let mut tags = StdVec::new();
+        if let Some((types, false)) = &ty {
+            tags.push(format!("@var {}", union_type(types, self.nullable, opts)?));
+        }
+        if self.readonly && !readonly {
+            tags.push("@readonly".to_string());
+        }
+        fmt_doc_block(buf, &self.docs, &tags)?;
+        self.vis.fmt_stub(buf, opts)?;
 
         write!(buf, " ")?;
 
         if self.static_ {
             write!(buf, "static ")?;
         }
-        if let Option::Some(ty) = &self.ty {
-            ty.fmt_stub(buf)?;
+        if readonly {
+            write!(buf, "readonly ")?;
+        }
+        if let Some((types, true)) = &ty {
+            match types.as_slice() {
+                [ty] => fmt_type(buf, ty, self.nullable, opts)?,
+                types => write!(buf, "{}", union_type(types, self.nullable, opts)?)?,
+            }
+            write!(buf, " ")?;
         }
         write!(buf, "${}", self.name)?;
         if let Option::Some(default) = &self.default {
@@ -252,7 +500,7 @@ impl ToStub for Property {
 }
 
 impl ToStub for Visibility {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
+    fn fmt_stub(&self, buf: &mut String, _: StubOptions) -> FmtResult {
         write!(
             buf,
             "{}",
@@ -266,9 +514,21 @@ impl ToStub for Visibility {
 }
 
 impl ToStub for Method {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        self.docs.fmt_stub(buf)?;
-        self.visibility.fmt_stub(buf)?;
+    fn fmt_stub(&self, buf: &mut String, opts: StubOptions) -> FmtResult {
+        let retval = match self.ty {
+            MethodType::Constructor => None,
+            _ => self.retval.as_ref(),
+        };
+        let mut tags = signature_tags(&self.params, retval, opts)?;
+        tags.extend(deprecated_tag(&self.deprecated));
+        fmt_doc_block(buf, &self.docs, &tags)?;
+        fmt_attributes(buf, &self.attributes)?;
+
+        if self._abstract {
+            write!(buf, "abstract ")?;
+        }
+
+        self.visibility.fmt_stub(buf, opts)?;
 
         write!(buf, " ")?;
 
@@ -282,36 +542,265 @@ impl ToStub for Method {
             self.name,
             self.params
                 .iter()
-                .map(ToStub::to_stub)
+                .map(|param| param.to_stub_with(opts))
                 .collect::<Result<StdVec<_>, FmtError>>()?
                 .join(", ")
         )?;
 
-        if !matches!(self.ty, MethodType::Constructor) {
-            if let Option::Some(retval) = &self.retval {
+        if let Some(retval) = retval {
+            if can_declare(&retval.ty, retval.nullable, opts) {
                 write!(buf, ": ")?;
-                if retval.nullable {
-                    write!(buf, "?")?;
-                }
-                retval.ty.fmt_stub(buf)?;
+                fmt_type(buf, &retval.ty, retval.nullable, opts)?;
             }
         }
 
-        writeln!(buf, " {{}}")
+        if self._abstract {
+            writeln!(buf, ";")
+        } else {
+            writeln!(buf, " {{}}")
+        }
     }
 }
 
 impl ToStub for Constant {
-    fn fmt_stub(&self, buf: &mut String) -> FmtResult {
-        self.docs.fmt_stub(buf)?;
+    fn fmt_stub(&self, buf: &mut String, opts: StubOptions) -> FmtResult {
+        self.docs.fmt_stub(buf, opts)?;
 
-        write!(buf, "const {} = ", self.name)?;
-        if let Option::Some(value) = &self.value {
-            write!(buf, "{}", value)?;
-        } else {
-            write!(buf, "null")?;
+        write!(buf, "const ")?;
+        match &self.value {
+            Option::Some(value) => {
+                // Constants without a value are written as `null`, which is not a
+                // valid value of the type, so only constants with a value are typed.
+                if opts.version >= PhpVersion::TYPED_CONSTANTS {
+                    if let Option::Some(ty) = &self.ty {
+                        if is_constant_type(ty) {
+                            ty.fmt_stub(buf, opts)?;
+                            write!(buf, " ")?;
+                        }
+                    }
+                }
+                writeln!(buf, "{} = {};", self.name, value)
+            }
+            Option::None => writeln!(buf, "{} = null;", self.name),
         }
-        writeln!(buf, ";")
+    }
+}
+
+/// Returns a note for an enum declared as a class, as the version of PHP
+/// targeted by the stubs does not support enums.
+fn enum_note(ty: &ClassType, opts: StubOptions) -> Result<String, FmtError> {
+    let kind = match ty {
+        ClassType::BackedEnum(ty) => format!("An enum backed by `{}`", ty.to_stub_with(opts)?),
+        _ => "A pure enum".to_string(),
+    };
+    Ok(format!(
+        "{}, declared as a class as enums require PHP {}. Each constant is a case of the enum.",
+        kind,
+        PhpVersion::ENUMS
+    ))
+}
+
+/// Formats a constant of an enum as a case of the enum.
+fn fmt_case(case: &Constant, opts: StubOptions) -> Result<String, FmtError> {
+    let mut buf = String::new();
+    case.docs.fmt_stub(&mut buf, opts)?;
+    write!(buf, "case {}", case.name)?;
+    if let Option::Some(value) = &case.value {
+        write!(buf, " = {}", value)?;
+    }
+    writeln!(buf, ";")?;
+    Ok(buf)
+}
+
+/// Returns true if the given type can be declared in a signature or property
+/// in the given version of PHP, false if it must be documented instead.
+fn can_declare(ty: &DataType, nullable: bool, opts: StubOptions) -> bool {
+    let min = match ty {
+        DataType::Bool
+        | DataType::True
+        | DataType::False
+        | DataType::Long
+        | DataType::Double
+        | DataType::String
+        | DataType::Array
+        | DataType::Callable
+        | DataType::Object(Some(_)) => PhpVersion::TYPE_DECLARATIONS,
+        DataType::Void => PhpVersion::NULLABLE_TYPES,
+        DataType::Object(None) => PhpVersion::OBJECT_TYPE,
+        DataType::Mixed => PhpVersion::MIXED_TYPE,
+        DataType::Never => PhpVersion::NEVER_TYPE,
+        _ => return false,
+    };
+
+    opts.version >= min
+        && (!nullable || !is_nullable(ty) || opts.version >= PhpVersion::NULLABLE_TYPES)
+}
+
+/// Returns true if the given property type, made up of one or more members of
+/// a union type, can be declared in the given version of PHP. `callable` is
+/// not a valid property type, and the members of a union type must not be
+/// `mixed`, `void` or `never`.
+fn can_declare_property(types: &[&DataType], nullable: bool, opts: StubOptions) -> bool {
+    match types {
+        [ty] => !matches!(ty, DataType::Callable) && can_declare(ty, nullable, opts),
+        types => {
+            opts.version >= PhpVersion::UNION_TYPES
+                && types.iter().all(|ty| {
+                    is_nullable(ty)
+                        && !matches!(ty, DataType::Callable)
+                        && can_declare(ty, false, opts)
+                })
+        }
+    }
+}
+
+/// Returns true if the given type can be marked as nullable. `mixed` already
+/// includes `null`, and `void` and `never` cannot be nullable.
+fn is_nullable(ty: &DataType) -> bool {
+    !matches!(ty, DataType::Mixed | DataType::Void | DataType::Never)
+}
+
+/// Writes a type declaration, e.g. `?int`.
+fn fmt_type(buf: &mut String, ty: &DataType, nullable: bool, opts: StubOptions) -> FmtResult {
+    if nullable && is_nullable(ty) {
+        write!(buf, "?")?;
+    }
+    ty.fmt_stub(buf, opts)
+}
+
+/// Returns a type as written in a PHPDoc tag, e.g. `int|null`.
+fn doc_type(ty: &DataType, nullable: bool, opts: StubOptions) -> Result<String, FmtError> {
+    let mut buf = ty.to_stub_with(opts)?;
+    if nullable && is_nullable(ty) {
+        buf.push_str("|null");
+    }
+    Ok(buf)
+}
+
+/// Returns a union type as written in a declaration or PHPDoc tag, e.g.
+/// `int|string|null`.
+fn union_type(types: &[&DataType], nullable: bool, opts: StubOptions) -> Result<String, FmtError> {
+    let mut members = types
+        .iter()
+        .map(|ty| ty.to_stub_with(opts))
+        .collect::<Result<StdVec<_>, FmtError>>()?;
+    if nullable && types.iter().all(|ty| is_nullable(ty)) {
+        members.push("null".to_string());
+    }
+    Ok(members.join("|"))
+}
+
+/// Returns the `@param` and `@return` tags documenting the types of a function
+/// or method which cannot be declared in the given version of PHP.
+fn signature_tags(
+    params: &[Parameter],
+    retval: StdOption<&Retval>,
+    opts: StubOptions,
+) -> Result<StdVec<String>, FmtError> {
+    let mut tags = StdVec::new();
+
+    for param in params {
+        if let Option::Some(ty) = &param.ty {
+            if !can_declare(ty, param.nullable, opts) {
+                tags.push(format!(
+                    "@param {} {}${}",
+                    doc_type(ty, param.nullable, opts)?,
+                    if param.variadic { "..." } else { "" },
+                    param.name
+                ));
+            }
+        }
+    }
+
+    if let Some(retval) = retval {
+        if !can_declare(&retval.ty, retval.nullable, opts) {
+            tags.push(format!(
+                "@return {}",
+                doc_type(&retval.ty, retval.nullable, opts)?
+            ));
+        }
+    }
+
+    Ok(tags)
+}
+
+/// Writes a doc block containing the given comments, followed by the given
+/// PHPDoc tags. Nothing is written if there are no comments or tags.
+fn fmt_doc_block(buf: &mut String, docs: &DocBlock, tags: &[String]) -> FmtResult {
+    if docs.0.is_empty() && tags.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(buf, "/**")?;
+    for comment in docs.0.iter() {
+        writeln!(buf, " *{}", comment)?;
+    }
+    if !docs.0.is_empty() && !tags.is_empty() {
+        writeln!(buf, " *")?;
+    }
+    for tag in tags {
+        writeln!(buf, " * {}", tag)?;
+    }
+    writeln!(buf, " */")
+}
+
+/// Writes the PHP attributes of a declaration, each on its own line. PHP 7
+/// reads a line starting with `#` as a comment, so attributes are written for
+/// every version of PHP.
+fn fmt_attributes(buf: &mut String, attributes: &[Str]) -> FmtResult {
+    for attr in attributes {
+        writeln!(buf, "#[{}]", attr)?;
+    }
+    Ok(())
+}
+
+/// Returns the `@deprecated` PHPDoc tag for a deprecated function, method or
+/// class, including the deprecation message if one was given.
+fn deprecated_tag(deprecated: &Option<Str>) -> StdOption<String> {
+    deprecated.as_ref().map(|message| match message.str() {
+        "" => "@deprecated".to_string(),
+        message => format!("@deprecated {}", message),
+    })
+}
+
+/// Returns a note for a function or class which is only registered when a
+/// condition holds, as the symbol may not exist at runtime.
+fn condition_note(condition: &Option<Str>) -> StdOption<String> {
+    condition.as_ref().map(|condition| {
+        format!(
+            "Only available if `{}()` returns true when the extension starts up.",
+            condition
+        )
+    })
+}
+
+/// Returns true if the given type can be declared as the type of a class
+/// constant, false otherwise.
+fn is_constant_type(ty: &DataType) -> bool {
+    matches!(
+        ty,
+        DataType::Bool
+            | DataType::Long
+            | DataType::Double
+            | DataType::String
+            | DataType::Array
+            | DataType::Object(_)
+            | DataType::Mixed
+    )
+}
+
+/// Returns a class name as written in the stubs, prefixed with a backslash if
+/// fully-qualified names were requested. `self`, `static` and `parent` refer to
+/// the class they are used in, so are never qualified.
+fn class_name(name: &str, opts: StubOptions) -> Cow<'_, str> {
+    let relative = matches!(
+        name.to_ascii_lowercase().as_str(),
+        "self" | "static" | "parent"
+    );
+    if opts.fully_qualified && !relative && !name.starts_with('\\') {
+        format!("\\{}", name).into()
+    } else {
+        name.into()
     }
 }
 
@@ -365,8 +854,15 @@ fn indent(s: &str, depth: usize) -> String {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod test {
-    use super::{indent, split_namespace};
+    use super::{indent, split_namespace, PhpVersion, StubOptions, ToStub};
+    use crate::describe::{
+        abi::Option, Class, ClassType, Constant, DocBlock, Function, Method, MethodType, Module,
+        Parameter, Property, Retval, Visibility,
+    };
+    use crate::flags::DataType;
+    use std::vec::Vec as StdVec;
 
     #[test]
     pub fn test_split_ns() {
@@ -380,4 +876,553 @@ mod test {
         assert_eq!(indent("hello", 4), "    hello");
         assert_eq!(indent("hello\nworld\n", 4), "    hello\n    world\n");
     }
+
+    #[test]
+    pub fn test_typed_constants() {
+        let constant = |name: &'static str, value: &'static str, ty| Constant {
+            name: name.into(),
+            value: Option::Some(value.into()),
+            ty: Option::Some(ty),
+            ..Default::default()
+        };
+        let class = Class {
+            name: "Config".into(),
+            constants: vec![
+                constant("RETRIES", "3", DataType::Long),
+                constant("NAME", "'config'", DataType::String),
+                constant("CALLBACK", "'strlen'", DataType::Callable),
+                Constant {
+                    name: "DEFAULT".into(),
+                    ty: Option::Some(DataType::Object(Some("Config"))),
+                    ..Default::default()
+                },
+            ]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            class.to_stub_for(PhpVersion::new(8, 3)).unwrap(),
+            "class Config {\n    const int RETRIES = 3;\n\n    const string NAME = 'config';\n\n    const CALLBACK = 'strlen';\n\n    const DEFAULT = null;\n}\n"
+        );
+        assert_eq!(
+            class.to_stub_for(PhpVersion::new(8, 2)).unwrap(),
+            "class Config {\n    const RETRIES = 3;\n\n    const NAME = 'config';\n\n    const CALLBACK = 'strlen';\n\n    const DEFAULT = null;\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_downgraded_function() {
+        let function = Function {
+            name: "lookup".into(),
+            ret: Option::Some(Retval {
+                ty: DataType::Mixed,
+                nullable: false,
+            }),
+            params: vec![Parameter {
+                name: "key".into(),
+                ty: Option::Some(DataType::Long),
+                nullable: true,
+                ..Default::default()
+            }]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            function.to_stub_for(PhpVersion::new(8, 0)).unwrap(),
+            "function lookup(?int $key): mixed {}\n"
+        );
+        assert_eq!(
+            function.to_stub_for(PhpVersion::new(7, 4)).unwrap(),
+            "/**\n * @return mixed\n */\nfunction lookup(?int $key) {}\n"
+        );
+        assert_eq!(
+            function.to_stub_for(PhpVersion::new(7, 0)).unwrap(),
+            "/**\n * @param int|null $key\n * @return mixed\n */\nfunction lookup($key) {}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_downgraded_property() {
+        let property = Property {
+            name: "id".into(),
+            ty: Option::Some(DataType::Long),
+            readonly: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            property.to_stub_for(PhpVersion::new(8, 1)).unwrap(),
+            "public readonly int $id;\n"
+        );
+        assert_eq!(
+            property.to_stub_for(PhpVersion::new(8, 0)).unwrap(),
+            "/**\n * @readonly\n */\npublic int $id;\n"
+        );
+        assert_eq!(
+            property.to_stub_for(PhpVersion::new(7, 3)).unwrap(),
+            "/**\n * @var int\n * @readonly\n */\npublic $id;\n"
+        );
+    }
+
+    #[test]
+    pub fn test_typed_properties() {
+        let property = |name: &'static str, ty, union: StdVec<DataType>, nullable| Property {
+            name: name.into(),
+            ty,
+            union: union.into(),
+            nullable,
+            ..Default::default()
+        };
+        let class = Class {
+            name: "Point".into(),
+            properties: vec![
+                property("x", Option::Some(DataType::Long), vec![], false),
+                property("label", Option::Some(DataType::String), vec![], true),
+                property(
+                    "id",
+                    Option::Some(DataType::Long),
+                    vec![DataType::String],
+                    true,
+                ),
+                property("handler", Option::Some(DataType::Callable), vec![], false),
+                property("extra", Option::None, vec![], false),
+            ]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            class.to_stub().unwrap(),
+            "class Point {\n    public int $x;\n\n    public ?string $label;\n\n    public int|string|null $id;\n\n    /**\n     * @var callable\n     */\n    public $handler;\n\n    public $extra;\n}\n"
+        );
+        assert_eq!(
+            class.to_stub_for(PhpVersion::new(7, 4)).unwrap(),
+            "class Point {\n    public int $x;\n\n    public ?string $label;\n\n    /**\n     * @var int|string|null\n     */\n    public $id;\n\n    /**\n     * @var callable\n     */\n    public $handler;\n\n    public $extra;\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_never_return() {
+        let function = Function {
+            name: "fail".into(),
+            ret: Option::Some(Retval {
+                ty: DataType::Never,
+                nullable: false,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            function.to_stub_for(PhpVersion::new(8, 1)).unwrap(),
+            "function fail(): never {}\n"
+        );
+        assert_eq!(
+            function.to_stub_for(PhpVersion::new(8, 0)).unwrap(),
+            "/**\n * @return never\n */\nfunction fail() {}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_trait() {
+        let method = |_abstract| Method {
+            name: "greet".into(),
+            retval: Option::Some(Retval {
+                ty: DataType::String,
+                nullable: false,
+            }),
+            _abstract,
+            ..Default::default()
+        };
+        let class = |name: &'static str, ty, uses: StdVec<_>, methods: StdVec<_>| Class {
+            name: name.into(),
+            ty,
+            uses: uses.into(),
+            methods: methods.into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            class("Greeter", ClassType::Trait, vec![], vec![method(true)])
+                .to_stub()
+                .unwrap(),
+            "trait Greeter {\n    abstract public function greet(): string;\n}\n"
+        );
+        assert_eq!(
+            class("Person", ClassType::Class, vec!["Greeter".into()], vec![])
+                .to_stub()
+                .unwrap(),
+            "class Person {\n    use Greeter;\n}\n"
+        );
+        assert_eq!(
+            class(
+                "Person",
+                ClassType::Class,
+                vec!["Greeter".into()],
+                vec![method(false)]
+            )
+            .to_stub()
+            .unwrap(),
+            "class Person {\n    use Greeter;\n\n    public function greet(): string {}\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_abstract_class() {
+        let method = |name: &'static str, _abstract| Method {
+            name: name.into(),
+            retval: Option::Some(Retval {
+                ty: DataType::Double,
+                nullable: false,
+            }),
+            _abstract,
+            ..Default::default()
+        };
+        let class = Class {
+            name: "Shape".into(),
+            ty: ClassType::Abstract,
+            methods: vec![method("area", true), method("scale", false)].into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            class.to_stub().unwrap(),
+            "abstract class Shape {\n    abstract public function area(): float;\n\n    public function scale(): float {}\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_void_function() {
+        let function = Function {
+            name: "reset".into(),
+            ret: Option::Some(Retval {
+                ty: DataType::Void,
+                nullable: false,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            function.to_stub_for(PhpVersion::new(7, 1)).unwrap(),
+            "function reset(): void {}\n"
+        );
+        assert_eq!(
+            function.to_stub_for(PhpVersion::new(7, 0)).unwrap(),
+            "/**\n * @return void\n */\nfunction reset() {}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_deprecated_function() {
+        let function = |deprecated: &'static str| Function {
+            name: "foo".into(),
+            docs: DocBlock(vec![" Does foo.".into()].into()),
+            deprecated: Option::Some(deprecated.into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            function("use bar() instead").to_stub().unwrap(),
+            "/**\n * Does foo.\n *\n * @deprecated use bar() instead\n */\nfunction foo() {}\n"
+        );
+        assert_eq!(
+            function("").to_stub().unwrap(),
+            "/**\n * Does foo.\n *\n * @deprecated\n */\nfunction foo() {}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_uninstantiable_class() {
+        let class = Class {
+            name: "Units".into(),
+            methods: vec![Method {
+                name: "__construct".into(),
+                ty: MethodType::Constructor,
+                visibility: Visibility::Private,
+                ..Default::default()
+            }]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            class.to_stub().unwrap(),
+            "class Units {\n    private function __construct() {}\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_attributes() {
+        let function = Function {
+            name: "legacy".into(),
+            docs: DocBlock(vec![" Old API.".into()].into()),
+            attributes: vec!["\\Deprecated".into(), "Pure".into()].into(),
+            ..Default::default()
+        };
+        let class = Class {
+            name: "Route".into(),
+            attributes: vec!["Attribute(Attribute::TARGET_METHOD)".into()].into(),
+            methods: vec![Method {
+                name: "path".into(),
+                attributes: vec!["Pure".into()].into(),
+                ..Default::default()
+            }]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            function.to_stub().unwrap(),
+            "/**\n * Old API.\n */\n#[\\Deprecated]\n#[Pure]\nfunction legacy() {}\n"
+        );
+        assert_eq!(
+            class.to_stub().unwrap(),
+            "#[Attribute(Attribute::TARGET_METHOD)]\nclass Route {\n    #[Pure]\n    public function path() {}\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_namespaced_functions() {
+        let function = |name: &'static str| Function {
+            name: name.into(),
+            ..Default::default()
+        };
+        let module = Module {
+            name: "myext".into(),
+            functions: vec![function("MyExt\\hello"), function("world")].into(),
+            classes: vec![Class {
+                name: "MyExt\\Greeter".into(),
+                ..Default::default()
+            }]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            module.to_stub().unwrap(),
+            "<?php\n\n// Stubs for myext\n\nnamespace MyExt {\n    function hello() {}\n\n    class Greeter {}\n}\n\nnamespace {\n    function world() {}\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_fully_qualified_names() {
+        let class =
+            |name: &'static str, extends, implements: StdVec<_>, methods: StdVec<_>| Class {
+                name: name.into(),
+                extends,
+                implements: implements.into(),
+                methods: methods.into(),
+                ..Default::default()
+            };
+        let method = Method {
+            name: "convert".into(),
+            params: vec![Parameter {
+                name: "other".into(),
+                ty: Option::Some(DataType::Object(Some("Shapes\\Circle"))),
+                nullable: true,
+                ..Default::default()
+            }]
+            .into(),
+            retval: Option::Some(Retval {
+                ty: DataType::Object(Some("Geometry\\Point")),
+                nullable: false,
+            }),
+            ..Default::default()
+        };
+        let module = Module {
+            name: "myext".into(),
+            classes: vec![
+                class("Geometry\\Point", Option::None, vec![], vec![]),
+                class(
+                    "Shapes\\Circle",
+                    Option::Some("Geometry\\Point".into()),
+                    vec!["Countable".into()],
+                    vec![method],
+                ),
+            ]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            module.to_stub().unwrap(),
+            "<?php\n\n// Stubs for myext\n\nnamespace Geometry {\n    class Point {}\n}\n\nnamespace Shapes {\n    class Circle extends Geometry\\Point implements Countable {\n        public function convert(?Shapes\\Circle $other): Geometry\\Point {}\n    }\n}\n"
+        );
+        assert_eq!(
+            module
+                .to_stub_with(StubOptions {
+                    fully_qualified: true,
+                    ..StubOptions::new(PhpVersion::LATEST)
+                })
+                .unwrap(),
+            "<?php\n\n// Stubs for myext\n\nnamespace Geometry {\n    class Point {}\n}\n\nnamespace Shapes {\n    class Circle extends \\Geometry\\Point implements \\Countable {\n        public function convert(?\\Shapes\\Circle $other): \\Geometry\\Point {}\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_declare_loaded() {
+        let module = Module {
+            name: "myext".into(),
+            ..Default::default()
+        };
+
+        assert_eq!(module.to_stub().unwrap(), "<?php\n\n// Stubs for myext\n\n");
+        assert_eq!(
+            module.to_stub_declaring_loaded(PhpVersion::LATEST).unwrap(),
+            "<?php\n\n// Stubs for myext\n\nnamespace {\n    \\assert(\\extension_loaded('myext'));\n}\n\n"
+        );
+    }
+
+    #[test]
+    pub fn test_conditional_symbols() {
+        let function = |name: &'static str, condition| Function {
+            name: name.into(),
+            condition,
+            ..Default::default()
+        };
+        let mut module = Module {
+            name: "myext".into(),
+            functions: vec![
+                function("always", Option::None),
+                function("fetch", Option::Some("curl_loaded".into())),
+            ]
+            .into(),
+            classes: vec![Class {
+                name: "Client".into(),
+                condition: Option::Some("curl_loaded".into()),
+                ..Default::default()
+            }]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            module.to_stub().unwrap(),
+            "<?php\n\n// Stubs for myext\n\nnamespace {\n    function always() {}\n\n    /**\n     * Only available if `curl_loaded()` returns true when the extension starts up.\n     */\n    function fetch() {}\n\n    /**\n     * Only available if `curl_loaded()` returns true when the extension starts up.\n     */\n    class Client {}\n}\n"
+        );
+
+        module.remove_conditional();
+        assert_eq!(
+            module.to_stub().unwrap(),
+            "<?php\n\n// Stubs for myext\n\nnamespace {\n    function always() {}\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_internal_function() {
+        let function = |name: &'static str, internal| Function {
+            name: name.into(),
+            internal,
+            ..Default::default()
+        };
+        let module = Module {
+            name: "myext".into(),
+            functions: vec![function("hello", false), function("_myext_helper", true)].into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            module.to_stub().unwrap(),
+            "<?php\n\n// Stubs for myext\n\nnamespace {\n    function hello() {}\n}\n"
+        );
+        assert_eq!(
+            function("_myext_helper", true).to_stub().unwrap(),
+            "/**\n * @internal\n */\nfunction _myext_helper() {}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_array_constant() {
+        let module = Module {
+            name: "myext".into(),
+            constants: vec![Constant {
+                name: "MYEXT_DEFAULTS".into(),
+                value: Option::Some("['timeout' => 30, 'host' => 'localhost']".into()),
+                ..Default::default()
+            }]
+            .into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            module.to_stub().unwrap(),
+            "<?php\n\n// Stubs for myext\n\nnamespace {\n    const MYEXT_DEFAULTS = ['timeout' => 30, 'host' => 'localhost'];\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_enum() {
+        let case = |name: &'static str, value| Constant {
+            name: name.into(),
+            value,
+            ..Default::default()
+        };
+        let class = |ty, constants: StdVec<Constant>| Class {
+            name: "Suit".into(),
+            ty,
+            constants: constants.into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            class(
+                ClassType::Enum,
+                vec![case("Hearts", Option::None), case("Spades", Option::None)]
+            )
+            .to_stub()
+            .unwrap(),
+            "enum Suit {\n    case Hearts;\n\n    case Spades;\n}\n"
+        );
+        assert_eq!(
+            class(
+                ClassType::BackedEnum(DataType::String),
+                vec![
+                    case("Hearts", Option::Some("'H'".into())),
+                    case("Spades", Option::Some("'S'".into()))
+                ]
+            )
+            .to_stub()
+            .unwrap(),
+            "enum Suit: string {\n    case Hearts = 'H';\n\n    case Spades = 'S';\n}\n"
+        );
+    }
+
+    #[test]
+    pub fn test_downgraded_enum() {
+        let case = |name: &'static str, value| Constant {
+            name: name.into(),
+            value,
+            ..Default::default()
+        };
+        let class = |ty, constants: StdVec<Constant>| Class {
+            name: "Suit".into(),
+            docs: DocBlock(vec![" The suit of a card.".into()].into()),
+            ty,
+            constants: constants.into(),
+            ..Default::default()
+        };
+
+        let pure = class(
+            ClassType::Enum,
+            vec![case("Hearts", Option::None), case("Spades", Option::None)],
+        );
+        assert_eq!(
+            pure.to_stub_for(PhpVersion::new(8, 0)).unwrap(),
+            "/**\n * The suit of a card.\n *\n * A pure enum, declared as a class as enums require PHP 8.1. Each constant is a case of the enum.\n */\nfinal class Suit {\n    const Hearts = null;\n\n    const Spades = null;\n}\n"
+        );
+        assert!(pure
+            .to_stub_for(PhpVersion::new(8, 1))
+            .unwrap()
+            .contains("enum Suit {\n    case Hearts;\n"));
+
+        let backed = class(
+            ClassType::BackedEnum(DataType::Long),
+            vec![case("Low", Option::Some("1".into()))],
+        );
+        assert_eq!(
+            backed.to_stub_for(PhpVersion::new(8, 0)).unwrap(),
+            "/**\n * The suit of a card.\n *\n * An enum backed by `int`, declared as a class as enums require PHP 8.1. Each constant is a case of the enum.\n */\nfinal class Suit {\n    const Low = 1;\n}\n"
+        );
+    }
 }
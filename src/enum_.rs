@@ -0,0 +1,152 @@
+//! Types and traits for exporting Rust enums to PHP as enums. PHP enums were
+//! introduced in PHP 8.1.
+
+use std::{
+    ffi::CString,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::{
+    error::{Error, Result},
+    ffi::zend_enum_get_case_cstr,
+    types::{ZendObject, Zval},
+    zend::ClassEntry,
+};
+
+/// Implemented on Rust enums which are exported to PHP as enums, mapping each
+/// variant of the Rust enum to a case of the PHP enum. Usually implemented
+/// through the [`macro@php_enum`] macro.
+///
+/// [`macro@php_enum`]: crate::php_enum
+pub trait RegisteredEnum: Sized + 'static {
+    /// PHP name of the registered enum.
+    const ENUM_NAME: &'static str;
+
+    /// Returns a reference to the enum metadata, which stores the class entry
+    /// of the enum.
+    ///
+    /// This must be statically allocated, and is usually done through the
+    /// [`macro@php_enum`] macro.
+    ///
+    /// [`macro@php_enum`]: crate::php_enum
+    fn get_metadata() -> &'static EnumMetadata;
+
+    /// Returns the name of the PHP case corresponding to the variant.
+    fn case_name(&self) -> &'static str;
+
+    /// Returns the variant corresponding to the PHP case with the given name,
+    /// or [`None`] if the enum has no such case.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the PHP case.
+    fn from_case_name(name: &str) -> Option<Self>;
+
+    /// Returns the object of the PHP case corresponding to the variant. Enum
+    /// cases are singletons owned by the enum, so the same object is returned
+    /// each time and it cannot be modified.
+    fn case_object(&self) -> Result<&'static ZendObject> {
+        // SAFETY: The pointer is non-null, and the case object lives as long as the
+        // class entry of the enum.
+        Ok(unsafe { &*case_object_ptr(self)? })
+    }
+
+    /// Returns the variant corresponding to the given PHP case object, or
+    /// [`None`] if the object is not a case of the enum.
+    ///
+    /// # Parameters
+    ///
+    /// * `obj` - The case object.
+    fn from_case_object(obj: &ZendObject) -> Option<Self> {
+        if !obj.instance_of(Self::get_metadata().ce()) {
+            return None;
+        }
+
+        // The name of the case is always stored in the first property slot.
+        Self::from_case_name(obj.properties_table[0].str()?)
+    }
+}
+
+/// Returns a pointer to the object of the PHP case corresponding to the given
+/// variant, which is never null.
+fn case_object_ptr<T: RegisteredEnum>(case: &T) -> Result<*mut ZendObject> {
+    let ce = T::get_metadata().ce();
+    let name = CString::new(case.case_name())?;
+    let obj = unsafe {
+        zend_enum_get_case_cstr(ce as *const ClassEntry as *mut ClassEntry, name.as_ptr())
+    };
+
+    if obj.is_null() {
+        Err(Error::InvalidPointer)
+    } else {
+        Ok(obj)
+    }
+}
+
+/// INTERNAL: Sets the zval to a new reference to the object of the PHP case
+/// corresponding to the given variant. Used by the `IntoZval` implementation of
+/// exported enums.
+#[doc(hidden)]
+pub fn set_case_zval<T: RegisteredEnum>(case: &T, zv: &mut Zval) -> Result<()> {
+    // SAFETY: The pointer is non-null and points to the case object. Storing the
+    // object in the zval only increments its reference count, the same as PHP
+    // does when the case is assigned to a variable.
+    zv.set_object(unsafe { &mut *case_object_ptr(case)? });
+    Ok(())
+}
+
+/// Stores the class entry for a Rust enum which has been exported to PHP.
+/// Usually allocated statically.
+pub struct EnumMetadata {
+    ce: AtomicPtr<ClassEntry>,
+}
+
+impl EnumMetadata {
+    /// Creates a new enum metadata instance.
+    pub const fn new() -> Self {
+        Self {
+            ce: AtomicPtr::new(std::ptr::null_mut()),
+        }
+    }
+
+    /// Checks if the class entry has been stored, returning a boolean.
+    pub fn has_ce(&self) -> bool {
+        !self.ce.load(Ordering::SeqCst).is_null()
+    }
+
+    /// Retrieves a reference to the stored class entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no class entry stored inside the enum metadata.
+    pub fn ce(&self) -> &'static ClassEntry {
+        // SAFETY: There are only two values that can be stored in the atomic ptr: null
+        // or a static reference to a class entry.
+        unsafe { self.ce.load(Ordering::SeqCst).as_ref() }
+            .expect("Attempted to retrieve class entry before it has been stored.")
+    }
+
+    /// Stores a reference to a class entry inside the enum metadata.
+    ///
+    /// # Parameters
+    ///
+    /// * `ce` - The class entry to store.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the class entry has already been set in the enum metadata.
+    /// This function should only be called once.
+    pub fn set_ce(&self, ce: &'static mut ClassEntry) {
+        if !self.ce.load(Ordering::SeqCst).is_null() {
+            panic!("Class entry has already been set.");
+        }
+
+        self.ce.store(ce, Ordering::SeqCst);
+    }
+}
+
+impl Default for EnumMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
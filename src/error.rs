@@ -6,7 +6,8 @@ use crate::{
     boxed::ZBox,
     exception::PhpException,
     flags::{ClassFlags, DataType, ZvalTypeFlags},
-    types::ZendObject,
+    types::{ArrayKey, ZendObject, Zval},
+    zend::ce,
 };
 
 /// The main result type which is passed by the library.
@@ -52,10 +53,64 @@ pub enum Error {
     Callable,
     /// An invalid exception type was thrown.
     InvalidException(ClassFlags),
+    /// A class which does not implement `Throwable` was thrown.
+    ///
+    /// The enum carries the name of the class.
+    NotThrowable(String),
+    /// The class with the given name has not been declared.
+    ///
+    /// The enum carries the name of the class.
+    UnknownClass(String),
     /// Converting integer arguments resulted in an overflow.
     IntegerOverflow,
     /// An exception was thrown in a function.
     Exception(ZBox<ZendObject>),
+    /// The INI entries of the extension could not be registered, generally
+    /// because an entry with the same name has already been registered.
+    IniRegistration,
+    /// The tag of a tagged enum array did not match any of the variants of the
+    /// enum.
+    ///
+    /// The enum carries the tag that was given, or [`None`] if the array did not
+    /// contain a tag.
+    InvalidEnumTag(Option<String>),
+    /// An array did not contain the expected number of elements.
+    ///
+    /// The enum carries two integers - the first representing the expected
+    /// number of elements, and the second representing the number of elements
+    /// in the array.
+    IncorrectArrayLength(usize, usize),
+    /// A resource was created for a type which has not been declared on the
+    /// module.
+    UnregisteredResource,
+    /// The value of a resource was accessed after the resource was closed.
+    ClosedResource,
+    /// A module dependency was declared with an empty name, on the module
+    /// itself, or more than once.
+    ///
+    /// The enum carries the name of the dependency.
+    InvalidModuleDependency(String),
+    /// The value of a constant contained a value which cannot be stored in a
+    /// constant, such as an object.
+    ///
+    /// The enum carries the type of the value.
+    InvalidConstant(DataType),
+    /// An enum case was declared more than once, with a value which is already
+    /// used by another case, or with a value which does not match the backing
+    /// type of the enum.
+    ///
+    /// The enum carries the name of the case.
+    InvalidEnumCase(String),
+    /// The method with the given name does not exist on an object, or cannot
+    /// be called from the current scope.
+    ///
+    /// The enum carries the name of the class and method, as `Class::method`.
+    UnknownMethod(String),
+    /// The function with the given name has not been declared, or cannot be
+    /// called from the current scope.
+    ///
+    /// The enum carries the name of the function.
+    UnknownFunction(String),
 }
 
 impl Display for Error {
@@ -86,10 +141,41 @@ impl Display for Error {
             Error::InvalidException(flags) => {
                 write!(f, "Invalid exception type was thrown: {:?}", flags)
             }
+            Error::NotThrowable(name) => write!(
+                f,
+                "Class `{}` cannot be thrown as it does not implement `Throwable`.",
+                name
+            ),
+            Error::UnknownClass(name) => write!(f, "Class `{}` does not exist.", name),
             Error::IntegerOverflow => {
                 write!(f, "Converting integer arguments resulted in an overflow.")
             }
             Error::Exception(e) => write!(f, "Exception was thrown: {:?}", e),
+            Error::IniRegistration => write!(f, "Failed to register INI entries."),
+            Error::InvalidEnumTag(Some(tag)) => write!(f, "Unknown enum variant `{}`.", tag),
+            Error::InvalidEnumTag(None) => write!(f, "Array did not contain an enum tag."),
+            Error::IncorrectArrayLength(expected, n) => write!(
+                f,
+                "Expected an array with {} elements, got {} elements.",
+                expected, n
+            ),
+            Error::UnregisteredResource => {
+                write!(f, "Resource type has not been registered with the module.")
+            }
+            Error::ClosedResource => write!(f, "Resource has already been closed."),
+            Error::InvalidModuleDependency(name) => {
+                write!(f, "Invalid module dependency `{}`.", name)
+            }
+            Error::InvalidConstant(ty) => {
+                write!(f, "Constants cannot contain values of type {}.", ty)
+            }
+            Error::InvalidEnumCase(name) => write!(f, "Invalid enum case `{}`.", name),
+            Error::UnknownMethod(name) => {
+                write!(f, "Method `{}` does not exist or cannot be called.", name)
+            }
+            Error::UnknownFunction(name) => {
+                write!(f, "Function `{}` does not exist or cannot be called.", name)
+            }
         }
     }
 }
@@ -107,3 +193,141 @@ impl From<Error> for PhpException {
         Self::default(err.to_string())
     }
 }
+
+/// Error returned when a [`Zval`] could not be converted into a Rust type,
+/// carrying the PHP type names of the expected and actual values.
+///
+/// Displayed as `expected string, got array`. Converting the error into a
+/// [`PhpException`] creates a `TypeError`, so it can be returned directly from
+/// exported functions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    expected: String,
+    actual: String,
+    key: Option<ArrayKey<'static>>,
+    missing: bool,
+}
+
+impl ConversionError {
+    /// Creates a new conversion error from the expected data type and the
+    /// zval which could not be converted.
+    ///
+    /// # Parameters
+    ///
+    /// * `expected` - The data type which was expected.
+    /// * `zval` - The zval which could not be converted.
+    pub fn new(expected: DataType, zval: &Zval) -> Self {
+        Self {
+            expected: type_name(expected).into(),
+            actual: zval.get_type_name().into_owned(),
+            key: None,
+            missing: false,
+        }
+    }
+
+    /// Creates a new conversion error for an array which did not contain an
+    /// element with the given key.
+    ///
+    /// # Parameters
+    ///
+    /// * `expected` - The data type which was expected for the element.
+    /// * `key` - The key of the missing element.
+    pub fn missing_key(expected: DataType, key: ArrayKey<'static>) -> Self {
+        Self {
+            expected: type_name(expected).into(),
+            actual: type_name(DataType::Null).into(),
+            key: Some(key),
+            missing: true,
+        }
+    }
+
+    /// Sets the key of the array element which could not be converted, which
+    /// is included in the error message.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The key of the array element.
+    pub fn with_key(mut self, key: ArrayKey<'static>) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Returns the PHP name of the expected type.
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// Returns the PHP name of the type of the value which was given.
+    pub fn actual(&self) -> &str {
+        &self.actual
+    }
+
+    /// Returns the key of the array element which could not be converted, if
+    /// the error was returned when converting an array element.
+    pub fn key(&self) -> Option<&ArrayKey<'static>> {
+        self.key.as_ref()
+    }
+
+    /// Returns true if the error was returned because an array did not contain
+    /// an element with the key returned by [`key`](Self::key).
+    pub fn is_missing(&self) -> bool {
+        self.missing
+    }
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.missing {
+            return match &self.key {
+                Some(key @ (ArrayKey::String(_) | ArrayKey::Bytes(_))) => {
+                    write!(
+                        f,
+                        "missing array key \"{}\", expected {}",
+                        key, self.expected
+                    )
+                }
+                Some(key) => write!(f, "missing array key {}, expected {}", key, self.expected),
+                None => write!(f, "missing value, expected {}", self.expected),
+            };
+        }
+
+        if self.expected == self.actual {
+            write!(f, "invalid {} value", self.expected)?;
+        } else {
+            write!(f, "expected {}, got {}", self.expected, self.actual)?;
+        }
+
+        match &self.key {
+            Some(key @ (ArrayKey::String(_) | ArrayKey::Bytes(_))) => {
+                write!(f, " for array key \"{}\"", key)
+            }
+            Some(key) => write!(f, " for array key {}", key),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ErrorTrait for ConversionError {}
+
+impl From<ConversionError> for PhpException {
+    fn from(err: ConversionError) -> Self {
+        Self::new(err.to_string(), 0, ce::type_error())
+    }
+}
+
+/// Returns the name of the given data type as used by PHP in type errors.
+pub(crate) fn type_name(ty: DataType) -> &'static str {
+    match ty {
+        DataType::Undef | DataType::Null | DataType::Void => "null",
+        DataType::False | DataType::True | DataType::Bool => "bool",
+        DataType::Long => "int",
+        DataType::Double => "float",
+        DataType::String => "string",
+        DataType::Array => "array",
+        DataType::Object(Some(name)) => name,
+        DataType::Object(None) => "object",
+        DataType::Resource => "resource",
+        DataType::Callable => "callable",
+        _ => "mixed",
+    }
+}
@@ -1,6 +1,16 @@
 //! Types and functions used for throwing exceptions from Rust to PHP.
 
-use std::ffi::CString;
+use std::{
+    ffi::CString,
+    fmt::Display,
+    io,
+    num::{ParseFloatError, ParseIntError, TryFromIntError},
+    str::{ParseBoolError, Utf8Error},
+    string::FromUtf8Error,
+    sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+};
+
+use parking_lot::{const_mutex, Mutex};
 
 use crate::{
     class::RegisteredClass,
@@ -59,8 +69,96 @@ impl PhpException {
         Self::new(message, 0, T::get_metadata().ce())
     }
 
+    /// Creates an instance of an exception from a Rust type which has been
+    /// exported to PHP as an exception class. The message is taken from the
+    /// [`Display`] implementation of the type, and the code is the default
+    /// code of the exception class.
+    ///
+    /// # Parameters
+    ///
+    /// * `err` - The exception to convert.
+    pub fn from_exception<T: RegisteredException>(err: T) -> Self {
+        Self::new(err.to_string(), T::CODE, T::get_metadata().ce())
+    }
+
+    /// Creates an instance of an exception from the name of a PHP exception
+    /// class, which can be a class declared in PHP or registered by an
+    /// extension. The class is looked up in the same way as
+    /// [`ClassEntry::try_find`], so it may trigger the autoloader.
+    ///
+    /// Returns an error if the class does not exist, or if it does not
+    /// implement `Throwable`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the exception class, e.g. `MyExt\\FooException`.
+    /// * `message` - Message to contain in the exception.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ext_php_rs::exception::{PhpException, PhpResult};
+    ///
+    /// fn find_user(id: i64) -> PhpResult<String> {
+    ///     Err(PhpException::from_class_name("MyExt\\NotFoundException", "User not found.".into())?
+    ///         .with_code(404))
+    /// }
+    /// ```
+    pub fn from_class_name(name: &str, message: String) -> Result<Self> {
+        let ex = ClassEntry::try_find(name).ok_or_else(|| Error::UnknownClass(name.into()))?;
+        if !ex.instance_of(ce::throwable()) {
+            return Err(Error::NotThrowable(name.into()));
+        }
+
+        Ok(Self::new(message, 0, ex))
+    }
+
+    /// Sets the integer code of the exception, returned by `getCode()` when
+    /// the exception is caught in PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `code` - Integer code to go inside the exception.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{exception::PhpException, zend::ce};
+    ///
+    /// let ex = PhpException::new("Invalid range.".into(), 0, ce::value_error()).with_code(42);
+    /// assert_eq!(ex.code(), 42);
+    /// ex.throw().unwrap();
+    /// ```
+    ///
+    /// The thrown exception can be caught in PHP:
+    ///
+    /// ```php
+    /// try {
+    ///     check_range(10, 5);
+    /// } catch (ValueError $e) {
+    ///     assert($e->getCode() === 42);
+    /// }
+    /// ```
+    pub fn with_code(mut self, code: i32) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Returns the message of the exception.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the integer code of the exception.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
     /// Throws the exception, returning nothing inside a result if successful
     /// and an error otherwise.
+    ///
+    /// Returns an error if the class of the exception cannot be thrown, such
+    /// as when it does not implement `Throwable`.
     pub fn throw(self) -> Result<()> {
         throw_with_code(self.ex, self.code, &self.message)
     }
@@ -85,6 +183,217 @@ impl From<anyhow::Error> for PhpException {
     }
 }
 
+/// Function returning the name of the exception class thrown for an I/O error
+/// of a given kind. Replaced with [`set_io_error_class`].
+static IO_ERROR_CLASS: Mutex<fn(io::ErrorKind) -> &'static str> =
+    const_mutex(default_io_error_class);
+
+/// Returns the name of the exception class thrown for an I/O error of the given
+/// kind, unless replaced with [`set_io_error_class`]:
+///
+/// | [`io::ErrorKind`]  | Exception class             |
+/// | ------------------ | --------------------------- |
+/// | `InvalidInput`     | `InvalidArgumentException`  |
+/// | `InvalidData`      | `UnexpectedValueException`  |
+/// | `UnexpectedEof`    | `UnexpectedValueException`  |
+/// | Any other kind     | `RuntimeException`          |
+pub fn default_io_error_class(kind: io::ErrorKind) -> &'static str {
+    match kind {
+        io::ErrorKind::InvalidInput => "InvalidArgumentException",
+        io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => "UnexpectedValueException",
+        _ => "RuntimeException",
+    }
+}
+
+/// Replaces the function used to choose the exception class thrown for an I/O
+/// error, which is [`default_io_error_class`] by default. Usually called when
+/// the extension starts up.
+///
+/// The function returns the name of the exception class, which can be a class
+/// registered by the extension. If the class does not exist or is not
+/// throwable when the error is converted, an `Exception` is thrown instead.
+///
+/// # Parameters
+///
+/// * `class` - Function returning the name of the exception class for an I/O
+///   error of the given kind.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ext_php_rs::exception::{default_io_error_class, set_io_error_class};
+/// use std::io::ErrorKind;
+///
+/// set_io_error_class(|kind| match kind {
+///     ErrorKind::NotFound => "MyExt\\NotFoundException",
+///     kind => default_io_error_class(kind),
+/// });
+/// ```
+pub fn set_io_error_class(class: fn(io::ErrorKind) -> &'static str) {
+    *IO_ERROR_CLASS.lock() = class;
+}
+
+/// Converts an I/O error into an exception of the class given by
+/// [`default_io_error_class`], or the function given to
+/// [`set_io_error_class`]. The code of the exception is the OS error code of
+/// the error, if any.
+impl From<io::Error> for PhpException {
+    fn from(err: io::Error) -> Self {
+        let class = *IO_ERROR_CLASS.lock();
+        let class = class(err.kind());
+        let message = err.to_string();
+        let code = err.raw_os_error().unwrap_or(0);
+
+        PhpException::from_class_name(class, message.clone())
+            .unwrap_or_else(|_| Self::default(message))
+            .with_code(code)
+    }
+}
+
+/// Implements [`From`] for errors which are converted into a `ValueError`.
+macro_rules! value_error {
+    ($($t: ty),*) => {
+        $(
+            impl From<$t> for PhpException {
+                fn from(err: $t) -> Self {
+                    Self::new(err.to_string(), 0, ce::value_error())
+                }
+            }
+        )*
+    };
+}
+
+value_error!(
+    ParseIntError,
+    ParseFloatError,
+    ParseBoolError,
+    TryFromIntError,
+    Utf8Error,
+    FromUtf8Error
+);
+
+/// Implemented on Rust types which are exported to PHP as exception classes,
+/// usually through the [`macro@php_exception`] macro.
+///
+/// The message of the thrown exception is taken from the [`Display`]
+/// implementation of the type.
+///
+/// [`macro@php_exception`]: crate::php_exception
+pub trait RegisteredException: Display + Sized + 'static {
+    /// PHP class name of the registered exception.
+    const CLASS_NAME: &'static str;
+
+    /// PHP class name of the parent class of the exception.
+    const PARENT: &'static str = "Exception";
+
+    /// Default integer code of the exception.
+    const CODE: i32 = 0;
+
+    /// Returns a reference to the exception metadata, which stores the class
+    /// entry.
+    ///
+    /// This must be statically allocated, and is usually done through the
+    /// [`macro@php_exception`] macro.
+    ///
+    /// [`macro@php_exception`]: crate::php_exception
+    fn get_metadata() -> &'static ExceptionMetadata;
+
+    /// Throws the exception, returning nothing inside a result if successful
+    /// and an error otherwise.
+    fn throw(self) -> Result<()> {
+        PhpException::from_exception(self).throw()
+    }
+}
+
+/// Stores the class entry for a Rust type which has been exported to PHP as an
+/// exception. Usually allocated statically.
+pub struct ExceptionMetadata {
+    ce: AtomicPtr<ClassEntry>,
+}
+
+impl ExceptionMetadata {
+    /// Creates a new exception metadata instance.
+    pub const fn new() -> Self {
+        Self {
+            ce: AtomicPtr::new(std::ptr::null_mut()),
+        }
+    }
+
+    /// Checks if the class entry has been stored, returning a boolean.
+    pub fn has_ce(&self) -> bool {
+        !self.ce.load(Ordering::SeqCst).is_null()
+    }
+
+    /// Retrieves a reference to the stored class entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no class entry stored inside the exception metadata.
+    pub fn ce(&self) -> &'static ClassEntry {
+        // SAFETY: There are only two values that can be stored in the atomic ptr: null
+        // or a static reference to a class entry.
+        unsafe { self.ce.load(Ordering::SeqCst).as_ref() }
+            .expect("Attempted to retrieve class entry before it has been stored.")
+    }
+
+    /// Stores a reference to a class entry inside the exception metadata.
+    ///
+    /// # Parameters
+    ///
+    /// * `ce` - The class entry to store.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the class entry has already been set in the exception
+    /// metadata. This function should only be called once.
+    pub fn set_ce(&self, ce: &'static mut ClassEntry) {
+        if !self.ce.load(Ordering::SeqCst).is_null() {
+            panic!("Class entry has already been set.");
+        }
+
+        self.ce.store(ce, Ordering::SeqCst);
+    }
+}
+
+impl Default for ExceptionMetadata {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether panics in exported functions are converted into exceptions when the
+/// function does not set a [`PanicMode`] itself.
+static PANIC_AS_EXCEPTION: AtomicBool = AtomicBool::new(false);
+
+/// Determines what happens when a Rust panic reaches a function or method
+/// exported to PHP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicMode {
+    /// The panic unwinds out of the function, which aborts the PHP process.
+    #[default]
+    Abort,
+    /// The panic is caught and an `Error` is thrown in PHP, containing the
+    /// panic message. The request can catch the error or fail as usual, while
+    /// the PHP process keeps running.
+    Exception,
+}
+
+impl PanicMode {
+    /// Returns the panic mode used for functions which do not set a mode.
+    pub fn module() -> Self {
+        if PANIC_AS_EXCEPTION.load(Ordering::Relaxed) {
+            Self::Exception
+        } else {
+            Self::Abort
+        }
+    }
+
+    /// Sets the panic mode used for functions which do not set a mode.
+    pub(crate) fn set_module(mode: Self) {
+        PANIC_AS_EXCEPTION.store(mode == Self::Exception, Ordering::Relaxed);
+    }
+}
+
 /// Throws an exception with a given message. See [`ClassEntry`] for some
 /// built-in exception types.
 ///
@@ -132,6 +441,10 @@ pub fn throw_with_code(ex: &ClassEntry, code: i32, message: &str) -> Result<()>
         return Err(Error::InvalidException(flags));
     }
 
+    if !ex.instance_of(ce::throwable()) {
+        return Err(Error::NotThrowable(ex.name().unwrap_or_default().into()));
+    }
+
     // SAFETY: We are given a reference to a `ClassEntry` therefore when we cast it
     // to a pointer it will be valid.
     unsafe {
@@ -144,3 +457,25 @@ pub fn throw_with_code(ex: &ClassEntry, code: i32, message: &str) -> Result<()>
     };
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::default_io_error_class;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_default_io_error_class() {
+        assert_eq!(
+            default_io_error_class(ErrorKind::NotFound),
+            "RuntimeException"
+        );
+        assert_eq!(
+            default_io_error_class(ErrorKind::InvalidInput),
+            "InvalidArgumentException"
+        );
+        assert_eq!(
+            default_io_error_class(ErrorKind::UnexpectedEof),
+            "UnexpectedValueException"
+        );
+    }
+}
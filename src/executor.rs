@@ -0,0 +1,169 @@
+//! Types and functions used for exporting asynchronous Rust functions to PHP.
+//!
+//! PHP functions are synchronous, so when an `async fn` annotated with
+//! [`macro@php_function`] is called from PHP, the returned future is driven to
+//! completion on the calling thread before the function returns. The future is
+//! polled by an [`Executor`], which is [`LocalExecutor`] unless another
+//! executor is given with the `executor` argument of the attribute:
+//!
+//! ```
+//! # use ext_php_rs::prelude::*;
+//! #[php_function]
+//! pub async fn add_later(a: i64, b: i64) -> i64 {
+//!     a + b
+//! }
+//!
+//! #[php_function(executor = "ext_php_rs::executor::LocalExecutor")]
+//! pub async fn greet(name: String) -> String {
+//!     format!("Hello, {}!", name)
+//! }
+//!
+//! #[php_module]
+//! pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+//!     module
+//! }
+//! ```
+//!
+//! The future is created and completed within a single call from PHP, so no
+//! state is kept between calls or requests, and futures may borrow the
+//! arguments of the function.
+//!
+//! If the future panics, the panic is caught and thrown as an exception. Errors
+//! returned from the future are converted into exceptions in the same way as
+//! synchronous functions.
+//!
+//! # Reentrancy
+//!
+//! The PHP interpreter is not thread-safe. The future is always polled on the
+//! thread that called the function, and may call into PHP, but work which is
+//! moved onto other threads (such as tasks spawned onto a multi-threaded
+//! runtime) must never call into PHP, including creating or dropping values
+//! such as [`Zval`]s. Wakers may be invoked from any thread.
+//!
+//! The future must not call back into PHP code which calls another
+//! asynchronous function, as the inner executor would block the thread while
+//! the outer executor is still running.
+//!
+//! [`macro@php_function`]: crate::php_function
+//! [`Zval`]: crate::types::Zval
+
+use std::{
+    any::Any,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+use crate::exception::{PhpException, PhpResult};
+
+/// Drives futures returned from asynchronous PHP functions to completion.
+///
+/// Implement this trait to use another runtime, such as a Tokio runtime stored
+/// in a `static`, to run asynchronous functions. See the [module level
+/// documentation](self) for the constraints on executors.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use std::future::Future;
+/// use ext_php_rs::executor::{Executor, LocalExecutor};
+///
+/// pub struct LoggingExecutor;
+///
+/// impl Executor for LoggingExecutor {
+///     fn block_on<F: Future>(future: F) -> F::Output {
+///         println!("running future");
+///         LocalExecutor::block_on(future)
+///     }
+/// }
+///
+/// #[php_function(executor = "LoggingExecutor")]
+/// pub async fn answer() -> i64 {
+///     42
+/// }
+/// # #[php_module]
+/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// ```
+pub trait Executor {
+    /// Runs the given future to completion on the current thread, returning
+    /// the output of the future.
+    ///
+    /// # Parameters
+    ///
+    /// * `future` - The future to run.
+    fn block_on<F: Future>(future: F) -> F::Output;
+}
+
+/// A single-threaded executor, which polls the future on the current thread
+/// and parks the thread while the future is waiting to be woken.
+///
+/// A new executor is used for every call, so nothing is shared between calls,
+/// requests or threads.
+///
+/// # Example
+///
+/// ```
+/// use ext_php_rs::executor::{Executor, LocalExecutor};
+///
+/// assert_eq!(LocalExecutor::block_on(async { 1 + 1 }), 2);
+/// ```
+pub struct LocalExecutor;
+
+impl Executor for LocalExecutor {
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+}
+
+/// Wakes the executor by unparking the thread that is running the future.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs the given future to completion with the executor `E`, converting a
+/// panic inside the future into an exception. Used by the code generated for
+/// asynchronous functions.
+///
+/// # Parameters
+///
+/// * `future` - The future to run.
+#[doc(hidden)]
+pub fn run<E: Executor, F: Future>(future: F) -> PhpResult<F::Output> {
+    panic::catch_unwind(AssertUnwindSafe(|| E::block_on(future))).map_err(|payload| {
+        PhpException::default(format!(
+            "Asynchronous function panicked: {}",
+            panic_message(&*payload)
+        ))
+    })
+}
+
+/// Returns the message of a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
@@ -3,13 +3,14 @@
 use bitflags::bitflags;
 
 use crate::ffi::{
-    CONST_CS, CONST_DEPRECATED, CONST_NO_FILE_CACHE, CONST_PERSISTENT, IS_ARRAY, IS_CALLABLE,
-    IS_CONSTANT_AST, IS_DOUBLE, IS_FALSE, IS_LONG, IS_MIXED, IS_NULL, IS_OBJECT, IS_PTR,
-    IS_REFERENCE, IS_RESOURCE, IS_STRING, IS_TRUE, IS_TYPE_COLLECTABLE, IS_TYPE_REFCOUNTED,
-    IS_UNDEF, IS_VOID, ZEND_ACC_ABSTRACT, ZEND_ACC_ANON_CLASS, ZEND_ACC_CALL_VIA_TRAMPOLINE,
-    ZEND_ACC_CHANGED, ZEND_ACC_CLOSURE, ZEND_ACC_CONSTANTS_UPDATED, ZEND_ACC_CTOR,
-    ZEND_ACC_DEPRECATED, ZEND_ACC_DONE_PASS_TWO, ZEND_ACC_EARLY_BINDING, ZEND_ACC_FAKE_CLOSURE,
-    ZEND_ACC_FINAL, ZEND_ACC_GENERATOR, ZEND_ACC_HAS_FINALLY_BLOCK, ZEND_ACC_HAS_RETURN_TYPE,
+    _IS_BOOL, CONST_CS, CONST_DEPRECATED, CONST_NO_FILE_CACHE, CONST_PERSISTENT, IS_ARRAY,
+    IS_CALLABLE, IS_CONSTANT_AST, IS_DOUBLE, IS_FALSE, IS_LONG, IS_MIXED, IS_NULL, IS_OBJECT,
+    IS_PTR, IS_REFERENCE, IS_RESOURCE, IS_STRING, IS_TRUE, IS_TYPE_COLLECTABLE, IS_TYPE_REFCOUNTED,
+    IS_UNDEF, IS_VOID, MODULE_DEP_CONFLICTS, MODULE_DEP_OPTIONAL, MODULE_DEP_REQUIRED,
+    ZEND_ACC_ABSTRACT, ZEND_ACC_ANON_CLASS, ZEND_ACC_CALL_VIA_TRAMPOLINE, ZEND_ACC_CHANGED,
+    ZEND_ACC_CLOSURE, ZEND_ACC_CONSTANTS_UPDATED, ZEND_ACC_CTOR, ZEND_ACC_DEPRECATED,
+    ZEND_ACC_DONE_PASS_TWO, ZEND_ACC_EARLY_BINDING, ZEND_ACC_FAKE_CLOSURE, ZEND_ACC_FINAL,
+    ZEND_ACC_GENERATOR, ZEND_ACC_HAS_FINALLY_BLOCK, ZEND_ACC_HAS_RETURN_TYPE,
     ZEND_ACC_HAS_TYPE_HINTS, ZEND_ACC_HEAP_RT_CACHE, ZEND_ACC_IMMUTABLE,
     ZEND_ACC_IMPLICIT_ABSTRACT_CLASS, ZEND_ACC_INTERFACE, ZEND_ACC_LINKED, ZEND_ACC_NEARLY_LINKED,
     ZEND_ACC_NEVER_CACHE, ZEND_ACC_NO_DYNAMIC_PROPERTIES, ZEND_ACC_PRELOADED, ZEND_ACC_PRIVATE,
@@ -17,7 +18,8 @@ use crate::ffi::{
     ZEND_ACC_RESOLVED_PARENT, ZEND_ACC_RETURN_REFERENCE, ZEND_ACC_REUSE_GET_ITERATOR,
     ZEND_ACC_STATIC, ZEND_ACC_STRICT_TYPES, ZEND_ACC_TOP_LEVEL, ZEND_ACC_TRAIT,
     ZEND_ACC_TRAIT_CLONE, ZEND_ACC_UNRESOLVED_VARIANCE, ZEND_ACC_USES_THIS, ZEND_ACC_USE_GUARDS,
-    ZEND_ACC_VARIADIC, ZEND_HAS_STATIC_IN_METHODS, Z_TYPE_FLAGS_SHIFT, _IS_BOOL,
+    ZEND_ACC_VARIADIC, ZEND_HAS_STATIC_IN_METHODS, ZEND_INI_ALL, ZEND_INI_PERDIR, ZEND_INI_SYSTEM,
+    ZEND_INI_USER, Z_TYPE_FLAGS_SHIFT,
 };
 
 use std::{convert::TryFrom, fmt::Display};
@@ -130,6 +132,9 @@ bitflags! {
         const Changed = ZEND_ACC_CHANGED;
         const Static = ZEND_ACC_STATIC;
         const Promoted = ZEND_ACC_PROMOTED;
+
+        #[cfg(php81)]
+        const Readonly = crate::ffi::ZEND_ACC_READONLY;
     }
 }
 
@@ -153,6 +158,21 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Represents where an INI entry can be changed.
+    pub struct IniEntryPermission: u32 {
+        /// The entry can be set in user scripts with `ini_set()`.
+        const User = ZEND_INI_USER;
+        /// The entry can be set in `php.ini`, `.htaccess`, `httpd.conf` or
+        /// `.user.ini`.
+        const PerDir = ZEND_INI_PERDIR;
+        /// The entry can be set in `php.ini` or `httpd.conf`.
+        const System = ZEND_INI_SYSTEM;
+        /// The entry can be set anywhere.
+        const All = ZEND_INI_ALL;
+    }
+}
+
 bitflags! {
     /// Represents the result of a function.
     pub struct ZendResult: i32 {
@@ -161,9 +181,35 @@ bitflags! {
     }
 }
 
+/// Represents how an extension depends on another extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModuleDependency {
+    /// The other extension must be loaded, and is started up before this
+    /// extension.
+    Required,
+    /// The extension cannot be loaded if the other extension has already been
+    /// loaded.
+    Conflicts,
+    /// If the other extension is loaded, it is started up before this
+    /// extension.
+    Optional,
+}
+
+impl ModuleDependency {
+    /// Returns the integer representation of the dependency type.
+    pub const fn as_u8(&self) -> u8 {
+        match self {
+            Self::Required => MODULE_DEP_REQUIRED as u8,
+            Self::Conflicts => MODULE_DEP_CONFLICTS as u8,
+            Self::Optional => MODULE_DEP_OPTIONAL as u8,
+        }
+    }
+}
+
 /// Valid data types for PHP.
 #[repr(C, u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum DataType {
     Undef,
     Null,
@@ -182,6 +228,9 @@ pub enum DataType {
     Mixed,
     Bool,
     Ptr,
+    /// The `never` return type, for functions which do not return. Only
+    /// available from PHP 8.1, and declared as `void` on earlier versions.
+    Never,
 }
 
 impl Default for DataType {
@@ -211,6 +260,10 @@ impl DataType {
             DataType::Mixed => IS_MIXED,
             DataType::Bool => _IS_BOOL,
             DataType::Ptr => IS_PTR,
+            #[cfg(php81)]
+            DataType::Never => crate::ffi::IS_NEVER,
+            #[cfg(not(php81))]
+            DataType::Never => IS_VOID,
         }
     }
 }
@@ -315,6 +368,7 @@ impl Display for DataType {
             DataType::Bool => write!(f, "Bool"),
             DataType::Mixed => write!(f, "Mixed"),
             DataType::Ptr => write!(f, "Pointer"),
+            DataType::Never => write!(f, "Never"),
         }
     }
 }
@@ -0,0 +1,176 @@
+//! Types used for returning lazy Rust iterators to PHP, in the same way as a
+//! PHP generator.
+
+use std::collections::HashMap;
+
+use crate::{
+    builders::ClassBuilder,
+    class::{ClassMetadata, RegisteredClass},
+    convert::{IntoZendObject, IntoZval},
+    error::Result,
+    exception::PhpException,
+    flags::{ClassFlags, DataType},
+    iterator::{IterState, PhpIterator},
+    props::Property,
+    types::Zval,
+};
+
+/// Class entry and handlers for Rust generators.
+static GENERATOR_META: ClassMetadata<Generator> = ClassMetadata::new();
+
+/// Iterator backing a [`Generator`].
+type YieldIter = Box<dyn Iterator<Item = Yielded>>;
+
+/// Wrapper around a Rust iterator, which can be returned to PHP and iterated
+/// over lazily with `foreach`.
+///
+/// Internally, generators are implemented as a PHP class. A class
+/// `RustGenerator` is registered, which implements the `Iterator` interface:
+///
+/// ```php
+/// <?php
+///
+/// final class RustGenerator implements Iterator {
+///     public function current(): mixed {}
+///     public function key(): mixed {}
+///     public function next(): void {}
+///     public function rewind(): void {}
+///     public function valid(): bool {}
+/// }
+/// ```
+///
+/// Items are taken from the Rust iterator one at a time, when PHP advances the
+/// generator, so the iterator is never collected into an array. The keys of the
+/// items are their zero-based positions.
+///
+/// Generators behave like PHP generators in most cases, with some limitations:
+///
+/// * A generator is not an instance of PHP's `Generator` class, so it is typed
+///   as `Iterator` in the generated stubs. The `send()`, `throw()` and
+///   `getReturn()` methods are not available.
+/// * A generator can only be iterated over once. Rewinding it after it has
+///   been advanced past the first item throws an exception, in the same way as
+///   a PHP generator.
+/// * The Rust iterator must not advance the generator it is wrapped in, for
+///   example by calling back into PHP code which iterates over the generator.
+///
+/// If an item cannot be converted into a zval, the conversion error is thrown
+/// as an exception and the generator ends.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{generator::Generator, prelude::*};
+///
+/// #[php_function]
+/// pub fn squares(count: i64) -> Generator {
+///     Generator::new((0..count).map(|i| i * i))
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+pub struct Generator(IterState<YieldIter>);
+
+unsafe impl Send for Generator {}
+unsafe impl Sync for Generator {}
+
+impl Generator {
+    /// Wraps a Rust iterator into a type which can be returned to PHP. The
+    /// items of the iterator must implement [`IntoZval`].
+    ///
+    /// # Parameters
+    ///
+    /// * `iter` - The iterator to wrap.
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: 'static,
+        I::Item: IntoZval,
+    {
+        let mut iter = iter.into_iter();
+        // The iterator is fused so the generator stays finished after an item
+        // fails to convert, rather than resuming with the following items.
+        let iter = std::iter::from_fn(move || match iter.next()?.into_zval(false) {
+            Ok(zv) => Some(Yielded(zv)),
+            Err(e) => {
+                let _ = PhpException::from(e).throw();
+                None
+            }
+        })
+        .fuse();
+
+        Self(IterState::new(Box::new(iter) as YieldIter))
+    }
+
+    /// Builds the class entry for [`Generator`], registering it with PHP. This
+    /// function should only be called once inside your module startup
+    /// function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function is called more than once.
+    pub fn build() {
+        if GENERATOR_META.has_ce() {
+            panic!("Generator has already been built.");
+        }
+
+        let ce = ClassBuilder::new("RustGenerator")
+            .flags(ClassFlags::Final)
+            .iterator::<Self>()
+            .object_override::<Self>()
+            .build()
+            .expect("Failed to build `RustGenerator` PHP class.");
+        GENERATOR_META.set_ce(ce);
+    }
+}
+
+impl PhpIterator for Generator {
+    type Iter = YieldIter;
+
+    fn iter_state(&mut self) -> &mut IterState<Self::Iter> {
+        &mut self.0
+    }
+}
+
+impl RegisteredClass for Generator {
+    const CLASS_NAME: &'static str = "RustGenerator";
+
+    fn get_metadata() -> &'static ClassMetadata<Self> {
+        &GENERATOR_META
+    }
+
+    fn get_properties<'a>() -> HashMap<&'static str, Property<'a, Self>> {
+        HashMap::new()
+    }
+}
+
+class_derives!(@object Generator);
+
+impl IntoZval for Generator {
+    const TYPE: DataType = DataType::Object(Some("Iterator"));
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        self.into_zend_object()?.set_zval(zv, persistent)
+    }
+}
+
+/// An item yielded by a [`Generator`], already converted into a zval.
+pub struct Yielded(Zval);
+
+impl Clone for Yielded {
+    fn clone(&self) -> Self {
+        Self(self.0.shallow_clone())
+    }
+}
+
+impl IntoZval for Yielded {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        *zv = self.0;
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+//! Types and functions used for declaring and reading INI entries, the
+//! settings which can be set in `php.ini` or with `ini_set()`.
+//!
+//! Entries are declared on the module with [`ModuleBuilder::ini_entry`]. They
+//! are registered with PHP when the module starts up, before the startup
+//! function of the extension is called, and are unregistered when the module
+//! shuts down, after the shutdown function of the extension is called.
+//!
+//! The current value of an entry can then be read with [`long`], [`bool`] or
+//! [`string`], which are the equivalents of the `INI_INT`, `INI_BOOL` and
+//! `INI_STR` macros in C.
+//!
+//! [`ModuleBuilder::ini_entry`]: crate::builders::ModuleBuilder::ini_entry
+
+use std::{
+    convert::TryInto,
+    ffi::{CStr, CString},
+    os::raw::c_int,
+    ptr,
+};
+
+use parking_lot::{const_mutex, Mutex};
+
+use crate::{
+    error::{Error, Result},
+    ffi::{
+        zend_ini_entry_def, zend_ini_long, zend_ini_string_ex, zend_register_ini_entries,
+        zend_result, zend_unregister_ini_entries,
+    },
+    flags::{IniEntryPermission, ZendResult},
+    zend::ModuleEntry,
+};
+
+/// The INI entries of the extension, along with the startup and shutdown
+/// functions they wrap. Only one module can be built per extension, so only
+/// one set of entries is stored.
+static MODULE_INI: Mutex<Option<ModuleIni>> = const_mutex(None);
+
+/// Startup or shutdown function stored inside a module entry.
+type ModuleFunc = unsafe extern "C" fn(c_int, c_int) -> zend_result;
+
+struct ModuleIni {
+    entries: Vec<IniEntry>,
+    startup: Option<ModuleFunc>,
+    shutdown: Option<ModuleFunc>,
+}
+
+/// An INI entry declared by the extension.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{builders::ModuleBuilder, flags::IniEntryPermission, ini::IniEntry};
+///
+/// let module = ModuleBuilder::new("myext", "0.1.0")
+///     .ini_entry(IniEntry::new("myext.max_items", "100", IniEntryPermission::All))
+///     .ini_entry(IniEntry::new("myext.enabled", "1", IniEntryPermission::System));
+/// ```
+#[derive(Debug, Clone)]
+pub struct IniEntry {
+    name: String,
+    default: String,
+    permission: IniEntryPermission,
+}
+
+impl IniEntry {
+    /// Creates a new INI entry.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the entry, generally prefixed with the name of
+    ///   the extension, e.g. `myext.max_items`.
+    /// * `default` - The default value of the entry, used when the entry is
+    ///   not set in `php.ini`. Boolean entries should use `1` or `0`.
+    /// * `permission` - Where the entry can be changed. See
+    ///   [`IniEntryPermission`].
+    pub fn new<N: Into<String>, D: Into<String>>(
+        name: N,
+        default: D,
+        permission: IniEntryPermission,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            default: default.into(),
+            permission,
+        }
+    }
+
+    /// Returns the name of the entry.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the default value of the entry.
+    pub fn default(&self) -> &str {
+        &self.default
+    }
+
+    /// Returns where the entry can be changed.
+    pub fn permission(&self) -> IniEntryPermission {
+        self.permission
+    }
+}
+
+/// Returns the current value of an INI entry as an integer, or [`None`] if the
+/// entry does not exist. Equivalent to `INI_INT(name)`.
+///
+/// Values are parsed the same way as PHP, so values which are not integers
+/// return zero.
+///
+/// # Parameters
+///
+/// * `name` - The name of the entry.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::ini;
+///
+/// let max_items = ini::long("myext.max_items").unwrap_or(100);
+/// ```
+pub fn long(name: &str) -> Option<i64> {
+    // `zend_ini_long` returns zero for missing entries, so check that the entry
+    // exists first.
+    string(name)?;
+    let cname = CString::new(name).ok()?;
+
+    Some(unsafe { zend_ini_long(cname.as_ptr(), name.len() as _, 0) })
+}
+
+/// Returns the current value of an INI entry as a boolean, or [`None`] if the
+/// entry does not exist. Equivalent to `INI_BOOL(name)`, with the addition that
+/// `on`, `yes` and `true` are treated as `true` as they are by `ini_get()`
+/// for boolean entries.
+///
+/// # Parameters
+///
+/// * `name` - The name of the entry.
+pub fn bool(name: &str) -> Option<bool> {
+    let value = string(name)?;
+
+    Some(match value.to_ascii_lowercase().as_str() {
+        "on" | "yes" | "true" => true,
+        _ => long(name)? != 0,
+    })
+}
+
+/// Returns the current value of an INI entry as a string, or [`None`] if the
+/// entry does not exist or its value is not valid UTF-8. Equivalent to
+/// `INI_STR(name)`.
+///
+/// # Parameters
+///
+/// * `name` - The name of the entry.
+pub fn string(name: &str) -> Option<String> {
+    let cname = CString::new(name).ok()?;
+    let mut exists = false;
+    let value = unsafe { zend_ini_string_ex(cname.as_ptr(), name.len() as _, 0, &mut exists) };
+
+    if !exists || value.is_null() {
+        return None;
+    }
+
+    unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .ok()
+        .map(ToString::to_string)
+}
+
+/// Stores the given INI entries, and replaces the startup and shutdown
+/// functions of the module with functions which register the entries before
+/// calling the original startup function and unregister them after calling the
+/// original shutdown function.
+pub(crate) fn wrap_module(module: &mut ModuleEntry, entries: Vec<IniEntry>) {
+    MODULE_INI.lock().replace(ModuleIni {
+        entries,
+        startup: module.module_startup_func,
+        shutdown: module.module_shutdown_func,
+    });
+    module.module_startup_func = Some(startup);
+    module.module_shutdown_func = Some(shutdown);
+}
+
+/// Registers the given INI entries with PHP.
+fn register_entries(entries: &[IniEntry], module_number: c_int) -> Result<()> {
+    let strings = entries
+        .iter()
+        .map(|entry| {
+            Ok((
+                CString::new(entry.name.as_str())?,
+                CString::new(entry.default.as_str())?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut defs = entries
+        .iter()
+        .zip(strings.iter())
+        .map(|(entry, (name, value))| {
+            Ok(zend_ini_entry_def {
+                name: name.as_ptr(),
+                on_modify: None,
+                mh_arg1: ptr::null_mut(),
+                mh_arg2: ptr::null_mut(),
+                mh_arg3: ptr::null_mut(),
+                value: value.as_ptr(),
+                displayer: None,
+                value_length: entry
+                    .default
+                    .len()
+                    .try_into()
+                    .map_err(|_| Error::IntegerOverflow)?,
+                name_length: entry
+                    .name
+                    .len()
+                    .try_into()
+                    .map_err(|_| Error::IntegerOverflow)?,
+                modifiable: entry.permission.bits() as _,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // PHP stops reading entries when it reaches an entry with a null name.
+    defs.push(unsafe { std::mem::zeroed() });
+
+    if unsafe { zend_register_ini_entries(defs.as_ptr(), module_number) }
+        == ZendResult::Success.bits()
+    {
+        Ok(())
+    } else {
+        Err(Error::IniRegistration)
+    }
+}
+
+unsafe extern "C" fn startup(ty: c_int, module_number: c_int) -> zend_result {
+    let startup = {
+        let ini = MODULE_INI.lock();
+        let ini = match ini.as_ref() {
+            Some(ini) => ini,
+            None => return ZendResult::Failure.bits(),
+        };
+
+        if register_entries(&ini.entries, module_number).is_err() {
+            return ZendResult::Failure.bits();
+        }
+
+        ini.startup
+    };
+
+    match startup {
+        Some(startup) => startup(ty, module_number),
+        None => ZendResult::Success.bits(),
+    }
+}
+
+unsafe extern "C" fn shutdown(ty: c_int, module_number: c_int) -> zend_result {
+    let shutdown = MODULE_INI.lock().as_ref().and_then(|ini| ini.shutdown);
+    let result = match shutdown {
+        Some(shutdown) => shutdown(ty, module_number),
+        None => ZendResult::Success.bits(),
+    };
+
+    zend_unregister_ini_entries(module_number);
+    result
+}
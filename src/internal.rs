@@ -1,11 +1,139 @@
 //! Internal, public functions that are called from downstream extensions.
 
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{
+    convert::FromZval,
+    error::ConversionError,
+    exception::{PanicMode, PhpException},
+    types::{ArrayKey, Zval},
+    zend::ce,
+};
+
 /// Called by startup functions registered with the [`#[php_startup]`] macro.
-/// Initializes all classes that are defined by ext-php-rs (i.e. `Closure`).
+/// Initializes all classes that are defined by ext-php-rs (i.e. `Closure` and
+/// `Generator`).
 ///
 /// [`#[php_startup]`]: crate::php_startup
 #[inline(always)]
 pub fn ext_php_rs_startup() {
     #[cfg(feature = "closure")]
     crate::closure::Closure::build();
+    #[cfg(feature = "generator")]
+    crate::generator::Generator::build();
+}
+
+/// Called by the functions and methods generated by the macros. Calls the
+/// given function, catching any panic when the panic mode (or the module panic
+/// mode if [`None`]) is [`PanicMode::Exception`], and returning an `Error`
+/// exception containing the panic message.
+pub fn catch_panic<R, F: FnOnce() -> R>(mode: Option<PanicMode>, f: F) -> Result<R, PhpException> {
+    if mode.unwrap_or_else(PanicMode::module) == PanicMode::Abort {
+        return Ok(f());
+    }
+
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| PhpException::new(panic_message(&*payload), 0, ce::error()))
+}
+
+/// Called by the code generated by the [`FromZval`](crate::FromZval) derive
+/// macro. Converts the value of the array element with the given key into `T`,
+/// calling `default` if the array did not contain the element.
+///
+/// # Parameters
+///
+/// * `value` - The value of the element, or [`None`] if the array did not
+///   contain the element.
+/// * `key` - The key of the element, used in the returned error.
+/// * `default` - Returns the value used when the array did not contain the
+///   element.
+pub fn array_field<'a, T: FromZval<'a>>(
+    value: Option<&'a Zval>,
+    key: &str,
+    default: Option<fn() -> T>,
+) -> Result<T, ConversionError> {
+    match (value, default) {
+        (Some(zv), _) => T::from_zval(zv)
+            .ok_or_else(|| ConversionError::new(T::TYPE, zv).with_key(owned_key(key))),
+        (None, Some(default)) => Ok(default()),
+        (None, None) => Err(ConversionError::missing_key(T::TYPE, owned_key(key))),
+    }
+}
+
+fn owned_key(key: &str) -> ArrayKey<'static> {
+    ArrayKey::String(key.to_string().into())
+}
+
+/// Returns the message given to `panic!` from the payload of a panic.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Rust panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{array_field, catch_panic, panic_message};
+    use crate::{exception::PanicMode, types::Zval};
+    use std::panic;
+
+    #[test]
+    fn test_panic_message() {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let str_payload = panic::catch_unwind(|| panic!("boom")).expect_err("did not panic");
+        let string_payload =
+            panic::catch_unwind(|| panic!("boom {}", 1)).expect_err("did not panic");
+        let other_payload = panic::catch_unwind(|| panic::panic_any(1)).expect_err("did not panic");
+        panic::set_hook(hook);
+
+        assert_eq!(panic_message(&*str_payload), "boom");
+        assert_eq!(panic_message(&*string_payload), "boom 1");
+        assert_eq!(panic_message(&*other_payload), "Rust panic");
+    }
+
+    #[test]
+    fn test_catch_panic_ok() {
+        assert_eq!(catch_panic(Some(PanicMode::Exception), || 1).ok(), Some(1));
+        assert_eq!(catch_panic(Some(PanicMode::Abort), || 2).ok(), Some(2));
+    }
+
+    #[test]
+    fn test_array_field() {
+        let mut zv = Zval::new();
+        zv.set_long(5);
+        assert_eq!(array_field::<i64>(Some(&zv), "a", None), Ok(5));
+        assert_eq!(array_field::<i64>(Some(&zv), "a", Some(|| 1)), Ok(5));
+        assert_eq!(
+            array_field::<Option<i64>>(Some(&zv), "a", None),
+            Ok(Some(5))
+        );
+
+        let err = array_field::<bool>(Some(&zv), "a", None).expect_err("bool field did not fail");
+        assert!(!err.is_missing());
+        assert_eq!(
+            err.to_string(),
+            "expected bool, got int for array key \"a\""
+        );
+    }
+
+    #[test]
+    fn test_array_field_missing() {
+        let err = array_field::<i64>(None, "count", None).expect_err("missing field did not fail");
+        assert!(err.is_missing());
+        assert_eq!(err.to_string(), "missing array key \"count\", expected int");
+
+        assert_eq!(array_field::<i64>(None, "count", Some(|| 3)), Ok(3));
+        assert_eq!(
+            array_field::<Option<i64>>(None, "count", Some(Default::default)),
+            Ok(None)
+        );
+    }
 }
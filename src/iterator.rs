@@ -0,0 +1,220 @@
+//! Types and traits used for exporting Rust iterators to PHP, allowing Rust
+//! types to be used in `foreach` loops through the `Iterator` interface.
+//!
+//! PHP iterators are driven through the `rewind`, `valid`, `current`, `key`
+//! and `next` methods. PHP always calls `rewind` before the first call to
+//! `valid`, while Rust iterators generally cannot be rewound. The
+//! [`IterState`] type bridges these semantics:
+//!
+//! * [`IterState::new`] creates a *one-shot* iterator. It may be rewound as
+//!   long as it has not been advanced past the first item, after which
+//!   rewinding throws an exception (the same behaviour as PHP generators).
+//! * [`IterState::rewindable`] requires the iterator to implement [`Clone`].
+//!   A pristine copy of the iterator is kept, and rewinding restarts
+//!   iteration from that copy.
+//!
+//! The key of each item is its zero-based position in the iterator.
+
+use crate::{
+    class::RegisteredClass,
+    exception::{PhpException, PhpResult},
+    ffi::zend_long,
+};
+
+/// Implemented on Rust types which are exported to PHP as classes which
+/// implement the `Iterator` interface.
+///
+/// The class must be registered with the `#[iterator]` attribute underneath the
+/// [`macro@php_class`] attribute, which implements the interface and registers
+/// the iterator methods on the class.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use ext_php_rs::iterator::{IterState, PhpIterator};
+///
+/// #[php_class]
+/// #[iterator]
+/// pub struct Range {
+///     state: IterState<std::ops::Range<i64>>,
+/// }
+///
+/// impl PhpIterator for Range {
+///     type Iter = std::ops::Range<i64>;
+///
+///     fn iter_state(&mut self) -> &mut IterState<Self::Iter> {
+///         &mut self.state
+///     }
+/// }
+///
+/// #[php_function]
+/// pub fn range(start: i64, end: i64) -> Range {
+///     Range {
+///         state: IterState::rewindable(start..end),
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`macro@php_class`]: crate::php_class
+pub trait PhpIterator: RegisteredClass {
+    /// The Rust iterator backing the PHP iterator. The items returned by the
+    /// iterator must implement [`IntoZval`] and [`Clone`].
+    ///
+    /// [`IntoZval`]: crate::convert::IntoZval
+    type Iter: Iterator;
+
+    /// Returns a mutable reference to the iterator state stored inside the
+    /// class.
+    fn iter_state(&mut self) -> &mut IterState<Self::Iter>;
+}
+
+/// Pristine copy of a rewindable iterator, along with the function used to
+/// clone it.
+type Source<I> = (I, fn(&I) -> I);
+
+/// Stores the state of a Rust iterator being iterated over by PHP. See the
+/// [module level documentation](self) for the rewind semantics.
+pub struct IterState<I: Iterator> {
+    iter: I,
+    source: Option<Source<I>>,
+    current: Option<I::Item>,
+    key: zend_long,
+    started: bool,
+}
+
+impl<I: Iterator> IterState<I> {
+    /// Creates a new one-shot iterator state. The iterator cannot be rewound
+    /// once it has been advanced past the first item.
+    ///
+    /// # Parameters
+    ///
+    /// * `iter` - The iterator to iterate over.
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter,
+            source: None,
+            current: None,
+            key: 0,
+            started: false,
+        }
+    }
+
+    /// Creates a new rewindable iterator state. A copy of the iterator is kept
+    /// and cloned whenever the iterator is rewound.
+    ///
+    /// # Parameters
+    ///
+    /// * `iter` - The iterator to iterate over.
+    pub fn rewindable(iter: I) -> Self
+    where
+        I: Clone,
+    {
+        Self {
+            iter: iter.clone(),
+            source: Some((iter, I::clone)),
+            current: None,
+            key: 0,
+            started: false,
+        }
+    }
+
+    /// Rewinds the iterator to the first item.
+    ///
+    /// Returns an error if the iterator is one-shot and has already been
+    /// advanced past the first item.
+    pub fn rewind(&mut self) -> PhpResult {
+        if self.started {
+            match &self.source {
+                Some((source, clone)) => self.iter = clone(source),
+                None if self.key == 0 => return Ok(()),
+                None => {
+                    return Err(PhpException::default(
+                        "Cannot rewind an iterator that has already been advanced.".into(),
+                    ))
+                }
+            }
+        }
+
+        self.started = true;
+        self.key = 0;
+        self.current = self.iter.next();
+        Ok(())
+    }
+
+    /// Returns `true` if the iterator is positioned on an item.
+    pub fn valid(&mut self) -> bool {
+        self.start();
+        self.current.is_some()
+    }
+
+    /// Returns a reference to the current item, or [`None`] if the iterator has
+    /// been exhausted.
+    pub fn current(&mut self) -> Option<&I::Item> {
+        self.start();
+        self.current.as_ref()
+    }
+
+    /// Returns the key of the current item, or [`None`] if the iterator has
+    /// been exhausted.
+    pub fn key(&mut self) -> Option<zend_long> {
+        self.start();
+        self.current.as_ref().map(|_| self.key)
+    }
+
+    /// Advances the iterator to the next item.
+    pub fn next(&mut self) {
+        self.start();
+
+        if self.current.is_some() {
+            self.current = self.iter.next();
+            self.key += 1;
+        }
+    }
+
+    /// Fetches the first item if the iterator is yet to be started, in the case
+    /// that the iterator is used without first calling `rewind`.
+    fn start(&mut self) {
+        if !self.started {
+            self.started = true;
+            self.current = self.iter.next();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::IterState;
+
+    #[test]
+    fn test_lazy_iteration() {
+        let pulled = Rc::new(Cell::new(0));
+        let counter = pulled.clone();
+        let mut state =
+            IterState::new((0..1000i64).inspect(move |_| counter.set(counter.get() + 1)));
+
+        assert_eq!(pulled.get(), 0);
+
+        // Iterate in the same way as a PHP `foreach` loop.
+        state.rewind().expect("failed to rewind");
+        let mut expected = 0;
+        while state.valid() {
+            assert_eq!(state.key(), Some(expected));
+            assert_eq!(state.current(), Some(&expected));
+            // Only the current item has been taken from the iterator.
+            assert_eq!(pulled.get(), expected + 1);
+            state.next();
+            expected += 1;
+        }
+
+        assert_eq!(expected, 1000);
+        assert_eq!(state.current(), None);
+    }
+}
@@ -0,0 +1,54 @@
+//! Traits used for exporting Rust types to PHP as classes which implement the
+//! `JsonSerializable` interface, customising the output of `json_encode()`.
+
+use crate::{class::RegisteredClass, exception::PhpResult, types::Zval};
+
+/// Implemented on Rust types which are exported to PHP as classes which
+/// implement the `JsonSerializable` interface.
+///
+/// The class must be registered with the `#[json_serializable]` attribute
+/// underneath the [`macro@php_class`] attribute, which implements the
+/// interface and registers the `jsonSerialize` method. `json_encode($obj)`
+/// encodes the value returned by [`PhpJsonSerialize::json_serialize`] in place
+/// of the object, so the value may contain other serializable objects.
+///
+/// Returning an error throws the exception from `jsonSerialize()`, which is
+/// then rethrown by `json_encode()`.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use ext_php_rs::convert::IntoZval;
+/// use ext_php_rs::json::PhpJsonSerialize;
+/// use ext_php_rs::types::Zval;
+/// use std::collections::HashMap;
+///
+/// #[php_class]
+/// #[json_serializable]
+/// pub struct User {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// impl PhpJsonSerialize for User {
+///     fn json_serialize(&self) -> PhpResult<Zval> {
+///         let mut map = HashMap::new();
+///         map.insert("id", self.id.into_zval(false)?);
+///         map.insert("name", self.name.as_str().into_zval(false)?);
+///         Ok(map.into_zval(false)?)
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`macro@php_class`]: crate::php_class
+pub trait PhpJsonSerialize: RegisteredClass {
+    /// Returns the value to be encoded by `json_encode()` in place of the
+    /// object.
+    fn json_serialize(&self) -> PhpResult<Zval>;
+}
@@ -7,13 +7,18 @@
 
 pub mod alloc;
 pub mod args;
+pub mod array_access;
 pub mod binary;
 pub mod builders;
 pub mod convert;
 pub mod error;
 pub mod exception;
+#[cfg(any(docs, feature = "async"))]
+#[cfg_attr(docs, doc(cfg(feature = "async")))]
+pub mod executor;
 pub mod ffi;
 pub mod flags;
+pub mod ini;
 #[macro_use]
 pub mod macros;
 pub mod boxed;
@@ -22,11 +27,25 @@ pub mod class;
 #[cfg_attr(docs, doc(cfg(feature = "closure")))]
 pub mod closure;
 pub mod constant;
+pub mod countable;
 pub mod describe;
+#[cfg(any(docs, feature = "enum"))]
+#[cfg_attr(docs, doc(cfg(feature = "enum")))]
+pub mod enum_;
+#[cfg(any(docs, feature = "generator"))]
+#[cfg_attr(docs, doc(cfg(feature = "generator")))]
+pub mod generator;
 #[doc(hidden)]
 pub mod internal;
+pub mod iterator;
+pub mod json;
+pub mod magic;
 pub mod props;
 pub mod rc;
+pub mod request;
+pub mod resource;
+pub mod serialize;
+pub mod stringable;
 pub mod types;
 pub mod zend;
 
@@ -38,14 +57,25 @@ pub mod prelude {
     #[cfg_attr(docs, doc(cfg(feature = "closure")))]
     pub use crate::closure::Closure;
     pub use crate::exception::{PhpException, PhpResult};
+    #[cfg(any(docs, feature = "generator"))]
+    #[cfg_attr(docs, doc(cfg(feature = "generator")))]
+    pub use crate::generator::Generator;
     pub use crate::php_class;
     pub use crate::php_const;
+    #[cfg(any(docs, feature = "enum"))]
+    #[cfg_attr(docs, doc(cfg(feature = "enum")))]
+    pub use crate::php_enum;
+    pub use crate::php_exception;
     pub use crate::php_extern;
     pub use crate::php_function;
     pub use crate::php_impl;
     pub use crate::php_module;
+    pub use crate::php_shutdown;
     pub use crate::php_startup;
-    pub use crate::types::ZendCallable;
+    pub use crate::php_trait;
+    pub use crate::types::{Omittable, ZendCallable, ZvalRef};
+    pub use crate::FromZval;
+    pub use crate::IntoZval;
     pub use crate::ZvalConvert;
 }
 
@@ -169,6 +199,29 @@ pub use ext_php_rs_derive::php_extern;
 ///
 /// Generics are *not* supported.
 ///
+/// The `namespace` option places the function inside a PHP namespace, i.e.
+/// `#[php_function(namespace = "MyExt")]` registers the function as
+/// `MyExt\hello`. This overrides the namespace given to
+/// [`macro@php_module`], and an empty namespace places the function in the
+/// global namespace.
+///
+/// The `#[php(deprecated = "...")]` attribute marks the function as deprecated,
+/// emitting a `@deprecated` tag with the message in the generated stubs. The
+/// `emit_deprecated` option, i.e. `#[php(deprecated = "...",
+/// emit_deprecated)]`, also makes PHP raise an `E_DEPRECATED` error when the
+/// function is called.
+///
+/// The `#[php(return_override(...))]` attribute declares a return type which
+/// depends on the value of an argument, emitted into the `.phpstorm.meta.php`
+/// file generated by `cargo php stubs --meta`. See the guide for the supported
+/// overrides.
+///
+/// With the `async` feature enabled, `async fn`s may also be exported. The
+/// future is run to completion on the calling thread when the function is
+/// called from PHP. The `executor` attribute option sets the [`Executor`] used
+/// to run the future. See the [`executor`](crate::executor) module for more
+/// details.
+///
 /// Behind the scenes, an `extern "C"` wrapper function is generated, which is
 /// actually called by PHP. The first example function would be converted into a
 /// function which looks like so:
@@ -271,6 +324,54 @@ pub use ext_php_rs_derive::php_extern;
 /// # }
 /// ```
 ///
+/// Defaults can also be given on each parameter through the `#[default]`
+/// attribute, which is equivalent to the `defaults` option:
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// #[php_function]
+/// pub fn hello(name: String, #[default("David")] description: String, #[default(10)] age: i32) -> String {
+///     format!("Hello, {}! {}. I am {} year(s) old.", name, description, age)
+/// }
+/// # #[php_module]
+/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// ```
+///
+/// The last parameter can be made variadic by passing its name in the
+/// `variadic` attribute option. The parameter must be a [`Vec<T>`], which
+/// contains the remaining arguments given to the function, each converted into
+/// `T`:
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// #[php_function(variadic = "nums")]
+/// pub fn sum(nums: Vec<i64>) -> i64 {
+///     nums.iter().sum()
+/// }
+/// # #[php_module]
+/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// ```
+///
+/// Panics unwinding out of the function abort the PHP process. Passing
+/// `panic = "exception"` catches the panic and throws an `Error` containing the
+/// panic message instead, which can be caught in PHP:
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// #[php_function(panic = "exception")]
+/// pub fn divide(a: i64, b: i64) -> i64 {
+///     a / b
+/// }
+/// # #[php_module]
+/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// ```
+///
 /// [`Result<T, E>`]: std::result::Result
 /// [`FunctionBuilder`]: crate::php::function::FunctionBuilder
 /// [`FromZval`]: crate::php::types::zval::FromZval
@@ -279,6 +380,7 @@ pub use ext_php_rs_derive::php_extern;
 /// [`Binary<T>`]: crate::php::types::binary::Binary
 /// [`ZendCallable`]: crate::php::types::callable::ZendCallable
 /// [`PhpException`]: crate::php::exceptions::PhpException
+/// [`Executor`]: crate::executor::Executor
 pub use ext_php_rs_derive::php_function;
 
 /// Annotates a structs `impl` block, declaring that all methods and constants
@@ -306,9 +408,23 @@ pub use ext_php_rs_derive::php_function;
 /// - `#[defaults(key = value, ...)]` for setting defaults of method variables,
 ///   similar to the
 /// function macro. Arguments with defaults need to be optional.
+/// - `#[default(value)]` on a parameter for setting the default of that
+///   parameter.
+/// - `#[php(name = "...")]` for setting the name of the method in PHP, the same
+///   as `#[rename]`.
+/// - `#[php(deprecated = "...")]` for marking the method as deprecated in the
+///   generated stubs. The `emit_deprecated` option also raises an
+///   `E_DEPRECATED` error when the method is called.
+/// - `#[php(return_override(...))]` for declaring a return type which depends
+///   on an argument in the `.phpstorm.meta.php` file, the same as the function
+///   macro.
+/// - `#[php(magic = "invoke")]` for registering the method as the `__invoke`
+///   magic method, making the objects of the class callable.
 /// - `#[optional(key)]` for setting `key` as an optional argument (and
 ///   therefore the rest of the
 /// arguments).
+/// - `#[variadic(key)]` for setting `key` as a variadic argument, similar to the
+///   function macro.
 /// - `#[public]`, `#[protected]` and `#[private]` for setting the visibility of
 ///   the method,
 /// defaulting to public. The Rust visibility has no effect on the PHP
@@ -318,6 +434,12 @@ pub use ext_php_rs_derive::php_function;
 /// consume `self`. They can also take no reference to `self` which indicates a
 /// static method.
 ///
+/// Methods declared without a body, e.g. `fn area(&self) -> f64;`, are
+/// registered as abstract methods, which must be implemented by the PHP
+/// classes extending the class. Abstract methods can only be declared on
+/// classes declared with `#[php_class(abstract)]`, and are removed from the
+/// Rust `impl` block.
+///
 /// ## Constructors
 ///
 /// You may add *one* constructor to the impl block. This method must be called
@@ -388,6 +510,22 @@ pub use ext_php_rs_derive::php_impl;
 ///
 /// Note that if the function is not called `get_module`, it will be renamed.
 ///
+/// Functions are registered under their Rust name, unless given a name through
+/// the `#[php(name = "...")]` attribute. The `rename_all` option renames the
+/// remaining functions, taking one of `"none"`, `"camelCase"` or
+/// `"snake_case"`, i.e. `#[php_module(rename_all = "camelCase")]`.
+///
+/// The `namespace` option places all functions inside a PHP namespace, i.e.
+/// `#[php_module(namespace = "MyExt")]` registers a function `hello` as
+/// `MyExt\hello`. Functions given a namespace through the `namespace` option
+/// of [`macro@php_function`], or with a namespace in their name, are not
+/// affected. Classes are not affected either, and are placed in a namespace
+/// through their name, i.e. `#[php_class(name = "MyExt\\Greeter")]`.
+///
+/// The `panic` option sets the panic mode of functions and methods which do not
+/// set their own, i.e. `#[php_module(panic = "exception")]` throws panics as
+/// PHP `Error`s instead of aborting the process.
+///
 /// If you have defined classes using the [`macro@php_class`] macro and you have
 /// not defined a startup function, it will be automatically declared and
 /// registered.
@@ -423,6 +561,19 @@ pub use ext_php_rs_derive::php_module;
 /// * `name` - The name of the exported class, if it is different from the Rust
 ///   struct name. This can be useful for namespaced classes, as you cannot
 ///   place backslashes in Rust struct names.
+/// * `extends` - The name of the parent class, e.g. `extends = "Base"`. The
+///   parent must be a built-in class, or a class registered by ext-php-rs
+///   (classes of the extension are registered after their parent).
+/// * `implements` - PHP interfaces implemented by the class, given by name,
+///   e.g. `implements = "JsonSerializable"` or `implements("Countable",
+///   "JsonSerializable")`. The methods required by built-in interfaces must be
+///   declared on the class, otherwise a compile error is raised.
+/// * `abstract` - Declares the class as abstract, so it cannot be instantiated
+///   from PHP. Abstract methods can then be declared in the
+///   [`macro@php_impl`] block.
+/// * `uninstantiable` - Registers the class with a private constructor, so it
+///   cannot be instantiated from PHP, for classes which only hold static
+///   methods and constants. A constructor cannot be declared on the class.
 ///
 /// Any struct that uses this attribute can also provide an optional set of
 /// extra attributes, used to modify the class. These attributes must be used
@@ -433,6 +584,45 @@ pub use ext_php_rs_derive::php_module;
 ///   used once, and `ce` may be any valid expression.
 /// * `#[implements(ce)]` - Implements an interface on the new class. Can be
 ///   used multiple times, and `ce` may be any valid expression.
+/// * `#[php(deprecated = "...")]` - Marks the class as deprecated in the
+///   generated stubs.
+/// * `#[iterator]` - Implements the `Iterator` interface on the new class. The
+///   struct must implement [`PhpIterator`], which provides the Rust iterator
+///   backing the PHP iterator.
+/// * `#[array_access]` - Implements the `ArrayAccess` interface on the new
+///   class. The struct must implement [`PhpArrayAccess`].
+/// * `#[countable]` - Implements the `Countable` interface on the new class.
+///   The struct must implement [`PhpCountable`].
+/// * `#[stringable]` - Implements the `Stringable` interface on the new class,
+///   allowing it to be converted into a string. The struct must implement
+///   [`PhpToString`].
+/// * `#[json_serializable]` - Implements the `JsonSerializable` interface on
+///   the new class, customising the output of `json_encode()`. The struct must
+///   implement [`PhpJsonSerialize`].
+/// * `#[serializable]` - Registers the `__serialize` and `__unserialize` magic
+///   methods on the new class, allowing its objects to be serialized. The
+///   struct must implement [`PhpSerialize`].
+/// * `#[debug_info]` - Registers the `__debugInfo` magic method on the new
+///   class, describing the Rust value of its objects in the output of
+///   `var_dump()`. The struct must implement [`PhpDebugInfo`].
+/// * `#[magic_properties]` - Registers the `__get`, `__set`, `__isset` and
+///   `__unset` magic methods on the new class, routing access to undefined
+///   properties to Rust. The struct must implement [`PhpMagicProperties`].
+/// * `#[cloneable]` - Allows objects of the new class to be cloned from PHP
+///   with `clone`, which clones the Rust value of the object. The struct must
+///   implement [`Clone`]. Structs which derive [`Clone`] are cloneable without
+///   the attribute, while cloning other objects throws an `Error`.
+/// * `#[comparable]` - Compares objects of the new class with `==`, `<`, `>`
+///   and `<=>`, and when sorting, using the [`PartialOrd`] implementation of
+///   the struct. Structs which derive [`PartialOrd`] are comparable without
+///   the attribute.
+/// * `#[static_prop(name: Type = default)]` - Registers a public static
+///   property on the new class with the given type and default value. Can be
+///   used multiple times. The value is stored by PHP, and is accessible from
+///   Rust through [`ClassEntry::static_property`].
+/// * `#[uses(Trait)]` - Uses a trait declared with [`macro@php_trait`] on the
+///   new class. Can be used multiple times. The struct must implement the Rust
+///   trait, and the methods of the trait are registered on the class.
 ///
 /// This attribute (and its associated structs) must be defined *above* the
 /// startup function (which is annotated by the [`macro@php_startup`] macro, or
@@ -482,21 +672,231 @@ pub use ext_php_rs_derive::php_module;
 ///     module
 /// }
 /// ```
+///
+/// Count the number of instances created in a static property, accessible from
+/// PHP as `Counter::$instances`:
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use ext_php_rs::class::RegisteredClass;
+///
+/// #[php_class]
+/// #[static_prop(instances: i64 = 0)]
+/// pub struct Counter;
+///
+/// #[php_impl]
+/// impl Counter {
+///     pub fn __construct() -> Self {
+///         let ce = Self::get_metadata().ce();
+///         let instances = ce.static_property("instances").and_then(|zv| zv.long());
+///         ce.set_static_property("instances", instances.unwrap_or(0) + 1)
+///             .expect("failed to update instance count");
+///         Self
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`PhpIterator`]: crate::iterator::PhpIterator
+/// [`PhpArrayAccess`]: crate::array_access::PhpArrayAccess
+/// [`PhpCountable`]: crate::countable::PhpCountable
+/// [`PhpToString`]: crate::stringable::PhpToString
+/// [`PhpJsonSerialize`]: crate::json::PhpJsonSerialize
+/// [`PhpSerialize`]: crate::serialize::PhpSerialize
+/// [`PhpMagicProperties`]: crate::magic::PhpMagicProperties
+/// [`PhpDebugInfo`]: crate::magic::PhpDebugInfo
+/// [`ClassEntry::static_property`]: crate::zend::ClassEntry::static_property
 pub use ext_php_rs_derive::php_class;
 
-/// Annotates a function that will be called by PHP when the module starts up.
-/// Generally used to register classes and constants.
+/// Annotates a Rust enum that will be exported to PHP as an enum. Requires PHP
+/// 8.1 and the `enum` feature.
+///
+/// Each variant of the enum is registered as a case of the PHP enum, so the
+/// variants must not have fields. The kind of PHP enum depends on the variants:
+///
+/// * Variants without discriminants or values are registered as the cases of a
+///   pure enum.
+/// * Variants with integer literal discriminants are registered as the cases
+///   of an int-backed enum. Variants without a discriminant follow the previous
+///   variant, in the same way as in Rust.
+/// * Variants with a string value given through `#[php(value = "...")]` are
+///   registered as the cases of a string-backed enum. All variants must have a
+///   value.
+///
+/// The name of a case can be changed with `#[php(name = "...")]`.
+///
+/// Implementations of `FromZval` and `IntoZval` are generated for the enum, so
+/// it can be used as a parameter or return type of exported functions and
+/// methods. Variants are converted to and from the case objects of the PHP
+/// enum.
+///
+/// This attribute takes a set of optional arguments:
+///
+/// * `name` - The name of the exported enum, if it is different from the Rust
+///   type name. This can be useful for namespaced enums, as you cannot place
+///   backslashes in Rust type names.
+///
+/// Like the [`macro@php_class`] attribute, this attribute must be used *above*
+/// the startup function.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ext_php_rs::prelude::*;
+/// #[php_enum(name = "MyExt\\Suit")]
+/// pub enum Suit {
+///     #[php(value = "H")]
+///     Hearts,
+///     #[php(value = "S")]
+///     Spades,
+/// }
+///
+/// #[php_function]
+/// pub fn is_red(suit: Suit) -> bool {
+///     matches!(suit, Suit::Hearts)
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+#[cfg(any(docs, feature = "enum"))]
+#[cfg_attr(docs, doc(cfg(feature = "enum")))]
+pub use ext_php_rs_derive::php_enum;
+
+/// Annotates a struct or enum that will be exported to PHP as an exception
+/// class.
 ///
-/// As well as annotating the function, any classes and constants that had been
-/// declared using the [`macro@php_class`], [`macro@php_const`] and
-/// [`macro@php_impl`] attributes will be registered inside this function.
+/// The type must implement [`Display`], which is used as the message of the
+/// exception when it is thrown. An implementation of
+/// `From<T> for PhpException` is generated, so the type can be returned as the
+/// error variant of a [`PhpResult`] from functions and methods, or thrown
+/// directly with [`RegisteredException::throw`].
 ///
-/// This function *must* be declared before the [`macro@php_module`] function,
-/// as this function needs to be declared when building the module.
+/// This attribute takes a set of optional arguments:
 ///
-/// This function will automatically be generated if not already declared with
-/// this macro if you have registered any classes or constants when using the
-/// [`macro@php_module`] macro.
+/// * `name` - The name of the exported class, if it is different from the Rust
+///   type name. This can be useful for namespaced classes, as you cannot place
+///   backslashes in Rust type names.
+/// * `extends` - The name of the PHP class the exception extends. Defaults to
+///   `Exception`. The class is looked up when the extension starts up, so it
+///   can be any built-in exception class or an exception declared above this
+///   one.
+/// * `code` - The default integer code of the exception. Defaults to zero.
+///
+/// Like the [`macro@php_class`] attribute, this attribute must be used *above*
+/// the startup function.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use std::fmt;
+///
+/// #[php_exception(name = "MyExt\\NotFoundException", extends = "RuntimeException", code = 404)]
+/// #[derive(Debug)]
+/// pub struct NotFound(String);
+///
+/// impl fmt::Display for NotFound {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "Could not find `{}`", self.0)
+///     }
+/// }
+///
+/// #[php_function]
+/// pub fn find(key: String) -> PhpResult<String> {
+///     Err(NotFound(key).into())
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`Display`]: std::fmt::Display
+/// [`PhpResult`]: crate::exception::PhpResult
+/// [`RegisteredException::throw`]: crate::exception::RegisteredException::throw
+pub use ext_php_rs_derive::php_exception;
+
+/// Annotates a Rust trait that will be exported to PHP as a trait, sharing a
+/// set of methods between classes.
+///
+/// The methods of the trait are exported in the same way as the methods of a
+/// [`macro@php_impl`] block, and may have a default implementation. They
+/// cannot be static, constructors, getters or setters, and cannot refer to
+/// `Self` in their parameter or return types. The trait is registered with PHP
+/// with its methods declared as abstract, and classes declared with
+/// [`macro@php_class`] use the trait through the `#[uses(Trait)]` attribute,
+/// which registers the methods on the class. The struct must then implement
+/// the Rust trait.
+///
+/// This attribute takes a set of optional arguments:
+///
+/// * `name` - The name of the exported trait, if it is different from the Rust
+///   trait name.
+/// * `rename_methods` - The rule used to rename the methods of the trait, in
+///   the same way as the [`macro@php_impl`] attribute.
+///
+/// Like the [`macro@php_class`] attribute, this attribute must be used *above*
+/// the startup function.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// #[php_trait]
+/// pub trait Greeter {
+///     fn name(&self) -> String;
+///
+///     fn greet(&self) -> String {
+///         format!("Hello, {}!", self.name())
+///     }
+/// }
+///
+/// #[php_class]
+/// #[uses(Greeter)]
+/// #[derive(Default)]
+/// pub struct Person {
+///     name: String,
+/// }
+///
+/// impl Greeter for Person {
+///     fn name(&self) -> String {
+///         self.name.clone()
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+pub use ext_php_rs_derive::php_trait;
+
+/// Annotates a function that will be called by PHP when the module starts up
+/// (`MINIT`).
+///
+/// Any classes and constants that had been declared using the
+/// [`macro@php_class`], [`macro@php_const`] and [`macro@php_impl`] attributes
+/// are registered before the function is called.
+///
+/// The macro can be used on more than one function. The functions are called in
+/// ascending order of the optional `priority` argument (which defaults to `0`),
+/// and functions with the same priority are called in the order they were
+/// declared.
+///
+/// These functions *must* be declared before the [`macro@php_module`] function,
+/// as they need to be declared when building the module.
+///
+/// Module startup happens once per process, before any request is handled, so
+/// request-specific state such as superglobals, the output buffer or the
+/// currently executing script is not available.
 ///
 /// # Example
 ///
@@ -506,6 +906,11 @@ pub use ext_php_rs_derive::php_class;
 /// pub fn startup_function() {
 ///     // do whatever you need to do...
 /// }
+///
+/// #[php_startup(priority = -10)]
+/// pub fn early_startup_function() {
+///     // called before `startup_function`
+/// }
 /// # #[php_module]
 /// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
 /// #     module
@@ -513,6 +918,41 @@ pub use ext_php_rs_derive::php_class;
 /// ```
 pub use ext_php_rs_derive::php_startup;
 
+/// Annotates a function that will be called by PHP when the module shuts down
+/// (`MSHUTDOWN`).
+///
+/// The macro can be used on more than one function. The functions are called in
+/// descending order of the optional `priority` argument (which defaults to
+/// `0`), the reverse of the order of [`macro@php_startup`] functions, and
+/// functions with the same priority are called in the reverse order they were
+/// declared.
+///
+/// These functions *must* be declared before the [`macro@php_module`] function.
+///
+/// Module shutdown happens once per process, after the last request has been
+/// handled. Classes and INI entries of the extension are still registered when
+/// the function is called, but no request is active.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// #[php_startup(priority = -10)]
+/// pub fn open_pool() {
+///     // called first on startup
+/// }
+///
+/// #[php_shutdown(priority = -10)]
+/// pub fn close_pool() {
+///     // called last on shutdown
+/// }
+/// # #[php_module]
+/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// ```
+pub use ext_php_rs_derive::php_shutdown;
+
 /// Derives the traits required to convert a struct or enum to and from a
 /// [`Zval`]. Both [`FromZval`] and [`IntoZval`] are implemented on types which
 /// use this macro.
@@ -641,6 +1081,34 @@ pub use ext_php_rs_derive::php_startup;
 /// var_dump(give_union()); // int(5)
 /// ```
 ///
+/// ## Tagged enums
+///
+/// Enums with variants carrying any kind of data can instead be represented as
+/// tagged arrays by adding the `#[zval_convert(tagged)]` attribute. The name of
+/// the variant is stored under the `type` key, and the data of the variant
+/// under the `data` key. The keys can be changed through the `tag` and
+/// `content` options, i.e. `#[zval_convert(tag = "kind", content = "value")]`.
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// #[derive(Debug, ZvalConvert)]
+/// #[zval_convert(tagged)]
+/// pub enum Shape {
+///     Empty,
+///     Circle(f64),
+///     Rect(f64, f64),
+///     Polygon { sides: i64, length: f64 },
+/// }
+///
+/// #[php_function]
+/// pub fn scale(shape: Shape) -> Shape {
+///     match shape {
+///         Shape::Circle(r) => Shape::Circle(r * 2.0),
+///         shape => shape,
+///     }
+/// }
+/// ```
+///
 /// [`FromZval`]: crate::php::types::zval::FromZval
 /// [`IntoZval`]: crate::php::types::zval::IntoZval
 /// [`FromZendObject`]: crate::php::types::object::FromZendObject
@@ -648,3 +1116,153 @@ pub use ext_php_rs_derive::php_startup;
 /// [`Zval`]: crate::php::types::zval::Zval
 /// [`Zval::string`]: crate::php::types::zval::Zval::string
 pub use ext_php_rs_derive::ZvalConvert;
+
+/// Derives the [`FromZval`] trait on a struct with named fields, reading the
+/// struct from a PHP associative array. Each field is read from the element
+/// with the same key, converting the element into the type of the field
+/// through [`FromZval`].
+///
+/// The key of a field can be changed with the `#[php(rename = "key")]`
+/// attribute. When the array does not contain a key, the conversion fails,
+/// unless the field has the `#[php(default)]` attribute, in which case the
+/// field is set to its [`Default`] value. Missing [`Option`] fields are set to
+/// [`None`].
+///
+/// `TryFrom<&Zval>` and `TryFrom<&ZendHashTable>` are also implemented on the
+/// struct, returning a [`ConversionError`] which names the missing or invalid
+/// key when the array could not be converted.
+///
+/// Structs deriving the macro can be nested, in which case the nested struct
+/// is read from an array inside the array. Generics are supported, however, a
+/// [`FromZval`] bound will be added to all type parameters.
+///
+/// With the `#[php(object)]` attribute on the struct, the struct is read from
+/// the properties of an object instead, such as an object created by the
+/// [`IntoZval`](macro@IntoZval) derive macro with the same attribute.
+///
+/// # Examples
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// #[derive(Debug, FromZval)]
+/// pub struct Retry {
+///     attempts: u32,
+///     #[php(rename = "delay-ms", default)]
+///     delay_ms: u64,
+/// }
+///
+/// #[derive(Debug, FromZval)]
+/// pub struct Options {
+///     url: String,
+///     timeout: Option<f64>,
+///     retry: Retry,
+/// }
+///
+/// #[php_function]
+/// pub fn fetch(options: Options) {
+///     dbg!(options);
+/// }
+/// ```
+///
+/// Can be used in PHP:
+///
+/// ```php
+/// fetch([
+///     'url' => 'https://example.com',
+///     'retry' => ['attempts' => 3],
+/// ]);
+/// ```
+///
+/// The error can be returned to PHP as a `TypeError` by taking the array as a
+/// [`Zval`] and converting it in the function:
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// # #[derive(Debug, FromZval)]
+/// # pub struct Options {
+/// #     url: String,
+/// # }
+/// use std::convert::TryInto;
+/// use ext_php_rs::types::Zval;
+///
+/// #[php_function]
+/// pub fn fetch(options: &Zval) -> PhpResult<()> {
+///     // Throws `TypeError: missing array key "url", expected string`.
+///     let options: Options = options.try_into()?;
+///     dbg!(options);
+///     Ok(())
+/// }
+/// ```
+///
+/// [`FromZval`]: crate::convert::FromZval
+/// [`ConversionError`]: crate::error::ConversionError
+/// [`ZendHashTable`]: crate::types::ZendHashTable
+/// [`Zval`]: crate::types::Zval
+pub use ext_php_rs_derive::FromZval;
+
+/// Derives the [`IntoZval`] trait on a struct with named fields, converting the
+/// struct into a PHP associative array. Each field is converted through
+/// [`IntoZval`] and stored under the name of the field, which can be changed
+/// with the `#[php(rename = "key")]` attribute.
+///
+/// With the `#[php(object)]` attribute on the struct, the struct is converted
+/// into a `stdClass` object instead, with each field stored as a property.
+///
+/// [`Option`] fields which are [`None`] are stored as `null`. The
+/// `#[php(skip_none)]` attribute leaves out the key instead, and can be given
+/// either on an [`Option`] field or on the struct to apply to all of its
+/// [`Option`] fields.
+///
+/// The attributes are shared with the [`FromZval`](macro@FromZval) derive
+/// macro, so a struct deriving both macros is read from the same structure it
+/// is converted into. Structs deriving the macro can be nested, and generics
+/// are supported, however, an [`IntoZval`] bound will be added to all type
+/// parameters.
+///
+/// # Examples
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// #[derive(Debug, FromZval, IntoZval)]
+/// pub struct Retry {
+///     attempts: u32,
+///     #[php(rename = "delay-ms")]
+///     delay_ms: u64,
+/// }
+///
+/// #[derive(Debug, FromZval, IntoZval)]
+/// #[php(object, skip_none)]
+/// pub struct Options {
+///     url: String,
+///     timeout: Option<f64>,
+///     retry: Retry,
+/// }
+///
+/// #[php_function]
+/// pub fn default_options() -> Options {
+///     Options {
+///         url: "https://example.com".into(),
+///         timeout: None,
+///         retry: Retry {
+///             attempts: 3,
+///             delay_ms: 500,
+///         },
+///     }
+/// }
+/// ```
+///
+/// Can be used in PHP:
+///
+/// ```php
+/// var_dump(default_options());
+/// // object(stdClass)#1 (2) {
+/// //   ["url"]=> string(19) "https://example.com"
+/// //   ["retry"]=> array(2) {
+/// //     ["attempts"]=> int(3)
+/// //     ["delay-ms"]=> int(500)
+/// //   }
+/// // }
+/// ```
+///
+/// [`IntoZval`]: crate::convert::IntoZval
+pub use ext_php_rs_derive::IntoZval;
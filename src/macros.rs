@@ -242,7 +242,9 @@ macro_rules! throw {
 /// [`php_class`]: crate::php_class
 #[macro_export]
 macro_rules! class_derives {
-    ($type: ty) => {
+    // Derives everything except `IntoZval`, for types which are converted into
+    // zvals differently to other classes.
+    (@object $type: ty) => {
         impl<'a> $crate::convert::FromZendObject<'a> for &'a $type {
             #[inline]
             fn from_zend_object(obj: &'a $crate::types::ZendObject) -> $crate::error::Result<Self> {
@@ -296,6 +298,10 @@ macro_rules! class_derives {
                 Ok($crate::types::ZendClassObject::new(self).into())
             }
         }
+    };
+
+    ($type: ty) => {
+        $crate::class_derives!(@object $type);
 
         impl $crate::convert::IntoZval for $type {
             const TYPE: $crate::flags::DataType = $crate::flags::DataType::Object(Some(
@@ -316,6 +322,52 @@ macro_rules! class_derives {
     };
 }
 
+/// Derives the conversion traits for a Rust enum which has been exported to
+/// PHP as an enum, through the [`RegisteredEnum`] trait. Generally this macro
+/// should not be called directly, as it is called on any type that uses the
+/// [`php_enum`] macro.
+///
+/// The following traits are implemented:
+///
+/// * `FromZval for T`
+/// * `IntoZval for T`
+///
+/// Variants are converted into the corresponding case objects of the PHP enum,
+/// and case objects are converted back into variants.
+///
+/// [`RegisteredEnum`]: crate::enum_::RegisteredEnum
+/// [`php_enum`]: crate::php_enum
+#[macro_export]
+macro_rules! enum_derives {
+    ($type: ty) => {
+        impl<'a> $crate::convert::FromZval<'a> for $type {
+            const TYPE: $crate::flags::DataType = $crate::flags::DataType::Object(Some(
+                <$type as $crate::enum_::RegisteredEnum>::ENUM_NAME,
+            ));
+
+            #[inline]
+            fn from_zval(zval: &'a $crate::types::Zval) -> ::std::option::Option<Self> {
+                <$type as $crate::enum_::RegisteredEnum>::from_case_object(zval.object()?)
+            }
+        }
+
+        impl $crate::convert::IntoZval for $type {
+            const TYPE: $crate::flags::DataType = $crate::flags::DataType::Object(Some(
+                <$type as $crate::enum_::RegisteredEnum>::ENUM_NAME,
+            ));
+
+            #[inline]
+            fn set_zval(
+                self,
+                zv: &mut $crate::types::Zval,
+                _: bool,
+            ) -> $crate::error::Result<()> {
+                $crate::enum_::set_case_zval(&self, zv)
+            }
+        }
+    };
+}
+
 /// Derives `From<T> for Zval` and `IntoZval` for a given type.
 macro_rules! into_zval {
     ($type: ty, $fn: ident, $dt: ident) => {
@@ -338,7 +390,8 @@ macro_rules! into_zval {
     };
 }
 
-/// Derives `TryFrom<Zval> for T` and `FromZval for T` on a given type.
+/// Derives `TryFrom<Zval> for T`, `TryFrom<&Zval> for T` and `FromZval for T`
+/// on a given type.
 macro_rules! try_from_zval {
     ($type: ty, $fn: ident, $dt: ident) => {
         impl $crate::convert::FromZval<'_> for $type {
@@ -359,8 +412,29 @@ macro_rules! try_from_zval {
                     .ok_or($crate::error::Error::ZvalConversion(value.get_type()))
             }
         }
+
+        $crate::macros::try_from_zval_ref!($type);
+    };
+}
+
+/// Derives `TryFrom<&Zval> for T` on a type which implements `FromZval`, using
+/// [`Zval::try_into_type`] to perform the conversion.
+///
+/// [`Zval::try_into_type`]: crate::types::Zval::try_into_type
+macro_rules! try_from_zval_ref {
+    ($type: ty) => {
+        impl ::std::convert::TryFrom<&$crate::types::Zval> for $type {
+            type Error = $crate::error::ConversionError;
+
+            fn try_from(
+                value: &$crate::types::Zval,
+            ) -> ::std::result::Result<Self, $crate::error::ConversionError> {
+                value.try_into_type()
+            }
+        }
     };
 }
 
 pub(crate) use into_zval;
 pub(crate) use try_from_zval;
+pub(crate) use try_from_zval_ref;
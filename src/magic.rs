@@ -0,0 +1,165 @@
+//! Traits used for implementing PHP magic methods on Rust types exported to
+//! PHP as classes.
+
+use crate::{
+    boxed::ZBox,
+    class::RegisteredClass,
+    convert::IntoZval,
+    exception::PhpResult,
+    types::{ZendHashTable, Zval},
+};
+
+/// Implemented on Rust types which are exported to PHP as classes with dynamic
+/// properties, computed on demand by Rust.
+///
+/// The class must be registered with the `#[magic_properties]` attribute
+/// underneath the [`macro@php_class`] attribute, which registers the `__get`,
+/// `__set`, `__isset` and `__unset` magic methods on the class.
+///
+/// # Invocation order
+///
+/// When a property is accessed on an object, PHP looks for the property in the
+/// following order, only calling the methods of this trait when the property
+/// could not be found otherwise:
+///
+/// 1. Properties exported from Rust with the `#[prop]` attribute, or getter
+///    and setter methods.
+/// 2. Properties declared on the class, and dynamic properties which have
+///    previously been set on the object.
+/// 3. The magic methods, calling [`get_property`] when reading,
+///    [`set_property`] when writing, [`isset_property`] when using `isset()`
+///    or `empty()`, and [`unset_property`] when using `unset()`.
+///
+/// PHP guards against recursion, so accessing the same property from inside a
+/// magic method does not call the magic method again.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use std::collections::HashMap;
+/// use ext_php_rs::{magic::PhpMagicProperties, types::Zval};
+///
+/// #[php_class]
+/// #[magic_properties]
+/// #[derive(Default)]
+/// pub struct Record {
+///     fields: HashMap<String, i64>,
+/// }
+///
+/// impl PhpMagicProperties for Record {
+///     type Value = Option<i64>;
+///
+///     fn get_property(&mut self, name: &str) -> PhpResult<Option<i64>> {
+///         Ok(self.fields.get(name).copied())
+///     }
+///
+///     fn set_property(&mut self, name: &str, value: &Zval) -> PhpResult {
+///         let value = value.long().ok_or("Fields must be integers.")?;
+///         self.fields.insert(name.into(), value);
+///         Ok(())
+///     }
+///
+///     fn isset_property(&mut self, name: &str) -> PhpResult<bool> {
+///         Ok(self.fields.contains_key(name))
+///     }
+///
+///     fn unset_property(&mut self, name: &str) -> PhpResult {
+///         self.fields.remove(name);
+///         Ok(())
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`macro@php_class`]: crate::php_class
+/// [`get_property`]: PhpMagicProperties::get_property
+/// [`set_property`]: PhpMagicProperties::set_property
+/// [`isset_property`]: PhpMagicProperties::isset_property
+/// [`unset_property`]: PhpMagicProperties::unset_property
+pub trait PhpMagicProperties: RegisteredClass {
+    /// The type returned when reading a property.
+    type Value: IntoZval;
+
+    /// Returns the value of the property. Called by `__get`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    fn get_property(&mut self, name: &str) -> PhpResult<Self::Value>;
+
+    /// Sets the value of the property. Called by `__set`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    /// * `value` - The new value of the property.
+    fn set_property(&mut self, name: &str, value: &Zval) -> PhpResult;
+
+    /// Returns whether the property is set. Called by `__isset`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    fn isset_property(&mut self, name: &str) -> PhpResult<bool>;
+
+    /// Removes the property. Called by `__unset`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the property.
+    fn unset_property(&mut self, name: &str) -> PhpResult;
+}
+
+/// Implemented on Rust types which are exported to PHP as classes which
+/// describe their state when they are dumped with `var_dump()`.
+///
+/// The Rust value of an object is not stored in the properties of the object,
+/// so it is not shown by `var_dump()` or `print_r()`. The class must be
+/// registered with the `#[debug_info]` attribute underneath the
+/// [`macro@php_class`] attribute, which registers the `__debugInfo` magic
+/// method and overrides the `get_debug_info` object handler, so the array
+/// returned by [`PhpDebugInfo::debug_info`] is dumped in place of the
+/// properties of the object.
+///
+/// Returning an error throws the exception, and the object is dumped without
+/// any properties.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use ext_php_rs::{boxed::ZBox, magic::PhpDebugInfo, types::ZendHashTable};
+///
+/// #[php_class]
+/// #[debug_info]
+/// pub struct Connection {
+///     host: String,
+///     port: i64,
+/// }
+///
+/// impl PhpDebugInfo for Connection {
+///     fn debug_info(&self) -> PhpResult<ZBox<ZendHashTable>> {
+///         let mut info = ZendHashTable::new();
+///         info.insert("host", self.host.as_str())?;
+///         info.insert("port", self.port)?;
+///         Ok(info)
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`macro@php_class`]: crate::php_class
+pub trait PhpDebugInfo: RegisteredClass {
+    /// Returns the state of the object as an associative array, which is
+    /// dumped by `var_dump()` and `print_r()`. Called by `__debugInfo`.
+    fn debug_info(&self) -> PhpResult<ZBox<ZendHashTable>>;
+}
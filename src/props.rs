@@ -22,8 +22,10 @@
 use crate::{
     convert::{FromZval, IntoZval},
     error::{Error, Result},
-    exception::PhpResult,
+    exception::{PhpException, PhpResult},
+    flags::DataType,
     types::Zval,
+    zend::ce,
 };
 
 /// Implemented on types which can be used as PHP properties.
@@ -45,6 +47,12 @@ pub trait Prop<'a> {
     ///
     /// * `zv` - The zval containing the new value of `self`.
     fn set(&mut self, zv: &'a Zval) -> Result<()>;
+
+    /// Returns the PHP type of the property, used in the error thrown when a
+    /// value of another type is assigned to the property.
+    fn ty(&self) -> DataType {
+        DataType::Mixed
+    }
 }
 
 impl<'a, T: Clone + IntoZval + FromZval<'a>> Prop<'a> for T {
@@ -57,20 +65,39 @@ impl<'a, T: Clone + IntoZval + FromZval<'a>> Prop<'a> for T {
         *self = x;
         Ok(())
     }
+
+    fn ty(&self) -> DataType {
+        <T as IntoZval>::TYPE
+    }
 }
 
+/// Getter of a method property, which loads the value of the property into
+/// the given zval.
+type PropertyGetter<'a, T> = Box<dyn Fn(&T, &mut Zval) -> PhpResult + 'a>;
+
+/// Setter of a method property, which sets the property to the value of the
+/// given zval.
+type PropertySetter<'a, T> = Box<dyn Fn(&mut T, &Zval) -> PhpResult + 'a>;
+
+/// Getter of a property which can fail, as given to
+/// [`Property::fallible_method`].
+type FallibleGetter<T, V, E> = fn(&T) -> std::result::Result<V, E>;
+
 /// Represents a property added to a PHP class.
 ///
-/// There are two types of properties:
+/// There are three types of properties:
 ///
 /// * Field properties, where the data is stored inside a struct field.
+/// * Readonly field properties, which are the same as field properties but
+///   cannot be modified from PHP once the object has been constructed.
 /// * Method properties, where getter and/or setter functions are provided,
 ///   which are used to get and set the value of the property.
 pub enum Property<'a, T> {
     Field(Box<dyn Fn(&mut T) -> &mut dyn Prop>),
+    ReadonlyField(Box<dyn Fn(&mut T) -> &mut dyn Prop>),
     Method {
-        get: Option<Box<dyn Fn(&T, &mut Zval) -> PhpResult + 'a>>,
-        set: Option<Box<dyn Fn(&mut T, &Zval) -> PhpResult + 'a>>,
+        get: Option<PropertyGetter<'a, T>>,
+        set: Option<PropertySetter<'a, T>>,
     },
 }
 
@@ -98,6 +125,43 @@ impl<'a, T: 'a> Property<'a, T> {
         Self::Field(Box::new(f) as Box<dyn Fn(&mut T) -> &mut dyn Prop>)
     }
 
+    /// Creates a readonly field property. The property can be read from PHP,
+    /// but attempting to set it results in an error. The field can still be
+    /// modified from Rust, such as when the object is being constructed.
+    ///
+    /// # Parameters
+    ///
+    /// * `f` - The function used to get a mutable reference to the property.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ext_php_rs::props::Property;
+    /// # use ext_php_rs::types::Zval;
+    /// # use ext_php_rs::convert::IntoZval;
+    /// struct Test {
+    ///     pub a: i32,
+    /// }
+    ///
+    /// let prop: Property<Test> = Property::readonly_field(|test: &mut Test| &mut test.a);
+    ///
+    /// let mut test = Test { a: 500 };
+    /// let zv = 100.into_zval(false).unwrap();
+    /// assert!(prop.set(&mut test, &zv).is_err());
+    /// assert_eq!(test.a, 500);
+    /// ```
+    pub fn readonly_field<F>(f: F) -> Self
+    where
+        F: (Fn(&mut T) -> &mut dyn Prop) + 'static,
+    {
+        Self::ReadonlyField(Box::new(f) as Box<dyn Fn(&mut T) -> &mut dyn Prop>)
+    }
+
+    /// Returns `true` if the property is a readonly field property.
+    pub fn is_readonly(&self) -> bool {
+        matches!(self, Self::ReadonlyField(_))
+    }
+
     /// Creates a method property with getters and setters.
     ///
     /// If either the getter or setter is not given, an exception will be thrown
@@ -139,19 +203,94 @@ impl<'a, T: 'a> Property<'a, T> {
                     .set_zval(retval, false)
                     .map_err(|e| format!("Failed to return property value to PHP: {:?}", e))?;
                 Ok(())
-            }) as Box<dyn Fn(&T, &mut Zval) -> PhpResult + 'a>
+            }) as PropertyGetter<'a, T>
         });
 
-        let set = set.map(|set| {
-            Box::new(move |self_: &mut T, value: &Zval| -> PhpResult {
-                let val = V::from_zval(value)
-                    .ok_or("Unable to convert property value into required type.")?;
-                set(self_, val);
+        Self::Method {
+            get,
+            set: set.map(Self::setter),
+        }
+    }
+
+    /// Creates a method property with a fallible getter and a setter.
+    ///
+    /// When the getter returns an error, the error is converted into a
+    /// [`PhpException`] and thrown when the property is read from PHP. The
+    /// property is skipped when the properties of the object are listed, such
+    /// as when calling `var_dump()` or casting the object to an array.
+    ///
+    /// # Parameters
+    ///
+    /// * `get` - Function used to get the value of the property, in an
+    ///   [`Option`].
+    /// * `set` - Function used to set the value of the property, in an
+    ///   [`Option`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ext_php_rs::props::Property;
+    /// # use ext_php_rs::types::Zval;
+    /// # use ext_php_rs::prelude::*;
+    /// struct Test {
+    ///     value: Option<i64>,
+    /// }
+    ///
+    /// impl Test {
+    ///     pub fn get_value(&self) -> PhpResult<i64> {
+    ///         self.value.ok_or_else(|| "Value has not been set.".into())
+    ///     }
+    ///
+    ///     pub fn set_value(&mut self, val: i64) {
+    ///         self.value = Some(val);
+    ///     }
+    /// }
+    ///
+    /// let prop: Property<Test> =
+    ///     Property::fallible_method(Some(Test::get_value), Some(Test::set_value));
+    ///
+    /// let mut test = Test { value: None };
+    /// let mut zv = Zval::new();
+    /// assert!(prop.get(&mut test, &mut zv).is_err());
+    /// ```
+    ///
+    /// [`PhpException`]: crate::exception::PhpException
+    pub fn fallible_method<V, E>(
+        get: Option<FallibleGetter<T, V, E>>,
+        set: Option<fn(&mut T, V)>,
+    ) -> Self
+    where
+        for<'b> V: IntoZval + FromZval<'b> + 'a,
+        E: Into<PhpException> + 'a,
+    {
+        let get = get.map(|get| {
+            Box::new(move |self_: &T, retval: &mut Zval| -> PhpResult {
+                let value = get(self_).map_err(Into::into)?;
+                value
+                    .set_zval(retval, false)
+                    .map_err(|e| format!("Failed to return property value to PHP: {:?}", e))?;
                 Ok(())
-            }) as Box<dyn Fn(&mut T, &Zval) -> PhpResult + 'a>
+            }) as PropertyGetter<'a, T>
         });
 
-        Self::Method { get, set }
+        Self::Method {
+            get,
+            set: set.map(Self::setter),
+        }
+    }
+
+    /// Wraps a property setter, converting the zval given by PHP into the type
+    /// of the setter parameter.
+    fn setter<V>(set: fn(&mut T, V)) -> PropertySetter<'a, T>
+    where
+        for<'b> V: FromZval<'b> + 'a,
+    {
+        Box::new(move |self_: &mut T, value: &Zval| -> PhpResult {
+            let val = V::from_zval(value)
+                .ok_or("Unable to convert property value into required type.")?;
+            set(self_, val);
+            Ok(())
+        })
     }
 
     /// Attempts to retrieve the value of the property from the given object
@@ -191,7 +330,7 @@ impl<'a, T: 'a> Property<'a, T> {
     /// [`PhpException`]: crate::exception::PhpException
     pub fn get(&self, self_: &'a mut T, retval: &mut Zval) -> PhpResult {
         match self {
-            Property::Field(field) => field(self_)
+            Property::Field(field) | Property::ReadonlyField(field) => field(self_)
                 .get(retval)
                 .map_err(|e| format!("Failed to get property value: {:?}", e).into()),
             Property::Method { get, set: _ } => match get {
@@ -239,9 +378,19 @@ impl<'a, T: 'a> Property<'a, T> {
     /// [`PhpException`]: crate::exception::PhpException
     pub fn set(&self, self_: &'a mut T, value: &Zval) -> PhpResult {
         match self {
-            Property::Field(field) => field(self_)
-                .set(value)
-                .map_err(|e| format!("Failed to set property value: {:?}", e).into()),
+            Property::Field(field) => {
+                let field = field(self_);
+                field.set(value).map_err(|e| match e {
+                    // Mirrors the `TypeError` thrown by PHP for typed properties.
+                    Error::ZvalConversion(given) => PhpException::new(
+                        format!("Cannot assign {} to property of type {}", given, field.ty()),
+                        0,
+                        ce::type_error(),
+                    ),
+                    e => format!("Failed to set property value: {:?}", e).into(),
+                })
+            }
+            Property::ReadonlyField(_) => Err("Cannot modify readonly property.".into()),
             Property::Method { get: _, set } => match set {
                 Some(set) => set(self_, value),
                 None => Err("No setter available for this property.".into()),
@@ -3,8 +3,8 @@
 use std::fmt::Debug;
 
 use crate::{
-    ffi::{zend_refcounted_h, zend_string},
-    types::ZendObject,
+    ffi::{zend_refcounted_h, zend_resource, zend_string},
+    types::{ZendHashTable, ZendObject},
 };
 
 /// Object used to store Zend reference counter.
@@ -58,4 +58,4 @@ macro_rules! rc {
     };
 }
 
-rc!(ZendObject, zend_string);
+rc!(ZendObject, ZendHashTable, zend_string, zend_resource);
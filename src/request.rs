@@ -0,0 +1,388 @@
+//! Types used for storing state which lives for the duration of a single PHP
+//! request.
+//!
+//! PHP handles requests independently, and state which is only valid for one
+//! request must be reset between requests. [`RequestGlobals`] stores a value
+//! which is created the first time it is accessed during a request, and is
+//! dropped when the request ends (`RSHUTDOWN`).
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::HashMap,
+    marker::PhantomData,
+    mem,
+    os::raw::{c_int, c_void},
+    ptr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use parking_lot::{const_mutex, Mutex};
+
+use crate::{ffi::zend_result, flags::ZendResult, zend::ModuleEntry};
+
+/// The request startup and shutdown functions of the extension, wrapped by the
+/// functions which manage request globals.
+static REQUEST_HOOKS: Mutex<RequestHooks> = const_mutex(RequestHooks {
+    startup: None,
+    shutdown: None,
+});
+
+/// Request startup or shutdown function stored inside a module entry.
+type ModuleFunc = unsafe extern "C" fn(c_int, c_int) -> zend_result;
+
+struct RequestHooks {
+    startup: Option<ModuleFunc>,
+    shutdown: Option<ModuleFunc>,
+}
+
+/// State of the request globals, stored in the module globals of the
+/// extension.
+#[derive(Default)]
+struct State {
+    /// Whether a request is currently being handled.
+    active: bool,
+    /// Values of the request globals for the current request, keyed by the
+    /// address of the [`RequestGlobals`] static. The value is [`None`] while it
+    /// is being accessed.
+    values: HashMap<usize, Option<Box<dyn Any>>>,
+}
+
+type Globals = RefCell<State>;
+
+/// Storage of the module globals. PHP constructs the globals in place when the
+/// extension is registered, and destructs them when the extension shuts down.
+#[cfg(not(php_zts))]
+static mut GLOBALS: mem::MaybeUninit<Globals> = mem::MaybeUninit::uninit();
+
+/// Whether [`GLOBALS`] has been constructed.
+#[cfg(not(php_zts))]
+static CONSTRUCTED: AtomicBool = AtomicBool::new(false);
+
+/// The thread which constructed [`GLOBALS`]. Without ZTS, PHP only handles
+/// requests on this thread, and other threads must not access the globals.
+#[cfg(not(php_zts))]
+static OWNER: Mutex<Option<std::thread::ThreadId>> = const_mutex(None);
+
+/// The TSRM resource ID of the module globals, which is assigned by PHP when
+/// the extension is registered. Each thread has its own copy of the globals.
+#[cfg(php_zts)]
+static mut GLOBALS_ID: crate::ffi::ts_rsrc_id = 0;
+
+/// A value which is created on first access during a request and dropped at
+/// the end of the request, so each request starts with a fresh
+/// [`Default`] value.
+///
+/// Values are stored in the module globals of the extension. Thread-safe (ZTS)
+/// builds of PHP give each thread its own copy of the module globals through
+/// TSRM, so concurrent requests do not share a value.
+///
+/// The request global must be registered with the extension through
+/// [`ModuleBuilder::request_global`]. The value can only be accessed inside the
+/// closure given to [`RequestGlobals::with`], so a reference to the value
+/// cannot outlive the request.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::prelude::*;
+/// use ext_php_rs::request::RequestGlobals;
+///
+/// #[derive(Default)]
+/// struct Stats {
+///     calls: i64,
+/// }
+///
+/// static STATS: RequestGlobals<Stats> = RequestGlobals::new();
+///
+/// /// Returns the number of times the function has been called during the
+/// /// current request.
+/// #[php_function]
+/// pub fn count_calls() -> i64 {
+///     STATS
+///         .with(|stats| {
+///             stats.calls += 1;
+///             stats.calls
+///         })
+///         .unwrap_or_default()
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module.request_global(&STATS)
+/// }
+/// ```
+///
+/// [`ModuleBuilder::request_global`]: crate::builders::ModuleBuilder::request_global
+pub struct RequestGlobals<T> {
+    _marker: PhantomData<fn() -> T>,
+    registered: AtomicBool,
+}
+
+impl<T> RequestGlobals<T> {
+    /// Creates a new request global. Should be assigned to a `static`.
+    pub const fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+            registered: AtomicBool::new(false),
+        }
+    }
+
+    /// Marks the request global as registered with the extension.
+    pub(crate) fn register(&self) {
+        self.registered.store(true, Ordering::Release);
+    }
+}
+
+impl<T> Default for RequestGlobals<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Default + 'static> RequestGlobals<T> {
+    /// Calls the given function with a mutable reference to the value for the
+    /// current request, creating the value if it has not yet been accessed
+    /// during the request.
+    ///
+    /// Returns [`None`] if the request global has not been registered with the
+    /// extension, if no request is being handled, such as during module
+    /// startup and shutdown, or if the value is already being accessed by an
+    /// outer call to this function.
+    ///
+    /// # Parameters
+    ///
+    /// * `f` - The function to call with the value.
+    pub fn with<R, F: FnOnce(&mut T) -> R>(&'static self, f: F) -> Option<R> {
+        if !self.registered.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let globals = globals()?;
+        let key = self as *const Self as usize;
+
+        // Take the value out of the storage while it is in use, so other request
+        // globals can be accessed from inside the function.
+        let existing = {
+            let mut state = globals.try_borrow_mut().ok()?;
+            if !state.active {
+                return None;
+            }
+            match state.values.get_mut(&key) {
+                Some(value) => Some(value.take()?),
+                None => {
+                    state.values.insert(key, None);
+                    None
+                }
+            }
+        };
+
+        // The guard puts the value back when it is dropped, including when `f`
+        // panics.
+        let mut slot = Slot {
+            globals,
+            key,
+            value: None,
+        };
+        let value = slot
+            .value
+            .insert(existing.unwrap_or_else(|| Box::new(T::default())));
+        value.downcast_mut().map(f)
+    }
+}
+
+/// Returns a value taken from the request globals to its slot when dropped.
+struct Slot {
+    globals: &'static Globals,
+    key: usize,
+    value: Option<Box<dyn Any>>,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.globals.try_borrow_mut() {
+            match self.value.take() {
+                // The slot no longer exists if the request ended while the value
+                // was in use.
+                Some(value) => {
+                    if let Some(slot) = state.values.get_mut(&self.key) {
+                        *slot = Some(value);
+                    }
+                }
+                // Creating the value failed, so it is created again on the next
+                // access.
+                None => {
+                    state.values.remove(&self.key);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the module globals of the current thread, or [`None`] if they have
+/// not been constructed for the current thread.
+fn globals() -> Option<&'static Globals> {
+    #[cfg(not(php_zts))]
+    {
+        if !CONSTRUCTED.load(Ordering::Acquire)
+            || *OWNER.lock() != Some(std::thread::current().id())
+        {
+            return None;
+        }
+        // SAFETY: The globals have been constructed, and are only accessed from
+        // the thread which constructed them.
+        unsafe { ptr::addr_of!(GLOBALS).cast::<Globals>().as_ref() }
+    }
+
+    #[cfg(php_zts)]
+    {
+        // SAFETY: The ID is only written while PHP registers the extension.
+        let id = unsafe { GLOBALS_ID };
+        if id == 0 {
+            return None;
+        }
+        // SAFETY: The globals of each thread are constructed by PHP when the
+        // thread starts, and are only accessed from that thread.
+        unsafe {
+            crate::ffi::ext_php_rs_zts_module_globals(id)
+                .cast::<Globals>()
+                .as_ref()
+        }
+    }
+}
+
+/// Declares the module globals which store the request globals, and replaces
+/// the request startup and shutdown functions of the module with functions
+/// which reset the request globals before calling the original startup
+/// function and after calling the original shutdown function.
+pub(crate) fn wrap_module(module: &mut ModuleEntry) {
+    let mut hooks = REQUEST_HOOKS.lock();
+    hooks.startup = module.request_startup_func;
+    hooks.shutdown = module.request_shutdown_func;
+    module.request_startup_func = Some(startup);
+    module.request_shutdown_func = Some(shutdown);
+
+    module.globals_size = mem::size_of::<Globals>() as _;
+    #[cfg(not(php_zts))]
+    {
+        module.globals_ptr = ptr::addr_of_mut!(GLOBALS).cast();
+    }
+    #[cfg(php_zts)]
+    {
+        module.globals_id_ptr = ptr::addr_of_mut!(GLOBALS_ID);
+    }
+    module.globals_ctor = Some(globals_ctor);
+    module.globals_dtor = Some(globals_dtor);
+}
+
+unsafe extern "C" fn globals_ctor(globals: *mut c_void) {
+    ptr::write(globals.cast::<Globals>(), Globals::default());
+
+    #[cfg(not(php_zts))]
+    {
+        *OWNER.lock() = Some(std::thread::current().id());
+        CONSTRUCTED.store(true, Ordering::Release);
+    }
+}
+
+unsafe extern "C" fn globals_dtor(globals: *mut c_void) {
+    #[cfg(not(php_zts))]
+    CONSTRUCTED.store(false, Ordering::Release);
+
+    ptr::drop_in_place(globals.cast::<Globals>());
+}
+
+/// Starts or ends a request, dropping the values of all request globals of the
+/// current thread.
+fn reset(active: bool) {
+    let values = match globals().and_then(|globals| globals.try_borrow_mut().ok()) {
+        Some(mut state) => {
+            state.active = active;
+            mem::take(&mut state.values)
+        }
+        None => return,
+    };
+    // Values are dropped outside of the borrow, in case they access request
+    // globals when dropped.
+    drop(values);
+}
+
+unsafe extern "C" fn startup(ty: c_int, module_number: c_int) -> zend_result {
+    reset(true);
+
+    let startup = REQUEST_HOOKS.lock().startup;
+    match startup {
+        Some(startup) => startup(ty, module_number),
+        None => ZendResult::Success.bits(),
+    }
+}
+
+unsafe extern "C" fn shutdown(ty: c_int, module_number: c_int) -> zend_result {
+    let shutdown = REQUEST_HOOKS.lock().shutdown;
+    let result = match shutdown {
+        Some(shutdown) => shutdown(ty, module_number),
+        None => ZendResult::Success.bits(),
+    };
+
+    reset(false);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static COUNTER: RequestGlobals<i64> = RequestGlobals::new();
+    static UNREGISTERED: RequestGlobals<i64> = RequestGlobals::new();
+
+    fn increment() -> Option<i64> {
+        COUNTER.with(|counter| {
+            *counter += 1;
+            *counter
+        })
+    }
+
+    #[test]
+    fn test_request_globals() {
+        COUNTER.register();
+        let globals = ptr::addr_of_mut!(GLOBALS).cast();
+        unsafe { globals_ctor(globals) };
+
+        // Outside of a request
+        assert_eq!(increment(), None);
+
+        for _ in 0..2 {
+            unsafe { startup(0, 0) };
+            assert_eq!(increment(), Some(1));
+            assert_eq!(increment(), Some(2));
+            assert_eq!(UNREGISTERED.with(|_| ()), None);
+
+            // Nested access to the same value
+            assert_eq!(COUNTER.with(|_| increment()), Some(None));
+
+            // The value is returned to its slot when the function panics
+            let result = std::panic::catch_unwind(|| {
+                COUNTER.with(|counter| {
+                    *counter += 1;
+                    panic!("failed");
+                })
+            });
+            assert!(result.is_err());
+            assert_eq!(increment(), Some(4));
+
+            // Other threads cannot access the value
+            assert_eq!(
+                std::thread::spawn(increment)
+                    .join()
+                    .expect("thread panicked"),
+                None
+            );
+
+            unsafe { shutdown(0, 0) };
+            assert_eq!(increment(), None);
+        }
+
+        unsafe { globals_dtor(globals) };
+        assert_eq!(increment(), None);
+    }
+}
@@ -0,0 +1,336 @@
+//! Types used for exposing Rust values to PHP as resources.
+//!
+//! A resource is an opaque handle, such as a file or a connection, which PHP
+//! code can pass around but not inspect. Resource types are declared on the
+//! module with [`ModuleBuilder::resource`] and are registered with PHP when the
+//! module starts up, before the startup function of the extension is called.
+//! Values are then wrapped in a [`Resource`], which frees the value when the
+//! resource is closed or is no longer referenced from PHP.
+//!
+//! [`ModuleBuilder::resource`]: crate::builders::ModuleBuilder::resource
+
+use std::{any::TypeId, ffi::CString, fmt::Debug, marker::PhantomData, os::raw::c_int};
+
+use parking_lot::{const_mutex, Mutex};
+
+use crate::{
+    boxed::{ZBox, ZBoxable},
+    convert::{FromZval, FromZvalMut, IntoZval},
+    error::{Error, Result},
+    ffi::{
+        zend_list_close, zend_list_delete, zend_register_list_destructors_ex,
+        zend_register_resource, zend_resource, zend_result,
+    },
+    flags::{DataType, ZendResult},
+    rc::PhpRc,
+    types::Zval,
+    zend::ModuleEntry,
+};
+
+/// The resource types of the extension, along with the startup function they
+/// wrap. Only one module can be built per extension, so only one set of types
+/// is stored.
+static MODULE_RESOURCES: Mutex<Option<ModuleResources>> = const_mutex(None);
+
+/// Startup function stored inside a module entry.
+type ModuleFunc = unsafe extern "C" fn(c_int, c_int) -> zend_result;
+
+struct ModuleResources {
+    types: Vec<RegisteredType>,
+    startup: Option<ModuleFunc>,
+}
+
+/// A resource type declared by the extension.
+#[derive(Debug, Clone)]
+pub(crate) struct ResourceType {
+    type_id: TypeId,
+    name: String,
+    dtor: unsafe extern "C" fn(*mut zend_resource),
+}
+
+impl ResourceType {
+    /// Creates a new resource type for values of type `T`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the type, returned by `get_resource_type()`.
+    pub(crate) fn new<T: 'static>(name: String) -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            name,
+            dtor: dtor::<T>,
+        }
+    }
+}
+
+/// A resource type stored until the module starts up.
+struct RegisteredType {
+    type_id: TypeId,
+    // PHP keeps a pointer to the name, so it is stored for the lifetime of the
+    // extension.
+    name: CString,
+    dtor: unsafe extern "C" fn(*mut zend_resource),
+    /// The ID given to the type by PHP, set when the module starts up.
+    id: Option<c_int>,
+}
+
+/// A Rust value stored inside a PHP resource.
+///
+/// The type `T` must first be declared on the module with
+/// [`ModuleBuilder::resource`]. Resources are created with [`Resource::new`]
+/// and returned to PHP as a [`ZBox<Resource<T>>`]. Functions accept resources
+/// as `&Resource<T>` or `&mut Resource<T>` parameters.
+///
+/// A resource can be closed with [`Resource::close`] while PHP still holds a
+/// reference to it, in which case the value is freed and any later attempt to
+/// access the value returns [`Error::ClosedResource`]. Otherwise, the value is
+/// freed when the last reference to the resource is released.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::{boxed::ZBox, prelude::*, resource::Resource};
+///
+/// pub struct Counter(i64);
+///
+/// #[php_function]
+/// pub fn counter_new() -> PhpResult<ZBox<Resource<Counter>>> {
+///     Ok(Resource::new(Counter(0))?)
+/// }
+///
+/// #[php_function]
+/// pub fn counter_increment(counter: &mut Resource<Counter>) -> PhpResult<i64> {
+///     let counter = counter.get_mut()?;
+///     counter.0 += 1;
+///     Ok(counter.0)
+/// }
+///
+/// #[php_function]
+/// pub fn counter_close(counter: &mut Resource<Counter>) {
+///     counter.close();
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module.resource::<Counter>("counter")
+/// }
+/// ```
+///
+/// [`ModuleBuilder::resource`]: crate::builders::ModuleBuilder::resource
+#[repr(transparent)]
+pub struct Resource<T> {
+    res: zend_resource,
+    _marker: PhantomData<T>,
+}
+
+impl<T: 'static> Resource<T> {
+    /// Creates a new resource containing the given value. Must be called while
+    /// a request is being handled, as resources are freed at the end of the
+    /// request.
+    ///
+    /// Returns [`Error::UnregisteredResource`] if the type `T` has not been
+    /// declared on the module.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The value to store inside the resource.
+    pub fn new(value: T) -> Result<ZBox<Self>> {
+        let id = type_id::<T>().ok_or(Error::UnregisteredResource)?;
+        let ptr = Box::into_raw(Box::new(value));
+
+        // SAFETY: `zend_register_resource` returns a new resource with a
+        // reference count of one, which is owned by the returned box.
+        unsafe {
+            let res = zend_register_resource(ptr.cast(), id);
+            Ok(ZBox::from_raw(res.cast()))
+        }
+    }
+
+    /// Returns a reference to the value stored inside the resource, or
+    /// [`Error::ClosedResource`] if the resource has been closed.
+    pub fn get(&self) -> Result<&T> {
+        // SAFETY: The pointer is either null or points to a `T`, as the type of
+        // the resource was checked when the resource was created or converted.
+        unsafe { (self.res.ptr as *const T).as_ref() }.ok_or(Error::ClosedResource)
+    }
+
+    /// Returns a mutable reference to the value stored inside the resource, or
+    /// [`Error::ClosedResource`] if the resource has been closed.
+    pub fn get_mut(&mut self) -> Result<&mut T> {
+        // SAFETY: See `get`.
+        unsafe { (self.res.ptr as *mut T).as_mut() }.ok_or(Error::ClosedResource)
+    }
+
+    /// Returns true if the resource has been closed, false otherwise.
+    pub fn is_closed(&self) -> bool {
+        // PHP sets the type of a resource to -1 when it is closed.
+        self.res.type_ < 0
+    }
+
+    /// Closes the resource, freeing the value stored inside it. The resource
+    /// itself remains valid while PHP holds a reference to it, but the value
+    /// can no longer be accessed. Closing a resource more than once has no
+    /// effect.
+    pub fn close(&mut self) {
+        // SAFETY: The resource is valid, and `zend_list_close` does nothing if
+        // the resource has already been closed.
+        unsafe { zend_list_close(&mut self.res) }
+    }
+
+    /// Returns the handle of the resource, the number shown when the resource
+    /// is converted into an integer in PHP.
+    pub fn handle(&self) -> i64 {
+        self.res.handle.into()
+    }
+
+    /// Attempts to convert a raw resource into a [`Resource`] of type `T`.
+    /// Closed resources are accepted, as their type is no longer known.
+    fn from_raw(res: &zend_resource) -> Option<&Self> {
+        let id = type_id::<T>()?;
+        if res.type_ == id || res.type_ < 0 {
+            // SAFETY: `Resource<T>` is a transparent wrapper around a
+            // `zend_resource`, and the type of the resource has been checked.
+            Some(unsafe { &*(res as *const zend_resource).cast() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: 'static> Debug for Resource<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Resource")
+            .field("handle", &self.res.handle)
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+unsafe impl<T> ZBoxable for Resource<T> {
+    fn free(&mut self) {
+        // SAFETY: Releases the reference owned by the box, freeing the value if
+        // no other references remain.
+        unsafe { zend_list_delete(&mut self.res) }
+    }
+}
+
+impl<T: 'static> IntoZval for ZBox<Resource<T>> {
+    const TYPE: DataType = DataType::Resource;
+
+    #[inline]
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        // The reference held by the box is handed over to the zval.
+        zv.set_resource(&mut self.into_raw().res);
+        Ok(())
+    }
+}
+
+impl<T: 'static> IntoZval for &mut Resource<T> {
+    const TYPE: DataType = DataType::Resource;
+
+    #[inline]
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        self.res.inc_count();
+        zv.set_resource(&mut self.res);
+        Ok(())
+    }
+}
+
+impl<'a, T: 'static> FromZval<'a> for &'a Resource<T> {
+    const TYPE: DataType = DataType::Resource;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        // SAFETY: Resource zvals contain a valid pointer to a resource.
+        Resource::from_raw(unsafe { zval.resource()?.as_ref() }?)
+    }
+}
+
+impl<'a, T: 'static> FromZvalMut<'a> for &'a mut Resource<T> {
+    const TYPE: DataType = DataType::Resource;
+
+    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
+        // SAFETY: Resource zvals contain a valid pointer to a resource.
+        let res = unsafe { zval.resource()?.as_mut() }?;
+        Resource::<T>::from_raw(res)?;
+        // SAFETY: The type of the resource has been checked above.
+        Some(unsafe { &mut *(res as *mut zend_resource).cast() })
+    }
+}
+
+/// Returns the ID given by PHP to the resource type of values of type `T`, or
+/// [`None`] if the type has not been registered.
+fn type_id<T: 'static>() -> Option<c_int> {
+    let type_id = TypeId::of::<T>();
+    MODULE_RESOURCES
+        .lock()
+        .as_ref()?
+        .types
+        .iter()
+        .find(|ty| ty.type_id == type_id)?
+        .id
+}
+
+/// Frees the value of type `T` stored inside a resource. Called by PHP when
+/// the resource is closed or no longer referenced.
+unsafe extern "C" fn dtor<T>(res: *mut zend_resource) {
+    if let Some(res) = res.as_mut() {
+        if !res.ptr.is_null() {
+            drop(Box::from_raw(res.ptr as *mut T));
+            res.ptr = std::ptr::null_mut();
+        }
+    }
+}
+
+/// Stores the given resource types, and replaces the startup function of the
+/// module with a function which registers the types before calling the
+/// original startup function.
+pub(crate) fn wrap_module(module: &mut ModuleEntry, types: Vec<ResourceType>) -> Result<()> {
+    let types = types
+        .into_iter()
+        .map(|ty| {
+            Ok(RegisteredType {
+                type_id: ty.type_id,
+                name: CString::new(ty.name)?,
+                dtor: ty.dtor,
+                id: None,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    MODULE_RESOURCES.lock().replace(ModuleResources {
+        types,
+        startup: module.module_startup_func,
+    });
+    module.module_startup_func = Some(startup);
+    Ok(())
+}
+
+unsafe extern "C" fn startup(ty: c_int, module_number: c_int) -> zend_result {
+    let startup = {
+        let mut resources = MODULE_RESOURCES.lock();
+        let resources = match resources.as_mut() {
+            Some(resources) => resources,
+            None => return ZendResult::Failure.bits(),
+        };
+
+        for ty in resources.types.iter_mut() {
+            let id = zend_register_list_destructors_ex(
+                Some(ty.dtor),
+                None,
+                ty.name.as_ptr(),
+                module_number,
+            );
+            if id < 0 {
+                return ZendResult::Failure.bits();
+            }
+            ty.id = Some(id);
+        }
+
+        resources.startup
+    };
+
+    match startup {
+        Some(startup) => startup(ty, module_number),
+        None => ZendResult::Success.bits(),
+    }
+}
@@ -0,0 +1,70 @@
+//! Traits used for exporting Rust types to PHP as classes which can be
+//! serialized with `serialize()` and restored with `unserialize()`.
+
+use crate::{boxed::ZBox, class::RegisteredClass, exception::PhpResult, types::ZendHashTable};
+
+/// Implemented on Rust types which are exported to PHP as classes which can be
+/// serialized.
+///
+/// Objects of classes backed by Rust cannot be serialized by default, as PHP
+/// has no way of storing the Rust value. The class must be registered with the
+/// `#[serializable]` attribute underneath the [`macro@php_class`] attribute,
+/// which registers the `__serialize` and `__unserialize` magic methods and
+/// allows the objects of the class to be serialized.
+///
+/// `serialize($obj)` stores the array returned by [`PhpSerialize::serialize`].
+/// `unserialize()` creates a new object without calling its constructor, and
+/// initializes it with the value returned by [`PhpSerialize::unserialize`],
+/// called with the stored array. Returning an error from either method throws
+/// the exception from `serialize()` or `unserialize()`.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use ext_php_rs::{boxed::ZBox, serialize::PhpSerialize, types::ZendHashTable};
+///
+/// #[php_class]
+/// #[serializable]
+/// pub struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// impl PhpSerialize for Point {
+///     fn serialize(&self) -> PhpResult<ZBox<ZendHashTable>> {
+///         let mut data = ZendHashTable::new();
+///         data.insert("x", self.x)?;
+///         data.insert("y", self.y)?;
+///         Ok(data)
+///     }
+///
+///     fn unserialize(data: &ZendHashTable) -> PhpResult<Self> {
+///         let coord = |key| data.get(key).and_then(|zv| zv.long()).ok_or("Invalid point data");
+///         Ok(Self {
+///             x: coord("x")?,
+///             y: coord("y")?,
+///         })
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`macro@php_class`]: crate::php_class
+pub trait PhpSerialize: RegisteredClass {
+    /// Returns the state of the object as an associative array, which is
+    /// stored by `serialize()`. Called by `__serialize`.
+    fn serialize(&self) -> PhpResult<ZBox<ZendHashTable>>;
+
+    /// Creates the object from the array returned by
+    /// [`serialize`](PhpSerialize::serialize). Called by `__unserialize`.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - The state of the object, as stored by `serialize()`.
+    fn unserialize(data: &ZendHashTable) -> PhpResult<Self>;
+}
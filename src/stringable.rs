@@ -0,0 +1,48 @@
+//! Traits used for exporting Rust types to PHP as classes which implement the
+//! `Stringable` interface, allowing objects to be converted into strings.
+
+use crate::class::RegisteredClass;
+
+/// Implemented on Rust types which are exported to PHP as classes which can be
+/// converted into strings.
+///
+/// The class must be registered with the `#[stringable]` attribute underneath
+/// the [`macro@php_class`] attribute, which implements the `Stringable`
+/// interface, registers the `__toString` method and overrides the
+/// `cast_object` object handler, so both explicit casts (`(string) $obj`) and
+/// implicit conversions (`"$obj"`) call [`PhpToString::to_php_string`].
+///
+/// Rust strings are always valid UTF-8. However, PHP strings created from Rust
+/// cannot contain NUL bytes, so returning a string containing a NUL byte
+/// throws an exception instead.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use ext_php_rs::stringable::PhpToString;
+///
+/// #[php_class]
+/// #[stringable]
+/// pub struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// impl PhpToString for Point {
+///     fn to_php_string(&self) -> String {
+///         format!("({}, {})", self.x, self.y)
+///     }
+/// }
+///
+/// #[php_module]
+/// pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+///     module
+/// }
+/// ```
+///
+/// [`macro@php_class`]: crate::php_class
+pub trait PhpToString: RegisteredClass {
+    /// Returns the string representation of the object.
+    fn to_php_string(&self) -> String;
+}
@@ -2,10 +2,11 @@
 //! they are represented by hash tables.
 
 use std::{
+    borrow::Cow,
     collections::HashMap,
     convert::{TryFrom, TryInto},
     ffi::CString,
-    fmt::Debug,
+    fmt::{Debug, Display},
     iter::FromIterator,
     ptr::NonNull,
     u64,
@@ -14,12 +15,12 @@ use std::{
 use crate::{
     boxed::{ZBox, ZBoxable},
     convert::{FromZval, IntoZval},
-    error::{Error, Result},
+    error::{ConversionError, Error, Result},
     ffi::{
         _Bucket, _zend_new_array, zend_array_destroy, zend_array_dup, zend_hash_clean,
-        zend_hash_index_del, zend_hash_index_find, zend_hash_index_update,
+        zend_hash_extend, zend_hash_index_del, zend_hash_index_find, zend_hash_index_update,
         zend_hash_next_index_insert, zend_hash_str_del, zend_hash_str_find, zend_hash_str_update,
-        HT_MIN_SIZE,
+        HASH_FLAG_PACKED, HT_MIN_SIZE,
     },
     flags::DataType,
     types::Zval,
@@ -74,6 +75,10 @@ impl ZendHashTable {
     /// Creates a new, empty, PHP hashtable with an initial size, returned
     /// inside a [`ZBox`].
     ///
+    /// The size is only a hint - the hashtable is allocated with enough space
+    /// for `size` elements so that it does not need to be resized while they
+    /// are inserted, but it will still grow if more elements are inserted.
+    ///
     /// # Parameters
     ///
     /// * `size` - The size to initialize the array with.
@@ -102,6 +107,46 @@ impl ZendHashTable {
         }
     }
 
+    /// Returns the number of elements the hashtable can hold before it needs
+    /// to be resized.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let ht = ZendHashTable::with_capacity(100);
+    /// assert!(ht.capacity() >= 100);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.nTableSize as usize
+    }
+
+    /// Reserves space for at least `additional` more elements to be inserted
+    /// into the hashtable, resizing it at most once.
+    ///
+    /// # Parameters
+    ///
+    /// * `additional` - The number of elements expected to be inserted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendHashTable;
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.reserve(100);
+    /// assert!(ht.capacity() >= 100);
+    /// ```
+    pub fn reserve(&mut self, additional: u32) {
+        let size = self.nNumUsed.saturating_add(additional);
+        // SAFETY: Both variants of the union are plain integers.
+        let packed = unsafe { self.u.flags } & HASH_FLAG_PACKED != 0;
+
+        // SAFETY: The packed flag given matches the current layout of the hashtable.
+        unsafe { zend_hash_extend(self, size, packed) };
+    }
+
     /// Returns the current number of elements in the array.
     ///
     /// # Example
@@ -284,6 +329,12 @@ impl ZendHashTable {
     /// Attempts to insert an item into the hash table, or update if the key
     /// already exists. Returns nothing in a result if successful.
     ///
+    /// The key can be a string or an integer, see [`IntoArrayKey`]. Integer
+    /// keys are inserted at an index, the same as
+    /// [`insert_at_index`](#method.insert_at_index). Negative integer keys are
+    /// stored the same way as PHP stores them, by reinterpreting them as
+    /// unsigned integers.
+    ///
     /// # Parameters
     ///
     /// * `key` - The key to insert the value at in the hash table.
@@ -291,9 +342,8 @@ impl ZendHashTable {
     ///
     /// # Returns
     ///
-    /// Returns nothing in a result on success. Returns an error if the key
-    /// could not be converted into a [`CString`], or converting the value into
-    /// a [`Zval`] failed.
+    /// Returns nothing in a result on success. Returns an error if converting
+    /// the value into a [`Zval`] failed.
     ///
     /// # Example
     ///
@@ -303,23 +353,26 @@ impl ZendHashTable {
     /// let mut ht = ZendHashTable::new();
     ///
     /// ht.insert("a", "A");
-    /// ht.insert("b", "B");
-    /// ht.insert("c", "C");
+    /// ht.insert(String::from("b"), "B");
+    /// ht.insert(5, "C");
+    /// ht.insert("a", "D"); // notice overriding key "a"
     /// assert_eq!(ht.len(), 3);
+    /// assert_eq!(ht.get("a").and_then(|zv| zv.str()), Some("D"));
+    /// assert_eq!(ht.get_index(5).and_then(|zv| zv.str()), Some("C"));
     /// ```
-    pub fn insert<V>(&mut self, key: &str, val: V) -> Result<()>
+    pub fn insert<'a, K, V>(&mut self, key: K, val: V) -> Result<()>
     where
+        K: IntoArrayKey<'a>,
         V: IntoZval,
     {
-        let mut val = val.into_zval(false)?;
-        unsafe {
-            zend_hash_str_update(
-                self,
-                CString::new(key)?.as_ptr(),
-                key.len() as u64,
-                &mut val,
-            )
+        let key = key.into_array_key();
+        let key: &[u8] = match &key {
+            ArrayKey::Long(key) => return self.insert_at_index(*key as u64, val),
+            ArrayKey::String(key) => key.as_bytes(),
+            ArrayKey::Bytes(key) => key,
         };
+        let mut val = val.into_zval(false)?;
+        unsafe { zend_hash_str_update(self, key.as_ptr().cast(), key.len() as u64, &mut val) };
         val.release();
         Ok(())
     }
@@ -433,6 +486,61 @@ impl ZendHashTable {
     pub fn values(&self) -> Values {
         Values::new(self)
     }
+
+    /// Returns an iterator over the keys and values contained inside the
+    /// hashtable, where each key is either an integer or a string
+    /// [`ArrayKey`]. String keys which are not valid UTF-8 are returned as
+    /// [`ArrayKey::Bytes`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ArrayKey, ZendHashTable};
+    ///
+    /// let mut ht = ZendHashTable::new();
+    ///
+    /// for (key, val) in ht.entries() {
+    ///     match key {
+    ///         ArrayKey::Long(idx) => println!("{}: {:?}", idx, val),
+    ///         ArrayKey::String(key) => println!("{}: {:?}", key, val),
+    ///         ArrayKey::Bytes(key) => println!("{:?}: {:?}", key, val),
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn entries(&self) -> Entries<'_> {
+        Entries::new(self)
+    }
+
+    /// Converts each value contained inside the hashtable into `T`, returning
+    /// the keys and converted values in the order of the hashtable.
+    ///
+    /// Returns an error on the first value which cannot be converted, which
+    /// contains the key of the value. The error is thrown as a `TypeError` when
+    /// returned from an exported function.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{prelude::*, types::ZendHashTable};
+    ///
+    /// #[php_function]
+    /// pub fn total(prices: &ZendHashTable) -> PhpResult<f64> {
+    ///     let prices = prices.try_map_values::<f64>()?;
+    ///     Ok(prices.into_iter().map(|(_, price)| price).sum())
+    /// }
+    /// ```
+    pub fn try_map_values<'a, T>(&'a self) -> Result<Vec<(ArrayKey<'a>, T)>, ConversionError>
+    where
+        T: FromZval<'a>,
+    {
+        self.entries()
+            .map(|(key, val)| match T::from_zval(val) {
+                Some(val) => Ok((key, val)),
+                None => Err(ConversionError::new(T::TYPE, val).with_key(key.into_owned())),
+            })
+            .collect()
+    }
 }
 
 unsafe impl ZBoxable for ZendHashTable {
@@ -492,21 +600,42 @@ impl<'a> Iter<'a> {
     }
 }
 
-impl<'a> Iterator for Iter<'a> {
-    type Item = (u64, Option<String>, &'a Zval);
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl<'a> Iter<'a> {
+    /// Returns the next bucket from the front of the hashtable.
+    fn next_bucket(&mut self) -> Option<&'a _Bucket> {
         let pos = self.pos?;
 
         if pos == self.end? {
             return None;
         }
 
-        let bucket = unsafe { pos.as_ref() };
-        let key = unsafe { bucket.key.as_ref() }.and_then(|s| s.try_into().ok());
-
         self.pos = NonNull::new(unsafe { pos.as_ptr().offset(1) });
 
+        Some(unsafe { &*pos.as_ptr() })
+    }
+
+    /// Returns the next bucket from the back of the hashtable.
+    fn next_bucket_back(&mut self) -> Option<&'a _Bucket> {
+        let end = self.end?;
+
+        if end == self.pos? {
+            return None;
+        }
+
+        let new_end = NonNull::new(unsafe { end.as_ptr().offset(-1) })?;
+        self.end = Some(new_end);
+
+        Some(unsafe { &*new_end.as_ptr() })
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (u64, Option<String>, &'a Zval);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bucket = self.next_bucket()?;
+        let key = unsafe { bucket.key.as_ref() }.and_then(|s| s.try_into().ok());
+
         Some((bucket.h, key, &bucket.val))
     }
 
@@ -526,16 +655,8 @@ impl<'a> ExactSizeIterator for Iter<'a> {
 
 impl<'a> DoubleEndedIterator for Iter<'a> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        let end = self.end?;
-
-        if end == self.pos? {
-            return None;
-        }
-
-        let new_end = NonNull::new(unsafe { end.as_ptr().offset(-1) })?;
-        let bucket = unsafe { new_end.as_ref() };
+        let bucket = self.next_bucket_back()?;
         let key = unsafe { bucket.key.as_ref() }.and_then(|s| s.try_into().ok());
-        self.end = Some(new_end);
 
         Some((bucket.h, key, &bucket.val))
     }
@@ -583,6 +704,61 @@ impl<'a> DoubleEndedIterator for Values<'a> {
     }
 }
 
+/// Immutable iterator over the keys and values of a hashtable, where each key
+/// is an [`ArrayKey`].
+pub struct Entries<'a>(Iter<'a>);
+
+impl<'a> Entries<'a> {
+    /// Creates a new iterator over the keys and values of a hashtable.
+    ///
+    /// # Parameters
+    ///
+    /// * `ht` - The hashtable to iterate.
+    pub fn new(ht: &'a ZendHashTable) -> Self {
+        Self(Iter::new(ht))
+    }
+
+    /// Returns the key and value stored in the given bucket.
+    fn entry(bucket: &'a _Bucket) -> (ArrayKey<'a>, &'a Zval) {
+        let key = match unsafe { bucket.key.as_ref() } {
+            Some(key) => match std::str::from_utf8(key.as_bytes()) {
+                Ok(key) => ArrayKey::String(Cow::Borrowed(key)),
+                Err(_) => ArrayKey::Bytes(Cow::Borrowed(key.as_bytes())),
+            },
+            None => ArrayKey::Long(bucket.h as i64),
+        };
+
+        (key, &bucket.val)
+    }
+}
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = (ArrayKey<'a>, &'a Zval);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_bucket().map(Self::entry)
+    }
+
+    fn count(self) -> usize
+    where
+        Self: Sized,
+    {
+        self.0.count()
+    }
+}
+
+impl<'a> ExactSizeIterator for Entries<'a> {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Entries<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_bucket_back().map(Self::entry)
+    }
+}
+
 impl Default for ZBox<ZendHashTable> {
     fn default() -> Self {
         ZendHashTable::new()
@@ -636,9 +812,9 @@ where
     }
 }
 
-impl<K, V> TryFrom<HashMap<K, V>> for ZBox<ZendHashTable>
+impl<'a, K, V> TryFrom<HashMap<K, V>> for ZBox<ZendHashTable>
 where
-    K: AsRef<str>,
+    K: IntoArrayKey<'a>,
     V: IntoZval,
 {
     type Error = Error;
@@ -649,16 +825,16 @@ where
         );
 
         for (k, v) in value.into_iter() {
-            ht.insert(k.as_ref(), v)?;
+            ht.insert(k, v)?;
         }
 
         Ok(ht)
     }
 }
 
-impl<K, V> IntoZval for HashMap<K, V>
+impl<'a, K, V> IntoZval for HashMap<K, V>
 where
-    K: AsRef<str>,
+    K: IntoArrayKey<'a>,
     V: IntoZval,
 {
     const TYPE: DataType = DataType::Array;
@@ -681,6 +857,17 @@ where
     }
 }
 
+impl<T> TryFrom<&Zval> for HashMap<String, T>
+where
+    for<'a> T: FromZval<'a>,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: &Zval) -> std::result::Result<Self, ConversionError> {
+        value.try_into_type()
+    }
+}
+
 ///////////////////////////////////////////
 //// Vec
 ///////////////////////////////////////////
@@ -745,10 +932,108 @@ where
     }
 }
 
+impl<T> TryFrom<&Zval> for Vec<T>
+where
+    for<'a> T: FromZval<'a>,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: &Zval) -> std::result::Result<Self, ConversionError> {
+        value.try_into_type()
+    }
+}
+
+///////////////////////////////////////////
+//// Tuples
+///////////////////////////////////////////
+
+/// Implements conversions between tuples and PHP list arrays, where each
+/// element of the tuple is stored at the index of its position.
+macro_rules! tuple_conversions {
+    ($len: literal; $($t: ident: $i: tt),+) => {
+        impl<'a, $($t),+> TryFrom<&'a ZendHashTable> for ($($t,)+)
+        where
+            $($t: FromZval<'a>),+
+        {
+            type Error = Error;
+
+            fn try_from(value: &'a ZendHashTable) -> Result<Self> {
+                if value.len() != $len {
+                    return Err(Error::IncorrectArrayLength($len, value.len()));
+                }
+
+                Ok(($({
+                    let val = value
+                        .get_index($i)
+                        .ok_or(Error::ZvalConversion(DataType::Undef))?;
+                    $t::from_zval(val).ok_or_else(|| Error::ZvalConversion(val.get_type()))?
+                },)+))
+            }
+        }
+
+        impl<'a, $($t),+> FromZval<'a> for ($($t,)+)
+        where
+            $($t: FromZval<'a>),+
+        {
+            const TYPE: DataType = DataType::Array;
+
+            fn from_zval(zval: &'a Zval) -> Option<Self> {
+                zval.array().and_then(|arr| arr.try_into().ok())
+            }
+        }
+
+        impl<$($t),+> TryFrom<($($t,)+)> for ZBox<ZendHashTable>
+        where
+            $($t: IntoZval),+
+        {
+            type Error = Error;
+
+            fn try_from(value: ($($t,)+)) -> Result<Self> {
+                let mut ht = ZendHashTable::with_capacity($len);
+                $(ht.push(value.$i)?;)+
+                Ok(ht)
+            }
+        }
+
+        impl<$($t),+> IntoZval for ($($t,)+)
+        where
+            $($t: IntoZval),+
+        {
+            const TYPE: DataType = DataType::Array;
+
+            fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+                let arr = self.try_into()?;
+                zv.set_hashtable(arr);
+                Ok(())
+            }
+        }
+    };
+}
+
+tuple_conversions!(1; A: 0);
+tuple_conversions!(2; A: 0, B: 1);
+tuple_conversions!(3; A: 0, B: 1, C: 2);
+tuple_conversions!(4; A: 0, B: 1, C: 2, D: 3);
+tuple_conversions!(5; A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple_conversions!(6; A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+tuple_conversions!(7; A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+tuple_conversions!(8; A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+/// Returns the initial size of a hashtable collected from an iterator, using
+/// the lower bound of the iterators size hint.
+fn size_hint(iter: &impl Iterator) -> u32 {
+    iter.size_hint()
+        .0
+        .try_into()
+        .unwrap_or(u32::MAX)
+        .max(HT_MIN_SIZE)
+}
+
 impl FromIterator<Zval> for ZBox<ZendHashTable> {
     fn from_iter<T: IntoIterator<Item = Zval>>(iter: T) -> Self {
-        let mut ht = ZendHashTable::new();
-        for item in iter.into_iter() {
+        let iter = iter.into_iter();
+        let mut ht = ZendHashTable::with_capacity(size_hint(&iter));
+        for item in iter {
             // Inserting a zval cannot fail, as `push` only returns `Err` if converting
             // `val` to a zval fails.
             let _ = ht.push(item);
@@ -757,26 +1042,173 @@ impl FromIterator<Zval> for ZBox<ZendHashTable> {
     }
 }
 
-impl FromIterator<(u64, Zval)> for ZBox<ZendHashTable> {
-    fn from_iter<T: IntoIterator<Item = (u64, Zval)>>(iter: T) -> Self {
-        let mut ht = ZendHashTable::new();
-        for (key, val) in iter.into_iter() {
-            // Inserting a zval cannot fail, as `push` only returns `Err` if converting
+impl<'a, K> FromIterator<(K, Zval)> for ZBox<ZendHashTable>
+where
+    K: IntoArrayKey<'a>,
+{
+    fn from_iter<T: IntoIterator<Item = (K, Zval)>>(iter: T) -> Self {
+        let iter = iter.into_iter();
+        let mut ht = ZendHashTable::with_capacity(size_hint(&iter));
+        for (key, val) in iter {
+            // Inserting a zval cannot fail, as `insert` only returns `Err` if converting
             // `val` to a zval fails.
-            let _ = ht.insert_at_index(key, val);
+            let _ = ht.insert(key, val);
         }
         ht
     }
 }
 
-impl<'a> FromIterator<(&'a str, Zval)> for ZBox<ZendHashTable> {
-    fn from_iter<T: IntoIterator<Item = (&'a str, Zval)>>(iter: T) -> Self {
-        let mut ht = ZendHashTable::new();
-        for (key, val) in iter.into_iter() {
-            // Inserting a zval cannot fail, as `push` only returns `Err` if converting
-            // `val` to a zval fails.
-            let _ = ht.insert(key, val);
+///////////////////////////////////////////
+//// Keys
+///////////////////////////////////////////
+
+/// The key of an element in a PHP array, which is either an integer or a
+/// string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrayKey<'a> {
+    /// An integer key, also known as an index.
+    Long(i64),
+    /// A string key.
+    String(Cow<'a, str>),
+    /// A string key which is not valid UTF-8. PHP strings are byte strings, so
+    /// array keys may contain any bytes.
+    Bytes(Cow<'a, [u8]>),
+}
+
+impl ArrayKey<'_> {
+    /// Converts the key into a key which owns its string, if it is a string
+    /// key.
+    pub fn into_owned(self) -> ArrayKey<'static> {
+        match self {
+            ArrayKey::Long(idx) => ArrayKey::Long(idx),
+            ArrayKey::String(key) => ArrayKey::String(Cow::Owned(key.into_owned())),
+            ArrayKey::Bytes(key) => ArrayKey::Bytes(Cow::Owned(key.into_owned())),
         }
-        ht
+    }
+}
+
+/// Keys which are not valid UTF-8 are displayed with the invalid bytes
+/// replaced by `U+FFFD REPLACEMENT CHARACTER`.
+impl Display for ArrayKey<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArrayKey::Long(idx) => write!(f, "{}", idx),
+            ArrayKey::String(key) => write!(f, "{}", key),
+            ArrayKey::Bytes(key) => write!(f, "{}", String::from_utf8_lossy(key)),
+        }
+    }
+}
+
+/// Implemented on types which can be used as the key of an element in a PHP
+/// array, see [`ZendHashTable::insert`].
+///
+/// Implemented on strings and integers. Unsigned integers larger than
+/// [`i64::MAX`] wrap around to negative keys.
+pub trait IntoArrayKey<'a> {
+    /// Converts the value into an array key.
+    fn into_array_key(self) -> ArrayKey<'a>;
+}
+
+impl<'a> IntoArrayKey<'a> for ArrayKey<'a> {
+    fn into_array_key(self) -> ArrayKey<'a> {
+        self
+    }
+}
+
+impl<'a> IntoArrayKey<'a> for &'a str {
+    fn into_array_key(self) -> ArrayKey<'a> {
+        ArrayKey::String(Cow::Borrowed(self))
+    }
+}
+
+impl<'a> IntoArrayKey<'a> for &'a String {
+    fn into_array_key(self) -> ArrayKey<'a> {
+        ArrayKey::String(Cow::Borrowed(self.as_str()))
+    }
+}
+
+impl IntoArrayKey<'_> for String {
+    fn into_array_key(self) -> ArrayKey<'static> {
+        ArrayKey::String(Cow::Owned(self))
+    }
+}
+
+/// Implements [`IntoArrayKey`] for integer types.
+macro_rules! into_array_key_int {
+    ($($t: ty),*) => {
+        $(
+            impl IntoArrayKey<'_> for $t {
+                fn into_array_key(self) -> ArrayKey<'static> {
+                    ArrayKey::Long(self as i64)
+                }
+            }
+        )*
+    };
+}
+
+into_array_key_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, convert::TryFrom};
+
+    use super::{ArrayKey, IntoArrayKey};
+    use crate::{error::ConversionError, flags::DataType, types::Zval};
+
+    #[test]
+    fn test_into_array_key() {
+        assert_eq!("a".into_array_key(), ArrayKey::String("a".into()));
+        assert_eq!(
+            String::from("b").into_array_key(),
+            ArrayKey::String("b".into())
+        );
+        assert_eq!(5u32.into_array_key(), ArrayKey::Long(5));
+        assert_eq!((-1i64).into_array_key(), ArrayKey::Long(-1));
+        assert_eq!(u64::MAX.into_array_key(), ArrayKey::Long(-1));
+    }
+
+    #[test]
+    fn test_array_key_display() {
+        assert_eq!(ArrayKey::Long(5).to_string(), "5");
+        assert_eq!((-1i64).into_array_key().to_string(), "-1");
+        assert_eq!("key".into_array_key().to_string(), "key");
+        assert_eq!(
+            ArrayKey::Bytes((&b"a\xffb"[..]).into()).to_string(),
+            "a\u{fffd}b"
+        );
+    }
+
+    #[test]
+    fn test_conversion_error_key() {
+        let err = ConversionError::new(DataType::Long, &Zval::new());
+        assert_eq!(err.key(), None);
+
+        let err = err.with_key("price".into_array_key().into_owned());
+        assert_eq!(err.key(), Some(&ArrayKey::String("price".into())));
+        assert_eq!(
+            err.to_string(),
+            "expected int, got null for array key \"price\""
+        );
+
+        let err = ConversionError::new(DataType::Long, &Zval::new()).with_key(ArrayKey::Long(3));
+        assert_eq!(err.to_string(), "expected int, got null for array key 3");
+    }
+
+    #[test]
+    fn test_array_try_iter() {
+        let err = Zval::new()
+            .array_try_iter()
+            .err()
+            .expect("null iterated as an array");
+        assert_eq!(err.to_string(), "expected array, got null");
+    }
+
+    #[test]
+    fn test_try_from_zval_ref() {
+        let err = Vec::<i64>::try_from(&Zval::new()).expect_err("null converted into Vec");
+        assert_eq!(err.to_string(), "expected array, got null");
+        let err = HashMap::<String, i64>::try_from(&Zval::new())
+            .expect_err("null converted into HashMap");
+        assert_eq!(err.to_string(), "expected array, got null");
     }
 }
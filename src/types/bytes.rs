@@ -0,0 +1,67 @@
+//! Conversions between [`Bytes`] and binary-safe PHP strings, enabled by the
+//! `bytes` feature.
+//!
+//! The contents of a Zend string are stored inline, directly after its header
+//! and in memory allocated by PHP, so a Zend string cannot point to a buffer
+//! owned by Rust and a buffer cannot be handed to PHP without a copy. In the
+//! other direction, a [`Bytes`] cannot borrow a Zend string either, as the
+//! string may be freed at the end of the request while the buffer can outlive
+//! it. Both conversions therefore copy the contents exactly once, without
+//! validating them as UTF-8.
+
+use crate::{
+    convert::{FromZval, IntoZval},
+    error::Result,
+    flags::DataType,
+    macros::try_from_zval_ref,
+    types::Zval,
+};
+
+use ::bytes::{Bytes, BytesMut};
+
+/// Copies the contents of a PHP string into a [`Bytes`] buffer. Values which
+/// are not strings fail to convert.
+impl FromZval<'_> for Bytes {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.bytes().map(Bytes::copy_from_slice)
+    }
+}
+
+try_from_zval_ref!(Bytes);
+
+/// Copies the contents of the buffer into a binary-safe PHP string.
+impl IntoZval for Bytes {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        zv.set_bytes(&self, persistent);
+        Ok(())
+    }
+}
+
+/// Copies the contents of the buffer into a binary-safe PHP string.
+impl IntoZval for BytesMut {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        zv.set_bytes(&self, persistent);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{convert::FromZval, types::Zval};
+    use bytes::Bytes;
+
+    #[test]
+    fn test_non_string() {
+        let mut zv = Zval::new();
+        assert_eq!(Bytes::from_zval(&zv), None);
+
+        zv.set_long(0x00ff);
+        assert_eq!(Bytes::from_zval(&zv), None);
+    }
+}
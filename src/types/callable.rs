@@ -3,7 +3,7 @@
 use std::{convert::TryFrom, ops::Deref};
 
 use crate::{
-    convert::{FromZval, IntoZvalDyn},
+    convert::{FromZval, IntoZval, IntoZvalDyn},
     error::{Error, Result},
     ffi::_call_user_function_impl,
     flags::DataType,
@@ -13,9 +13,17 @@ use crate::{
 use super::Zval;
 
 /// Acts as a wrapper around a callable [`Zval`]. Allows the owner to call the
-/// [`Zval`] as if it was a PHP function through the [`try_call`] method.
+/// [`Zval`] as if it was a PHP function through the [`try_call`] and [`call`]
+/// methods.
+///
+/// When used as a parameter of a function exported to PHP, the parameter is
+/// typed as `callable`. The callable borrows the argument, and can be stored
+/// for the rest of the request by converting it into an owned callable with
+/// [`into_owned`].
 ///
 /// [`try_call`]: #method.try_call
+/// [`call`]: #method.call
+/// [`into_owned`]: #method.into_owned
 #[derive(Debug)]
 pub struct ZendCallable<'a>(OwnedZval<'a>);
 
@@ -100,16 +108,70 @@ impl<'a> ZendCallable<'a> {
     /// assert_eq!(result.long(), Some(1));
     /// ```
     pub fn try_call(&self, params: Vec<&dyn IntoZvalDyn>) -> Result<Zval> {
+        let params = params
+            .into_iter()
+            .map(|val| val.as_zval(false))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.call_zvals(params)
+    }
+
+    /// Calls the callable with a tuple of arguments, converting the value
+    /// returned from the callable into `R`.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - A tuple of up to 8 arguments, which must implement
+    ///   [`IntoZval`]. Use `()` to call the callable with no arguments.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted return value upon success. If calling the
+    /// callable fails or an exception is thrown, an [`Err`] is returned, and
+    /// if the return value cannot be converted into `R` an
+    /// [`Error::ZvalConversion`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendCallable;
+    ///
+    /// let strpos = ZendCallable::try_from_name("strpos").unwrap();
+    /// let pos: i64 = strpos.call(("hello", "e")).unwrap();
+    /// assert_eq!(pos, 1);
+    /// ```
+    pub fn call<A, R>(&self, args: A) -> Result<R>
+    where
+        A: IntoZvalArgs,
+        R: for<'b> FromZval<'b>,
+    {
+        let retval = self.call_zvals(args.into_zval_args()?)?;
+        R::from_zval(&retval).ok_or_else(|| Error::ZvalConversion(retval.get_type()))
+    }
+
+    /// Converts the callable into a callable which owns its [`Zval`], allowing
+    /// it to be stored after the function it was passed to has returned.
+    ///
+    /// The owned callable holds a reference to the underlying PHP value, so it
+    /// remains valid until it is dropped. It must not be used after the end of
+    /// the request it was created in, as PHP frees all values at the end of a
+    /// request.
+    pub fn into_owned(self) -> ZendCallable<'static> {
+        ZendCallable(OwnedZval::Owned(match self.0 {
+            OwnedZval::Reference(zv) => zv.shallow_clone(),
+            OwnedZval::Owned(zv) => zv,
+        }))
+    }
+
+    /// Calls the callable with the given arguments, returning the return
+    /// value of the callable.
+    pub(crate) fn call_zvals(&self, params: Vec<Zval>) -> Result<Zval> {
         if !self.0.is_callable() {
             return Err(Error::Callable);
         }
 
         let mut retval = Zval::new();
         let len = params.len();
-        let params = params
-            .into_iter()
-            .map(|val| val.as_zval(false))
-            .collect::<Result<Vec<_>>>()?;
         let packed = params.into_boxed_slice();
 
         let result = unsafe {
@@ -149,6 +211,41 @@ impl<'a> TryFrom<Zval> for ZendCallable<'a> {
     }
 }
 
+/// Implemented on tuples of arguments which can be passed to
+/// [`ZendCallable::call`]. Implemented for tuples of up to 8 elements which
+/// implement [`IntoZval`].
+pub trait IntoZvalArgs {
+    /// Converts the arguments into a list of zvals.
+    fn into_zval_args(self) -> Result<Vec<Zval>>;
+}
+
+macro_rules! into_zval_args_impl {
+    ($($arg: ident),*) => {
+        impl<$($arg: IntoZval),*> IntoZvalArgs for ($($arg,)*) {
+            #[allow(non_snake_case)]
+            fn into_zval_args(self) -> Result<Vec<Zval>> {
+                let ($($arg,)*) = self;
+                Ok(vec![$($arg.into_zval(false)?),*])
+            }
+        }
+    };
+}
+
+impl IntoZvalArgs for () {
+    fn into_zval_args(self) -> Result<Vec<Zval>> {
+        Ok(vec![])
+    }
+}
+
+into_zval_args_impl!(A);
+into_zval_args_impl!(A, B);
+into_zval_args_impl!(A, B, C);
+into_zval_args_impl!(A, B, C, D);
+into_zval_args_impl!(A, B, C, D, E);
+into_zval_args_impl!(A, B, C, D, E, F);
+into_zval_args_impl!(A, B, C, D, E, F, G);
+into_zval_args_impl!(A, B, C, D, E, F, G, H);
+
 /// A container for a zval. Either contains a reference to a zval or an owned
 /// zval.
 #[derive(Debug)]
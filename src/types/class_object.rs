@@ -270,13 +270,17 @@ impl<T: RegisteredClass + Clone> Clone for ZBox<ZendClassObject<T>> {
 impl<T: RegisteredClass> IntoZval for ZBox<ZendClassObject<T>> {
     const TYPE: DataType = DataType::Object(Some(T::CLASS_NAME));
 
-    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
-        let obj = self.into_raw();
-        zv.set_object(&mut obj.std);
-        Ok(())
+    #[inline]
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        // The reference held by the box is handed over to the zval.
+        ZendObject::from_class_object(self).set_zval(zv, persistent)
     }
 }
 
+/// Returns a new reference to an existing object, such as `$this`, rather than
+/// a copy. The reference counter of the object is incremented once, so the
+/// object is freed when both the original and the returned value have been
+/// released.
 impl<T: RegisteredClass> IntoZval for &mut ZendClassObject<T> {
     const TYPE: DataType = DataType::Object(Some(T::CLASS_NAME));
 
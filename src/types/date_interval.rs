@@ -0,0 +1,288 @@
+//! Conversions between PHP `DateInterval` objects, [`DateInterval`] and
+//! [`Duration`].
+//!
+//! Only [`std::time::Duration`] is supported. Conversions into the durations
+//! of the `chrono` and `time` crates are not provided, and can go through
+//! [`Duration`] instead.
+
+use std::{convert::TryFrom, fmt::Display, time::Duration};
+
+use crate::{
+    convert::{FromZval, IntoZval},
+    error::{Error, Result},
+    exception::PhpException,
+    flags::DataType,
+    types::{ZendCallable, ZendHashTable, Zval},
+    zend::{ce, ClassEntry},
+};
+
+/// An interval of time, converted to and from a PHP `DateInterval`.
+///
+/// The fields are the same as the fields of a PHP `DateInterval`, and are kept
+/// as given rather than being normalized, so an interval of 90 minutes stays
+/// 90 minutes. As with PHP, the sign of the interval is given by `invert` and
+/// the fields are usually positive.
+///
+/// Years and months do not have a fixed length, and a day is not always 24
+/// hours long when the interval is added to a date in a timezone with daylight
+/// saving time. The interval can only be converted into a [`Duration`] when it
+/// has no years or months, or when the total number of days it spans is known,
+/// and a day is then counted as 24 hours.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ext_php_rs::prelude::*;
+/// use ext_php_rs::types::DateInterval;
+///
+/// /// Returns the interval doubled.
+/// #[php_function]
+/// pub fn double_interval(interval: DateInterval) -> DateInterval {
+///     DateInterval {
+///         days: interval.days * 2,
+///         hours: interval.hours * 2,
+///         minutes: interval.minutes * 2,
+///         seconds: interval.seconds * 2,
+///         ..interval
+///     }
+/// }
+/// # #[php_module]
+/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DateInterval {
+    /// Number of years, the `y` property.
+    pub years: i64,
+    /// Number of months, the `m` property.
+    pub months: i64,
+    /// Number of days, the `d` property.
+    pub days: i64,
+    /// Number of hours, the `h` property.
+    pub hours: i64,
+    /// Number of minutes, the `i` property.
+    pub minutes: i64,
+    /// Number of seconds, the `s` property.
+    pub seconds: i64,
+    /// Number of microseconds, the `f` property multiplied by one million.
+    pub microseconds: i64,
+    /// Whether the interval is negative, the `invert` property.
+    pub invert: bool,
+    /// Total number of days spanned by the interval, the `days` property.
+    /// Only known for intervals returned by `DateTime::diff()`, where it
+    /// accounts for the varying length of months and years.
+    pub total_days: Option<i64>,
+}
+
+impl DateInterval {
+    /// Reads the fields of an interval from the properties of a PHP
+    /// `DateInterval`.
+    fn from_properties(props: &ZendHashTable) -> Option<Self> {
+        let int = |name| props.get(name)?.long();
+
+        Some(Self {
+            years: int("y")?,
+            months: int("m")?,
+            days: int("d")?,
+            hours: int("h")?,
+            minutes: int("i")?,
+            seconds: int("s")?,
+            microseconds: (props.get("f")?.double()? * 1_000_000.0).round() as i64,
+            invert: int("invert")? != 0,
+            // Intervals which were not created by `DateTime::diff()` have their
+            // total number of days set to `false`.
+            total_days: int("days"),
+        })
+    }
+}
+
+impl FromZval<'_> for DateInterval {
+    const TYPE: DataType = DataType::Object(Some("DateInterval"));
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        let obj = zval.object()?;
+        if !obj.instance_of(ClassEntry::try_find("DateInterval")?) {
+            return None;
+        }
+
+        // The fields are read from the properties rather than through
+        // `format()`, which can be overridden by subclasses. The properties
+        // of the interval are only filled in when they are requested.
+        Self::from_properties(obj.get_properties().ok()?)
+    }
+}
+
+try_from_zval_ref!(DateInterval);
+
+/// Creates a PHP `DateInterval` through `DateInterval::__set_state()`, which
+/// sets each field of the interval as given.
+impl IntoZval for DateInterval {
+    const TYPE: DataType = DataType::Object(Some("DateInterval"));
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        let mut fields = ZendHashTable::new();
+        fields.insert("y", self.years)?;
+        fields.insert("m", self.months)?;
+        fields.insert("d", self.days)?;
+        fields.insert("h", self.hours)?;
+        fields.insert("i", self.minutes)?;
+        fields.insert("s", self.seconds)?;
+        fields.insert("f", self.microseconds as f64 / 1_000_000.0)?;
+        fields.insert("invert", self.invert as i64)?;
+        if let Some(total_days) = self.total_days {
+            fields.insert("days", total_days)?;
+        }
+
+        let set_state = ZendCallable::try_from_name("DateInterval::__set_state")
+            .map_err(|_| Error::UnknownClass("DateInterval".into()))?;
+        let interval = set_state.call_zvals(vec![fields.into_zval(false)?])?;
+        interval.set_zval(zv, false)
+    }
+}
+
+/// Error returned when a [`DateInterval`] cannot be converted into a
+/// [`Duration`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationError {
+    /// The interval has years or months, which do not have a fixed length, and
+    /// the total number of days of the interval is not known.
+    Ambiguous,
+    /// The interval is negative.
+    Negative,
+    /// The interval is too long to be held by a [`Duration`].
+    Overflow,
+}
+
+impl Display for DurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DurationError::Ambiguous => write!(
+                f,
+                "Interval with years or months does not have a fixed length."
+            ),
+            DurationError::Negative => write!(f, "Interval is negative."),
+            DurationError::Overflow => write!(f, "Interval is too long."),
+        }
+    }
+}
+
+impl std::error::Error for DurationError {}
+
+impl From<DurationError> for PhpException {
+    fn from(err: DurationError) -> Self {
+        Self::new(err.to_string(), 0, ce::value_error())
+    }
+}
+
+/// Converts an interval into a [`Duration`], counting a day as 24 hours.
+///
+/// Intervals returned by `DateTime::diff()` are converted using their total
+/// number of days. Other intervals with years or months cannot be converted,
+/// as the length of a month or year depends on the date the interval is added
+/// to. Negative intervals cannot be converted either.
+impl TryFrom<DateInterval> for Duration {
+    type Error = DurationError;
+
+    fn try_from(interval: DateInterval) -> std::result::Result<Self, Self::Error> {
+        let days = match interval.total_days {
+            Some(total_days) => total_days,
+            None if interval.years != 0 || interval.months != 0 => {
+                return Err(DurationError::Ambiguous)
+            }
+            None => interval.days,
+        };
+
+        let seconds =
+            ((days as i128 * 24 + interval.hours as i128) * 60 + interval.minutes as i128) * 60
+                + interval.seconds as i128;
+        let micros = seconds * 1_000_000 + interval.microseconds as i128;
+
+        if micros < 0 || (interval.invert && micros != 0) {
+            return Err(DurationError::Negative);
+        }
+        u64::try_from(micros)
+            .map(Duration::from_micros)
+            .map_err(|_| DurationError::Overflow)
+    }
+}
+
+/// Converts a [`Duration`] into an interval of days, hours, minutes, seconds
+/// and microseconds. Nanoseconds which do not make up a whole microsecond are
+/// discarded, as PHP intervals hold microseconds.
+impl From<Duration> for DateInterval {
+    fn from(duration: Duration) -> Self {
+        let secs = duration.as_secs();
+
+        Self {
+            days: (secs / 86_400) as i64,
+            hours: (secs / 3_600 % 24) as i64,
+            minutes: (secs / 60 % 60) as i64,
+            seconds: (secs % 60) as i64,
+            microseconds: duration.subsec_micros() as i64,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DateInterval, DurationError};
+    use std::{convert::TryFrom, time::Duration};
+
+    #[test]
+    fn test_duration_round_trip() {
+        // 1 day, 2 hours, 3 minutes, 4.25 seconds.
+        let duration = Duration::from_micros(93_784_250_000);
+        let interval = DateInterval::from(duration);
+        assert_eq!(
+            interval,
+            DateInterval {
+                days: 1,
+                hours: 2,
+                minutes: 3,
+                seconds: 4,
+                microseconds: 250_000,
+                ..Default::default()
+            }
+        );
+        assert_eq!(Duration::try_from(interval), Ok(duration));
+
+        // Nanoseconds are discarded.
+        let interval = DateInterval::from(Duration::from_nanos(1_500));
+        assert_eq!(interval.microseconds, 1);
+    }
+
+    #[test]
+    fn test_duration_lossy_edges() {
+        let month = DateInterval {
+            months: 1,
+            ..Default::default()
+        };
+        assert_eq!(Duration::try_from(month), Err(DurationError::Ambiguous));
+
+        // The total number of days of an interval from `DateTime::diff()` is
+        // used instead of its years, months and days.
+        let diff = DateInterval {
+            total_days: Some(31),
+            ..month
+        };
+        assert_eq!(
+            Duration::try_from(diff),
+            Ok(Duration::from_secs(31 * 86_400))
+        );
+
+        let negative = DateInterval {
+            hours: 1,
+            invert: true,
+            ..Default::default()
+        };
+        assert_eq!(Duration::try_from(negative), Err(DurationError::Negative));
+
+        let long = DateInterval {
+            days: i64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(Duration::try_from(long), Err(DurationError::Overflow));
+    }
+}
@@ -0,0 +1,300 @@
+//! Conversions between [`Value`] and PHP values, enabled by the `serde_json`
+//! feature.
+//!
+//! PHP has a single array type for both lists and maps, so arrays are
+//! converted into JSON arrays when their keys are the integers `0` to `n - 1`
+//! in order, the same rule used by `json_encode()`. Any other array, including
+//! an array with the same keys in a different order, is converted into a JSON
+//! object, with integer keys converted into strings.
+//!
+//! The keys of JSON objects are sorted unless the `preserve_order` feature of
+//! `serde_json` is enabled, which is not enabled or forwarded by this crate.
+//! An extension can enable it on its own `serde_json` dependency to keep the
+//! order of PHP arrays.
+
+use std::convert::TryFrom;
+
+use crate::{
+    convert::{FromZval, IntoZval},
+    error::{Error, Result},
+    ffi::zend_long,
+    flags::DataType,
+    macros::try_from_zval_ref,
+    types::{ArrayKey, ZendHashTable, Zval},
+};
+
+use ::serde_json::{Map, Number, Value};
+
+/// Maximum nesting depth of arrays and objects converted into a [`Value`],
+/// matching the default depth of `json_encode()`. Arrays nested deeper, such as
+/// an array which contains a reference to itself, fail to convert.
+const MAX_DEPTH: usize = 512;
+
+/// Converts a zval into a [`Value`], failing when the zval or one of its
+/// elements cannot be represented as JSON.
+fn from_zval(zval: &Zval, depth: usize) -> Option<Value> {
+    if let Some(zval) = zval.reference() {
+        return from_zval(zval, depth);
+    }
+
+    if zval.is_null() {
+        Some(Value::Null)
+    } else if let Some(val) = zval.bool() {
+        Some(Value::Bool(val))
+    } else if let Some(val) = zval.long() {
+        Some(Value::Number(val.into()))
+    } else if zval.is_double() {
+        zval.double().and_then(Number::from_f64).map(Value::Number)
+    } else if let Some(val) = zval.str() {
+        Some(Value::String(val.into()))
+    } else if depth >= MAX_DEPTH {
+        None
+    } else if let Some(arr) = zval.array() {
+        from_entries(arr.entries(), |val| from_zval(val, depth + 1))
+    } else if let Some(obj) = zval.object() {
+        // Mangled names of protected and private properties start with a NUL
+        // byte, and are not public like the rest of the properties.
+        let props = obj
+            .get_properties()
+            .ok()?
+            .entries()
+            .filter(|(key, _)| !matches!(key, ArrayKey::String(key) if key.starts_with('\0')));
+        from_entries(props, |val| from_zval(val, depth + 1)).map(|val| match val {
+            Value::Array(list) if list.is_empty() => Value::Object(Map::new()),
+            val => val,
+        })
+    } else {
+        None
+    }
+}
+
+/// Converts the entries of a PHP array into a JSON array when the keys are
+/// sequential integers starting at zero, or into a JSON object otherwise.
+///
+/// # Parameters
+///
+/// * `entries` - The keys and values of the array.
+/// * `convert` - Converts a value of the array into a [`Value`].
+fn from_entries<'a, V, I, F>(entries: I, mut convert: F) -> Option<Value>
+where
+    I: IntoIterator<Item = (ArrayKey<'a>, V)>,
+    F: FnMut(V) -> Option<Value>,
+{
+    let mut list = Vec::new();
+    let mut map: Option<Map<String, Value>> = None;
+
+    for (key, val) in entries {
+        // JSON object keys are strings, which must be valid UTF-8.
+        if let ArrayKey::Bytes(_) = key {
+            return None;
+        }
+        let val = convert(val)?;
+        match &mut map {
+            Some(map) => {
+                map.insert(key.to_string(), val);
+            }
+            None if key == ArrayKey::Long(list.len() as i64) => list.push(val),
+            None => {
+                let mut items = list
+                    .drain(..)
+                    .enumerate()
+                    .map(|(idx, val)| (idx.to_string(), val))
+                    .collect::<Map<_, _>>();
+                items.insert(key.to_string(), val);
+                map = Some(items);
+            }
+        }
+    }
+
+    Some(match map {
+        Some(map) => Value::Object(map),
+        None => Value::Array(list),
+    })
+}
+
+/// Returns the key of a PHP array element for the key of a JSON object. PHP
+/// stores string keys containing a decimal integer, such as `"1"`, as integer
+/// keys, so these keys are converted in the same way.
+fn array_key(key: String) -> ArrayKey<'static> {
+    match key.parse::<zend_long>() {
+        Ok(idx) if idx.to_string() == key => ArrayKey::Long(idx),
+        _ => ArrayKey::String(key.into()),
+    }
+}
+
+/// Converts a PHP value into a [`Value`]. Arrays are converted into JSON
+/// arrays or objects following the rule described in the [module
+/// documentation](self), and objects are converted into JSON objects containing
+/// their public properties. Doubles which are not finite, invalid UTF-8 strings
+/// and array keys, and other types such as resources fail to convert.
+impl FromZval<'_> for Value {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        from_zval(zval, 0)
+    }
+}
+
+try_from_zval_ref!(Value);
+
+/// Converts a [`Value`] into a PHP value. JSON arrays become list arrays, and
+/// JSON objects become arrays with string keys, in the same way as
+/// `json_decode($json, true)`.
+impl IntoZval for Value {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        match self {
+            Value::Null => zv.set_null(),
+            Value::Bool(val) => zv.set_bool(val),
+            Value::Number(val) => match val.as_i64().map(zend_long::try_from) {
+                Some(Ok(val)) => zv.set_long(val),
+                _ => zv.set_double(val.as_f64().unwrap_or(f64::NAN)),
+            },
+            Value::String(val) => zv.set_string(&val, persistent)?,
+            Value::Array(list) => {
+                let mut ht = ZendHashTable::with_capacity(
+                    u32::try_from(list.len()).map_err(|_| Error::IntegerOverflow)?,
+                );
+                for val in list {
+                    ht.push(val)?;
+                }
+                zv.set_hashtable(ht);
+            }
+            Value::Object(map) => {
+                let mut ht = ZendHashTable::with_capacity(
+                    u32::try_from(map.len()).map_err(|_| Error::IntegerOverflow)?,
+                );
+                for (key, val) in map {
+                    ht.insert(array_key(key), val)?;
+                }
+                zv.set_hashtable(ht);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{array_key, from_entries, from_zval};
+    use crate::{convert::IntoZval, types::ArrayKey};
+    use serde_json::{json, Value};
+
+    /// Converts entries whose values are already JSON values.
+    fn convert<'a>(entries: Vec<(ArrayKey<'a>, Value)>) -> Value {
+        from_entries(entries, Some).expect("entries failed to convert")
+    }
+
+    fn key(key: &str) -> ArrayKey<'_> {
+        ArrayKey::String(key.into())
+    }
+
+    #[test]
+    fn test_list_or_map() {
+        assert_eq!(convert(vec![]), json!([]));
+        assert_eq!(
+            convert(vec![
+                (ArrayKey::Long(0), json!("a")),
+                (ArrayKey::Long(1), json!("b"))
+            ]),
+            json!(["a", "b"])
+        );
+        assert_eq!(
+            convert(vec![(ArrayKey::Long(1), json!("a"))]),
+            json!({ "1": "a" })
+        );
+        assert_eq!(
+            convert(vec![
+                (ArrayKey::Long(0), json!("a")),
+                (ArrayKey::Long(2), json!("b"))
+            ]),
+            json!({ "0": "a", "2": "b" })
+        );
+        assert_eq!(
+            convert(vec![
+                (ArrayKey::Long(1), json!("b")),
+                (ArrayKey::Long(0), json!("a"))
+            ]),
+            json!({ "0": "a", "1": "b" })
+        );
+        assert_eq!(
+            convert(vec![
+                (ArrayKey::Long(0), json!("a")),
+                (key("name"), json!("b"))
+            ]),
+            json!({ "0": "a", "name": "b" })
+        );
+    }
+
+    #[test]
+    fn test_nested() {
+        let tags = convert(vec![
+            (ArrayKey::Long(0), json!("x")),
+            (ArrayKey::Long(1), json!("y")),
+        ]);
+        let empty = convert(vec![]);
+        let user = convert(vec![
+            (key("id"), json!(1)),
+            (key("tags"), tags),
+            (key("meta"), empty),
+        ]);
+        let users = convert(vec![
+            (ArrayKey::Long(0), user.clone()),
+            (ArrayKey::Long(1), user),
+        ]);
+
+        assert_eq!(
+            users,
+            json!([
+                { "id": 1, "tags": ["x", "y"], "meta": [] },
+                { "id": 1, "tags": ["x", "y"], "meta": [] },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_failed_element() {
+        let entries = vec![
+            (ArrayKey::Long(0), Some(json!(1))),
+            (ArrayKey::Long(1), None),
+        ];
+        assert_eq!(from_entries(entries, |val| val), None);
+
+        let entries = vec![(ArrayKey::Bytes((&b"\xff"[..]).into()), json!(1))];
+        assert_eq!(from_entries(entries, Some), None);
+    }
+
+    #[test]
+    fn test_array_key() {
+        assert_eq!(array_key("1".into()), ArrayKey::Long(1));
+        assert_eq!(array_key("-1".into()), ArrayKey::Long(-1));
+        for key in ["01", "-0", "1.0", " 1", "+1", "a", "9223372036854775808"] {
+            assert_eq!(array_key(key.into()), ArrayKey::String(key.into()));
+        }
+    }
+
+    #[test]
+    fn test_scalar_round_trip() {
+        for val in [
+            json!(null),
+            json!(true),
+            json!(false),
+            json!(42),
+            json!(-7),
+            json!(1.5),
+        ] {
+            let zv = val
+                .clone()
+                .into_zval(false)
+                .expect("failed to convert value");
+            assert_eq!(from_zval(&zv, 0), Some(val));
+        }
+
+        let zv = json!(u64::MAX)
+            .into_zval(false)
+            .expect("failed to convert value");
+        assert_eq!(zv.double(), Some(u64::MAX as f64));
+    }
+}
@@ -3,15 +3,21 @@
 //! it is 64-bits.
 
 use crate::{
-    convert::IntoZval,
+    convert::{FromZval, IntoZval},
     error::{Error, Result},
     ffi::zend_long,
     flags::DataType,
-    macros::{into_zval, try_from_zval},
+    macros::{into_zval, try_from_zval, try_from_zval_ref},
     types::Zval,
 };
 
-use std::convert::{TryFrom, TryInto};
+use std::{
+    convert::{TryFrom, TryInto},
+    num::{
+        NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU16, NonZeroU32,
+        NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+};
 
 /// A PHP long.
 ///
@@ -70,3 +76,115 @@ try_from_zval!(u64, long, Long);
 
 try_from_zval!(usize, long, Long);
 try_from_zval!(isize, long, Long);
+
+/// Implements the conversion traits for a non-zero integer type, which are
+/// converted to and from PHP integers in the same way as the underlying
+/// integer type. Converting a zero from PHP fails, so a function taking a
+/// non-zero integer as a parameter rejects zero as an invalid argument.
+macro_rules! non_zero_int {
+    ($type: ty, $int: ty) => {
+        impl IntoZval for $type {
+            const TYPE: DataType = DataType::Long;
+
+            fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+                self.get().set_zval(zv, persistent)
+            }
+        }
+
+        impl FromZval<'_> for $type {
+            const TYPE: DataType = DataType::Long;
+
+            fn from_zval(zval: &Zval) -> Option<Self> {
+                <$int>::from_zval(zval).and_then(<$type>::new)
+            }
+        }
+
+        impl TryFrom<Zval> for $type {
+            type Error = Error;
+
+            fn try_from(value: Zval) -> Result<Self> {
+                Self::from_zval(&value).ok_or_else(|| Error::ZvalConversion(value.get_type()))
+            }
+        }
+
+        try_from_zval_ref!($type);
+    };
+}
+
+non_zero_int!(NonZeroI8, i8);
+non_zero_int!(NonZeroI16, i16);
+non_zero_int!(NonZeroI32, i32);
+non_zero_int!(NonZeroI64, i64);
+non_zero_int!(NonZeroIsize, isize);
+
+non_zero_int!(NonZeroU8, u8);
+non_zero_int!(NonZeroU16, u16);
+non_zero_int!(NonZeroU32, u32);
+non_zero_int!(NonZeroU64, u64);
+non_zero_int!(NonZeroUsize, usize);
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::TryFrom,
+        num::{NonZeroI64, NonZeroU32, NonZeroU8},
+    };
+
+    use crate::{
+        convert::{FromZval, IntoZval},
+        types::Zval,
+    };
+
+    fn long(val: i64) -> Zval {
+        let mut zv = Zval::new();
+        zv.set_long(val);
+        zv
+    }
+
+    #[test]
+    fn test_non_zero_from_zval() {
+        assert_eq!(NonZeroU32::from_zval(&long(5)), NonZeroU32::new(5));
+        assert_eq!(NonZeroI64::from_zval(&long(-5)), NonZeroI64::new(-5));
+        assert!(NonZeroU32::try_from(long(7)).is_ok());
+    }
+
+    #[test]
+    fn test_non_zero_rejects_zero() {
+        assert_eq!(NonZeroU8::from_zval(&long(0)), None);
+        assert_eq!(NonZeroU32::from_zval(&long(0)), None);
+        assert_eq!(NonZeroI64::from_zval(&long(0)), None);
+        assert!(NonZeroI64::try_from(long(0)).is_err());
+    }
+
+    #[test]
+    fn test_non_zero_rejects_out_of_range() {
+        assert_eq!(NonZeroU8::from_zval(&long(256)), None);
+        assert_eq!(NonZeroU32::from_zval(&long(-1)), None);
+        assert_eq!(NonZeroU32::from_zval(&Zval::new()), None);
+    }
+
+    #[test]
+    fn test_try_from_zval_ref() {
+        assert_eq!(i64::try_from(&long(5)), Ok(5));
+        assert_eq!(NonZeroU8::try_from(&long(5)).ok(), NonZeroU8::new(5));
+
+        let err = u8::try_from(&Zval::new()).expect_err("null converted into u8");
+        assert_eq!(err.to_string(), "expected int, got null");
+        let err = NonZeroU32::try_from(&long(0)).expect_err("zero converted into NonZeroU32");
+        assert_eq!(err.to_string(), "invalid int value");
+    }
+
+    #[test]
+    fn test_non_zero_into_zval() {
+        let zv = NonZeroU32::new(42)
+            .expect("42 is not zero")
+            .into_zval(false)
+            .expect("failed to convert NonZeroU32");
+        assert_eq!(zv.long(), Some(42));
+        let zv = NonZeroI64::new(-42)
+            .expect("-42 is not zero")
+            .into_zval(false)
+            .expect("failed to convert NonZeroI64");
+        assert_eq!(zv.long(), Some(-42));
+    }
+}
@@ -4,22 +4,44 @@
 //! these PHP types when required.
 
 mod array;
+#[cfg(feature = "bytes")]
+#[cfg_attr(docs, doc(cfg(feature = "bytes")))]
+mod bytes;
 mod callable;
 mod class_object;
+#[cfg(any(docs, feature = "date_interval"))]
+#[cfg_attr(docs, doc(cfg(feature = "date_interval")))]
+mod date_interval;
+#[cfg(feature = "serde_json")]
+#[cfg_attr(docs, doc(cfg(feature = "serde_json")))]
+mod json_value;
 mod long;
 mod object;
+mod omittable;
 mod string;
+#[cfg(feature = "uuid")]
+#[cfg_attr(docs, doc(cfg(feature = "uuid")))]
+mod uuid;
 mod zval;
+mod zval_ref;
 
-pub use array::ZendHashTable;
-pub use callable::ZendCallable;
+pub use array::{ArrayKey, IntoArrayKey, ZendHashTable};
+pub use callable::{IntoZvalArgs, ZendCallable};
 pub use class_object::ZendClassObject;
+#[cfg(any(docs, feature = "date_interval"))]
+pub use date_interval::{DateInterval, DurationError};
 pub use long::ZendLong;
 pub use object::{PropertyQuery, ZendObject};
+pub use omittable::Omittable;
 pub use string::ZendStr;
 pub use zval::Zval;
+pub use zval_ref::ZvalRef;
 
-use crate::{convert::FromZval, flags::DataType, macros::into_zval};
+use crate::{
+    convert::FromZval,
+    flags::DataType,
+    macros::{into_zval, try_from_zval_ref},
+};
 
 into_zval!(f32, set_double, Double);
 into_zval!(f64, set_double, Double);
@@ -35,3 +57,5 @@ impl FromZval<'_> for f32 {
         zval.double().map(|v| v as f32)
     }
 }
+
+try_from_zval_ref!(f32);
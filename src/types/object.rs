@@ -14,7 +14,7 @@ use crate::{
     },
     flags::DataType,
     rc::PhpRc,
-    types::{ZendClassObject, ZendStr, Zval},
+    types::{IntoZvalArgs, ZendCallable, ZendClassObject, ZendStr, Zval},
     zend::{ce, ClassEntry, ExecutorGlobals, ZendObjectHandlers},
 };
 
@@ -97,6 +97,17 @@ impl ZendObject {
         (self.ce as *const ClassEntry).eq(&(T::get_metadata().ce() as *const _))
     }
 
+    /// Checks if the object is an instance of the given class, or of a class
+    /// which extends or implements it.
+    ///
+    /// # Parameters
+    ///
+    /// * `ce` - The class or interface to check against.
+    pub fn instance_of(&self, ce: &ClassEntry) -> bool {
+        // SAFETY: All initialized objects have a class entry.
+        unsafe { self.ce.as_ref() }.is_some_and(|class| class.instance_of(ce))
+    }
+
     /// Attempts to read a property from the Object. Returns a result containing
     /// the value of the property if it exists and can be read, and an
     /// [`Error`] otherwise.
@@ -186,6 +197,101 @@ impl ZendObject {
         }
     }
 
+    /// Calls the method with the given name on the object, returning the value
+    /// returned by the method.
+    ///
+    /// The method is called in the same way as `$obj->name(...$args)` in PHP,
+    /// so userland methods and methods handled by `__call` can be called.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    /// * `args` - A tuple of up to 8 arguments, which must implement
+    ///   [`IntoZval`]. Use `()` to call the method with no arguments.
+    ///
+    /// # Returns
+    ///
+    /// Returns the return value of the method upon success. If the method does
+    /// not exist or cannot be called, an [`Error::UnknownMethod`] is returned.
+    /// If the method throws an exception, an [`Error::Exception`] containing
+    /// the exception is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{prelude::*, types::ZendObject};
+    ///
+    /// /// Notifies the listener of an event, returning whether the event was
+    /// /// handled.
+    /// #[php_function]
+    /// pub fn notify(listener: &ZendObject, event: String) -> PhpResult<bool> {
+    ///     let handled = listener.call_method("handle", (event, 1))?;
+    ///     Ok(handled.bool().unwrap_or(false))
+    /// }
+    /// # #[php_module]
+    /// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+    /// #     module
+    /// # }
+    /// ```
+    pub fn call_method(&self, name: &str, args: impl IntoZvalArgs) -> Result<Zval> {
+        let mut object = Zval::new();
+        // SAFETY: Setting the object on the zval only increments its reference
+        // count.
+        object.set_object(unsafe { &mut *self.mut_ptr() });
+
+        let mut callable = Zval::new();
+        callable.set_array(vec![object, name.into_zval(false)?])?;
+
+        let callable = ZendCallable::new_owned(callable).map_err(|_| {
+            Error::UnknownMethod(format!(
+                "{}::{}",
+                self.get_class_name().unwrap_or_default(),
+                name
+            ))
+        })?;
+        callable.call_zvals(args.into_zval_args()?)
+    }
+
+    /// Calls the method with the given name on the object, converting the
+    /// value returned by the method into `T`. See [`ZendObject::call_method`]
+    /// for more information.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the method.
+    /// * `args` - A tuple of up to 8 arguments, which must implement
+    ///   [`IntoZval`].
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted return value upon success, or an
+    /// [`Error::ZvalConversion`] if the return value cannot be converted into
+    /// `T`. Otherwise, returns the same errors as [`ZendObject::call_method`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{prelude::*, types::ZendObject};
+    ///
+    /// /// Returns the total of the cart, as computed by its userland `total`
+    /// /// method.
+    /// #[php_function]
+    /// pub fn cart_total(cart: &ZendObject, tax: f64) -> PhpResult<f64> {
+    ///     Ok(cart.call_method_into::<f64>("total", (tax,))?)
+    /// }
+    /// # #[php_module]
+    /// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+    /// #     module
+    /// # }
+    /// ```
+    pub fn call_method_into<T>(&self, name: &str, args: impl IntoZvalArgs) -> Result<T>
+    where
+        T: for<'a> FromZval<'a>,
+    {
+        let retval = self.call_method(name, args)?;
+        T::from_zval(&retval).ok_or_else(|| Error::ZvalConversion(retval.get_type()))
+    }
+
     /// Extracts some type from a Zend object.
     ///
     /// This is a wrapper function around `FromZendObject::extract()`.
@@ -265,6 +371,8 @@ impl IntoZval for ZBox<ZendObject> {
     }
 }
 
+/// Returns a new reference to an existing object rather than a copy. See the
+/// [`IntoZval`] implementation of [`&mut ZendClassObject<T>`](ZendClassObject).
 impl<'a> IntoZval for &'a mut ZendObject {
     const TYPE: DataType = DataType::Object(None);
 
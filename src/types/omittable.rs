@@ -0,0 +1,138 @@
+//! Types related to optional arguments which can be omitted when calling PHP
+//! functions.
+
+use crate::{convert::FromZval, flags::DataType};
+
+use super::Zval;
+
+/// An optional argument of a function exported to PHP, which distinguishes an
+/// argument given as `null` from an argument which was not given at all. An
+/// [`Option`] parameter is [`None`] in both cases.
+///
+/// When used as a parameter of a function exported to PHP, the parameter is
+/// registered as optional and nullable, with a default value of `null`:
+///
+/// * [`Omittable::Present`] - The argument was given a value other than
+///   `null`.
+/// * [`Omittable::Null`] - The argument was given as `null`, or was skipped
+///   with named arguments, in which case PHP passes the default value of the
+///   parameter.
+/// * [`Omittable::Absent`] - The argument was not given, as the function was
+///   called with fewer arguments.
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// use ext_php_rs::types::Omittable;
+///
+/// /// Updates the nickname of the user, removing it if `null` is given, and
+/// /// leaving it unchanged if no nickname is given.
+/// #[php_function]
+/// pub fn update_nickname(id: i64, nickname: Omittable<String>) -> String {
+///     match nickname {
+///         Omittable::Present(nickname) => format!("user {} is now {}", id, nickname),
+///         Omittable::Null => format!("user {} has no nickname", id),
+///         Omittable::Absent => format!("user {} is unchanged", id),
+///     }
+/// }
+/// # #[php_module]
+/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// ```
+///
+/// ```php
+/// <?php
+///
+/// update_nickname(1, 'Dave'); // user 1 is now Dave
+/// update_nickname(1, null); // user 1 has no nickname
+/// update_nickname(1); // user 1 is unchanged
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Omittable<T> {
+    /// The argument was given a value other than `null`.
+    Present(T),
+    /// The argument was given as `null`.
+    Null,
+    /// The argument was not given.
+    #[default]
+    Absent,
+}
+
+impl<T> Omittable<T> {
+    /// Returns true if the argument was given a value other than `null`.
+    pub fn is_present(&self) -> bool {
+        matches!(self, Self::Present(_))
+    }
+
+    /// Returns true if the argument was given as `null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns true if the argument was not given.
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Self::Absent)
+    }
+
+    /// Converts the argument into an [`Option`], discarding whether the
+    /// argument was given as `null` or not given.
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Self::Present(val) => Some(val),
+            Self::Null | Self::Absent => None,
+        }
+    }
+}
+
+impl<T> From<Omittable<T>> for Option<T> {
+    fn from(val: Omittable<T>) -> Self {
+        val.into_option()
+    }
+}
+
+impl<'a, T> FromZval<'a> for Omittable<T>
+where
+    T: FromZval<'a>,
+{
+    const TYPE: DataType = T::TYPE;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        if zval.is_null() {
+            Some(Self::Null)
+        } else {
+            T::from_zval(zval).map(Self::Present)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Omittable;
+    use crate::{convert::FromZval, types::Zval};
+
+    #[test]
+    fn test_omittable_from_zval() {
+        let mut zv = Zval::new();
+        zv.set_null();
+        assert_eq!(Omittable::<i64>::from_zval(&zv), Some(Omittable::Null));
+
+        zv.set_long(5);
+        assert_eq!(
+            Omittable::<i64>::from_zval(&zv),
+            Some(Omittable::Present(5))
+        );
+
+        zv.set_bool(true);
+        assert_eq!(Omittable::<i64>::from_zval(&zv), None);
+    }
+
+    #[test]
+    fn test_omittable_into_option() {
+        assert_eq!(Omittable::Present(5).into_option(), Some(5));
+        assert_eq!(Omittable::<i64>::Null.into_option(), None);
+        assert_eq!(Omittable::<i64>::default(), Omittable::Absent);
+        assert_eq!(Option::<i64>::from(Omittable::Absent), None);
+    }
+}
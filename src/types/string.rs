@@ -5,8 +5,8 @@ use std::{
     borrow::Cow,
     convert::TryFrom,
     ffi::{CStr, CString},
-    fmt::Debug,
-    slice,
+    fmt::{self, Debug},
+    ptr, slice, str,
 };
 
 use parking_lot::{const_mutex, Mutex};
@@ -16,8 +16,8 @@ use crate::{
     convert::{FromZval, IntoZval},
     error::{Error, Result},
     ffi::{
-        ext_php_rs_zend_string_init, ext_php_rs_zend_string_release, zend_string,
-        zend_string_init_interned,
+        ext_php_rs_zend_string_alloc, ext_php_rs_zend_string_init, ext_php_rs_zend_string_realloc,
+        ext_php_rs_zend_string_release, zend_string, zend_string_init_interned,
     },
     flags::DataType,
     macros::try_from_zval,
@@ -126,6 +126,135 @@ impl ZendStr {
         }
     }
 
+    /// Creates a new Zend string from a slice of bytes. Unlike
+    /// [`new`](#method.new), the bytes are copied as-is with their exact
+    /// length, so they may contain NUL bytes and do not need to be valid
+    /// UTF-8.
+    ///
+    /// # Parameters
+    ///
+    /// * `bytes` - String content.
+    /// * `persistent` - Whether the string should persist through the request
+    ///   boundary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function was unable to allocate memory for the Zend
+    /// string.
+    ///
+    /// # Safety
+    ///
+    /// When passing `persistent` as `false`, the caller must ensure that the
+    /// object does not attempt to live after the request finishes. When a
+    /// request starts and finishes in PHP, the Zend heap is deallocated and a
+    /// new one is created, which would leave a dangling pointer in the
+    /// [`ZBox`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::from_bytes(b"hello\0world", false);
+    /// assert_eq!(s.len(), 11);
+    /// assert_eq!(s.as_bytes(), b"hello\0world");
+    /// ```
+    pub fn from_bytes(bytes: &[u8], persistent: bool) -> ZBox<Self> {
+        unsafe {
+            let ptr =
+                ext_php_rs_zend_string_init(bytes.as_ptr().cast(), bytes.len() as _, persistent);
+
+            ZBox::from_raw(
+                ptr.as_mut()
+                    .expect("Failed to allocate memory for new Zend string"),
+            )
+        }
+    }
+
+    /// Creates a new Zend string from format arguments, as given by
+    /// [`format_args!`]. The formatted text is written directly into the
+    /// Zend string, which is grown as required, rather than being formatted
+    /// into a [`String`] and then copied.
+    ///
+    /// The returned string is not interned, and has a reference count of one.
+    ///
+    /// # Parameters
+    ///
+    /// * `args` - The format arguments.
+    /// * `persistent` - Whether the string should persist through the request
+    ///   boundary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function was unable to allocate memory for the Zend
+    /// string, or if a formatting trait implementation returns an error, in
+    /// the same way as [`format!`].
+    ///
+    /// # Safety
+    ///
+    /// When passing `persistent` as `false`, the caller must ensure that the
+    /// object does not attempt to live after the request finishes. When a
+    /// request starts and finishes in PHP, the Zend heap is deallocated and a
+    /// new one is created, which would leave a dangling pointer in the
+    /// [`ZBox`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let count = 5;
+    /// let s = ZendStr::from_fmt(format_args!("{} items", count), false);
+    /// assert_eq!(s.as_str(), Some("5 items"));
+    /// ```
+    pub fn from_fmt(args: fmt::Arguments, persistent: bool) -> ZBox<Self> {
+        if let Some(s) = args.as_str() {
+            return Self::from_bytes(s.as_bytes(), persistent);
+        }
+
+        let mut writer = Writer {
+            // The length of the string is used as its capacity while writing.
+            zs: Self::alloc(FMT_CAPACITY, persistent),
+            len: 0,
+            persistent,
+        };
+        fmt::write(&mut writer, args).expect("a formatting trait implementation returned an error");
+
+        let Writer { mut zs, len, .. } = writer;
+        Self::realloc(&mut zs, len, persistent);
+        // SAFETY: Zend strings are allocated with space for a trailing NUL byte.
+        unsafe { *(zs.val.as_mut_ptr() as *mut u8).add(len) = 0 };
+        zs
+    }
+
+    /// Allocates a Zend string of the given length, with uninitialized
+    /// contents.
+    fn alloc(len: usize, persistent: bool) -> ZBox<Self> {
+        unsafe {
+            ZBox::from_raw(
+                ext_php_rs_zend_string_alloc(len as _, persistent)
+                    .as_mut()
+                    .expect("Failed to allocate memory for new Zend string"),
+            )
+        }
+    }
+
+    /// Resizes a Zend string which is not interned and has a reference count
+    /// of one to the given length, keeping its contents.
+    fn realloc(zs: &mut ZBox<Self>, len: usize, persistent: bool) {
+        unsafe {
+            let ptr = ext_php_rs_zend_string_realloc(&mut **zs, len as _, persistent);
+            // The string may have been moved, in which case the old string was freed.
+            ptr::write(
+                zs,
+                ZBox::from_raw(
+                    ptr.as_mut()
+                        .expect("Failed to allocate memory for new Zend string"),
+                ),
+            );
+        }
+    }
+
     /// Creates a new interned Zend string from a [`str`].
     ///
     /// An interned string is only ever stored once and is immutable. PHP stores
@@ -272,10 +401,27 @@ impl ZendStr {
         }
     }
 
+    /// Returns a reference to the bytes of the Zend string, without the
+    /// trailing NUL byte. The contents are not copied, and as PHP strings are
+    /// binary-safe, the slice may contain NUL bytes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::ZendStr;
+    ///
+    /// let s = ZendStr::new("hello, world!", false).unwrap();
+    /// assert_eq!(s.as_bytes(), b"hello, world!");
+    /// ```
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: Zend strings store their readable length in a fat pointer.
+        unsafe { slice::from_raw_parts(self.val.as_ptr() as *const u8, self.len()) }
+    }
+
     /// Attempts to return a reference to the underlying [`str`] inside the Zend
-    /// string.
+    /// string. The contents are not copied, only validated as UTF-8.
     ///
-    /// Returns the [`None`] variant if the [`CStr`] contains non-UTF-8
+    /// Returns the [`None`] variant if the string contains non-UTF-8
     /// characters.
     ///
     /// # Example
@@ -288,7 +434,36 @@ impl ZendStr {
     /// assert_eq!(as_str, Some("hello, world!"));
     /// ```
     pub fn as_str(&self) -> Option<&str> {
-        self.as_c_str().to_str().ok()
+        str::from_utf8(self.as_bytes()).ok()
+    }
+}
+
+/// Initial capacity of a Zend string created from format arguments.
+const FMT_CAPACITY: usize = 64;
+
+/// Writes formatted text into a Zend string, doubling the capacity of the
+/// string when it is full.
+struct Writer {
+    zs: ZBox<ZendStr>,
+    len: usize,
+    persistent: bool,
+}
+
+impl fmt::Write for Writer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let len = self.len + s.len();
+        if len > self.zs.len() {
+            let capacity = len.max(self.zs.len() * 2);
+            ZendStr::realloc(&mut self.zs, capacity, self.persistent);
+        }
+
+        // SAFETY: The string has a capacity of at least `len` bytes.
+        unsafe {
+            let val = (self.zs.val.as_mut_ptr() as *mut u8).add(self.len);
+            ptr::copy_nonoverlapping(s.as_ptr(), val, s.len());
+        }
+        self.len = len;
+        Ok(())
     }
 }
 
@@ -308,14 +483,14 @@ impl ToOwned for ZendStr {
     type Owned = ZBox<ZendStr>;
 
     fn to_owned(&self) -> Self::Owned {
-        Self::from_c_str(self.as_c_str(), false)
+        Self::from_bytes(self.as_bytes(), false)
     }
 }
 
 impl PartialEq for ZendStr {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
-        self.as_c_str().eq(other.as_c_str())
+        self.as_bytes().eq(other.as_bytes())
     }
 }
 
@@ -344,6 +519,12 @@ impl<'a> TryFrom<&ZendStr> for String {
     }
 }
 
+impl From<&ZendStr> for Vec<u8> {
+    fn from(value: &ZendStr) -> Self {
+        value.as_bytes().to_vec()
+    }
+}
+
 impl<'a> From<&'a ZendStr> for Cow<'a, ZendStr> {
     fn from(value: &'a ZendStr) -> Self {
         Cow::Borrowed(value)
@@ -378,6 +559,18 @@ impl TryFrom<String> for ZBox<ZendStr> {
     }
 }
 
+impl From<&[u8]> for ZBox<ZendStr> {
+    fn from(value: &[u8]) -> Self {
+        ZendStr::from_bytes(value, false)
+    }
+}
+
+impl From<Vec<u8>> for ZBox<ZendStr> {
+    fn from(value: Vec<u8>) -> Self {
+        ZendStr::from_bytes(&value, false)
+    }
+}
+
 impl From<ZBox<ZendStr>> for Cow<'_, ZendStr> {
     fn from(value: ZBox<ZendStr>) -> Self {
         Cow::Owned(value)
@@ -414,8 +607,13 @@ macro_rules! try_into_zval_str {
 
 try_into_zval_str!(String);
 try_into_zval_str!(&str);
+try_into_zval_str!(Cow<'_, str>);
 try_from_zval!(String, string, String);
 
+/// Borrows the contents of a PHP string without copying. The lifetime of the
+/// borrow is tied to the zval, so when used as a function argument the string
+/// cannot be stored beyond the call - convert it into a [`String`] if it must
+/// outlive the call.
 impl<'a> FromZval<'a> for &'a str {
     const TYPE: DataType = DataType::String;
 
@@ -423,3 +621,58 @@ impl<'a> FromZval<'a> for &'a str {
         zval.str()
     }
 }
+
+/// Borrows the contents of a PHP string when it is valid UTF-8, in the same way
+/// as [`&str`](str). Otherwise, the value is converted into an owned string:
+/// invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`,
+/// and other values are converted in the same way as [`String`].
+impl<'a> FromZval<'a> for Cow<'a, str> {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        match zval.bytes() {
+            Some(bytes) => Some(String::from_utf8_lossy(bytes)),
+            None => zval.string().map(Cow::Owned),
+        }
+    }
+}
+
+/// Borrows the raw bytes of a PHP string without copying or validating them as
+/// UTF-8. Like [`&str`](str), the borrow cannot outlive the zval.
+impl<'a> FromZval<'a> for &'a [u8] {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &'a Zval) -> Option<Self> {
+        zval.bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for Zval {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        let mut zv = Self::new();
+        zv.set_bytes(value, false);
+        Ok(zv)
+    }
+}
+
+impl IntoZval for &[u8] {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        zv.set_bytes(self, persistent);
+        Ok(())
+    }
+}
+
+/// Hands the string over to the zval without copying it. The string keeps the
+/// persistence it was created with.
+impl IntoZval for ZBox<ZendStr> {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, _: bool) -> Result<()> {
+        zv.set_zend_string(self);
+        Ok(())
+    }
+}
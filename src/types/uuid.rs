@@ -0,0 +1,88 @@
+//! Conversions between [`Uuid`] and PHP strings, enabled by the `uuid`
+//! feature.
+
+use crate::{
+    convert::{FromZval, IntoZval},
+    error::Result,
+    flags::DataType,
+    macros::try_from_zval_ref,
+    types::Zval,
+};
+
+use ::uuid::Uuid;
+
+/// Parses a UUID given in its hyphenated form (36 characters) or its simple
+/// form (32 characters). Other forms accepted by [`Uuid::parse_str`], such as
+/// URNs or braced UUIDs, are rejected.
+fn parse(value: &str) -> Option<Uuid> {
+    match value.len() {
+        32 | 36 => Uuid::parse_str(value).ok(),
+        _ => None,
+    }
+}
+
+/// Converts a PHP string containing a UUID into a [`Uuid`]. The string may be
+/// in the hyphenated form (`67e55044-10b1-426f-9247-bb680e5fe0c8`) or the
+/// simple form (`67e5504410b1426f9247bb680e5fe0c8`), in either case. Strings
+/// which are not a valid UUID fail to convert.
+impl FromZval<'_> for Uuid {
+    const TYPE: DataType = DataType::String;
+
+    fn from_zval(zval: &Zval) -> Option<Self> {
+        zval.str().and_then(parse)
+    }
+}
+
+try_from_zval_ref!(Uuid);
+
+/// Converts a [`Uuid`] into a PHP string, using the lowercase hyphenated form.
+impl IntoZval for Uuid {
+    const TYPE: DataType = DataType::String;
+
+    fn set_zval(self, zv: &mut Zval, persistent: bool) -> Result<()> {
+        let mut buf = Uuid::encode_buffer();
+        zv.set_string(self.hyphenated().encode_lower(&mut buf), persistent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use uuid::Uuid;
+
+    const HYPHENATED: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    const SIMPLE: &str = "67e5504410b1426f9247bb680e5fe0c8";
+
+    #[test]
+    fn test_parse_forms() {
+        let expected = Uuid::parse_str(HYPHENATED).expect("invalid UUID");
+
+        assert_eq!(parse(HYPHENATED), Some(expected));
+        assert_eq!(parse(SIMPLE), Some(expected));
+        assert_eq!(parse(&HYPHENATED.to_uppercase()), Some(expected));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("not a uuid"), None);
+        assert_eq!(parse("67e55044-10b1-426f-9247-bb680e5fe0cz"), None);
+        assert_eq!(parse(&format!("{{{}}}", HYPHENATED)), None);
+        assert_eq!(parse(&format!("urn:uuid:{}", HYPHENATED)), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for uuid in [
+            Uuid::nil(),
+            Uuid::parse_str(HYPHENATED).expect("invalid UUID"),
+        ] {
+            let mut buf = Uuid::encode_buffer();
+            let hyphenated = uuid.hyphenated().encode_lower(&mut buf);
+
+            assert_eq!(hyphenated.len(), 36);
+            assert_eq!(parse(hyphenated), Some(uuid));
+            assert_eq!(parse(&uuid.simple().to_string()), Some(uuid));
+        }
+    }
+}
@@ -2,21 +2,28 @@
 //! contains is determined by a property inside the struct. The content of the
 //! Zval is stored in a union.
 
-use std::{convert::TryInto, ffi::c_void, fmt::Debug, ptr};
+use std::{
+    borrow::Cow,
+    convert::TryInto,
+    ffi::{c_void, CStr},
+    fmt::Debug,
+    ptr,
+};
 
 use crate::{
     binary::Pack,
     boxed::ZBox,
     convert::{FromZval, IntoZval, IntoZvalDyn},
-    error::{Error, Result},
+    error::{type_name, ConversionError, Error, Result},
     ffi::{
         _zval_struct__bindgen_ty_1, _zval_struct__bindgen_ty_2, zend_is_callable, zend_resource,
-        zend_value, zval, zval_ptr_dtor,
+        zend_rsrc_list_get_rsrc_type, zend_value, zval, zval_ptr_dtor,
     },
     flags::DataType,
     flags::ZvalTypeFlags,
     rc::PhpRc,
-    types::{ZendCallable, ZendHashTable, ZendLong, ZendObject, ZendStr},
+    types::{array::Entries, ZendCallable, ZendHashTable, ZendLong, ZendObject, ZendStr},
+    zend::ClassEntry,
 };
 
 /// A zend value. This is the primary storage container used throughout the Zend
@@ -48,6 +55,21 @@ impl Zval {
         }
     }
 
+    /// Creates a new, undefined zval. PHP uses undefined zvals to mark
+    /// properties which have not been initialized.
+    #[cfg(php81)]
+    pub(crate) const fn undef() -> Self {
+        Self {
+            value: zend_value {
+                ptr: ptr::null_mut(),
+            },
+            u1: _zval_struct__bindgen_ty_1 {
+                type_info: DataType::Undef.as_u32(),
+            },
+            u2: _zval_struct__bindgen_ty_2 { next: 0 },
+        }
+    }
+
     /// Returns the value of the zval if it is a long.
     pub fn long(&self) -> Option<ZendLong> {
         if self.is_long() {
@@ -115,6 +137,13 @@ impl Zval {
         self.zend_str().and_then(|zs| zs.as_str())
     }
 
+    /// Returns the raw bytes of the zval if it is a string. PHP strings are
+    /// binary-safe, so the bytes are not required to be valid UTF-8 and may
+    /// contain NUL bytes.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        self.zend_str().map(|zs| zs.as_bytes())
+    }
+
     /// Returns the value of the zval if it is a string and can be unpacked into
     /// a vector of a given type. Similar to the [`unpack`](https://www.php.net/manual/en/function.unpack.php)
     /// in PHP, except you can only unpack one type.
@@ -150,6 +179,10 @@ impl Zval {
 
     /// Returns an immutable reference to the underlying zval hashtable if the
     /// zval contains an array.
+    ///
+    /// The hashtable is borrowed as is, without copying it or separating it
+    /// from other variables sharing it, so reading a large array does not
+    /// allocate.
     pub fn array(&self) -> Option<&ZendHashTable> {
         if self.is_array() {
             unsafe { self.value.arr.as_ref() }
@@ -158,8 +191,35 @@ impl Zval {
         }
     }
 
+    /// Returns an iterator over the keys and values of the underlying zval
+    /// hashtable, or a [`ConversionError`] if the zval does not contain an
+    /// array.
+    ///
+    /// Each key is either an integer or a string [`ArrayKey`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{error::ConversionError, types::Zval};
+    ///
+    /// fn keys(arr: &Zval) -> Result<Vec<String>, ConversionError> {
+    ///     Ok(arr.array_try_iter()?.map(|(key, _)| key.to_string()).collect())
+    /// }
+    /// ```
+    pub fn array_try_iter(&self) -> Result<Entries<'_>, ConversionError> {
+        self.array()
+            .map(ZendHashTable::entries)
+            .ok_or_else(|| ConversionError::new(DataType::Array, self))
+    }
+
     /// Returns a mutable reference to the underlying zval hashtable if the zval
     /// contains an array.
+    ///
+    /// The hashtable is not separated, so modifying an array which is shared
+    /// with other variables, such as an array passed to a function by value,
+    /// also modifies the array seen by those variables. Use
+    /// [`separate_array`](Self::separate_array) to modify the array of this
+    /// zval only.
     pub fn array_mut(&mut self) -> Option<&mut ZendHashTable> {
         if self.is_array() {
             unsafe { self.value.arr.as_mut() }
@@ -247,6 +307,92 @@ impl Zval {
         DataType::from(unsafe { self.u1.v.type_ } as u32)
     }
 
+    /// Returns the name of the type of the zval, as returned by
+    /// `get_debug_type()` in PHP.
+    ///
+    /// Scalar types are given by their type declaration names, such as `int`
+    /// and `float`, and objects by the name of their class. References return
+    /// the type of the referenced value.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::Zval;
+    ///
+    /// let mut zv = Zval::new();
+    /// zv.set_long(5);
+    /// assert_eq!(zv.get_type_name(), "int");
+    /// ```
+    pub fn get_type_name(&self) -> Cow<'_, str> {
+        if let Some(zv) = self.reference() {
+            return zv.get_type_name();
+        }
+
+        match self.get_type() {
+            DataType::Object(_) => {
+                // Anonymous classes are named `class@anonymous` followed by a NUL
+                // byte and the location of the declaration, which is omitted.
+                match self
+                    .object()
+                    .and_then(|obj| unsafe { obj.ce.as_ref() })
+                    .and_then(ClassEntry::name)
+                {
+                    Some(name) => name.split('\0').next().unwrap_or(name).into(),
+                    None => "object".into(),
+                }
+            }
+            DataType::Resource => {
+                let res = match self.resource().and_then(|res| unsafe { res.as_ref() }) {
+                    Some(res) => res,
+                    None => return "resource".into(),
+                };
+
+                // Closed resources have their type set to -1.
+                if res.type_ < 0 {
+                    return "resource (closed)".into();
+                }
+
+                let ty = unsafe { zend_rsrc_list_get_rsrc_type(res as *const _ as *mut _) };
+                match unsafe { ty.as_ref() } {
+                    Some(ty) => format!(
+                        "resource ({})",
+                        unsafe { CStr::from_ptr(ty) }.to_string_lossy()
+                    )
+                    .into(),
+                    None => "resource (Unknown)".into(),
+                }
+            }
+            ty => type_name(ty).into(),
+        }
+    }
+
+    /// Attempts to convert the zval into the given Rust type, returning a
+    /// [`ConversionError`] containing the expected and actual PHP types if the
+    /// zval could not be converted.
+    ///
+    /// The error can be converted into a [`PhpException`], which throws a
+    /// `TypeError`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::{exception::PhpResult, types::Zval};
+    ///
+    /// fn describe(value: &Zval) -> PhpResult<String> {
+    ///     if value.is_array() {
+    ///         let items: Vec<String> = value.try_into_type()?;
+    ///         Ok(items.join(", "))
+    ///     } else {
+    ///         Ok(value.try_into_type::<String>()?)
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`PhpException`]: crate::exception::PhpException
+    pub fn try_into_type<'a, T: FromZval<'a>>(&'a self) -> Result<T, ConversionError> {
+        T::from_zval(self).ok_or_else(|| ConversionError::new(T::TYPE, self))
+    }
+
     /// Returns true if the zval is a long, false otherwise.
     pub fn is_long(&self) -> bool {
         self.get_type() == DataType::Long
@@ -259,7 +405,7 @@ impl Zval {
 
     /// Returns true if the zval is true, false otherwise.
     pub fn is_true(&self) -> bool {
-        self.get_type() == DataType::False
+        self.get_type() == DataType::True
     }
 
     /// Returns true if the zval is false, false otherwise.
@@ -308,6 +454,20 @@ impl Zval {
         unsafe { zend_is_callable(ptr as *mut Self, 0, std::ptr::null_mut()) }
     }
 
+    /// Returns true if the zval is an object which is an instance of the given
+    /// class, or of a class which extends or implements it. Returns false
+    /// otherwise.
+    ///
+    /// The class entry can be found by name with [`ClassEntry::try_find`],
+    /// which allows checking against classes declared in userland.
+    ///
+    /// # Parameters
+    ///
+    /// * `ce` - The class or interface to check against.
+    pub fn instance_of(&self, ce: &ClassEntry) -> bool {
+        self.object().is_some_and(|obj| obj.instance_of(ce))
+    }
+
     /// Returns true if the zval contains a pointer, false otherwise.
     pub fn is_ptr(&self) -> bool {
         self.get_type() == DataType::Ptr
@@ -325,6 +485,17 @@ impl Zval {
         Ok(())
     }
 
+    /// Sets the value of the zval as a binary-safe string, copying the given
+    /// bytes with their exact length.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The value to set the zval as.
+    /// * `persistent` - Whether the string should persist between requests.
+    pub fn set_bytes(&mut self, val: &[u8], persistent: bool) {
+        self.set_zend_string(ZendStr::from_bytes(val, persistent));
+    }
+
     /// Sets the value of the zval as a Zend string.
     ///
     /// # Parameters
@@ -467,6 +638,175 @@ impl Zval {
         self.value.ptr = ptr as *mut c_void;
     }
 
+    /// Sets the value of the zval by converting a Rust value into it, in place.
+    /// The previous contents of the zval are freed, decrementing their
+    /// reference count if they are reference counted. This is the counterpart
+    /// of [`Zval::try_into_type`].
+    ///
+    /// If the zval is a reference, such as a parameter passed by reference, the
+    /// value is written to the zval held by the reference, in the same way as
+    /// assigning to a reference in PHP.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The value to set the zval as.
+    pub fn set_from<T: IntoZval>(&mut self, val: T) -> Result<()> {
+        if self.is_reference() {
+            if let Some(zv) = self.reference_mut() {
+                return val.set_zval(zv, false);
+            }
+        }
+
+        val.set_zval(self, false)
+    }
+
+    /// Creates a copy of the zval which shares the same value, incrementing the
+    /// reference count of the value if it is reference counted. Equivalent to
+    /// `ZVAL_COPY` in C.
+    pub fn shallow_clone(&self) -> Zval {
+        // SAFETY: the value is only shared after the reference count has been
+        // incremented, so both zvals can be dropped.
+        let new = unsafe { ptr::read(self) };
+        if ZvalTypeFlags::from_bits_truncate(unsafe { self.u1.type_info })
+            .contains(ZvalTypeFlags::RefCounted)
+        {
+            if let Some(counted) = unsafe { self.value.counted.as_mut() } {
+                counted.gc.refcount += 1;
+            }
+        }
+        new
+    }
+
+    /// Creates a copy of the zval which is independent of the original value,
+    /// so later changes made to the original variable from PHP are not seen by
+    /// the copy.
+    ///
+    /// References are replaced by the value they point to, and arrays are
+    /// copied recursively, including the arrays they contain. Objects are not
+    /// copied: the copy refers to the same object, in the same way as assigning
+    /// an object to another variable in PHP, so changes made to the object are
+    /// seen through both zvals. Strings and resources are shared, as they
+    /// cannot be modified in place.
+    ///
+    /// An array containing itself through a reference is only copied once, and
+    /// the inner occurrence is shared with the original value copy-on-write.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ZendHashTable, Zval};
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.push(1).unwrap();
+    /// let mut caller = Zval::new();
+    /// caller.set_hashtable(ht);
+    ///
+    /// let stored = caller.deep_clone();
+    ///
+    /// // Mutating the caller's array does not affect the stored copy.
+    /// caller.separate();
+    /// caller.array_mut().unwrap().push(2).unwrap();
+    /// assert_eq!(caller.array().unwrap().len(), 2);
+    /// assert_eq!(stored.array().unwrap().len(), 1);
+    /// ```
+    pub fn deep_clone(&self) -> Zval {
+        self.deep_clone_inner(&mut vec![])
+    }
+
+    /// Recursively copies the zval. `path` contains the arrays being copied,
+    /// used to detect arrays which contain themselves.
+    fn deep_clone_inner(&self, path: &mut Vec<*const ZendHashTable>) -> Zval {
+        if let Some(val) = self.reference() {
+            return val.deep_clone_inner(path);
+        }
+
+        let arr = match self.array() {
+            Some(arr) if !path.contains(&(arr as *const _)) => arr,
+            _ => return self.shallow_clone(),
+        };
+
+        path.push(arr);
+        let mut copy = ZendHashTable::with_capacity(arr.len() as u32);
+        for (idx, key, val) in arr.iter() {
+            let val = val.deep_clone_inner(path);
+            // Inserting a zval cannot fail, as no conversion takes place.
+            let _ = match key {
+                Some(key) => copy.insert(key.as_str(), val),
+                None => copy.insert_at_index(idx, val),
+            };
+        }
+        path.pop();
+
+        let mut zv = Zval::new();
+        zv.set_hashtable(copy);
+        zv
+    }
+
+    /// Separates the zval from any other variable sharing its value, so it can
+    /// be modified without affecting them. Equivalent to `SEPARATE_ZVAL` in C.
+    ///
+    /// If the zval is a reference, it is replaced by a copy of the value it
+    /// points to. If the zval contains an array which is shared with another
+    /// variable, the array is duplicated. The elements of the duplicated array
+    /// are shared copy-on-write, so nested arrays must be separated before they
+    /// are modified. Other values are left as is.
+    pub fn separate(&mut self) {
+        if let Some(val) = self.reference() {
+            // Replacing the zval releases the reference to the original value.
+            *self = val.shallow_clone();
+        }
+
+        let arr = match self.array() {
+            // Immutable arrays have a reference count of 2, so they are also
+            // duplicated.
+            Some(arr) if arr.get_count() > 1 => arr.to_owned(),
+            _ => return,
+        };
+        self.set_hashtable(arr);
+    }
+
+    /// Returns a mutable reference to the underlying zval hashtable if the zval
+    /// contains an array, first duplicating the array if it is shared with
+    /// other variables. Equivalent to `SEPARATE_ARRAY` in C.
+    ///
+    /// Arrays are copy-on-write in PHP, so an array passed to a function is
+    /// usually shared with the caller, and must be separated before it is
+    /// modified. The array is only duplicated when it is shared, so separating
+    /// an array which is only held by this zval does not allocate.
+    ///
+    /// If the zval is a reference, the array held by the reference is
+    /// separated, so modifications are visible through the reference.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::types::{ZendHashTable, Zval};
+    ///
+    /// let mut ht = ZendHashTable::new();
+    /// ht.push(1).unwrap();
+    /// let mut caller = Zval::new();
+    /// caller.set_hashtable(ht);
+    ///
+    /// let mut arg = caller.shallow_clone();
+    /// arg.separate_array().unwrap().push(2).unwrap();
+    /// assert_eq!(arg.array().unwrap().len(), 2);
+    /// assert_eq!(caller.array().unwrap().len(), 1);
+    /// ```
+    pub fn separate_array(&mut self) -> Option<&mut ZendHashTable> {
+        if self.is_reference() {
+            return self.reference_mut()?.separate_array();
+        }
+
+        // Immutable arrays have a reference count of 2, so they are also
+        // duplicated.
+        if self.array()?.get_count() > 1 {
+            let copy = self.array()?.to_owned();
+            self.set_hashtable(copy);
+        }
+
+        self.array_mut()
+    }
+
     /// Used to drop the Zval but keep the value of the zval intact.
     ///
     /// This is important when copying the value of the zval, as the actual
@@ -528,7 +868,7 @@ impl Debug for Zval {
             DataType::Reference => field!(self.reference()),
             DataType::Callable => field!(self.string()),
             DataType::ConstantExpression => field!(Option::<()>::None),
-            DataType::Void => field!(Option::<()>::None),
+            DataType::Void | DataType::Never => field!(Option::<()>::None),
             DataType::Bool => field!(self.bool()),
             // SAFETY: We are not accessing the pointer.
             DataType::Ptr => field!(unsafe { self.ptr::<c_void>() }),
@@ -558,3 +898,141 @@ impl IntoZval for Zval {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Zval;
+    use crate::{
+        convert::FromZvalMut,
+        ffi::{zend_class_entry, zend_object, zend_reference, zend_resource, zend_string},
+        flags::ZvalTypeFlags,
+        types::{ZendHashTable, ZvalRef},
+    };
+
+    #[test]
+    fn test_set_from() {
+        let mut zv = Zval::new();
+        zv.set_from(5).expect("failed to set zval");
+        assert_eq!(zv.long(), Some(5));
+        zv.set_from(true).expect("failed to set zval");
+        assert_eq!(zv.bool(), Some(true));
+        zv.set_from(()).expect("failed to set zval");
+        assert!(zv.is_null());
+    }
+
+    #[test]
+    fn test_set_from_reference() {
+        // A variable passed by reference, as given to a function by PHP.
+        let mut reference: zend_reference = unsafe { std::mem::zeroed() };
+        reference.gc.refcount = 1;
+        reference.val.set_long(1);
+
+        let mut arg = Zval::new();
+        arg.u1.type_info = ZvalTypeFlags::ReferenceEx.bits();
+        arg.value.ref_ = &mut reference;
+
+        arg.set_from(2.5).expect("failed to set reference");
+        assert!(arg.is_reference());
+        assert_eq!(reference.val.double(), Some(2.5));
+
+        ZvalRef::from_zval_mut(&mut arg)
+            .expect("argument is not a reference")
+            .set(7)
+            .expect("failed to set reference");
+        assert_eq!(reference.val.long(), Some(7));
+
+        // The reference is not allocated by PHP, so must not be freed.
+        arg.release();
+    }
+
+    #[test]
+    fn test_separate_array_unshared() {
+        // An array only held by the variable, which is separated in place.
+        let mut arr: ZendHashTable = unsafe { std::mem::zeroed() };
+        arr.gc.refcount = 1;
+        let ptr: *mut ZendHashTable = &mut arr;
+
+        let mut reference: zend_reference = unsafe { std::mem::zeroed() };
+        reference.gc.refcount = 1;
+        reference.val.u1.type_info = ZvalTypeFlags::ArrayEx.bits();
+        reference.val.value.arr = ptr;
+
+        let mut arg = Zval::new();
+        arg.u1.type_info = ZvalTypeFlags::ReferenceEx.bits();
+        arg.value.ref_ = &mut reference;
+
+        assert!(arg.array().is_none());
+        assert_eq!(
+            arg.separate_array().map(|arr| arr as *mut ZendHashTable),
+            Some(ptr)
+        );
+        assert_eq!(
+            reference.val.array().map(|arr| arr as *const _),
+            Some(ptr as *const _)
+        );
+
+        // The array and reference are not allocated by PHP, so must not be freed.
+        reference.val.u1.type_info = ZvalTypeFlags::Null.bits();
+        arg.release();
+    }
+
+    #[test]
+    fn test_get_type_name() {
+        let mut zv = Zval::new();
+        assert_eq!(zv.get_type_name(), "null");
+        zv.set_bool(false);
+        assert_eq!(zv.get_type_name(), "bool");
+        zv.set_long(5);
+        assert_eq!(zv.get_type_name(), "int");
+        zv.set_double(1.5);
+        assert_eq!(zv.get_type_name(), "float");
+
+        let mut res: zend_resource = unsafe { std::mem::zeroed() };
+        res.type_ = -1;
+        zv.set_resource(&mut res);
+        assert_eq!(zv.get_type_name(), "resource (closed)");
+
+        // A variable passed by reference takes the type of the referenced value.
+        let mut reference: zend_reference = unsafe { std::mem::zeroed() };
+        reference.gc.refcount = 1;
+        reference.val.set_long(1);
+        zv.u1.type_info = ZvalTypeFlags::ReferenceEx.bits();
+        zv.value.ref_ = &mut reference;
+        assert_eq!(zv.get_type_name(), "int");
+        zv.release();
+    }
+
+    #[test]
+    fn test_get_type_name_object() {
+        // Builds a zend string in place, as strings cannot be allocated without
+        // PHP.
+        fn name(buf: &mut [u64; 16], name: &[u8]) -> *mut zend_string {
+            let s = buf.as_mut_ptr() as *mut zend_string;
+            unsafe {
+                (*s).len = name.len() as _;
+                let val = std::ptr::addr_of_mut!((*s).val) as *mut u8;
+                std::ptr::copy_nonoverlapping(name.as_ptr(), val, name.len());
+            }
+            s
+        }
+
+        let mut buf = [0u64; 16];
+        let mut ce: zend_class_entry = unsafe { std::mem::zeroed() };
+        let mut obj: zend_object = unsafe { std::mem::zeroed() };
+        obj.ce = &mut ce;
+
+        let mut zv = Zval::new();
+        zv.u1.type_info = ZvalTypeFlags::ObjectEx.bits();
+        zv.value.obj = &mut obj;
+        assert_eq!(zv.get_type_name(), "object");
+
+        ce.name = name(&mut buf, b"Closure");
+        assert_eq!(zv.get_type_name(), "Closure");
+
+        // Anonymous classes are named after their location in the source.
+        ce.name = name(&mut buf, b"Countable@anonymous\0/src/index.php:3$0");
+        assert_eq!(zv.get_type_name(), "Countable@anonymous");
+
+        zv.release();
+    }
+}
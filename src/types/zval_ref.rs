@@ -0,0 +1,109 @@
+//! Types related to arguments passed by reference to PHP functions.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::{
+    convert::{FromZval, FromZvalMut, IntoZval},
+    error::Result,
+    flags::DataType,
+};
+
+use super::{ZendHashTable, Zval};
+
+/// A PHP variable passed to a function by reference (`&$var`). Writes to the
+/// variable are visible to the caller once the function returns.
+///
+/// When used as a parameter of a function exported to PHP, the parameter is
+/// registered as being passed by reference, and is represented as `mixed &$var`
+/// in the generated stubs. As with PHP, only variables can be given for these
+/// parameters.
+///
+/// The wrapper dereferences to the [`Zval`] held by the reference, so it can be
+/// read like any other [`Zval`].
+///
+/// # Example
+///
+/// ```
+/// # use ext_php_rs::prelude::*;
+/// /// Appends a value to the array, creating the array if the variable is null.
+/// #[php_function]
+/// pub fn append_value(mut arr: ZvalRef, value: i64) -> PhpResult {
+///     if arr.is_null() {
+///         arr.set(Vec::<i64>::new())?;
+///     }
+///
+///     arr.array_mut()
+///         .ok_or("Expected an array.")?
+///         .push(value)?;
+///     Ok(())
+/// }
+/// # #[php_module]
+/// # pub fn module(module: ModuleBuilder) -> ModuleBuilder {
+/// #     module
+/// # }
+/// ```
+///
+/// ```php
+/// <?php
+///
+/// $values = [1, 2];
+/// append_value($values, 3);
+/// var_dump($values); // [1, 2, 3]
+/// ```
+#[derive(Debug)]
+pub struct ZvalRef<'a>(&'a mut Zval);
+
+impl<'a> ZvalRef<'a> {
+    /// Attempts to convert the value of the variable into `T`.
+    pub fn get<'b, T: FromZval<'b>>(&'b self) -> Option<T> {
+        T::from_zval(self.0)
+    }
+
+    /// Replaces the value of the variable, freeing the previous value.
+    ///
+    /// # Parameters
+    ///
+    /// * `val` - The new value of the variable.
+    pub fn set<T: IntoZval>(&mut self, val: T) -> Result<()> {
+        self.0.set_from(val)
+    }
+
+    /// Returns a mutable reference to the array held by the variable, if the
+    /// variable contains an array.
+    ///
+    /// Arrays are copy-on-write in PHP, so the array is separated from any
+    /// other variables sharing it before it is returned. Modifications are
+    /// therefore only visible through this variable.
+    pub fn array_mut(&mut self) -> Option<&mut ZendHashTable> {
+        self.0.separate_array()
+    }
+}
+
+impl Deref for ZvalRef<'_> {
+    type Target = Zval;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl DerefMut for ZvalRef<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
+
+impl<'a> FromZvalMut<'a> for ZvalRef<'a> {
+    const TYPE: DataType = DataType::Mixed;
+
+    fn from_zval_mut(zval: &'a mut Zval) -> Option<Self> {
+        // Arguments passed by reference are given as a reference zval, although
+        // the function may also be called with a plain value, such as through
+        // `call_user_func()`.
+        if zval.is_reference() {
+            zval.reference_mut().map(Self)
+        } else {
+            Some(Self(zval))
+        }
+    }
+}
@@ -5,6 +5,16 @@ zend_string *ext_php_rs_zend_string_init(const char *str, size_t len, bool persi
     return zend_string_init(str, len, persistent);
 }
 
+zend_string *ext_php_rs_zend_string_alloc(size_t len, bool persistent)
+{
+    return zend_string_alloc(len, persistent);
+}
+
+zend_string *ext_php_rs_zend_string_realloc(zend_string *zs, size_t len, bool persistent)
+{
+    return zend_string_realloc(zs, len, persistent);
+}
+
 void ext_php_rs_zend_string_release(zend_string *zs)
 {
     zend_string_release(zs);
@@ -37,3 +47,82 @@ zend_executor_globals *ext_php_rs_executor_globals()
     return &executor_globals;
 #endif
 }
+
+// Returns the module globals with the given resource ID for the current thread,
+// or NULL if the thread is not known to PHP. Only available with ZTS.
+void *ext_php_rs_zts_module_globals(int id)
+{
+#ifdef ZTS
+    void ***storage = (void ***) tsrm_get_ls_cache();
+    if (storage == NULL) {
+        return NULL;
+    }
+    return (*storage)[TSRM_UNSHUFFLE_RSRC_ID(id)];
+#else
+    return NULL;
+#endif
+}
+
+static zend_array *ext_php_rs_persist_array(zend_array *source);
+
+static void ext_php_rs_persist_zval(zval *dst, zval *src)
+{
+    ZVAL_DEREF(src);
+    switch (Z_TYPE_P(src)) {
+        case IS_STRING:
+            ZVAL_INTERNED_STR(dst, zend_string_init_interned(Z_STRVAL_P(src), Z_STRLEN_P(src), 1));
+            break;
+        case IS_ARRAY:
+            ZVAL_ARR(dst, ext_php_rs_persist_array(Z_ARRVAL_P(src)));
+            Z_TYPE_FLAGS_P(dst) = 0;
+            break;
+        default:
+            ZVAL_COPY_VALUE(dst, src);
+    }
+}
+
+// Copies an array into persistent memory, in the same way as opcache persists
+// arrays. The copy is immutable, so it is never refcounted or freed.
+static zend_array *ext_php_rs_persist_array(zend_array *source)
+{
+    zend_array *ht;
+    zend_ulong idx;
+    zend_string *key;
+    zval *val;
+
+    if (zend_hash_num_elements(source) == 0) {
+        return (zend_array *) &zend_empty_array;
+    }
+
+    ht = pemalloc(sizeof(zend_array), 1);
+    zend_hash_init(ht, zend_hash_num_elements(source), NULL, NULL, 1);
+    ZEND_HASH_FOREACH_KEY_VAL(source, idx, key, val) {
+        zval copy;
+        ext_php_rs_persist_zval(&copy, val);
+        if (key) {
+            zend_hash_update(ht, zend_string_init_interned(ZSTR_VAL(key), ZSTR_LEN(key), 1), &copy);
+        } else {
+            zend_hash_index_update(ht, idx, &copy);
+        }
+    } ZEND_HASH_FOREACH_END();
+
+    HT_FLAGS(ht) |= HASH_FLAG_STATIC_KEYS;
+    GC_SET_REFCOUNT(ht, 2);
+    GC_TYPE_INFO(ht) = GC_ARRAY | ((IS_ARRAY_IMMUTABLE | GC_NOT_COLLECTABLE) << GC_FLAGS_SHIFT);
+    return ht;
+}
+
+void ext_php_rs_zend_register_array_constant(const char *name, size_t name_len, zend_array *arr, int flags, int module_number)
+{
+    zend_constant c;
+
+    if (flags & CONST_PERSISTENT) {
+        ZVAL_ARR(&c.value, ext_php_rs_persist_array(arr));
+        Z_TYPE_FLAGS(c.value) = 0;
+    } else {
+        ZVAL_ARR(&c.value, zend_array_dup(arr));
+    }
+    ZEND_CONSTANT_SET_FLAGS(&c, flags, module_number);
+    c.name = zend_string_init_interned(name, name_len, flags & CONST_PERSISTENT);
+    zend_register_constant(&c);
+}
@@ -3,10 +3,18 @@
 #include "zend_exceptions.h"
 #include "zend_inheritance.h"
 #include "zend_interfaces.h"
+#include "ext/json/php_json.h"
+#if PHP_VERSION_ID >= 80100
+#include "zend_enum.h"
+#endif
 
 zend_string *ext_php_rs_zend_string_init(const char *str, size_t len, bool persistent);
+zend_string *ext_php_rs_zend_string_alloc(size_t len, bool persistent);
+zend_string *ext_php_rs_zend_string_realloc(zend_string *zs, size_t len, bool persistent);
 void ext_php_rs_zend_string_release(zend_string *zs);
 const char *ext_php_rs_php_build_id();
 void *ext_php_rs_zend_object_alloc(size_t obj_size, zend_class_entry *ce);
 void ext_php_rs_zend_object_release(zend_object *obj);
-zend_executor_globals *ext_php_rs_executor_globals();
\ No newline at end of file
+zend_executor_globals *ext_php_rs_executor_globals();
+void *ext_php_rs_zts_module_globals(int id);
+void ext_php_rs_zend_register_array_constant(const char *name, size_t name_len, zend_array *arr, int flags, int module_number);
\ No newline at end of file
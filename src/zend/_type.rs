@@ -4,11 +4,13 @@ use std::{
 };
 
 use crate::{
+    error::Result,
     ffi::{
-        zend_type, IS_MIXED, MAY_BE_ANY, MAY_BE_BOOL, _IS_BOOL, _ZEND_IS_VARIADIC_BIT,
-        _ZEND_SEND_MODE_SHIFT, _ZEND_TYPE_NAME_BIT, _ZEND_TYPE_NULLABLE_BIT,
+        zend_type, _IS_BOOL, _ZEND_IS_VARIADIC_BIT, _ZEND_SEND_MODE_SHIFT, _ZEND_TYPE_NAME_BIT,
+        _ZEND_TYPE_NULLABLE_BIT, IS_MIXED, MAY_BE_ANY, MAY_BE_BOOL,
     },
     flags::DataType,
+    types::ZendStr,
 };
 
 /// Internal Zend type.
@@ -61,6 +63,43 @@ impl ZendType {
         }
     }
 
+    /// Attempts to create a zend type for a typed class property. Unlike
+    /// argument types, the class name of a property type is stored as an
+    /// interned Zend string.
+    ///
+    /// Returns [`None`] if PHP does not allow properties to be declared with
+    /// the given type, e.g. `callable` or `void`, in which case the property
+    /// should be declared without a type. Returns an error if the class name
+    /// could not be converted into a Zend string.
+    ///
+    /// # Parameters
+    ///
+    /// * `type_` - Data type to create zend type for.
+    /// * `allow_null` - Whether the property can be set to null.
+    pub(crate) fn property_type(type_: DataType, allow_null: bool) -> Result<Option<Self>> {
+        Ok(Some(match type_ {
+            DataType::Object(Some(class)) => Self {
+                ptr: ZendStr::new_interned(class, true)?.into_raw() as *mut ZendStr as *mut c_void,
+                type_mask: _ZEND_TYPE_NAME_BIT
+                    | (if allow_null {
+                        _ZEND_TYPE_NULLABLE_BIT
+                    } else {
+                        0
+                    }),
+            },
+            DataType::Bool
+            | DataType::True
+            | DataType::False
+            | DataType::Long
+            | DataType::Double
+            | DataType::String
+            | DataType::Array
+            | DataType::Object(None)
+            | DataType::Mixed => Self::empty_from_primitive_type(type_, false, false, allow_null),
+            _ => return Ok(None),
+        }))
+    }
+
     /// Attempts to create a zend type for a class object type. Returns an
     /// option containing the type if successful.
     ///
@@ -3,10 +3,11 @@
 #![allow(clippy::unwrap_used)]
 
 use crate::ffi::{
-    zend_ce_argument_count_error, zend_ce_arithmetic_error, zend_ce_compile_error,
-    zend_ce_division_by_zero_error, zend_ce_error_exception, zend_ce_exception,
-    zend_ce_parse_error, zend_ce_throwable, zend_ce_type_error, zend_ce_unhandled_match_error,
-    zend_ce_value_error, zend_standard_class_def,
+    php_json_serializable_ce, zend_ce_argument_count_error, zend_ce_arithmetic_error,
+    zend_ce_arrayaccess, zend_ce_compile_error, zend_ce_countable, zend_ce_division_by_zero_error,
+    zend_ce_error, zend_ce_error_exception, zend_ce_exception, zend_ce_iterator,
+    zend_ce_parse_error, zend_ce_stringable, zend_ce_throwable, zend_ce_type_error,
+    zend_ce_unhandled_match_error, zend_ce_value_error, zend_standard_class_def,
 };
 
 use super::ClassEntry;
@@ -31,6 +32,11 @@ pub fn error_exception() -> &'static ClassEntry {
     unsafe { zend_ce_error_exception.as_ref() }.unwrap()
 }
 
+/// Returns the base `Error` class.
+pub fn error() -> &'static ClassEntry {
+    unsafe { zend_ce_error.as_ref() }.unwrap()
+}
+
 /// Returns the base `CompileError` class.
 pub fn compile_error() -> &'static ClassEntry {
     unsafe { zend_ce_compile_error.as_ref() }.unwrap()
@@ -70,3 +76,28 @@ pub fn division_by_zero_error() -> &'static ClassEntry {
 pub fn unhandled_match_error() -> &'static ClassEntry {
     unsafe { zend_ce_unhandled_match_error.as_ref() }.unwrap()
 }
+
+/// Returns the base `Iterator` interface.
+pub fn iterator() -> &'static ClassEntry {
+    unsafe { zend_ce_iterator.as_ref() }.unwrap()
+}
+
+/// Returns the base `ArrayAccess` interface.
+pub fn arrayaccess() -> &'static ClassEntry {
+    unsafe { zend_ce_arrayaccess.as_ref() }.unwrap()
+}
+
+/// Returns the base `Countable` interface.
+pub fn countable() -> &'static ClassEntry {
+    unsafe { zend_ce_countable.as_ref() }.unwrap()
+}
+
+/// Returns the base `Stringable` interface.
+pub fn stringable() -> &'static ClassEntry {
+    unsafe { zend_ce_stringable.as_ref() }.unwrap()
+}
+
+/// Returns the base `JsonSerializable` interface.
+pub fn json_serializable() -> &'static ClassEntry {
+    unsafe { php_json_serializable_ce.as_ref() }.unwrap()
+}
@@ -1,6 +1,15 @@
 //! Builder and objects for creating classes in the PHP world.
 
-use crate::{ffi::zend_class_entry, flags::ClassFlags, types::ZendStr, zend::ExecutorGlobals};
+use crate::{
+    convert::IntoZval,
+    error::{Error, Result},
+    ffi::{
+        zend_class_entry, zend_read_static_property_ex, zend_update_static_property_ex,
+    },
+    flags::{ClassFlags, ZendResult},
+    types::{ZendStr, Zval},
+    zend::ExecutorGlobals,
+};
 use std::{convert::TryInto, fmt::Debug, ops::DerefMut};
 
 /// A PHP class entry.
@@ -9,7 +18,15 @@ use std::{convert::TryInto, fmt::Debug, ops::DerefMut};
 pub type ClassEntry = zend_class_entry;
 
 impl ClassEntry {
-    /// Attempts to find a reference to a class in the global class table.
+    /// Attempts to find a reference to a class in the global class table,
+    /// calling the autoloader if the class has not been declared.
+    ///
+    /// As in PHP, the name is case-insensitive and may be fully qualified
+    /// with a leading backslash, for example `\App\Models\User`.
+    ///
+    /// Classes declared in userland are only found while the request which
+    /// declared them is being handled, and the returned reference must not be
+    /// kept once the request has ended.
     ///
     /// Returns a reference to the class if found, or [`None`] if the class
     /// could not be found or the class table has not been initialized.
@@ -22,6 +39,12 @@ impl ClassEntry {
         }
     }
 
+    /// Returns the name of the class, or [`None`] if the name is not valid
+    /// UTF-8.
+    pub fn name(&self) -> Option<&str> {
+        unsafe { self.name.as_ref() }.and_then(|name| name.as_str())
+    }
+
     /// Returns the class flags.
     pub fn flags(&self) -> ClassFlags {
         ClassFlags::from_bits_truncate(self.ce_flags)
@@ -98,6 +121,53 @@ impl ClassEntry {
             Self::try_find(name.as_str()?)
         }
     }
+
+    /// Returns a reference to the value of a static property of the class, or
+    /// [`None`] if the property does not exist.
+    ///
+    /// Static properties are stored by PHP, and their values are reset to
+    /// their defaults at the start of each request. On thread-safe (ZTS)
+    /// builds of PHP, each thread has its own copy of the static properties.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the static property.
+    pub fn static_property(&self, name: &str) -> Option<&Zval> {
+        let mut name = ZendStr::new(name, false).ok()?;
+        let zv = unsafe {
+            zend_read_static_property_ex(self as *const _ as *mut _, name.deref_mut(), true)
+                .as_ref()
+        }?;
+
+        Some(zv.reference().unwrap_or(zv))
+    }
+
+    /// Sets the value of a static property of the class. See
+    /// [`static_property`](Self::static_property) for details on how static
+    /// properties are stored.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the static property.
+    /// * `value` - The new value of the static property.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value could not be converted into a [`Zval`], or
+    /// [`Error::InvalidProperty`] if the static property does not exist.
+    pub fn set_static_property(&self, name: &str, value: impl IntoZval) -> Result<()> {
+        let mut name = ZendStr::new(name, false)?;
+        let mut value = value.into_zval(false)?;
+        let result = unsafe {
+            zend_update_static_property_ex(self as *const _ as *mut _, name.deref_mut(), &mut value)
+        };
+
+        if result == ZendResult::Success.bits() {
+            Ok(())
+        } else {
+            Err(Error::InvalidProperty)
+        }
+    }
 }
 
 impl PartialEq for ClassEntry {
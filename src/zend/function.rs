@@ -1,8 +1,14 @@
-//! Builder for creating functions and methods in PHP.
+//! Builder for creating functions and methods in PHP, and calling functions
+//! declared in PHP.
 
 use std::{os::raw::c_char, ptr};
 
-use crate::ffi::zend_function_entry;
+use crate::{
+    convert::FromZval,
+    error::{Error, Result},
+    ffi::zend_function_entry,
+    types::{IntoZvalArgs, ZendCallable, Zval},
+};
 
 /// A Zend function entry.
 pub type FunctionEntry = zend_function_entry;
@@ -25,3 +31,74 @@ impl FunctionEntry {
         Box::into_raw(Box::new(self))
     }
 }
+
+/// Calls the function with the given name, returning the value returned by the
+/// function. Equivalent to `call_user_func()` in PHP.
+///
+/// Both internal and userland functions can be called. Namespaced functions
+/// are given with their fully qualified name, e.g. `App\format_price`, with or
+/// without a leading backslash. As with `call_user_func()`, the namespace is
+/// not resolved, so functions are not looked up in the global namespace as a
+/// fallback.
+///
+/// # Parameters
+///
+/// * `name` - The name of the function.
+/// * `args` - A tuple of up to 8 arguments, which must implement
+///   [`IntoZval`]. Use `()` to call the function with no arguments.
+///
+/// # Returns
+///
+/// Returns the return value of the function upon success. If the function does
+/// not exist or cannot be called, an [`Error::UnknownFunction`] is returned.
+/// If the function throws an exception, an [`Error::Exception`] containing the
+/// exception is returned.
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::zend::call_function;
+///
+/// let len = call_function("strlen", ("hello",)).unwrap();
+/// assert_eq!(len.long(), Some(5));
+/// ```
+///
+/// [`IntoZval`]: crate::convert::IntoZval
+pub fn call_function(name: &str, args: impl IntoZvalArgs) -> Result<Zval> {
+    let callable =
+        ZendCallable::try_from_name(name).map_err(|_| Error::UnknownFunction(name.to_string()))?;
+    callable.call_zvals(args.into_zval_args()?)
+}
+
+/// Calls the function with the given name, converting the value returned by
+/// the function into `T`. See [`call_function`] for more information.
+///
+/// # Parameters
+///
+/// * `name` - The name of the function.
+/// * `args` - A tuple of up to 8 arguments, which must implement
+///   [`IntoZval`].
+///
+/// # Returns
+///
+/// Returns the converted return value upon success, or an
+/// [`Error::ZvalConversion`] if the return value cannot be converted into `T`.
+/// Otherwise, returns the same errors as [`call_function`].
+///
+/// # Example
+///
+/// ```no_run
+/// use ext_php_rs::zend::call_function_into;
+///
+/// let len = call_function_into::<i64>("strlen", ("hello",)).unwrap();
+/// assert_eq!(len, 5);
+/// ```
+///
+/// [`IntoZval`]: crate::convert::IntoZval
+pub fn call_function_into<T>(name: &str, args: impl IntoZvalArgs) -> Result<T>
+where
+    T: for<'a> FromZval<'a>,
+{
+    let retval = call_function(name, args)?;
+    T::from_zval(&retval).ok_or_else(|| Error::ZvalConversion(retval.get_type()))
+}
@@ -5,7 +5,8 @@ use std::ops::{Deref, DerefMut};
 use parking_lot::{const_rwlock, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::boxed::ZBox;
-use crate::ffi::{_zend_executor_globals, ext_php_rs_executor_globals};
+use crate::convert::FromZval;
+use crate::ffi::{_zend_executor_globals, ext_php_rs_executor_globals, zend_is_auto_global_str};
 
 use crate::types::{ZendHashTable, ZendObject};
 
@@ -50,6 +51,79 @@ impl ExecutorGlobals {
         unsafe { self.class_table.as_ref() }
     }
 
+    /// Attempts to retrieve one of the PHP superglobal arrays of the current
+    /// request, such as `$_SERVER`.
+    ///
+    /// When `auto_globals_jit` is enabled, PHP only populates `$_SERVER`,
+    /// `$_ENV` and `$_REQUEST` once they are first used by a script. This
+    /// function forces the superglobal to be populated before it is returned,
+    /// so it is safe to call even if no PHP code has accessed it yet.
+    ///
+    /// The returned hash table belongs to the current request and must not be
+    /// kept after the request has ended.
+    ///
+    /// # Parameters
+    ///
+    /// * `superglobal` - The superglobal to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// Returns the superglobal array, or [`None`] if it does not exist or is
+    /// not an array, for example outside of a request.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::zend::{ExecutorGlobals, Superglobal};
+    ///
+    /// let globals = ExecutorGlobals::get();
+    /// if let Some(server) = globals.superglobal(Superglobal::Server) {
+    ///     println!("{} server variables", server.len());
+    /// }
+    /// ```
+    pub fn superglobal(&self, superglobal: Superglobal) -> Option<&ZendHashTable> {
+        let name = superglobal.name();
+
+        // SAFETY: The name is a valid string with the given length. Arming the auto
+        // global only populates the symbol table if it has not been populated yet.
+        unsafe { zend_is_auto_global_str(name.as_ptr() as *const _, name.len() as _) };
+
+        let zv = self.symbol_table.get(name)?;
+        zv.reference().unwrap_or(zv).array()
+    }
+
+    /// Attempts to retrieve a value from one of the PHP superglobal arrays of
+    /// the current request, converting it into a Rust type.
+    ///
+    /// See [`superglobal`](Self::superglobal) for details on how the
+    /// superglobal is retrieved.
+    ///
+    /// # Parameters
+    ///
+    /// * `superglobal` - The superglobal to read from.
+    /// * `key` - The key of the value in the superglobal.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted value, or [`None`] if the superglobal or key does
+    /// not exist, or the value could not be converted into `T`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ext_php_rs::zend::{ExecutorGlobals, Superglobal};
+    ///
+    /// let globals = ExecutorGlobals::get();
+    /// let uri: Option<&str> = globals.superglobal_var(Superglobal::Server, "REQUEST_URI");
+    /// ```
+    pub fn superglobal_var<'a, T>(&'a self, superglobal: Superglobal, key: &str) -> Option<T>
+    where
+        T: FromZval<'a>,
+    {
+        let zv = self.superglobal(superglobal)?.get(key)?;
+        zv.reference().unwrap_or(zv).extract()
+    }
+
     /// Attempts to extract the last PHP exception captured by the interpreter.
     /// Returned inside a [`ZBox`].
     ///
@@ -67,6 +141,33 @@ impl ExecutorGlobals {
     }
 }
 
+/// The PHP superglobal arrays which can be retrieved with
+/// [`ExecutorGlobals::superglobal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Superglobal {
+    /// `$_SERVER`, containing the server and execution environment variables.
+    Server,
+    /// `$_GET`, containing the query string variables.
+    Get,
+    /// `$_POST`, containing the variables of the request body.
+    Post,
+    /// `$_ENV`, containing the environment variables.
+    Env,
+}
+
+impl Superglobal {
+    /// Returns the name of the superglobal in the PHP symbol table, without the
+    /// leading `$`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Server => "_SERVER",
+            Self::Get => "_GET",
+            Self::Post => "_POST",
+            Self::Env => "_ENV",
+        }
+    }
+}
+
 /// Executor globals rwlock.
 ///
 /// PHP provides no indication if the executor globals are being accessed so
@@ -110,3 +211,16 @@ impl<T> DerefMut for GlobalWriteGuard<T> {
         self.globals
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Superglobal;
+
+    #[test]
+    fn test_superglobal_names() {
+        assert_eq!(Superglobal::Server.name(), "_SERVER");
+        assert_eq!(Superglobal::Get.name(), "_GET");
+        assert_eq!(Superglobal::Post.name(), "_POST");
+        assert_eq!(Superglobal::Env.name(), "_ENV");
+    }
+}
@@ -1,15 +1,20 @@
-use std::{ffi::c_void, os::raw::c_int, ptr};
+use std::{cmp::Ordering, ffi::c_void, os::raw::c_int, ptr};
 
 use crate::{
     class::RegisteredClass,
-    exception::PhpResult,
+    countable::PhpCountable,
+    exception::{PhpException, PhpResult},
     ffi::{
         std_object_handlers, zend_is_true, zend_object_handlers, zend_object_std_dtor,
-        zend_std_get_properties, zend_std_has_property, zend_std_read_property,
-        zend_std_write_property,
+        zend_objects_clone_members, zend_std_cast_object_tostring, zend_std_get_properties,
+        zend_std_has_property, zend_std_read_property, zend_std_write_property, IS_STRING,
+        ZEND_UNCOMPARABLE,
     },
-    flags::ZvalTypeFlags,
-    types::{ZendClassObject, ZendHashTable, ZendObject, ZendStr, Zval},
+    flags::{ZendResult, ZvalTypeFlags},
+    magic::PhpDebugInfo,
+    stringable::PhpToString,
+    types::{ZendClassObject, ZendHashTable, ZendLong, ZendObject, ZendStr, Zval},
+    zend::ce,
 };
 
 /// A set of functions associated with a PHP class.
@@ -20,6 +25,10 @@ impl ZendObjectHandlers {
     /// object handlers into the memory location, as well as setting up the
     /// `T` type destructor.
     ///
+    /// The standard `clone_obj` handler cannot copy the Rust value of the
+    /// object, so it is removed, and cloning an object throws an `Error` unless
+    /// the handler is overridden with [`cloneable`](Self::cloneable).
+    ///
     /// # Parameters
     ///
     /// * `ptr` - Pointer to memory location to copy the standard handlers to.
@@ -36,6 +45,163 @@ impl ZendObjectHandlers {
         (*ptr).write_property = Some(Self::write_property::<T>);
         (*ptr).get_properties = Some(Self::get_properties::<T>);
         (*ptr).has_property = Some(Self::has_property::<T>);
+        (*ptr).clone_obj = None;
+        T::override_handlers(&mut *ptr);
+    }
+
+    /// Overrides the `clone_obj` handler, so that `clone $obj` creates a new
+    /// object of type `T` holding a clone of the Rust value of the object. The
+    /// properties of the object are copied and `__clone` is called in the same
+    /// way as for other objects.
+    pub fn cloneable<T: RegisteredClass + Clone>(&mut self) {
+        self.clone_obj = Some(Self::clone_obj::<T>);
+    }
+
+    /// Overrides the `count_elements` handler, so that calling `count()` on an
+    /// object of type `T` calls [`PhpCountable::count`].
+    pub fn countable<T: PhpCountable>(&mut self) {
+        self.count_elements = Some(Self::count_elements::<T>);
+    }
+
+    /// Overrides the `cast_object` handler, so that casting an object of type
+    /// `T` to a string calls [`PhpToString::to_php_string`]. Casts to other
+    /// types use the standard handler.
+    pub fn stringable<T: PhpToString>(&mut self) {
+        self.cast_object = Some(Self::cast_object::<T>);
+    }
+
+    /// Overrides the `get_debug_info` handler, so that dumping an object of
+    /// type `T` with `var_dump()` or `print_r()` shows the array returned by
+    /// [`PhpDebugInfo::debug_info`].
+    pub fn debug_info<T: PhpDebugInfo>(&mut self) {
+        self.get_debug_info = Some(Self::get_debug_info::<T>);
+    }
+
+    unsafe extern "C" fn get_debug_info<T: PhpDebugInfo>(
+        object: *mut ZendObject,
+        is_temp: *mut c_int,
+    ) -> *mut ZendHashTable {
+        // Objects which have not been constructed have no state to describe.
+        let result = match object
+            .as_mut()
+            .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
+            .and_then(|obj| obj.obj.as_ref())
+        {
+            Some(obj) => obj.debug_info(),
+            None => Ok(ZendHashTable::new()),
+        };
+
+        // The returned array is released by PHP once it has been dumped.
+        let info = result.unwrap_or_else(|e| {
+            let _ = e.throw();
+            ZendHashTable::new()
+        });
+        if let Some(is_temp) = is_temp.as_mut() {
+            *is_temp = 1;
+        }
+        info.into_raw()
+    }
+
+    unsafe extern "C" fn cast_object<T: PhpToString>(
+        readobj: *mut ZendObject,
+        retval: *mut Zval,
+        type_: c_int,
+    ) -> c_int {
+        #[inline(always)]
+        unsafe fn internal<T: PhpToString>(
+            readobj: *mut ZendObject,
+            retval: *mut Zval,
+        ) -> PhpResult {
+            let obj = readobj
+                .as_mut()
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
+                .ok_or("Invalid object pointer given")?;
+            let rv_mut = retval.as_mut().ok_or("Invalid return zval given")?;
+
+            // retval is uninitialized, so we set the type to null before writing to it
+            rv_mut.u1.type_info = ZvalTypeFlags::Null.bits();
+            rv_mut.set_string(&obj.to_php_string(), false)?;
+            Ok(())
+        }
+
+        if type_ as u32 != IS_STRING {
+            return zend_std_cast_object_tostring(readobj, retval, type_);
+        }
+
+        match internal::<T>(readobj, retval) {
+            Ok(()) => ZendResult::Success.bits(),
+            Err(e) => {
+                let _ = e.throw();
+                ZendResult::Failure.bits()
+            }
+        }
+    }
+
+    /// Overrides the `compare` handler, so that comparing two objects of type
+    /// `T` with `==`, `<`, `>` or `<=>`, or sorting them with `sort()`, uses
+    /// the [`PartialOrd`] implementation of `T`. Objects which cannot be
+    /// ordered, and comparisons with values of other types, are uncomparable,
+    /// so `==` returns false and `<` and `>` return false in both directions.
+    pub fn comparable<T: RegisteredClass + PartialOrd>(&mut self) {
+        self.compare = Some(Self::compare::<T>);
+    }
+
+    unsafe extern "C" fn compare<T: RegisteredClass + PartialOrd>(
+        object1: *mut Zval,
+        object2: *mut Zval,
+    ) -> c_int {
+        let value = |zv: *mut Zval| {
+            zv.as_mut()
+                .and_then(|zv| zv.object_mut())
+                .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
+                .and_then(|obj| obj.obj.as_ref())
+        };
+
+        match (value(object1), value(object2)) {
+            (Some(a), Some(b)) => compare_result(a.partial_cmp(b)),
+            _ => ZEND_UNCOMPARABLE as c_int,
+        }
+    }
+
+    unsafe extern "C" fn clone_obj<T: RegisteredClass + Clone>(
+        object: *mut ZendObject,
+    ) -> *mut ZendObject {
+        let old = object
+            .as_mut()
+            .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
+            .expect("Invalid object pointer given for `clone_obj`");
+
+        // PHP expects a new object to be returned, so an object which has not been
+        // constructed is cloned into another object which has not been constructed.
+        let mut new = match &old.obj {
+            Some(val) => ZendClassObject::new(val.clone()),
+            None => ZendClassObject::new_uninit(),
+        };
+        zend_objects_clone_members(&mut new.std, &mut old.std);
+        new.into_raw().get_mut_zend_obj()
+    }
+
+    unsafe extern "C" fn count_elements<T: PhpCountable>(
+        object: *mut ZendObject,
+        count: *mut ZendLong,
+    ) -> c_int {
+        let obj = match object
+            .as_mut()
+            .and_then(|obj| ZendClassObject::<T>::from_zend_obj_mut(obj))
+        {
+            Some(obj) => obj,
+            None => return ZendResult::Failure.bits(),
+        };
+        let result = obj.count();
+
+        // Negative counts fall back to the `count` method, which throws the error.
+        match (result >= 0, count.as_mut()) {
+            (true, Some(count)) => {
+                *count = result;
+                ZendResult::Success.bits()
+            }
+            _ => ZendResult::Failure.bits(),
+        }
     }
 
     unsafe extern "C" fn free_obj<T: RegisteredClass>(object: *mut ZendObject) {
@@ -125,6 +291,17 @@ impl ZendObjectHandlers {
             let value_mut = value.as_mut().ok_or("Invalid return zval given")?;
 
             Ok(match prop {
+                Some(prop) if prop.is_readonly() => {
+                    return Err(PhpException::new(
+                        format!(
+                            "Cannot modify readonly property {}::${}",
+                            T::CLASS_NAME,
+                            prop_name.as_str().unwrap_or_default()
+                        ),
+                        0,
+                        ce::error(),
+                    ))
+                }
                 Some(prop) => {
                     prop.set(self_, value_mut)?;
                     value
@@ -260,3 +437,28 @@ impl ZendObjectHandlers {
         }
     }
 }
+
+/// Converts the result of comparing two values into the result of a PHP
+/// comparison, where values which cannot be ordered are uncomparable.
+fn compare_result(ordering: Option<Ordering>) -> c_int {
+    match ordering {
+        Some(Ordering::Less) => -1,
+        Some(Ordering::Equal) => 0,
+        Some(Ordering::Greater) => 1,
+        None => ZEND_UNCOMPARABLE as c_int,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::compare_result;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_compare_result() {
+        assert_eq!(compare_result(Some(Ordering::Less)), -1);
+        assert_eq!(compare_result(Some(Ordering::Equal)), 0);
+        assert_eq!(compare_result(Some(Ordering::Greater)), 1);
+        assert_eq!(compare_result(None), 1);
+    }
+}
@@ -12,7 +12,7 @@ mod module;
 pub use _type::ZendType;
 pub use class::ClassEntry;
 pub use ex::ExecuteData;
-pub use function::FunctionEntry;
-pub use globals::ExecutorGlobals;
+pub use function::{call_function, call_function_into, FunctionEntry};
+pub use globals::{ExecutorGlobals, Superglobal};
 pub use handlers::ZendObjectHandlers;
-pub use module::ModuleEntry;
+pub use module::{extension_loaded, ModuleEntry};
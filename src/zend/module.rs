@@ -1,7 +1,12 @@
 //! Builder and objects for creating modules in PHP. A module is the base of a
 //! PHP extension.
 
-use crate::ffi::zend_module_entry;
+use std::ptr;
+
+use crate::{
+    ffi::{module_registry, zend_module_entry},
+    types::ZendHashTable,
+};
 
 /// A Zend module entry, also known as an extension.
 pub type ModuleEntry = zend_module_entry;
@@ -13,3 +18,24 @@ impl ModuleEntry {
         Box::into_raw(Box::new(self))
     }
 }
+
+/// Returns true if an extension with the given name is loaded, in the same
+/// way as `extension_loaded()` in PHP. Extension names are case-insensitive.
+///
+/// Every extension is loaded before the startup function of any extension is
+/// called, so this can be used to check for another extension from a startup
+/// function, or from the condition of a function registered with
+/// [`ModuleBuilder::conditional_function`]. The other extension may not have
+/// started up yet at that point.
+///
+/// # Parameters
+///
+/// * `name` - The name of the extension, e.g. `curl`.
+///
+/// [`ModuleBuilder::conditional_function`]: crate::builders::ModuleBuilder::conditional_function
+pub fn extension_loaded(name: &str) -> bool {
+    // SAFETY: The module registry is initialized before any extension is loaded,
+    // and is only modified when extensions are loaded or unloaded.
+    let registry: &ZendHashTable = unsafe { &*ptr::addr_of!(module_registry) };
+    registry.get(&name.to_ascii_lowercase()).is_some()
+}
@@ -0,0 +1,8 @@
+<?php
+
+// Builds arrays of 100,000 integers in Rust without a capacity hint, so the
+// hashtable is resized as it grows. Compare with `array_with_capacity.php`.
+
+for ($i = 0; $i < 100; $i++) {
+    bench_array_new(100000);
+}
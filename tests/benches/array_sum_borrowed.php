@@ -0,0 +1,10 @@
+<?php
+
+// Sums an array of 1,000,000 integers through a borrowed `&ZendHashTable`,
+// which reads the array in place. Compare with `array_sum_vec.php`.
+
+$values = range(1, 1000000);
+
+for ($i = 0; $i < 100; $i++) {
+    bench_array_sum_borrowed($values);
+}
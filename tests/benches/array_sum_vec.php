@@ -0,0 +1,10 @@
+<?php
+
+// Sums an array of 1,000,000 integers converted into a `Vec<i64>`, which copies
+// the array on every call. Compare with `array_sum_borrowed.php`.
+
+$values = range(1, 1000000);
+
+for ($i = 0; $i < 100; $i++) {
+    bench_array_sum_vec($values);
+}
@@ -0,0 +1,8 @@
+<?php
+
+// Builds arrays of 100,000 integers in Rust with a capacity hint, so the
+// hashtable is allocated once. Compare with `array_new.php`.
+
+for ($i = 0; $i < 100; $i++) {
+    bench_array_with_capacity(100000);
+}
@@ -0,0 +1,9 @@
+<?php
+
+// Builds the entries of 500 functions and adds them to a module in one batch,
+// the way the `php_module` macro registers functions when the extension starts
+// up. Compare with `minit_single.php`.
+
+for ($i = 0; $i < 100; $i++) {
+    bench_minit_batch(500);
+}
@@ -0,0 +1,8 @@
+<?php
+
+// Builds the entries of 500 functions and adds them to a module one at a time,
+// growing the list of functions as it goes. Compare with `minit_batch.php`.
+
+for ($i = 0; $i < 100; $i++) {
+    bench_minit_single(500);
+}
@@ -0,0 +1,10 @@
+<?php
+
+// Passes a 1 MiB string to a function taking a borrowed `&str`, which reads the
+// PHP string in place. Compare with `str_owned.php`.
+
+$input = str_repeat('a', 1024 * 1024);
+
+for ($i = 0; $i < 10000; $i++) {
+    bench_str_borrowed($input);
+}
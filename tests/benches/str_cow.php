@@ -0,0 +1,10 @@
+<?php
+
+// Passes a 1 MiB string to a function taking a `Cow<str>`, which borrows the PHP
+// string in place as it is valid UTF-8. Compare with `str_borrowed.php`.
+
+$input = str_repeat('a', 1024 * 1024);
+
+for ($i = 0; $i < 10000; $i++) {
+    bench_str_cow($input);
+}
@@ -0,0 +1,8 @@
+<?php
+
+// Returns 1 KiB strings formatted directly into a Zend string with
+// `ZendStr::from_fmt`. Compare with `str_format.php`.
+
+for ($i = 0; $i < 1000000; $i++) {
+    bench_str_fmt($i);
+}
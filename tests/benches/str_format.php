@@ -0,0 +1,8 @@
+<?php
+
+// Returns 1 KiB strings formatted into a `String` with `format!`, which is
+// copied into a Zend string when returned. Compare with `str_fmt.php`.
+
+for ($i = 0; $i < 1000000; $i++) {
+    bench_str_format($i);
+}
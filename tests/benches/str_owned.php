@@ -0,0 +1,10 @@
+<?php
+
+// Passes a 1 MiB string to a function taking an owned `String`, which copies the
+// string on every call. Compare with `str_borrowed.php`.
+
+$input = str_repeat('a', 1024 * 1024);
+
+for ($i = 0; $i < 10000; $i++) {
+    bench_str_owned($input);
+}
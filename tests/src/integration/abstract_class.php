@@ -0,0 +1,26 @@
+<?php
+
+class TestSquare extends TestShape {
+    public function area(): float {
+        return 4.0;
+    }
+}
+
+$square = new TestSquare;
+assert($square->area() === 4.0);
+assert($square->kind() === 'shape');
+assert($square instanceof TestShape);
+
+$class = new ReflectionClass(TestShape::class);
+assert($class->isAbstract());
+assert($class->getMethod('area')->isAbstract());
+assert(!$class->getMethod('kind')->isAbstract());
+
+// Instantiating the abstract class throws rather than creating an object
+// without Rust state
+try {
+    new TestShape;
+    assert(false, 'Instantiating an abstract class did not throw');
+} catch (Error $e) {
+    assert($e->getMessage() === 'Cannot instantiate abstract class TestShape');
+}
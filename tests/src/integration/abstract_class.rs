@@ -0,0 +1,4 @@
+#[test]
+fn abstract_class() {
+    super::run_php("abstract_class.php");
+}
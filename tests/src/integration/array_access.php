@@ -0,0 +1,33 @@
+<?php
+
+$obj = new TestArrayAccess;
+assert($obj instanceof ArrayAccess);
+
+// Write
+$obj['retries'] = 3;
+$obj[5] = 10;
+
+// Read
+assert($obj['retries'] === 3);
+assert($obj[5] === 10);
+assert($obj['missing'] === null);
+
+// isset and empty
+assert(isset($obj['retries']));
+assert(isset($obj[5]));
+assert(!isset($obj['missing']));
+assert(!empty($obj['retries']));
+
+// Unset
+unset($obj['retries']);
+assert(!isset($obj['retries']));
+assert($obj['retries'] === null);
+assert(isset($obj[5]));
+
+// Errors from the Rust implementation are thrown as exceptions
+try {
+    $obj['retries'] = 'three';
+    assert(false, 'Setting a non-integer value should throw');
+} catch (Exception $e) {
+    assert($e->getMessage() === 'Value must be an integer.');
+}
@@ -0,0 +1,4 @@
+#[test]
+fn array_access() {
+    super::run_php("array_access.php");
+}
@@ -0,0 +1,23 @@
+<?php
+
+assert(TEST_ARRAY_CONST === ['timeout' => 30, 'retries' => 3]);
+assert(TEST_LIST_CONST === ['debug', 'info', 'error']);
+assert(TEST_ARRAY_CONST['timeout'] === 30);
+assert(constant('TEST_LIST_CONST')[2] === 'error');
+
+ob_start();
+var_dump(TEST_LIST_CONST);
+assert(str_contains(ob_get_clean(), 'string(5) "debug"'));
+
+// Modifying a copy of the constant does not change the constant, which is
+// checked again by the following request
+$copy = TEST_ARRAY_CONST;
+$copy['timeout'] = 1;
+$copy['added'] = implode(',', TEST_LIST_CONST);
+$list = TEST_LIST_CONST;
+sort($list);
+assert($list === ['debug', 'error', 'info']);
+assert(TEST_ARRAY_CONST['timeout'] === 30);
+assert(TEST_LIST_CONST[1] === 'info');
+
+echo 'ok';
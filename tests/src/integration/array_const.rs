@@ -0,0 +1,6 @@
+#[test]
+fn array_const() {
+    // The constants are persistent, so they are read over several requests to
+    // check they survive the end of each request.
+    super::run_php_server("array_const.php", 3);
+}
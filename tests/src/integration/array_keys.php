@@ -0,0 +1,27 @@
+<?php
+
+// Integer keys are signed, and string keys which are not valid UTF-8 keep
+// their bytes
+$keys = test_array_keys([
+    1 => 'a',
+    -5 => 'b',
+    PHP_INT_MIN => 'c',
+    'name' => 'd',
+    '-7' => 'e',
+    "\xff\xfe" => 'f',
+    '' => 'g',
+]);
+assert($keys === [
+    'int:1',
+    'int:-5',
+    'int:' . PHP_INT_MIN,
+    'string:name',
+    'int:-7',
+    'bytes:fffe',
+    'string:',
+]);
+
+// Negative keys inserted from Rust are the same keys in PHP
+$arr = test_array_with_keys([-1, 0, PHP_INT_MAX]);
+assert($arr === [-1 => 0, 0 => 1, PHP_INT_MAX => 2, "\xff" => -1]);
+assert(test_array_keys($arr) === ['int:-1', 'int:0', 'int:' . PHP_INT_MAX, 'bytes:ff']);
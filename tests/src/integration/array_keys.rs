@@ -0,0 +1,4 @@
+#[test]
+fn array_keys() {
+    super::run_php("array_keys.php");
+}
@@ -0,0 +1,15 @@
+<?php
+
+$input = "a\0b\0\xff\xfe";
+$reversed = "\xfe\xff\0b\0a";
+
+// Borrowed byte slices and owned byte buffers keep NUL bytes and invalid UTF-8
+assert(test_bytes_slice($input) === $reversed);
+assert(test_bytes_vec($input) === $reversed);
+assert(test_bytes_zend_str($input) === $input);
+assert(strlen(test_bytes_zend_str($input)) === 6);
+
+// Round-trip through the extension in both directions
+assert(test_bytes_slice(test_bytes_vec($input)) === $input);
+assert(test_bytes_slice("\0") === "\0");
+assert(test_bytes_vec('') === '');
@@ -0,0 +1,4 @@
+#[test]
+fn binary() {
+    super::run_php("binary.php");
+}
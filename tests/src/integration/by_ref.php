@@ -0,0 +1,31 @@
+<?php
+
+// Appending to an array passed by reference
+$values = [1, 2];
+$copy = $values;
+test_append_by_ref($values, 3);
+assert($values === [1, 2, 3]);
+assert($copy === [1, 2], 'Copies of the array must not be modified');
+
+// Replacing a value passed by reference
+$count = 5;
+test_increment_by_ref($count);
+assert($count === 6);
+
+// References to array elements and properties
+$nested = ['list' => []];
+test_append_by_ref($nested['list'], 1);
+assert($nested === ['list' => [1]]);
+
+$obj = new stdClass;
+$obj->count = 1;
+test_increment_by_ref($obj->count);
+assert($obj->count === 2);
+
+// Undefined variables are created
+test_increment_by_ref($undefined);
+assert($undefined === 1);
+
+$reflection = new ReflectionFunction('test_append_by_ref');
+assert($reflection->getParameters()[0]->isPassedByReference());
+assert(!$reflection->getParameters()[1]->isPassedByReference());
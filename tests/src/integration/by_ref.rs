@@ -0,0 +1,4 @@
+#[test]
+fn by_ref() {
+    super::run_php("by_ref.php");
+}
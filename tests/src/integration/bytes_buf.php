@@ -0,0 +1,21 @@
+<?php
+
+$input = "a\0b\0\xff\xfe";
+
+// `Bytes` copies the string in both directions without validating it as UTF-8
+assert(test_bytes_buf($input) === $input);
+assert(test_bytes_buf('') === '');
+assert(test_bytes_buf("\0") === "\0");
+assert(test_bytes_buf_mut($input) === $input . $input);
+
+// Every byte value survives the round trip
+$all = implode('', array_map('chr', range(0, 255)));
+assert(test_bytes_buf($all) === $all);
+assert(strlen(test_bytes_buf_mut($all)) === 512);
+
+// Values which are not strings are rejected
+try {
+    test_bytes_buf(1);
+    assert(false, 'Passing an integer did not throw');
+} catch (Exception $e) {
+}
@@ -0,0 +1,4 @@
+#[test]
+fn bytes_buf() {
+    super::run_php("bytes_buf.php");
+}
@@ -0,0 +1,43 @@
+<?php
+
+function count_vowels(string $s): int
+{
+    return preg_match_all('/[aeiou]/i', $s);
+}
+
+function shout(string $s): string
+{
+    return strtoupper($s);
+}
+
+function fail(string $s): int
+{
+    throw new RuntimeException("Cannot handle {$s}");
+}
+
+// Internal functions
+assert(test_call_function('strlen', 'Hello, PHP!') === 11);
+assert(test_call_function('strlen', '') === 0);
+assert(test_call_function('STRLEN', 'abc') === 3);
+assert(test_call_function('\strlen', 'abc') === 3);
+
+// Userland functions
+assert(test_call_function('count_vowels', 'Hello, PHP!') === 2);
+
+// Unknown functions cannot be called
+try {
+    test_call_function('does_not_exist', 'PHP');
+    assert(false, 'Calling an unknown function did not throw');
+} catch (Exception $e) {
+    assert($e->getMessage() === 'Function `does_not_exist` does not exist or cannot be called.');
+}
+
+// Exceptions thrown by the function and return values which cannot be
+// converted are returned as errors
+foreach (['fail', 'shout'] as $function) {
+    try {
+        test_call_function($function, 'PHP');
+        assert(false, "Calling {$function} did not throw");
+    } catch (Exception $e) {
+    }
+}
@@ -0,0 +1,4 @@
+#[test]
+fn call_function() {
+    super::run_php("call_function.php");
+}
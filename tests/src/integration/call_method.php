@@ -0,0 +1,69 @@
+<?php
+
+class Greeter
+{
+    public function __construct(private string $greeting) {}
+
+    public function greet(string $name): string
+    {
+        return "{$this->greeting}, {$name}!";
+    }
+
+    public function count(string $name): int
+    {
+        return strlen($name);
+    }
+
+    public function letters(string $name): array
+    {
+        return str_split($name);
+    }
+
+    public function fail(string $name): string
+    {
+        throw new RuntimeException("Cannot greet {$name}");
+    }
+
+    private function secret(string $name): string
+    {
+        return $name;
+    }
+}
+
+class Magic
+{
+    public function __call(string $name, array $args): string
+    {
+        return $name . '(' . implode(', ', $args) . ')';
+    }
+}
+
+// Userland methods are called on the object with the given arguments
+$greeter = new Greeter('Hello');
+assert(test_call_method($greeter, 'greet', 'PHP') === 'Hello, PHP!');
+assert(test_call_method($greeter, 'GREET', 'Rust') === 'Hello, Rust!');
+assert(test_call_method(new Greeter('Hi'), 'greet', 'PHP') === 'Hi, PHP!');
+assert(test_call_method($greeter, 'count', 'PHP') === '3');
+
+// Methods handled by `__call`
+assert(test_call_method(new Magic, 'anything', 'x') === 'anything(x)');
+
+// Missing and private methods cannot be called
+foreach (['missing', 'secret'] as $method) {
+    try {
+        test_call_method($greeter, $method, 'PHP');
+        assert(false, "Calling {$method} did not throw");
+    } catch (Exception $e) {
+        assert($e->getMessage() === "Method `Greeter::{$method}` does not exist or cannot be called.");
+    }
+}
+
+// Exceptions thrown by the method and return values which cannot be converted
+// are returned as errors
+foreach (['fail', 'letters'] as $method) {
+    try {
+        test_call_method($greeter, $method, 'PHP');
+        assert(false, "Calling {$method} did not throw");
+    } catch (Exception $e) {
+    }
+}
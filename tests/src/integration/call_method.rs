@@ -0,0 +1,4 @@
+#[test]
+fn call_method() {
+    super::run_php("call_method.php");
+}
@@ -0,0 +1,28 @@
+<?php
+
+$original = new TestCounter();
+$original->increment();
+
+// The clone holds a copy of the Rust value, and `__clone` is called on it
+$clone = clone $original;
+assert($clone instanceof TestCounter);
+assert($clone !== $original);
+assert($clone->clones() === 1);
+assert($original->clones() === 0);
+
+// Changing the clone leaves the original unchanged
+assert($clone->increment() === 2);
+assert($clone->increment() === 3);
+assert($original->increment() === 2);
+
+assert((clone $clone)->clones() === 2);
+
+// Classes which are not cloneable throw, even when they declare `__clone`
+$uncloneable = new TestUncloneable(5);
+try {
+    $copy = clone $uncloneable;
+    assert(false, 'Cloning an uncloneable object did not throw');
+} catch (Error $e) {
+    assert($e->getMessage() === 'Trying to clone an uncloneable object of class TestUncloneable');
+}
+assert($uncloneable->value() === 5);
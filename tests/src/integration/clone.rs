@@ -0,0 +1,4 @@
+#[test]
+fn clone() {
+    super::run_php("clone.php");
+}
@@ -0,0 +1,29 @@
+<?php
+
+function versions(array $versions): string
+{
+    return implode(', ', array_map(fn ($v) => $v->toString(), $versions));
+}
+
+// Objects are sorted by their Rust ordering, not their properties
+$versions = [new TestVersion(1, 10), new TestVersion(1, 2), new TestVersion(0, 9), new TestVersion(1, 2)];
+sort($versions);
+assert(versions($versions) === '0.9, 1.2, 1.2, 1.10');
+
+usort($versions, fn ($a, $b) => $b <=> $a);
+assert(versions($versions) === '1.10, 1.2, 1.2, 0.9');
+
+// Comparison operators use the same ordering
+assert(new TestVersion(1, 2) == new TestVersion(1, 2));
+assert(new TestVersion(1, 2) != new TestVersion(1, 3));
+assert(new TestVersion(1, 2) < new TestVersion(1, 10));
+assert(new TestVersion(2, 0) > new TestVersion(1, 10));
+assert(new TestVersion(1, 2) <= new TestVersion(1, 2));
+assert((new TestVersion(0, 1) <=> new TestVersion(0, 2)) === -1);
+assert(max($versions)->toString() === '1.10');
+
+// Objects of other classes are uncomparable
+$other = new stdClass();
+assert(!(new TestVersion(1, 0) == $other));
+assert(!(new TestVersion(1, 0) < $other));
+assert(!(new TestVersion(1, 0) > $other));
@@ -0,0 +1,4 @@
+#[test]
+fn comparable() {
+    super::run_php("comparable.php");
+}
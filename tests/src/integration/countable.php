@@ -0,0 +1,20 @@
+<?php
+
+$obj = new TestCountable(3);
+assert($obj instanceof Countable);
+assert(count($obj) === 3);
+assert($obj->count() === 3);
+assert(count(new TestCountable(0)) === 0);
+
+// Negative counts are rejected with a `ValueError`
+$negative = new TestCountable(-1);
+try {
+    count($negative);
+    assert(false, 'Counting a negative count should throw');
+} catch (ValueError $e) {
+}
+try {
+    $negative->count();
+    assert(false, 'Calling count() with a negative count should throw');
+} catch (ValueError $e) {
+}
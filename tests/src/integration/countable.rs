@@ -0,0 +1,4 @@
+#[test]
+fn countable() {
+    super::run_php("countable.php");
+}
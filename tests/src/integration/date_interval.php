@@ -0,0 +1,49 @@
+<?php
+
+// The fields are kept as given, without being normalized
+$interval = test_date_interval(new DateInterval('P1Y2M3DT4H90M5S'));
+assert($interval instanceof DateInterval);
+assert([$interval->y, $interval->m, $interval->d] === [1, 2, 6]);
+assert([$interval->h, $interval->i, $interval->s] === [8, 90, 5]);
+assert($interval->invert === 0);
+
+$negative = new DateInterval('PT1H');
+$negative->invert = 1;
+$negative->f = 0.25;
+$interval = test_date_interval($negative);
+assert($interval->h === 2 && $interval->invert === 1 && $interval->f === 0.25);
+
+// Subclasses are converted from their properties, not from `format()`
+class FormattedInterval extends DateInterval
+{
+    public function format(string $format): string
+    {
+        return 'overridden';
+    }
+}
+
+$interval = test_date_interval(new FormattedInterval('P1DT1H'));
+assert($interval->d === 2 && $interval->h === 2);
+
+// Conversions to and from `Duration`
+assert(test_date_interval_seconds(new DateInterval('P1DT2H')) === 93600);
+assert(test_date_interval_from_seconds(93600)->format('%d %h') === '1 2');
+
+$diff = (new DateTime('2024-01-01'))->diff(new DateTime('2024-02-01'));
+assert($diff->days === 31);
+assert(test_date_interval_seconds($diff) === 2678400);
+
+foreach ([new DateInterval('P1M'), $negative] as $invalid) {
+    try {
+        test_date_interval_seconds($invalid);
+        assert(false, 'Converting the interval did not throw');
+    } catch (ValueError $e) {
+    }
+}
+
+// Values which are not intervals are rejected
+try {
+    test_date_interval(new DateTime());
+    assert(false, 'Passing a `DateTime` did not throw');
+} catch (Exception $e) {
+}
@@ -0,0 +1,4 @@
+#[test]
+fn date_interval() {
+    super::run_php("date_interval.php");
+}
@@ -0,0 +1,29 @@
+<?php
+
+$conn = new TestDebugInfo('localhost', 5432);
+assert($conn->__debugInfo() === ['host' => 'localhost', 'port' => 5432]);
+
+// `var_dump()` and `print_r()` show the Rust state of the object
+ob_start();
+var_dump($conn);
+$dump = ob_get_clean();
+assert(str_starts_with($dump, 'object(TestDebugInfo)#'));
+assert(str_contains($dump, " (2) {\n"));
+assert(str_contains($dump, "[\"host\"]=>\n  string(9) \"localhost\"\n"));
+assert(str_contains($dump, "[\"port\"]=>\n  int(5432)\n"));
+
+$printed = print_r($conn, true);
+assert(str_contains($printed, '[host] => localhost'));
+assert(str_contains($printed, '[port] => 5432'));
+
+// Errors are thrown from `var_dump()`
+$invalid = new TestDebugInfo('localhost', -1);
+try {
+    ob_start();
+    var_dump($invalid);
+    assert(false, 'Dumping an invalid port did not throw');
+} catch (Exception $e) {
+    assert($e->getMessage() === 'Invalid port');
+} finally {
+    ob_end_clean();
+}
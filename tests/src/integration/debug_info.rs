@@ -0,0 +1,4 @@
+#[test]
+fn debug_info() {
+    super::run_php("debug_info.php");
+}
@@ -0,0 +1,30 @@
+<?php
+
+// The array is passed by reference, so the snapshot receives the caller's
+// variable rather than a copy-on-write copy of it
+$config = ['name' => 'app', 'servers' => ['a', 'b'], 'limits' => ['cpu' => 1]];
+$snapshot = new TestSnapshot($config);
+
+// Mutating the caller's array, including its nested arrays, after it has been
+// stored does not affect the stored copy
+$config['name'] = 'changed';
+$config['servers'][] = 'c';
+$config['limits']['cpu'] = 2;
+unset($config['limits']);
+
+assert($snapshot->get() === ['name' => 'app', 'servers' => ['a', 'b'], 'limits' => ['cpu' => 1]]);
+
+// Nested arrays held by reference are copied rather than shared through the
+// reference
+$servers = ['a'];
+$config = ['servers' => &$servers];
+$snapshot = new TestSnapshot($config);
+$servers[] = 'b';
+assert($snapshot->get() === ['servers' => ['a']]);
+
+// Objects are shared, as when assigning an object to another variable
+$object = new stdClass;
+$value = ['object' => $object];
+$snapshot = new TestSnapshot($value);
+$object->name = 'shared';
+assert($snapshot->get()['object']->name === 'shared');
@@ -0,0 +1,4 @@
+#[test]
+fn deep_clone() {
+    super::run_php("deep_clone.php");
+}
@@ -0,0 +1,33 @@
+<?php
+
+class TestCodeException extends Exception
+{
+}
+
+$cases = [
+    'Exception' => 42,
+    'ValueError' => -1,
+    'TestCodeException' => 404,
+];
+foreach ($cases as $class => $code) {
+    try {
+        test_exception_code($class, $code);
+        assert(false, "Throwing `$class` did not throw");
+    } catch (Throwable $e) {
+        // The class and code are those set from Rust
+        assert(get_class($e) === $class);
+        assert($e->getCode() === $code);
+        assert($e->getMessage() === "Failed with $code.");
+    }
+}
+
+// Classes which cannot be thrown are rejected
+foreach (['NoSuchException', 'stdClass'] as $class) {
+    try {
+        test_exception_code($class, 1);
+        assert(false, "Throwing `$class` did not throw");
+    } catch (Exception $e) {
+        assert(get_class($e) === 'Exception');
+        assert($e->getCode() === 0);
+    }
+}
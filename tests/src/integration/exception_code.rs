@@ -0,0 +1,4 @@
+#[test]
+fn exception_code() {
+    super::run_php("exception_code.php");
+}
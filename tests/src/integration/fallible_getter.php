@@ -0,0 +1,17 @@
+<?php
+
+$conn = new TestConnection('localhost');
+assert($conn->host === 'localhost');
+
+// A getter returning `Err` throws a catchable exception
+$conn->close();
+try {
+    $conn->host;
+    assert(false, 'Reading the property did not throw');
+} catch (Exception $e) {
+    assert($e->getMessage() === 'The connection has been closed.');
+}
+
+// The property can be read again once the getter succeeds
+$conn->host = 'example.com';
+assert($conn->host === 'example.com');
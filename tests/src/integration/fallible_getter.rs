@@ -0,0 +1,4 @@
+#[test]
+fn fallible_getter() {
+    super::run_php("fallible_getter.php");
+}
@@ -0,0 +1,24 @@
+<?php
+
+$builder = new TestFluent;
+$same = $builder->from('users')->limit(10);
+
+// The same object is returned, not a copy
+assert($same === $builder);
+assert($builder->build() === 'SELECT * FROM users LIMIT 10');
+$same->limit(5);
+assert($builder->build() === 'SELECT * FROM users LIMIT 5');
+
+// Chaining directly on a temporary object
+assert((new TestFluent)->from('posts')->build() === 'SELECT * FROM posts');
+
+// Returning the object does not leak a reference, so the object is freed once
+// the last variable referring to it is released, and not before
+$weak = WeakReference::create($builder);
+unset($builder);
+assert($weak->get() === $same);
+unset($same);
+assert($weak->get() === null);
+
+$weak = WeakReference::create((new TestFluent)->from('users')->limit(1));
+assert($weak->get() === null);
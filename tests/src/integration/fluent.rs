@@ -0,0 +1,4 @@
+#[test]
+fn fluent() {
+    super::run_php("fluent.php");
+}
@@ -0,0 +1,45 @@
+<?php
+
+$options = [
+    'url' => 'https://example.com',
+    'timeout' => 1.5,
+    'retry' => ['attempts' => 3, 'delay-ms' => 500],
+];
+
+// The nested struct is read from the nested array, through its renamed key
+assert(test_from_zval_struct($options) === 'https://example.com Some(1.5) 3 500');
+assert(test_from_zval_table($options) === 'https://example.com Some(1.5) 3 500');
+
+// Missing `Option` and `#[php(default)]` fields are left empty, and elements
+// which are not read by a field are ignored
+$defaults = ['url' => 'https://example.com', 'retry' => ['attempts' => 1, 'delay_ms' => 9]];
+assert(test_from_zval_struct($defaults) === 'https://example.com None 1 0');
+assert(test_from_zval_table($defaults) === 'https://example.com None 1 0');
+
+// A missing key fails, naming the key when converted by hand. Errors inside
+// the nested array are reported against the key of the nested array.
+$invalid = [
+    'missing key' => [['url' => 'a'], 'missing array key "retry", expected array'],
+    'missing nested key' => [
+        ['url' => 'a', 'retry' => ['delay-ms' => 1]],
+        'invalid array value for array key "retry"',
+    ],
+    'invalid nested value' => [
+        ['url' => 'a', 'retry' => ['attempts' => 'three']],
+        'invalid array value for array key "retry"',
+    ],
+];
+foreach ($invalid as $case => [$value, $message]) {
+    try {
+        test_from_zval_struct($value);
+        assert(false, "Passing an array with a $case did not throw");
+    } catch (Exception $e) {
+    }
+
+    try {
+        test_from_zval_table($value);
+        assert(false, "Converting an array with a $case did not throw");
+    } catch (TypeError $e) {
+        assert($e->getMessage() === $message, $e->getMessage());
+    }
+}
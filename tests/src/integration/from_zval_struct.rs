@@ -0,0 +1,4 @@
+#[test]
+fn from_zval_struct() {
+    super::run_php("from_zval_struct.php");
+}
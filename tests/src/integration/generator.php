@@ -0,0 +1,41 @@
+<?php
+
+// Items are yielded lazily with their zero-based positions as keys
+$generator = test_generator([1, 4, 9]);
+assert($generator instanceof RustGenerator);
+assert($generator instanceof Iterator);
+
+$items = [];
+foreach ($generator as $key => $value) {
+    $items[$key] = $value;
+}
+assert($items === [0 => 1, 1 => 4, 2 => 9]);
+assert(iterator_to_array(test_generator([])) === []);
+
+// The generator can only be iterated over once
+try {
+    foreach ($generator as $value) {
+    }
+    assert(false, 'Rewinding the generator did not throw');
+} catch (Exception $e) {
+}
+
+// An item which fails to convert throws and ends the generator
+$items = [];
+try {
+    foreach (test_generator([1, 2, -1, 3]) as $value) {
+        $items[] = $value;
+    }
+    assert(false, 'Yielding an invalid item did not throw');
+} catch (Exception $e) {
+    assert($e->getMessage() === 'Converting integer arguments resulted in an overflow.');
+}
+assert($items === [1, 2]);
+
+$generator = test_generator([-1, 3]);
+try {
+    $generator->rewind();
+    assert(false, 'Yielding an invalid first item did not throw');
+} catch (Exception $e) {
+}
+assert(!$generator->valid());
@@ -0,0 +1,4 @@
+#[test]
+fn generator() {
+    super::run_php("generator.php");
+}
@@ -0,0 +1,24 @@
+<?php
+
+// The struct is converted into an array, with the nested struct converted into
+// an object under its renamed key
+$settings = test_into_zval_struct(1.5);
+assert(is_array($settings));
+assert(array_keys($settings) === ['url', 'retry-policy', 'timeout', 'proxy']);
+assert($settings['url'] === 'https://example.com');
+assert($settings['timeout'] === 1.5);
+assert($settings['proxy'] === null);
+
+$policy = $settings['retry-policy'];
+assert($policy instanceof stdClass);
+assert(get_object_vars($policy) === ['attempts' => 3, 'delay-ms' => 500]);
+
+// `#[php(skip_none)]` leaves out the key, other `None` fields are `null`
+$settings = test_into_zval_struct(null);
+assert(array_keys($settings) === ['url', 'retry-policy', 'proxy']);
+assert(array_key_exists('proxy', $settings) && $settings['proxy'] === null);
+
+// An object struct is read back from the object it was converted into
+$policy = test_into_zval_object($policy);
+assert(get_object_vars($policy) === ['attempts' => 4, 'delay-ms' => 500]);
+assert(get_object_vars(test_into_zval_object($policy)) === ['attempts' => 5, 'delay-ms' => 500]);
@@ -0,0 +1,4 @@
+#[test]
+fn into_zval_struct() {
+    super::run_php("into_zval_struct.php");
+}
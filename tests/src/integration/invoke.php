@@ -0,0 +1,21 @@
+<?php
+
+$double = new TestMultiplier(2);
+assert(is_callable($double));
+assert(method_exists($double, '__invoke'));
+assert(!method_exists($double, 'apply'));
+
+// The object can be called directly and passed as a callable
+assert($double(1) === 2);
+assert($double(21) === 42);
+assert($double->__invoke(3) === 6);
+assert(array_map($double, [1, 2, 3]) === [2, 4, 6]);
+assert(call_user_func($double, 5) === 10);
+assert(array_map(new TestMultiplier(-1), [1, 2]) === [-1, -2]);
+
+// Arguments are converted as for other methods
+try {
+    $double('two');
+    assert(false, 'Passing a string did not throw');
+} catch (Exception $e) {
+}
@@ -0,0 +1,4 @@
+#[test]
+fn invoke() {
+    super::run_php("invoke.php");
+}
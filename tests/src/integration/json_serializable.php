@@ -0,0 +1,22 @@
+<?php
+
+$money = new TestJsonSerializable(1250, 'EUR');
+assert($money instanceof JsonSerializable);
+assert($money->jsonSerialize() === ['amount' => 12.5, 'currency' => 'EUR']);
+assert(json_encode($money) === '{"amount":12.5,"currency":"EUR"}');
+
+// Nested in arrays and other objects
+assert(json_encode(['total' => $money]) === '{"total":{"amount":12.5,"currency":"EUR"}}');
+assert(json_encode([$money, new TestJsonSerializable(5, 'USD')]) === '[{"amount":12.5,"currency":"EUR"},{"amount":0.05,"currency":"USD"}]');
+$obj = new stdClass;
+$obj->price = $money;
+assert(json_encode($obj) === '{"price":{"amount":12.5,"currency":"EUR"}}');
+
+// Errors are thrown from json_encode
+$negative = new TestJsonSerializable(-1, 'EUR');
+try {
+    json_encode(['total' => $negative]);
+    assert(false, 'Serializing a negative amount should throw');
+} catch (Exception $e) {
+    assert($e->getMessage() === 'Negative amounts cannot be serialized');
+}
@@ -0,0 +1,4 @@
+#[test]
+fn json_serializable() {
+    super::run_php("json_serializable.php");
+}
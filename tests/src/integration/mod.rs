@@ -0,0 +1,181 @@
+//! Runs the PHP scripts in this directory against the test extension. Each
+//! script uses `assert()` to check the behaviour of the extension, and fails
+//! by throwing an `AssertionError`.
+
+mod abstract_class;
+mod array_access;
+mod array_const;
+mod array_keys;
+mod binary;
+mod by_ref;
+mod bytes_buf;
+mod call_function;
+mod call_method;
+mod clone;
+mod comparable;
+mod countable;
+mod date_interval;
+mod debug_info;
+mod deep_clone;
+mod exception_code;
+mod fallible_getter;
+mod fluent;
+mod from_zval_struct;
+mod generator;
+mod into_zval_struct;
+mod invoke;
+mod json_serializable;
+mod omittable;
+mod panic_exception;
+mod php_trait;
+mod readonly_prop;
+mod serializable;
+mod str_from;
+mod stringable;
+mod superglobals;
+mod uninstantiable;
+
+use std::{
+    env,
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    sync::Once,
+    thread,
+    time::Duration,
+};
+
+static BUILD: Once = Once::new();
+
+/// Builds the test extension, once for all tests.
+fn build() {
+    BUILD.call_once(|| {
+        let status = Command::new(env!("CARGO"))
+            .arg("build")
+            .arg("--manifest-path")
+            .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+            .status()
+            .expect("failed to run `cargo build`");
+        assert!(status.success(), "failed to build the test extension");
+    });
+}
+
+/// Returns the path to the built test extension.
+fn ext_path() -> PathBuf {
+    let mut path = env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../target")));
+    path.push("debug");
+    path.push(format!(
+        "{}tests{}",
+        env::consts::DLL_PREFIX,
+        env::consts::DLL_SUFFIX
+    ));
+    path
+}
+
+/// Returns a command running PHP with the test extension loaded and
+/// assertions enabled.
+fn php() -> Command {
+    let mut php = Command::new("php");
+    php.arg("-n")
+        .arg(format!("-dextension={}", ext_path().display()))
+        .arg("-dzend.assertions=1")
+        .arg("-dassert.exception=1");
+    php
+}
+
+/// Returns the path to the given script from `src/integration`.
+fn script_path(file: &str) -> String {
+    format!("{}/src/integration/{}", env!("CARGO_MANIFEST_DIR"), file)
+}
+
+/// Runs the given script from `src/integration` against the test extension,
+/// panicking with the output of PHP if the script fails.
+pub fn run_php(file: &str) {
+    build();
+
+    let output = php()
+        .arg(script_path(file))
+        .output()
+        .expect("failed to run `php`");
+
+    assert!(
+        output.status.success(),
+        "`{}` failed:\n{}{}",
+        file,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Kills the PHP web server when dropped, including when a test fails.
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Serves the given script from `src/integration` with the built-in web
+/// server of PHP, and requests it the given number of times. The requests are
+/// handled one after the other by the same process, so the script can check
+/// state which must survive the end of a request, such as persistent
+/// constants. Each request must print `ok`, otherwise the test panics with the
+/// response.
+pub fn run_php_server(file: &str, requests: usize) {
+    build();
+
+    let port = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("failed to find a free port")
+        .port();
+    let _server = Server(
+        php()
+            .arg("-S")
+            .arg(format!("127.0.0.1:{}", port))
+            .arg(script_path(file))
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to run `php -S`"),
+    );
+
+    for request in 1..=requests {
+        let response = get(port);
+        let body = response.split("\r\n\r\n").nth(1).unwrap_or_default();
+        assert_eq!(
+            body, "ok",
+            "`{}` failed on request {}:\n{}",
+            file, request, response
+        );
+    }
+}
+
+/// Sends a request to the web server listening on the given port, waiting for
+/// the server to start, and returns the response.
+fn get(port: u16) -> String {
+    let mut attempts = 0;
+    let mut stream = loop {
+        match TcpStream::connect(("127.0.0.1", port)) {
+            Ok(stream) => break stream,
+            Err(e) if attempts == 50 => panic!("failed to connect to `php -S`: {}", e),
+            Err(_) => {
+                attempts += 1;
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+    };
+
+    stream
+        .write_all(b"GET / HTTP/1.0\r\nHost: localhost\r\n\r\n")
+        .expect("failed to send request");
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .expect("failed to read response");
+    response
+}
@@ -0,0 +1,17 @@
+<?php
+
+// An argument given as `null` is told apart from an omitted argument
+assert(test_omittable() === 'absent absent');
+assert(test_omittable(null) === 'null absent');
+assert(test_omittable(5) === '5 absent');
+assert(test_omittable(null, null) === 'null null');
+assert(test_omittable(5, 'five') === '5 "five"');
+
+// Arguments skipped with named arguments are given their default of `null`
+assert(test_omittable(label: 'five') === 'null "five"');
+assert(test_omittable(limit: 5) === '5 absent');
+
+// The parameters are optional and nullable
+$params = (new ReflectionFunction('test_omittable'))->getParameters();
+assert($params[0]->isOptional() && $params[0]->allowsNull());
+assert($params[1]->isOptional() && $params[1]->allowsNull());
@@ -0,0 +1,4 @@
+#[test]
+fn omittable() {
+    super::run_php("omittable.php");
+}
@@ -0,0 +1,21 @@
+<?php
+
+assert(test_panic_divide(6, 3) === 2);
+
+// The panic is caught and thrown as an `Error` containing the panic message
+try {
+    test_panic_divide(1, 0);
+    assert(false, 'Panicking did not throw');
+} catch (Error $e) {
+    assert(get_class($e) === 'Error');
+    assert($e->getMessage() === 'cannot divide 1 by zero');
+}
+
+// The process keeps running, and the function can be called again
+assert(test_panic_divide(10, 5) === 2);
+try {
+    test_panic_divide(2, 0);
+    assert(false, 'Panicking a second time did not throw');
+} catch (Error $e) {
+    assert($e->getMessage() === 'cannot divide 2 by zero');
+}
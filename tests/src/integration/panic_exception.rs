@@ -0,0 +1,4 @@
+#[test]
+fn panic_exception() {
+    super::run_php("panic_exception.php");
+}
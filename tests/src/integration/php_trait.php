@@ -0,0 +1,14 @@
+<?php
+
+// The trait is registered, with its methods declared as abstract
+assert(trait_exists('TestGreeter'));
+$greet = new ReflectionMethod('TestGreeter', 'greet');
+assert($greet->isAbstract());
+
+// The class using the trait calls the Rust implementation of the methods,
+// including the default implementation
+$person = new TestPerson('David');
+assert(method_exists($person, 'greet'));
+assert($person->name() === 'David');
+assert($person->greet('Hello') === 'Hello, David!');
+assert((new TestPerson('Ada'))->greet('Hi') === 'Hi, Ada!');
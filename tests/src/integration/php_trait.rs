@@ -0,0 +1,4 @@
+#[test]
+fn php_trait() {
+    super::run_php("php_trait.php");
+}
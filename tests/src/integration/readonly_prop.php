@@ -0,0 +1,19 @@
+<?php
+
+$point = new TestPoint(1);
+assert($point->x === 1);
+
+// Writing the property after construction throws
+try {
+    $point->x = 5;
+    assert(false, 'Writing a readonly property did not throw');
+} catch (Error $e) {
+    assert($e->getMessage() === 'Cannot modify readonly property TestPoint::$x');
+}
+assert($point->x === 1);
+
+// Other properties can still be written, and the field can be changed from Rust
+$point->label = 'origin';
+assert($point->label === 'origin');
+$point->moveBy(2);
+assert($point->x === 3);
@@ -0,0 +1,4 @@
+#[test]
+fn readonly_prop() {
+    super::run_php("readonly_prop.php");
+}
@@ -0,0 +1,29 @@
+<?php
+
+$point = new TestSerializable(3, 'start');
+$data = serialize($point);
+assert($data === 'O:16:"TestSerializable":2:{s:1:"x";i:3;s:5:"label";s:5:"start";}');
+
+// Unserializing creates a new object with the same Rust value
+$copy = unserialize($data);
+assert($copy instanceof TestSerializable);
+assert($copy !== $point);
+assert($copy->describe() === 'start: 3');
+assert(unserialize(serialize($copy))->describe() === 'start: 3');
+
+// Errors from `unserialize` are thrown from `unserialize()`
+try {
+    unserialize('O:16:"TestSerializable":1:{s:1:"x";s:3:"one";}');
+    assert(false, 'Unserializing invalid data did not throw');
+} catch (Exception $e) {
+    assert($e->getMessage() === 'Invalid serialized x');
+}
+
+// The Rust value of a constructed object cannot be replaced
+try {
+    $point->__unserialize(['x' => 5, 'label' => 'replaced']);
+    assert(false, 'Calling __unserialize on a constructed object did not throw');
+} catch (Error $e) {
+    assert($e->getMessage() === 'TestSerializable::__unserialize() cannot be called on an initialized object');
+}
+assert($point->describe() === 'start: 3');
@@ -0,0 +1,4 @@
+#[test]
+fn serializable() {
+    super::run_php("serializable.php");
+}
@@ -0,0 +1,14 @@
+<?php
+
+// `ZendStr::from_fmt` formats directly into the Zend string
+assert(test_str_from_fmt('cart', 14) === 'cart has 14 items (2.00 per day)');
+assert(test_str_from_fmt('', -3) === ' has -3 items (-0.43 per day)');
+$name = str_repeat('long ', 1000);
+assert(test_str_from_fmt($name, 0) === "{$name} has 0 items (0.00 per day)");
+
+// `ZendStr::from_bytes` keeps NUL bytes and invalid UTF-8
+assert(test_str_from_bytes('') === '');
+assert(test_str_from_bytes('hello') === 'hello');
+assert(test_str_from_bytes("hello\0world") === "hello\0world");
+assert(strlen(test_str_from_bytes("\0\0\0")) === 3);
+assert(test_str_from_bytes("\xff\xfe") === "\xff\xfe");
@@ -0,0 +1,4 @@
+#[test]
+fn str_from() {
+    super::run_php("str_from.php");
+}
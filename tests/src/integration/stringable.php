@@ -0,0 +1,22 @@
+<?php
+
+$obj = new TestStringable('héllo');
+assert($obj instanceof Stringable);
+
+// Explicit casts and calls
+assert((string) $obj === 'héllo');
+assert(strval($obj) === 'héllo');
+assert($obj->__toString() === 'héllo');
+
+// Implicit conversions
+assert("value: $obj" === 'value: héllo');
+assert('value: ' . $obj === 'value: héllo');
+assert(str_repeat($obj, 2) === 'héllohéllo');
+
+// Strings which cannot be converted throw instead of being truncated
+$nul = new TestStringable("a\0b");
+try {
+    (string) $nul;
+    assert(false, 'Converting a string containing a NUL byte should throw');
+} catch (Exception $e) {
+}
@@ -0,0 +1,4 @@
+#[test]
+fn stringable() {
+    super::run_php("stringable.php");
+}
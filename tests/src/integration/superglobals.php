@@ -0,0 +1,15 @@
+<?php
+
+// Known server variables of the CLI are read from `$_SERVER`
+assert(basename(test_server_var('SCRIPT_FILENAME')) === 'superglobals.php');
+assert(test_server_var('PHP_SELF') === test_server_var('SCRIPT_FILENAME'));
+assert(test_server_var('MISSING_SERVER_VAR') === null);
+
+// Values are converted in the same way as arguments, so the integer `argc`
+// becomes a string
+assert($_SERVER['argc'] === 1);
+assert(test_server_var('argc') === '1');
+
+assert(test_superglobal_len('_SERVER') === count($_SERVER));
+assert(test_superglobal_len('_GET') === 0);
+assert(test_superglobal_len('_POST') === 0);
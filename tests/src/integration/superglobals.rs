@@ -0,0 +1,4 @@
+#[test]
+fn superglobals() {
+    super::run_php("superglobals.php");
+}
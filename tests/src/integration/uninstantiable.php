@@ -0,0 +1,19 @@
+<?php
+
+// `new` throws, as the constructor is private
+try {
+    new TestRegistry();
+    assert(false, 'Instantiating the class did not throw');
+} catch (Error $e) {
+    assert($e->getMessage() === 'Call to private TestRegistry::__construct() from global scope');
+}
+
+$constructor = new ReflectionMethod('TestRegistry', '__construct');
+assert($constructor->isPrivate());
+assert(!(new ReflectionClass('TestRegistry'))->isInstantiable());
+
+// The factory method and constants still work
+$registry = TestRegistry::create('main');
+assert($registry instanceof TestRegistry);
+assert($registry->name() === 'main');
+assert(TestRegistry::VERSION === 2);
@@ -0,0 +1,4 @@
+#[test]
+fn uninstantiable() {
+    super::run_php("uninstantiable.php");
+}
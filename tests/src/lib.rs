@@ -0,0 +1,809 @@
+//! Extension used by the integration tests, which load it into PHP and run the
+//! scripts in `src/integration` against it.
+
+use std::{borrow::Cow, collections::HashMap, convert::TryFrom, time::Duration};
+
+use bytes::{Bytes, BytesMut};
+
+use ext_php_rs::{
+    args::Arg,
+    array_access::PhpArrayAccess,
+    binary::Binary,
+    boxed::ZBox,
+    builders::FunctionBuilder,
+    convert::IntoZval,
+    countable::PhpCountable,
+    exception::PhpException,
+    flags::DataType,
+    generator::Generator,
+    json::PhpJsonSerialize,
+    magic::PhpDebugInfo,
+    prelude::*,
+    serialize::PhpSerialize,
+    stringable::PhpToString,
+    types::{ArrayKey, DateInterval, ZendClassObject, ZendHashTable, ZendObject, ZendStr, Zval},
+    zend::{call_function_into, ExecuteData, ExecutorGlobals, FunctionEntry, Superglobal},
+};
+
+/// Converts an integer or string offset into a map key.
+fn offset_key(offset: &Zval) -> Option<String> {
+    offset
+        .long()
+        .map(|offset| offset.to_string())
+        .or_else(|| offset.string())
+}
+
+#[php_class]
+#[array_access]
+#[derive(Default)]
+pub struct TestArrayAccess {
+    values: HashMap<String, i64>,
+}
+
+#[php_impl]
+impl TestArrayAccess {
+    pub fn __construct() -> Self {
+        Self::default()
+    }
+}
+
+impl PhpArrayAccess for TestArrayAccess {
+    type Value = Option<i64>;
+
+    fn offset_exists(&mut self, offset: &Zval) -> PhpResult<bool> {
+        Ok(offset_key(offset).is_some_and(|key| self.values.contains_key(&key)))
+    }
+
+    fn offset_get(&mut self, offset: &Zval) -> PhpResult<Option<i64>> {
+        Ok(offset_key(offset).and_then(|key| self.values.get(&key).copied()))
+    }
+
+    fn offset_set(&mut self, offset: &Zval, value: &Zval) -> PhpResult {
+        let key = offset_key(offset).ok_or("Offset must be an integer or string.")?;
+        let value = value.long().ok_or("Value must be an integer.")?;
+        self.values.insert(key, value);
+        Ok(())
+    }
+
+    fn offset_unset(&mut self, offset: &Zval) -> PhpResult {
+        if let Some(key) = offset_key(offset) {
+            self.values.remove(&key);
+        }
+        Ok(())
+    }
+}
+
+#[php_class]
+#[countable]
+pub struct TestCountable {
+    count: i64,
+}
+
+#[php_impl]
+impl TestCountable {
+    pub fn __construct(count: i64) -> Self {
+        Self { count }
+    }
+}
+
+impl PhpCountable for TestCountable {
+    fn count(&self) -> i64 {
+        self.count
+    }
+}
+
+#[php_class]
+#[stringable]
+pub struct TestStringable {
+    value: String,
+}
+
+#[php_impl]
+impl TestStringable {
+    pub fn __construct(value: String) -> Self {
+        Self { value }
+    }
+}
+
+impl PhpToString for TestStringable {
+    fn to_php_string(&self) -> String {
+        self.value.clone()
+    }
+}
+
+#[php_function]
+pub fn test_append_by_ref(mut arr: ZvalRef, value: i64) -> PhpResult {
+    arr.array_mut().ok_or("Expected an array.")?.push(value)?;
+    Ok(())
+}
+
+#[php_function]
+pub fn test_increment_by_ref(mut counter: ZvalRef) -> PhpResult {
+    let value: i64 = counter.get().unwrap_or(0);
+    counter.set(value + 1)?;
+    Ok(())
+}
+
+#[php_function]
+pub fn bench_str_owned(input: String) -> i64 {
+    input.len() as i64
+}
+
+#[php_function]
+pub fn bench_str_borrowed(input: &str) -> i64 {
+    input.len() as i64
+}
+
+#[php_function]
+pub fn bench_str_cow(input: Cow<str>) -> i64 {
+    input.len() as i64
+}
+
+#[php_function]
+pub fn bench_str_fmt(id: i64) -> ZBox<ZendStr> {
+    ZendStr::from_fmt(format_args!("{:>1024}", id), false)
+}
+
+#[php_function]
+pub fn bench_str_format(id: i64) -> String {
+    format!("{:>1024}", id)
+}
+
+#[php_function]
+pub fn test_bytes_slice(input: &[u8]) -> Binary<u8> {
+    input.iter().rev().copied().collect()
+}
+
+#[php_function]
+pub fn test_bytes_vec(input: Binary<u8>) -> Binary<u8> {
+    let mut bytes: Vec<u8> = input.into();
+    bytes.reverse();
+    bytes.into()
+}
+
+#[php_function]
+pub fn test_bytes_zend_str(input: &[u8]) -> ZBox<ZendStr> {
+    input.to_vec().into()
+}
+
+#[php_function]
+pub fn test_bytes_buf(input: Bytes) -> Bytes {
+    input
+}
+
+#[php_function]
+pub fn test_bytes_buf_mut(input: Bytes) -> BytesMut {
+    let mut buf = BytesMut::from(&input[..]);
+    buf.extend_from_slice(&input);
+    buf
+}
+
+#[php_function]
+pub fn bench_array_sum_borrowed(values: &ZendHashTable) -> i64 {
+    values.values().filter_map(|val| val.long()).sum()
+}
+
+#[php_function]
+pub fn bench_array_sum_vec(values: Vec<i64>) -> i64 {
+    values.iter().sum()
+}
+
+#[php_function]
+pub fn bench_array_new(len: i64) -> PhpResult<ZBox<ZendHashTable>> {
+    let mut ht = ZendHashTable::new();
+    for i in 0..len {
+        ht.push(i)?;
+    }
+    Ok(ht)
+}
+
+#[php_function]
+pub fn bench_array_with_capacity(len: i64) -> PhpResult<ZBox<ZendHashTable>> {
+    let mut ht = ZendHashTable::with_capacity(len as u32);
+    for i in 0..len {
+        ht.push(i)?;
+    }
+    Ok(ht)
+}
+
+/// Does nothing, used as the handler of the functions built by the `minit`
+/// benchmarks.
+extern "C" fn bench_noop(_: &mut ExecuteData, _: &mut Zval) {}
+
+/// Builds the entries of `count` functions taking two arguments, the same way
+/// the `php_module` macro builds the entries of the exported functions when the
+/// extension starts up. The names and argument infos of the entries are leaked,
+/// as PHP would own them once the extension is loaded.
+fn bench_function_entries(count: i64) -> impl Iterator<Item = FunctionEntry> {
+    (0..count).map(|i| {
+        FunctionBuilder::new(format!("bench_minit_{}", i), bench_noop)
+            .arg(Arg::new("a", DataType::Long))
+            .arg(Arg::new("b", DataType::String))
+            .returns(DataType::Long, false, false)
+            .build()
+            .expect("failed to build function entry")
+    })
+}
+
+#[php_function]
+pub fn bench_minit_batch(count: i64) {
+    ModuleBuilder::new("bench", "0.0.0").functions(bench_function_entries(count));
+}
+
+#[php_function]
+pub fn bench_minit_single(count: i64) {
+    let mut module = ModuleBuilder::new("bench", "0.0.0");
+    for entry in bench_function_entries(count) {
+        module = module.function(entry);
+    }
+}
+
+#[php_class]
+#[json_serializable]
+pub struct TestJsonSerializable {
+    cents: i64,
+    currency: String,
+}
+
+#[php_impl]
+impl TestJsonSerializable {
+    pub fn __construct(cents: i64, currency: String) -> Self {
+        Self { cents, currency }
+    }
+}
+
+impl PhpJsonSerialize for TestJsonSerializable {
+    fn json_serialize(&self) -> PhpResult<Zval> {
+        if self.cents < 0 {
+            return Err("Negative amounts cannot be serialized".into());
+        }
+
+        let mut ht = ZendHashTable::new();
+        ht.insert("amount", self.cents as f64 / 100.0)?;
+        ht.insert("currency", self.currency.as_str())?;
+        Ok(ht.into_zval(false)?)
+    }
+}
+
+#[php_class]
+#[derive(Default)]
+pub struct TestFluent {
+    table: String,
+    limit: Option<i64>,
+}
+
+#[php_impl]
+impl TestFluent {
+    pub fn __construct() -> Self {
+        Self::default()
+    }
+
+    pub fn from(
+        #[this] this: &mut ZendClassObject<Self>,
+        table: String,
+    ) -> &mut ZendClassObject<Self> {
+        this.table = table;
+        this
+    }
+
+    pub fn limit(
+        #[this] this: &mut ZendClassObject<Self>,
+        limit: i64,
+    ) -> &mut ZendClassObject<Self> {
+        this.limit = Some(limit);
+        this
+    }
+
+    pub fn build(&self) -> String {
+        match self.limit {
+            Some(limit) => format!("SELECT * FROM {} LIMIT {}", self.table, limit),
+            None => format!("SELECT * FROM {}", self.table),
+        }
+    }
+}
+
+#[php_class(abstract)]
+#[derive(Default)]
+pub struct TestShape;
+
+#[php_impl]
+impl TestShape {
+    pub fn area(&self) -> f64;
+
+    pub fn kind(&self) -> String {
+        "shape".into()
+    }
+}
+
+#[php_class]
+#[derive(Default)]
+pub struct TestSnapshot {
+    value: Zval,
+}
+
+#[php_impl]
+impl TestSnapshot {
+    pub fn __construct(value: ZvalRef) -> Self {
+        Self {
+            value: value.deep_clone(),
+        }
+    }
+
+    pub fn get(&self) -> Zval {
+        self.value.shallow_clone()
+    }
+}
+
+/// Describes each key of the given array as `<kind>:<key>`, with the key of
+/// invalid UTF-8 string keys given as hexadecimal bytes.
+#[php_function]
+pub fn test_array_keys(arr: &ZendHashTable) -> Vec<String> {
+    arr.entries()
+        .map(|(key, _)| match key {
+            ArrayKey::Long(idx) => format!("int:{}", idx),
+            ArrayKey::String(key) => format!("string:{}", key),
+            ArrayKey::Bytes(key) => format!(
+                "bytes:{}",
+                key.iter().map(|b| format!("{:02x}", b)).collect::<String>()
+            ),
+        })
+        .collect()
+}
+
+/// Builds an array with the given keys, each mapped to its position.
+#[php_function]
+pub fn test_array_with_keys(keys: Vec<i64>) -> PhpResult<ZBox<ZendHashTable>> {
+    let mut ht = ZendHashTable::new();
+    for (pos, key) in keys.into_iter().enumerate() {
+        ht.insert(key, pos as i64)?;
+    }
+    ht.insert(ArrayKey::Bytes((&b"\xff"[..]).into()), -1)?;
+    Ok(ht)
+}
+
+#[php_function]
+pub fn test_server_var(key: &str) -> Option<String> {
+    ExecutorGlobals::get().superglobal_var(Superglobal::Server, key)
+}
+
+#[php_function]
+pub fn test_superglobal_len(name: &str) -> Option<i64> {
+    let superglobal = match name {
+        "_SERVER" => Superglobal::Server,
+        "_GET" => Superglobal::Get,
+        "_POST" => Superglobal::Post,
+        "_ENV" => Superglobal::Env,
+        _ => return None,
+    };
+    ExecutorGlobals::get()
+        .superglobal(superglobal)
+        .map(|ht| ht.len() as i64)
+}
+
+#[php_class]
+#[serializable]
+pub struct TestSerializable {
+    x: i64,
+    label: String,
+}
+
+#[php_impl]
+impl TestSerializable {
+    pub fn __construct(x: i64, label: String) -> Self {
+        Self { x, label }
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{}: {}", self.label, self.x)
+    }
+}
+
+impl PhpSerialize for TestSerializable {
+    fn serialize(&self) -> PhpResult<ZBox<ZendHashTable>> {
+        let mut data = ZendHashTable::new();
+        data.insert("x", self.x)?;
+        data.insert("label", self.label.as_str())?;
+        Ok(data)
+    }
+
+    fn unserialize(data: &ZendHashTable) -> PhpResult<Self> {
+        Ok(Self {
+            x: data
+                .get("x")
+                .and_then(|zv| zv.long())
+                .ok_or("Invalid serialized x")?,
+            label: data
+                .get("label")
+                .and_then(|zv| zv.string())
+                .ok_or("Invalid serialized label")?,
+        })
+    }
+}
+
+#[php_class]
+#[debug_info]
+pub struct TestDebugInfo {
+    host: String,
+    port: i64,
+}
+
+#[php_impl]
+impl TestDebugInfo {
+    pub fn __construct(host: String, port: i64) -> Self {
+        Self { host, port }
+    }
+}
+
+impl PhpDebugInfo for TestDebugInfo {
+    fn debug_info(&self) -> PhpResult<ZBox<ZendHashTable>> {
+        if self.port < 0 {
+            return Err("Invalid port".into());
+        }
+
+        let mut info = ZendHashTable::new();
+        info.insert("host", self.host.as_str())?;
+        info.insert("port", self.port)?;
+        Ok(info)
+    }
+}
+
+#[php_function]
+pub fn test_call_method(obj: &ZendObject, name: &str, arg: String) -> PhpResult<String> {
+    Ok(obj.call_method_into::<String>(name, (arg,))?)
+}
+
+#[php_function]
+pub fn test_call_function(name: &str, arg: String) -> PhpResult<i64> {
+    Ok(call_function_into::<i64>(name, (arg,))?)
+}
+
+#[php_function]
+pub fn test_str_from_fmt(name: &str, count: i64) -> ZBox<ZendStr> {
+    ZendStr::from_fmt(
+        format_args!(
+            "{} has {} items ({:.2} per day)",
+            name,
+            count,
+            count as f64 / 7.0
+        ),
+        false,
+    )
+}
+
+#[php_function]
+pub fn test_str_from_bytes(input: &[u8]) -> ZBox<ZendStr> {
+    ZendStr::from_bytes(input, false)
+}
+
+#[php_function]
+pub fn test_date_interval(interval: DateInterval) -> DateInterval {
+    DateInterval {
+        days: interval.days * 2,
+        hours: interval.hours * 2,
+        ..interval
+    }
+}
+
+#[php_function]
+pub fn test_date_interval_seconds(interval: DateInterval) -> PhpResult<u64> {
+    Ok(Duration::try_from(interval)?.as_secs())
+}
+
+#[php_function]
+pub fn test_date_interval_from_seconds(secs: u64) -> DateInterval {
+    Duration::from_secs(secs).into()
+}
+
+#[derive(FromZval)]
+pub struct TestRetry {
+    attempts: u32,
+    #[php(rename = "delay-ms", default)]
+    delay_ms: u64,
+}
+
+#[derive(FromZval)]
+pub struct TestOptions {
+    url: String,
+    timeout: Option<f64>,
+    retry: TestRetry,
+}
+
+impl TestOptions {
+    fn describe(&self) -> String {
+        format!(
+            "{} {:?} {} {}",
+            self.url, self.timeout, self.retry.attempts, self.retry.delay_ms
+        )
+    }
+}
+
+#[php_function]
+pub fn test_from_zval_struct(options: TestOptions) -> String {
+    options.describe()
+}
+
+#[php_function]
+pub fn test_from_zval_table(options: &ZendHashTable) -> PhpResult<String> {
+    Ok(TestOptions::try_from(options)?.describe())
+}
+
+#[derive(FromZval, IntoZval)]
+#[php(object)]
+pub struct TestPolicy {
+    attempts: u32,
+    #[php(rename = "delay-ms")]
+    delay_ms: u64,
+}
+
+#[derive(IntoZval)]
+pub struct TestSettings {
+    url: String,
+    #[php(rename = "retry-policy")]
+    retry: TestPolicy,
+    #[php(skip_none)]
+    timeout: Option<f64>,
+    proxy: Option<String>,
+}
+
+#[php_function]
+pub fn test_into_zval_struct(timeout: Option<f64>) -> TestSettings {
+    TestSettings {
+        url: "https://example.com".into(),
+        retry: TestPolicy {
+            attempts: 3,
+            delay_ms: 500,
+        },
+        timeout,
+        proxy: None,
+    }
+}
+
+#[php_function]
+pub fn test_into_zval_object(policy: TestPolicy) -> TestPolicy {
+    TestPolicy {
+        attempts: policy.attempts + 1,
+        ..policy
+    }
+}
+
+#[php_function(panic = "exception")]
+pub fn test_panic_divide(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        panic!("cannot divide {} by zero", a);
+    }
+    a / b
+}
+
+#[php_trait]
+pub trait TestGreeter {
+    fn name(&self) -> String;
+
+    fn greet(&self, greeting: String) -> String {
+        format!("{}, {}!", greeting, self.name())
+    }
+}
+
+#[php_class]
+#[uses(TestGreeter)]
+pub struct TestPerson {
+    name: String,
+}
+
+#[php_impl]
+impl TestPerson {
+    pub fn __construct(name: String) -> Self {
+        Self { name }
+    }
+}
+
+impl TestGreeter for TestPerson {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+#[php_function]
+pub fn test_generator(values: Vec<i64>) -> Generator {
+    // Negative values wrap around to integers which do not fit in a PHP
+    // integer, so they fail to convert.
+    Generator::new(values.into_iter().map(|value| value as u64))
+}
+
+#[php_class]
+pub struct TestPoint {
+    #[prop(readonly)]
+    x: i64,
+    #[prop]
+    label: String,
+}
+
+#[php_impl]
+impl TestPoint {
+    pub fn __construct(x: i64) -> Self {
+        Self {
+            x,
+            label: String::new(),
+        }
+    }
+
+    pub fn move_by(&mut self, dx: i64) {
+        self.x += dx;
+    }
+}
+
+#[php_class]
+#[derive(Default)]
+pub struct TestConnection {
+    host: Option<String>,
+}
+
+#[php_impl]
+impl TestConnection {
+    pub fn __construct(host: String) -> Self {
+        Self { host: Some(host) }
+    }
+
+    #[getter]
+    pub fn host(&self) -> PhpResult<String> {
+        self.host
+            .clone()
+            .ok_or_else(|| "The connection has been closed.".into())
+    }
+
+    #[setter]
+    pub fn set_host(&mut self, host: String) {
+        self.host = Some(host);
+    }
+
+    pub fn close(&mut self) {
+        self.host = None;
+    }
+}
+
+#[php_function]
+pub fn test_exception_code(class: &str, code: i64) -> PhpResult {
+    let code = i32::try_from(code).map_err(|_| "Code does not fit in an i32.")?;
+    Err(PhpException::from_class_name(class, format!("Failed with {}.", code))?.with_code(code))
+}
+
+#[php_class]
+#[derive(Clone)]
+pub struct TestCounter {
+    count: i64,
+    clones: i64,
+}
+
+#[php_impl]
+impl TestCounter {
+    pub fn __construct() -> Self {
+        Self {
+            count: 0,
+            clones: 0,
+        }
+    }
+
+    pub fn __clone(&mut self) {
+        self.clones += 1;
+    }
+
+    pub fn increment(&mut self) -> i64 {
+        self.count += 1;
+        self.count
+    }
+
+    pub fn clones(&self) -> i64 {
+        self.clones
+    }
+}
+
+#[php_class]
+pub struct TestUncloneable {
+    value: i64,
+}
+
+#[php_impl]
+impl TestUncloneable {
+    pub fn __construct(value: i64) -> Self {
+        Self { value }
+    }
+
+    pub fn __clone(&mut self) {
+        self.value = 0;
+    }
+
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+}
+
+#[php_class]
+pub struct TestMultiplier {
+    factor: i64,
+}
+
+#[php_impl]
+impl TestMultiplier {
+    pub fn __construct(factor: i64) -> Self {
+        Self { factor }
+    }
+
+    #[php(magic = "invoke")]
+    pub fn apply(&self, value: i64) -> i64 {
+        value * self.factor
+    }
+}
+
+#[php_class]
+#[comparable]
+pub struct TestVersion {
+    major: i64,
+    minor: i64,
+}
+
+impl PartialEq for TestVersion {
+    fn eq(&self, other: &Self) -> bool {
+        (self.major, self.minor) == (other.major, other.minor)
+    }
+}
+
+impl PartialOrd for TestVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        (self.major, self.minor).partial_cmp(&(other.major, other.minor))
+    }
+}
+
+#[php_impl]
+impl TestVersion {
+    pub fn __construct(major: i64, minor: i64) -> Self {
+        Self { major, minor }
+    }
+
+    pub fn to_string(&self) -> String {
+        format!("{}.{}", self.major, self.minor)
+    }
+}
+
+#[php_const]
+const TEST_ARRAY_CONST: &[(&str, i64)] = &[("timeout", 30), ("retries", 3)];
+
+#[php_const]
+const TEST_LIST_CONST: &[&str] = &["debug", "info", "error"];
+
+#[php_function]
+pub fn test_omittable(limit: Omittable<i64>, label: Omittable<String>) -> String {
+    fn describe<T: std::fmt::Debug>(arg: Omittable<T>) -> String {
+        match arg {
+            Omittable::Present(value) => format!("{:?}", value),
+            Omittable::Null => "null".into(),
+            Omittable::Absent => "absent".into(),
+        }
+    }
+
+    format!("{} {}", describe(limit), describe(label))
+}
+
+#[php_class(uninstantiable)]
+pub struct TestRegistry {
+    name: String,
+}
+
+#[php_impl]
+impl TestRegistry {
+    const VERSION: i64 = 2;
+
+    pub fn create(name: String) -> Self {
+        Self { name }
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+#[php_module]
+pub fn get_module(module: ModuleBuilder) -> ModuleBuilder {
+    module
+}
+
+#[cfg(test)]
+mod integration;